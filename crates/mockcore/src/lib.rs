@@ -20,9 +20,10 @@ use {
     WPubkeyHash, Witness, Wtxid,
   },
   bitcoincore_rpc::json::{
-    Bip125Replaceable, CreateRawTransactionInput, EstimateMode, FeeRatePercentiles,
-    FinalizePsbtResult, GetBalancesResult, GetBalancesResultEntry, GetBlockHeaderResult,
-    GetBlockStatsResult, GetBlockchainInfoResult, GetDescriptorInfoResult, GetNetworkInfoResult,
+    Bip125Replaceable, CreateRawTransactionInput, EstimateMode, EstimateSmartFeeResult,
+    FeeRatePercentiles, FinalizePsbtResult, GetBalancesResult, GetBalancesResultEntry,
+    GetBlockHeaderResult, GetBlockStatsResult, GetBlockchainInfoResult, GetDescriptorInfoResult,
+    GetMempoolEntryResult, GetMempoolEntryResultFees, GetMempoolInfoResult, GetNetworkInfoResult,
     GetRawTransactionResult, GetRawTransactionResultVout, GetRawTransactionResultVoutScriptPubKey,
     GetTransactionResult, GetTransactionResultDetail, GetTransactionResultDetailCategory,
     GetTxOutResult, GetWalletInfoResult, ImportDescriptors, ImportMultiResult,
@@ -36,7 +37,7 @@ use {
   server::Server,
   state::State,
   std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs,
     path::PathBuf,
     sync::{Arc, Mutex, MutexGuard},
@@ -185,6 +186,8 @@ pub struct FundRawTransactionOptions {
   fee_rate: Option<Amount>,
   #[serde(skip_serializing_if = "Option::is_none")]
   change_position: Option<u32>,
+  #[serde(default)]
+  change_address: Option<Address<NetworkUnchecked>>,
 }
 
 #[derive(Deserialize, Clone, PartialEq, Eq, Debug, Serialize)]
@@ -343,6 +346,11 @@ impl Handle {
     self.state().locked.insert(output);
   }
 
+  /// Set the fee rate returned by `estimatesmartfee`, in sats per kvB.
+  pub fn set_fee_estimate(&self, fee_rate: Amount) {
+    self.state().fee_estimate = Some(fee_rate);
+  }
+
   pub fn network(&self) -> String {
     match self.state().network {
       Network::Bitcoin => "mainnet".to_string(),
@@ -241,18 +241,118 @@ impl Api for Server {
     )
   }
 
+  fn estimate_smart_fee(
+    &self,
+    conf_target: u16,
+    _estimate_mode: Option<EstimateMode>,
+  ) -> Result<EstimateSmartFeeResult, jsonrpc_core::Error> {
+    let fee_rate = self.state().fee_estimate;
+
+    Ok(EstimateSmartFeeResult {
+      errors: fee_rate
+        .is_none()
+        .then(|| vec!["Insufficient data or no feerate found".into()]),
+      fee_rate,
+      blocks: conf_target.into(),
+    })
+  }
+
+  fn get_mempool_info(&self) -> Result<GetMempoolInfoResult, jsonrpc_core::Error> {
+    let state = self.state();
+
+    Ok(GetMempoolInfoResult {
+      loaded: Some(true),
+      size: state.mempool.len(),
+      bytes: state.mempool.iter().map(|tx| tx.vsize()).sum(),
+      usage: 0,
+      total_fee: None,
+      max_mempool: 300_000_000,
+      mempool_min_fee: Amount::from_sat(1000),
+      min_relay_tx_fee: Amount::from_sat(1000),
+      incremental_relay_fee: Some(Amount::from_sat(1000)),
+      unbroadcast_count: Some(0),
+      full_rbf: Some(true),
+    })
+  }
+
+  fn get_mempool_entry(&self, txid: Txid) -> Result<GetMempoolEntryResult, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let Some(tx) = state.mempool.iter().find(|tx| tx.compute_txid() == txid) else {
+      return Err(Self::not_found());
+    };
+
+    let in_mempool = |txid: Txid| state.mempool.iter().find(|tx| tx.compute_txid() == txid);
+
+    let mut ancestors = HashSet::new();
+    let mut pending = vec![tx];
+
+    while let Some(tx) = pending.pop() {
+      for txin in &tx.input {
+        if let Some(parent) = in_mempool(txin.previous_output.txid) {
+          if ancestors.insert(txin.previous_output.txid) {
+            pending.push(parent);
+          }
+        }
+      }
+    }
+
+    Ok(GetMempoolEntryResult {
+      vsize: tx.vsize().try_into().unwrap(),
+      weight: Some(tx.weight().to_wu()),
+      time: 0,
+      height: state.hashes.len().try_into().unwrap(),
+      descendant_count: 1,
+      descendant_size: 0,
+      ancestor_count: (ancestors.len() + 1).try_into().unwrap(),
+      ancestor_size: 0,
+      wtxid: txid,
+      fees: GetMempoolEntryResultFees {
+        base: Amount::ZERO,
+        modified: Amount::ZERO,
+        ancestor: Amount::ZERO,
+        descendant: Amount::ZERO,
+      },
+      depends: Vec::new(),
+      spent_by: Vec::new(),
+      bip125_replaceable: true,
+      unbroadcast: Some(false),
+    })
+  }
+
   fn get_tx_out(
     &self,
     txid: Txid,
     vout: u32,
-    _include_mempool: Option<bool>,
+    include_mempool: Option<bool>,
   ) -> Result<Option<GetTxOutResult>, jsonrpc_core::Error> {
     let state = self.state();
 
-    let Some(value) = state.utxos.get(&OutPoint { txid, vout }) else {
+    let outpoint = OutPoint { txid, vout };
+
+    let include_mempool = include_mempool.unwrap_or(true);
+
+    let mempool_value = state
+      .mempool
+      .iter()
+      .filter(|_| include_mempool)
+      .find(|tx| tx.compute_txid() == txid)
+      .and_then(|tx| tx.output.get(usize::try_from(vout).unwrap()))
+      .map(|txout| txout.value);
+
+    let Some(value) = state.utxos.get(&outpoint).copied().or(mempool_value) else {
       return Ok(None);
     };
 
+    if include_mempool
+      && state
+        .mempool
+        .iter()
+        .any(|tx| tx.input.iter().any(|txin| txin.previous_output == outpoint))
+    {
+      return Ok(None);
+    }
+
     let mut confirmations = None;
 
     for (height, hash) in state.hashes.iter().enumerate() {
@@ -266,7 +366,7 @@ impl Api for Server {
     Ok(Some(GetTxOutResult {
       bestblock: BlockHash::all_zeros(),
       coinbase: false,
-      confirmations: confirmations.unwrap().try_into().unwrap(),
+      confirmations: confirmations.unwrap_or_default().try_into().unwrap(),
       script_pub_key: GetRawTransactionResultVoutScriptPubKey {
         asm: String::new(),
         hex: Vec::new(),
@@ -275,7 +375,7 @@ impl Api for Server {
         addresses: Vec::new(),
         address: None,
       },
-      value: *value,
+      value,
     }))
   }
 
@@ -387,12 +487,32 @@ impl Api for Server {
       .map(|txout| txout.value.to_sat())
       .sum();
 
+    let spent = state
+      .mempool
+      .iter()
+      .flat_map(|tx| tx.input.iter().map(|txin| txin.previous_output))
+      .collect::<HashSet<OutPoint>>();
+
     let mut utxos = state
       .utxos
       .clone()
       .into_iter()
-      .map(|(outpoint, value)| (value, outpoint))
-      .collect::<Vec<(Amount, OutPoint)>>();
+      .map(|(outpoint, value)| (value, outpoint, state.transactions[&outpoint.txid].clone()))
+      .chain(state.mempool.iter().flat_map(|tx| {
+        let txid = tx.compute_txid();
+        tx.output.iter().enumerate().map(move |(vout, txout)| {
+          (
+            txout.value,
+            OutPoint {
+              txid,
+              vout: vout.try_into().unwrap(),
+            },
+            tx.clone(),
+          )
+        })
+      }))
+      .filter(|(_value, outpoint, _tx)| !spent.contains(outpoint))
+      .collect::<Vec<(Amount, OutPoint, Transaction)>>();
 
     let mut input_value = transaction
       .input
@@ -404,13 +524,11 @@ impl Api for Server {
     utxos.reverse();
 
     if output_value > input_value {
-      for (value, outpoint) in utxos {
+      for (value, outpoint, tx) in utxos {
         if state.locked.contains(&outpoint) {
           continue;
         }
 
-        let tx = state.transactions.get(&outpoint.txid).unwrap();
-
         let tx_out = &tx.output[usize::try_from(outpoint.vout).unwrap()];
 
         let Ok(address) = Address::from_script(&tx_out.script_pubkey, state.network) else {
@@ -451,7 +569,10 @@ impl Api for Server {
     if change > 0 {
       transaction.output.push(TxOut {
         value: Amount::from_sat(change),
-        script_pubkey: state.new_address(true).into(),
+        script_pubkey: match options.change_address {
+          Some(address) => address.assume_checked().script_pubkey(),
+          None => state.new_address(true).into(),
+        },
       });
     }
 
@@ -532,6 +653,19 @@ impl Api for Server {
       }
     }
 
+    let spent = tx
+      .input
+      .iter()
+      .map(|txin| txin.previous_output)
+      .collect::<HashSet<OutPoint>>();
+
+    state.mempool.retain(|replaced| {
+      !replaced
+        .input
+        .iter()
+        .any(|txin| spent.contains(&txin.previous_output))
+    });
+
     state.mempool.push(tx.clone());
 
     Ok(tx.compute_txid().to_string())
@@ -615,13 +749,17 @@ impl Api for Server {
   ) -> Result<Value, jsonrpc_core::Error> {
     let state = self.state();
 
-    let Some(tx) = state.transactions.get(&txid) else {
+    let Some(tx) = state.transaction(&txid) else {
       return Err(jsonrpc_core::Error::new(
         jsonrpc_core::types::error::ErrorCode::ServerError(-8),
       ));
     };
 
-    let mut confirmations = None;
+    let mut confirmations = state
+      .mempool
+      .iter()
+      .any(|tx| tx.compute_txid() == txid)
+      .then_some(0);
 
     'outer: for (height, hash) in state.hashes.iter().enumerate() {
       for tx in &state.blocks[hash].txdata {
@@ -713,7 +851,7 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match state.transactions.get(&txid) {
+      match state.transaction(&txid) {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
@@ -736,10 +874,16 @@ impl Api for Server {
 
     let state = self.state();
 
+    let spent = state
+      .mempool
+      .iter()
+      .flat_map(|tx| tx.input.iter().map(|txin| txin.previous_output))
+      .collect::<HashSet<OutPoint>>();
+
     let mut unspent = Vec::new();
 
     for (outpoint, &amount) in &state.utxos {
-      if state.locked.contains(outpoint) {
+      if state.locked.contains(outpoint) || spent.contains(outpoint) {
         continue;
       }
 
@@ -824,6 +968,20 @@ impl Api for Server {
     }])
   }
 
+  fn get_address_info(
+    &self,
+    address: Address<NetworkUnchecked>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let address = address.require_network(state.network).unwrap();
+
+    Ok(serde_json::json!({
+      "address": address.to_string(),
+      "ismine": state.is_wallet_address(&address),
+    }))
+  }
+
   fn get_new_address(
     &self,
     _label: Option<String>,
@@ -840,13 +998,24 @@ impl Api for Server {
     _include_watchonly: Option<bool>,
   ) -> Result<Vec<ListTransactionResult>, jsonrpc_core::Error> {
     let state = self.state();
+
+    let transactions = state
+      .hashes
+      .iter()
+      .skip(1)
+      .flat_map(|hash| &state.blocks[hash].txdata)
+      .chain(&state.mempool)
+      .collect::<Vec<&Transaction>>();
+
     Ok(
-      state
-        .transactions
+      transactions
         .iter()
-        .take(count.unwrap_or(u16::MAX).into())
-        .map(|(txid, tx)| (*txid, tx))
-        .chain(state.mempool.iter().map(|tx| (tx.compute_txid(), tx)))
+        .skip(
+          transactions
+            .len()
+            .saturating_sub(count.unwrap_or(u16::MAX).into()),
+        )
+        .map(|tx| (tx.compute_txid(), *tx))
         .map(|(txid, tx)| ListTransactionResult {
           info: WalletTxInfo {
             confirmations: state.get_confirmations(tx),
@@ -881,8 +1050,6 @@ impl Api for Server {
     unlock: bool,
     outputs: Vec<JsonOutPoint>,
   ) -> Result<bool, jsonrpc_core::Error> {
-    assert!(!unlock);
-
     let mut state = self.state();
 
     if state.fail_lock_unspent {
@@ -894,7 +1061,12 @@ impl Api for Server {
         vout: output.vout,
         txid: output.txid,
       };
-      assert!(state.locked.insert(output));
+
+      if unlock {
+        assert!(state.locked.remove(&output));
+      } else {
+        assert!(state.locked.insert(output));
+      }
     }
 
     Ok(true)
@@ -959,12 +1131,17 @@ impl Api for Server {
     &self,
     psbt: String,
     sign: Option<bool>,
-    sighash_type: Option<()>,
+    sighash_type: Option<String>,
     bip32derivs: Option<bool>,
   ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error> {
-    assert!(sighash_type.is_none());
     assert!(bip32derivs.is_none());
 
+    let signature = match sighash_type.as_deref() {
+      None => vec![0; 64],
+      Some("SINGLE|ANYONECANPAY") => [0; 64].into_iter().chain([0x83]).collect(),
+      Some(sighash_type) => panic!("unsupported sighash type: {sighash_type}"),
+    };
+
     let mut psbt = Psbt::deserialize(
       &base64::engine::general_purpose::STANDARD
         .decode(psbt)
@@ -976,8 +1153,7 @@ impl Api for Server {
       psbt.inputs[i].witness_utxo = Some(
         self
           .state()
-          .transactions
-          .get(&txin.previous_output.txid)
+          .transaction(&txin.previous_output.txid)
           .unwrap()
           .output[txin.previous_output.vout as usize]
           .clone(),
@@ -987,7 +1163,9 @@ impl Api for Server {
     if let Some(sign) = sign {
       if sign {
         for input in psbt.inputs.iter_mut() {
-          input.final_script_witness = Some(Witness::from_slice(&[&[0; 64]]));
+          if input.final_script_witness.is_none() {
+            input.final_script_witness = Some(Witness::from_slice(&[&signature]));
+          }
         }
       }
     }
@@ -1003,16 +1181,19 @@ impl Api for Server {
     psbt: String,
     _extract: Option<bool>,
   ) -> Result<FinalizePsbtResult, jsonrpc_core::Error> {
-    let mut transaction = Psbt::deserialize(
+    let psbt = Psbt::deserialize(
       &base64::engine::general_purpose::STANDARD
         .decode(psbt)
         .unwrap(),
     )
-    .unwrap()
-    .unsigned_tx;
+    .unwrap();
 
-    for input in &mut transaction.input {
-      if input.witness.is_empty() {
+    let mut transaction = psbt.unsigned_tx;
+
+    for (input, psbt_input) in transaction.input.iter_mut().zip(psbt.inputs) {
+      if let Some(witness) = psbt_input.final_script_witness {
+        input.witness = witness;
+      } else if input.witness.is_empty() {
         input.witness = Witness::from_slice(&[&[0; 64]]);
       }
     }
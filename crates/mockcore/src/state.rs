@@ -5,6 +5,7 @@ pub struct State {
   pub blocks: BTreeMap<BlockHash, Block>,
   pub descriptors: Vec<(String, bitcoincore_rpc::json::Timestamp)>,
   pub fail_lock_unspent: bool,
+  pub fee_estimate: Option<Amount>,
   pub hashes: Vec<BlockHash>,
   pub loaded_wallets: BTreeSet<String>,
   pub locked: BTreeSet<OutPoint>,
@@ -36,6 +37,7 @@ impl State {
       change_addresses: Vec::new(),
       descriptors: Vec::new(),
       fail_lock_unspent,
+      fee_estimate: None,
       hashes,
       loaded_wallets: BTreeSet::new(),
       locked: BTreeSet::new(),
@@ -281,6 +283,13 @@ impl State {
     txid
   }
 
+  pub(crate) fn transaction(&self, txid: &Txid) -> Option<&Transaction> {
+    self
+      .transactions
+      .get(txid)
+      .or_else(|| self.mempool.iter().find(|tx| tx.compute_txid() == *txid))
+  }
+
   pub(crate) fn mempool(&self) -> &[Transaction] {
     &self.mempool
   }
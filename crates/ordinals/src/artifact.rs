@@ -1,6 +1,7 @@
 use super::*;
 
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Artifact {
   Cenotaph(Cenotaph),
   Runestone(Runestone),
@@ -127,6 +127,24 @@ impl FromStr for Charm {
   }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Charm {
+  fn inline_schema() -> bool {
+    true
+  }
+
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "Charm".into()
+  }
+
+  fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "type": "string",
+      "enum": Self::ALL.iter().map(Self::to_string).collect::<Vec<String>>(),
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1,6 +1,7 @@
 use super::*;
 
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Etching {
   pub divisibility: Option<u8>,
   pub premine: Option<u128>,
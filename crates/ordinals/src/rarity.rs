@@ -90,6 +90,27 @@ impl FromStr for Rarity {
   }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Rarity {
+  fn inline_schema() -> bool {
+    true
+  }
+
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "Rarity".into()
+  }
+
+  fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "type": "string",
+      "enum": (0..)
+        .map_while(|n| Self::try_from(n).ok())
+        .map(|rarity| rarity.to_string())
+        .collect::<Vec<String>>(),
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -3,6 +3,11 @@ use super::*;
 #[derive(
   Default, Debug, PartialEq, Copy, Clone, PartialOrd, Ord, Eq, DeserializeFromStr, SerializeDisplay,
 )]
+#[cfg_attr(
+  feature = "schemars",
+  derive(schemars::JsonSchema),
+  schemars(with = "String", extend("pattern" = r"^[A-Z]+$"), inline)
+)]
 pub struct Rune(pub u128);
 
 impl Rune {
@@ -13,6 +13,11 @@ use super::*;
   DeserializeFromStr,
   SerializeDisplay,
 )]
+#[cfg_attr(
+  feature = "schemars",
+  derive(schemars::JsonSchema),
+  schemars(with = "String", extend("pattern" = r"^[0-9]+:[0-9]+$"), inline)
+)]
 pub struct RuneId {
   pub block: u64,
   pub tx: u32,
@@ -5,6 +5,7 @@ mod message;
 mod tag;
 
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Runestone {
   pub edicts: Vec<Edict>,
   pub etching: Option<Etching>,
@@ -22,6 +22,11 @@ use {super::*, bitcoin::transaction::ParseOutPointError};
   DeserializeFromStr,
   SerializeDisplay,
 )]
+#[cfg_attr(
+  feature = "schemars",
+  derive(schemars::JsonSchema),
+  schemars(with = "String", extend("pattern" = r"^[0-9a-f]{64}:[0-9]+:[0-9]+$"), inline)
+)]
 pub struct SatPoint {
   pub outpoint: OutPoint,
   pub offset: u64,
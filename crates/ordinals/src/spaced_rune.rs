@@ -3,6 +3,11 @@ use super::*;
 #[derive(
   Copy, Clone, Debug, PartialEq, Ord, PartialOrd, Eq, Default, DeserializeFromStr, SerializeDisplay,
 )]
+#[cfg_attr(
+  feature = "schemars",
+  derive(schemars::JsonSchema),
+  schemars(with = "String", extend("pattern" = r"^[A-Z•.]+$"), inline)
+)]
 pub struct SpacedRune {
   pub rune: Rune,
   pub spacers: u32,
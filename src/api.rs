@@ -4,21 +4,24 @@ use {
 };
 
 pub use crate::{
-  subcommand::decode::RawOutput as Decode,
+  subcommand::decode::{RawOutput as Decode, SimulationOutput as DecodeSimulation},
   templates::{
     BlocksHtml as Blocks, RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status,
     TransactionHtml as Transaction,
   },
 };
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Block {
   pub best_height: u32,
+  #[schemars(with = "schema::Hash")]
   pub hash: BlockHash,
   pub height: u32,
   pub inscriptions: Vec<InscriptionId>,
   pub runes: Vec<SpacedRune>,
+  #[schemars(with = "schema::Hash")]
   pub target: BlockHash,
+  #[schemars(with = "Vec<schema::Transaction>")]
   pub transactions: Vec<bitcoin::blockdata::transaction::Transaction>,
 }
 
@@ -42,15 +45,17 @@ impl Block {
   }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BlockInfo {
   pub average_fee: u64,
   pub average_fee_rate: u64,
   pub bits: u32,
   #[serde(with = "SerHex::<Strict>")]
+  #[schemars(with = "schema::Hash")]
   pub chainwork: [u8; 32],
   pub confirmations: i32,
   pub difficulty: f64,
+  #[schemars(with = "schema::Hash")]
   pub hash: BlockHash,
   pub feerate_percentiles: [u64; 5],
   pub height: u32,
@@ -59,13 +64,17 @@ pub struct BlockInfo {
   pub max_tx_size: u32,
   pub median_fee: u64,
   pub median_time: Option<u64>,
+  #[schemars(with = "schema::Hash")]
   pub merkle_root: TxMerkleNode,
   pub min_fee: u64,
   pub min_fee_rate: u64,
+  #[schemars(with = "Option<schema::Hash>")]
   pub next_block: Option<BlockHash>,
   pub nonce: u32,
+  #[schemars(with = "Option<schema::Hash>")]
   pub previous_block: Option<BlockHash>,
   pub subsidy: u64,
+  #[schemars(with = "schema::Hash")]
   pub target: BlockHash,
   pub timestamp: u64,
   pub total_fee: u64,
@@ -75,21 +84,156 @@ pub struct BlockInfo {
   pub version: u32,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Children {
   pub ids: Vec<InscriptionId>,
   pub more: bool,
   pub page: usize,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ChildInscriptions {
   pub children: Vec<ChildInscriptionRecursive>,
   pub more: bool,
   pub page: usize,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Event {
+  Block {
+    #[schemars(with = "schema::Hash")]
+    hash: BlockHash,
+    height: u32,
+  },
+  InscriptionCreated {
+    block_height: u32,
+    charms: Vec<Charm>,
+    inscription_id: InscriptionId,
+    location: Option<SatPoint>,
+    parent_inscription_ids: Vec<InscriptionId>,
+    sequence_number: u32,
+  },
+  InscriptionTransferred {
+    block_height: u32,
+    inscription_id: InscriptionId,
+    new_location: SatPoint,
+    old_location: SatPoint,
+    sequence_number: u32,
+  },
+  RuneBurned {
+    amount: u128,
+    block_height: u32,
+    rune_id: RuneId,
+    #[schemars(with = "schema::Hash")]
+    txid: Txid,
+  },
+  RuneEtched {
+    block_height: u32,
+    rune_id: RuneId,
+    #[schemars(with = "schema::Hash")]
+    txid: Txid,
+  },
+  RuneMinted {
+    amount: u128,
+    block_height: u32,
+    rune_id: RuneId,
+    #[schemars(with = "schema::Hash")]
+    txid: Txid,
+  },
+  RuneTransferred {
+    amount: u128,
+    block_height: u32,
+    #[schemars(with = "schema::OutPoint")]
+    outpoint: OutPoint,
+    rune_id: RuneId,
+    #[schemars(with = "schema::Hash")]
+    txid: Txid,
+  },
+}
+
+impl From<index::event::Event> for Event {
+  fn from(event: index::event::Event) -> Self {
+    use index::event::Event as IndexEvent;
+
+    match event {
+      IndexEvent::InscriptionCreated {
+        block_height,
+        charms,
+        inscription_id,
+        location,
+        parent_inscription_ids,
+        sequence_number,
+      } => Self::InscriptionCreated {
+        block_height,
+        charms: Charm::charms(charms),
+        inscription_id,
+        location,
+        parent_inscription_ids,
+        sequence_number,
+      },
+      IndexEvent::InscriptionTransferred {
+        block_height,
+        inscription_id,
+        new_location,
+        old_location,
+        sequence_number,
+      } => Self::InscriptionTransferred {
+        block_height,
+        inscription_id,
+        new_location,
+        old_location,
+        sequence_number,
+      },
+      IndexEvent::RuneBurned {
+        amount,
+        block_height,
+        rune_id,
+        txid,
+      } => Self::RuneBurned {
+        amount,
+        block_height,
+        rune_id,
+        txid,
+      },
+      IndexEvent::RuneEtched {
+        block_height,
+        rune_id,
+        txid,
+      } => Self::RuneEtched {
+        block_height,
+        rune_id,
+        txid,
+      },
+      IndexEvent::RuneMinted {
+        amount,
+        block_height,
+        rune_id,
+        txid,
+      } => Self::RuneMinted {
+        amount,
+        block_height,
+        rune_id,
+        txid,
+      },
+      IndexEvent::RuneTransferred {
+        amount,
+        block_height,
+        outpoint,
+        rune_id,
+        txid,
+      } => Self::RuneTransferred {
+        amount,
+        block_height,
+        outpoint,
+        rune_id,
+        txid,
+      },
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Inscription {
   pub address: Option<String>,
   pub charms: Vec<Charm>,
@@ -113,7 +257,7 @@ pub struct Inscription {
   pub metaprotocol: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct InscriptionRecursive {
   pub charms: Vec<Charm>,
   pub content_type: Option<String>,
@@ -123,6 +267,7 @@ pub struct InscriptionRecursive {
   pub height: u32,
   pub id: InscriptionId,
   pub number: i32,
+  #[schemars(with = "schema::OutPoint")]
   pub output: OutPoint,
   pub sat: Option<ordinals::Sat>,
   pub satpoint: SatPoint,
@@ -131,36 +276,91 @@ pub struct InscriptionRecursive {
   pub address: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RuneRecursive {
+  pub entry: RuneEntry,
+  pub id: RuneId,
+  pub inscription: Option<InscriptionId>,
+  pub mintable: bool,
+  pub supply: u128,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Simulation {
+  pub burned: Option<BTreeMap<SpacedRune, Pile>>,
+  pub cenotaph: bool,
+  pub fee: u64,
+  pub inscriptions: Vec<SimulatedInscription>,
+  pub outputs: Vec<SimulatedOutput>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SimulatedInscription {
+  pub id: InscriptionId,
+  pub new: bool,
+  pub satpoint: Option<SatPoint>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SimulatedOutput {
+  pub runes: Option<BTreeMap<SpacedRune, Pile>>,
+  pub value: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UtxoRecursive {
+  pub inscriptions: Vec<InscriptionId>,
+  pub runes: Option<BTreeMap<SpacedRune, Pile>>,
+  pub sat_ranges: Option<Vec<(u64, u64)>>,
+  pub value: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BroadcastRejection {
+  pub burned: BTreeMap<SpacedRune, Pile>,
+  pub cenotaph: bool,
+  pub lost_inscriptions: Vec<InscriptionId>,
+  pub missing_indexes: Vec<String>,
+  pub rare_sats_as_fee: Vec<ordinals::Sat>,
+  #[schemars(with = "Vec<schema::OutPoint>")]
+  pub unindexed_inputs: Vec<OutPoint>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ChildInscriptionRecursive {
   pub charms: Vec<Charm>,
   pub fee: u64,
   pub height: u32,
   pub id: InscriptionId,
   pub number: i32,
+  #[schemars(with = "schema::OutPoint")]
   pub output: OutPoint,
   pub sat: Option<ordinals::Sat>,
   pub satpoint: SatPoint,
   pub timestamp: i64,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Inscriptions {
   pub ids: Vec<InscriptionId>,
   pub more: bool,
   pub page_index: u32,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Output {
+  #[schemars(with = "Option<schema::Address>")]
   pub address: Option<Address<NetworkUnchecked>>,
   pub indexed: bool,
   pub inscriptions: Vec<InscriptionId>,
+  #[schemars(with = "schema::OutPoint")]
   pub outpoint: OutPoint,
   pub runes: BTreeMap<SpacedRune, Pile>,
   pub sat_ranges: Option<Vec<(u64, u64)>>,
+  #[schemars(with = "schema::Hex")]
   pub script_pubkey: ScriptBuf,
   pub spent: bool,
+  #[schemars(with = "schema::Hash")]
   pub transaction: Txid,
   pub value: u64,
 }
@@ -194,7 +394,7 @@ impl Output {
   }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Sat {
   pub address: Option<String>,
   pub block: u32,
@@ -214,22 +414,55 @@ pub struct Sat {
   pub timestamp: i64,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SatInscription {
   pub id: Option<InscriptionId>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SatInscriptions {
   pub ids: Vec<InscriptionId>,
   pub more: bool,
   pub page: u64,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AddressInfo {
+  #[schemars(with = "Vec<schema::OutPoint>")]
   pub outputs: Vec<OutPoint>,
   pub inscriptions: Vec<InscriptionId>,
   pub sat_balance: u64,
   pub runes_balances: Vec<(SpacedRune, Decimal, Option<char>)>,
 }
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Hide {
+  pub content: bool,
+  pub inscription: InscriptionId,
+  pub reason: String,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Hidden {
+  #[schemars(with = "BTreeMap<schema::Hash, Moderation>")]
+  pub content: BTreeMap<bitcoin::hashes::sha256::Hash, Moderation>,
+  pub inscriptions: BTreeMap<InscriptionId, Moderation>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Moderation {
+  pub reason: String,
+  pub timestamp: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Unhide {
+  pub inscription: InscriptionId,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Usage {
+  pub cost: u64,
+  pub limited: u64,
+  pub requests: u64,
+}
@@ -1,6 +1,9 @@
 use super::*;
 
-#[derive(Debug, PartialEq, Copy, Clone, Default, DeserializeFromStr, SerializeDisplay)]
+#[derive(
+  Debug, PartialEq, Copy, Clone, Default, DeserializeFromStr, SerializeDisplay, JsonSchema,
+)]
+#[schemars(with = "String", extend("pattern" = r"^\d+(\.\d+)?$"), inline)]
 pub struct Decimal {
   pub value: u128,
   pub scale: u8,
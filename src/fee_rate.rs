@@ -22,6 +22,38 @@ impl TryFrom<f64> for FeeRate {
   }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FeeRateArg {
+  Auto(u16),
+  Rate(FeeRate),
+}
+
+impl FeeRateArg {
+  pub(crate) const DEFAULT_CONF_TARGET: u16 = 6;
+}
+
+impl FromStr for FeeRateArg {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s == "auto" {
+      return Ok(Self::Auto(Self::DEFAULT_CONF_TARGET));
+    }
+
+    if let Some(conf_target) = s.strip_prefix("auto:") {
+      let conf_target = conf_target
+        .parse::<u16>()
+        .with_context(|| format!("invalid confirmation target: {conf_target}"))?;
+
+      ensure!(conf_target > 0, "confirmation target must be at least 1");
+
+      return Ok(Self::Auto(conf_target));
+    }
+
+    Ok(Self::Rate(s.parse()?))
+  }
+}
+
 impl FeeRate {
   pub fn fee(&self, vsize: usize) -> Amount {
     #[allow(clippy::cast_possible_truncation)]
@@ -48,6 +80,22 @@ mod tests {
     assert!(FeeRate::try_from(f64::NAN).is_err());
   }
 
+  #[test]
+  fn parse_arg() {
+    assert_eq!(
+      "auto".parse::<FeeRateArg>().unwrap(),
+      FeeRateArg::Auto(FeeRateArg::DEFAULT_CONF_TARGET)
+    );
+    assert_eq!("auto:2".parse::<FeeRateArg>().unwrap(), FeeRateArg::Auto(2));
+    assert_eq!(
+      "1.5".parse::<FeeRateArg>().unwrap(),
+      FeeRateArg::Rate(FeeRate(1.5))
+    );
+    assert!("auto:0".parse::<FeeRateArg>().is_err());
+    assert!("auto:foo".parse::<FeeRateArg>().is_err());
+    assert!("automatic".parse::<FeeRateArg>().is_err());
+  }
+
   #[test]
   fn fee() {
     assert_eq!(
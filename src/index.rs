@@ -5,7 +5,6 @@ use {
       OutPointValue, RuneEntryValue, RuneIdValue, SatPointValue, SatRange, TxidValue,
     },
     event::Event,
-    lot::Lot,
     reorg::Reorg,
     updater::Updater,
     utxo_entry::{ParsedUtxoEntry, UtxoEntry, UtxoEntryBuf},
@@ -16,7 +15,7 @@ use {
     subcommand::{find::FindRangeOutput, server::query},
     templates::StatusHtml,
   },
-  bitcoin::block::Header,
+  bitcoin::{block::Header, hashes::sha256},
   bitcoincore_rpc::{
     json::{GetBlockHeaderResult, GetBlockStatsResult},
     Client,
@@ -27,7 +26,7 @@ use {
   redb::{
     Database, DatabaseError, MultimapTable, MultimapTableDefinition, MultimapTableHandle,
     ReadOnlyTable, ReadableMultimapTable, ReadableTable, ReadableTableMetadata, RepairSession,
-    StorageError, Table, TableDefinition, TableHandle, TableStats, WriteTransaction,
+    StorageError, Table, TableDefinition, TableError, TableHandle, TableStats, WriteTransaction,
   },
   std::{
     collections::HashMap,
@@ -38,6 +37,8 @@ use {
 
 pub use self::entry::RuneEntry;
 
+pub(crate) use self::{lot::Lot, updater::allocate};
+
 pub(crate) mod entry;
 pub mod event;
 mod fetcher;
@@ -55,9 +56,11 @@ const SCHEMA_VERSION: u64 = 29;
 define_multimap_table! { SAT_TO_SEQUENCE_NUMBER, u64, u32 }
 define_multimap_table! { SEQUENCE_NUMBER_TO_CHILDREN, u32, u32 }
 define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], OutPointValue }
+define_table! { CONTENT_HASH_TO_MODERATION, &[u8; 32], (u64, &str) }
 define_table! { HEIGHT_TO_BLOCK_HEADER, u32, &HeaderValue }
 define_table! { HEIGHT_TO_LAST_SEQUENCE_NUMBER, u32, u32 }
 define_table! { HOME_INSCRIPTIONS, u32, InscriptionIdValue }
+define_table! { INSCRIPTION_ID_TO_MODERATION, InscriptionIdValue, (u64, &str) }
 define_table! { INSCRIPTION_ID_TO_SEQUENCE_NUMBER, InscriptionIdValue, u32 }
 define_table! { INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER, i32, u32 }
 define_table! { OUTPOINT_TO_RUNE_BALANCES, &OutPointValue, &[u8] }
@@ -307,9 +310,11 @@ impl Index {
         tx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
         tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
         tx.open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?;
+        tx.open_table(CONTENT_HASH_TO_MODERATION)?;
         tx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
         tx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
         tx.open_table(HOME_INSCRIPTIONS)?;
+        tx.open_table(INSCRIPTION_ID_TO_MODERATION)?;
         tx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
         tx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
@@ -554,6 +559,7 @@ impl Index {
       transaction_index: statistic(Statistic::IndexTransactions)? != 0,
       unrecoverably_reorged: self.unrecoverably_reorged.load(atomic::Ordering::Relaxed),
       uptime: (Utc::now() - self.started).to_std()?,
+      usage: None,
     })
   }
 
@@ -844,6 +850,19 @@ impl Index {
     self.begin_read()?.block_hash(height)
   }
 
+  pub(crate) fn tip_header(&self) -> Result<Option<Header>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .range(0..)?
+        .next_back()
+        .transpose()?
+        .map(|(_height, header)| Header::load(*header.value())),
+    )
+  }
+
   pub fn blocks(&self, take: usize) -> Result<Vec<(u32, BlockHash)>> {
     let rtx = self.begin_read()?;
 
@@ -1590,6 +1609,22 @@ impl Index {
     self.client.get_raw_transaction(&txid, None).into_option()
   }
 
+  /// Hash of the block `txid` was confirmed in, or `None` if it is
+  /// unconfirmed or unknown to Bitcoin Core.
+  pub fn get_transaction_blockhash(&self, txid: Txid) -> Result<Option<BlockHash>> {
+    if txid == self.genesis_block_coinbase_txid {
+      return self.block_hash(Some(0));
+    }
+
+    Ok(
+      self
+        .client
+        .get_raw_transaction_info(&txid, None)
+        .into_option()?
+        .and_then(|info| info.blockhash),
+    )
+  }
+
   pub fn find(&self, sat: Sat) -> Result<Option<SatPoint>> {
     let sat = sat.0;
     let rtx = self.begin_read()?;
@@ -1783,6 +1818,16 @@ impl Index {
     &self,
     page_size: u32,
     page_index: u32,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    self.get_inscriptions_by_offset(page_size.saturating_mul(page_index), page_size)
+  }
+
+  /// Get up to `limit` inscriptions, newest first, skipping the `offset`
+  /// newest, and whether there are more.
+  pub fn get_inscriptions_by_offset(
+    &self,
+    offset: u32,
+    limit: u32,
   ) -> Result<(Vec<InscriptionId>, bool)> {
     let rtx = self.database.begin_read()?;
 
@@ -1796,9 +1841,13 @@ impl Index {
       .transpose()?
       .unwrap_or_default();
 
-    let start = last.saturating_sub(page_size.saturating_mul(page_index));
+    if offset > last {
+      return Ok((Vec::new(), false));
+    }
+
+    let start = last - offset;
 
-    let end = start.saturating_sub(page_size);
+    let end = start.saturating_sub(limit);
 
     let mut inscriptions = sequence_number_to_inscription_entry
       .range(end..=start)?
@@ -1806,7 +1855,7 @@ impl Index {
       .map(|result| result.map(|(_number, entry)| InscriptionEntry::load(entry.value()).id))
       .collect::<Result<Vec<InscriptionId>, StorageError>>()?;
 
-    let more = u32::try_from(inscriptions.len()).unwrap_or(u32::MAX) > page_size;
+    let more = u32::try_from(inscriptions.len()).unwrap_or(u32::MAX) > limit;
 
     if more {
       inscriptions.pop();
@@ -2387,6 +2436,495 @@ impl Index {
       txout,
     )))
   }
+
+  fn content_hash(inscription: &Inscription) -> Option<sha256::Hash> {
+    inscription.body().map(sha256::Hash::hash)
+  }
+
+  /// Hide `inscription_id`, and if `content` is true, every inscription with
+  /// the same content, recording `reason` and the current time.
+  pub fn hide(&self, inscription_id: InscriptionId, content: bool, reason: &str) -> Result {
+    let content_hash = if content {
+      let inscription = self
+        .get_inscription_by_id(inscription_id)?
+        .with_context(|| format!("inscription {inscription_id} not found"))?;
+
+      Some(
+        Self::content_hash(&inscription)
+          .with_context(|| format!("inscription {inscription_id} has no content"))?,
+      )
+    } else {
+      None
+    };
+
+    let timestamp = SystemTime::now()
+      .duration_since(SystemTime::UNIX_EPOCH)?
+      .as_secs();
+
+    let wtx = self.begin_write()?;
+
+    wtx
+      .open_table(INSCRIPTION_ID_TO_MODERATION)?
+      .insert(&inscription_id.store(), (timestamp, reason))?;
+
+    let mut content_hash_to_moderation = wtx.open_table(CONTENT_HASH_TO_MODERATION)?;
+
+    if let Some(content_hash) = content_hash {
+      content_hash_to_moderation.insert(content_hash.as_byte_array(), (timestamp, reason))?;
+    }
+
+    drop(content_hash_to_moderation);
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  /// Unhide `inscription_id`, and every inscription with the same content.
+  pub fn unhide(&self, inscription_id: InscriptionId) -> Result {
+    let content_hash = self
+      .get_inscription_by_id(inscription_id)?
+      .as_ref()
+      .and_then(Self::content_hash);
+
+    let wtx = self.begin_write()?;
+
+    wtx
+      .open_table(INSCRIPTION_ID_TO_MODERATION)?
+      .remove(&inscription_id.store())?;
+
+    if let Some(content_hash) = content_hash {
+      wtx
+        .open_table(CONTENT_HASH_TO_MODERATION)?
+        .remove(content_hash.as_byte_array())?;
+    }
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  /// Moderation tables are created by indices built by this version of ord,
+  /// and by `hide` otherwise, so older indices without them are still usable.
+  fn open_moderation_tables(
+    &self,
+  ) -> Result<
+    Option<(
+      ReadOnlyTable<InscriptionIdValue, (u64, &'static str)>,
+      ReadOnlyTable<&'static [u8; 32], (u64, &'static str)>,
+    )>,
+  > {
+    let rtx = self.database.begin_read()?;
+
+    match rtx.open_table(INSCRIPTION_ID_TO_MODERATION) {
+      Ok(inscriptions) => Ok(Some((
+        inscriptions,
+        rtx.open_table(CONTENT_HASH_TO_MODERATION)?,
+      ))),
+      Err(TableError::TableDoesNotExist(_)) => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn load_hidden(
+    inscriptions: &impl ReadableTable<InscriptionIdValue, (u64, &'static str)>,
+    content: &impl ReadableTable<&'static [u8; 32], (u64, &'static str)>,
+  ) -> Result<api::Hidden> {
+    let moderation = |(timestamp, reason): (u64, &str)| api::Moderation {
+      reason: reason.into(),
+      timestamp,
+    };
+
+    Ok(api::Hidden {
+      content: content
+        .iter()?
+        .map(|result| {
+          result.map(|(content_hash, value)| {
+            (
+              sha256::Hash::from_byte_array(*content_hash.value()),
+              moderation(value.value()),
+            )
+          })
+        })
+        .collect::<Result<_, StorageError>>()?,
+      inscriptions: inscriptions
+        .iter()?
+        .map(|result| {
+          result.map(|(inscription_id, value)| {
+            (
+              InscriptionId::load(inscription_id.value()),
+              moderation(value.value()),
+            )
+          })
+        })
+        .collect::<Result<_, StorageError>>()?,
+    })
+  }
+
+  pub(crate) fn store_hidden(wtx: &WriteTransaction, hidden: &api::Hidden) -> Result {
+    wtx.delete_table(INSCRIPTION_ID_TO_MODERATION)?;
+    wtx.delete_table(CONTENT_HASH_TO_MODERATION)?;
+
+    let mut inscriptions = wtx.open_table(INSCRIPTION_ID_TO_MODERATION)?;
+
+    for (inscription_id, moderation) in &hidden.inscriptions {
+      inscriptions.insert(
+        &inscription_id.store(),
+        (moderation.timestamp, moderation.reason.as_str()),
+      )?;
+    }
+
+    let mut content = wtx.open_table(CONTENT_HASH_TO_MODERATION)?;
+
+    for (content_hash, moderation) in &hidden.content {
+      content.insert(
+        content_hash.as_byte_array(),
+        (moderation.timestamp, moderation.reason.as_str()),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  pub fn hidden(&self) -> Result<api::Hidden> {
+    match self.open_moderation_tables()? {
+      Some((inscriptions, content)) => Self::load_hidden(&inscriptions, &content),
+      None => Ok(api::Hidden::default()),
+    }
+  }
+
+  /// Whether `inscription_id`, or an inscription with the same content as
+  /// `inscription`, has been hidden with `hide`.
+  pub fn is_hidden(
+    &self,
+    inscription_id: InscriptionId,
+    inscription: &Inscription,
+  ) -> Result<bool> {
+    let Some((inscriptions, content)) = self.open_moderation_tables()? else {
+      return Ok(false);
+    };
+
+    if inscriptions.get(&inscription_id.store())?.is_some() {
+      return Ok(true);
+    }
+
+    let Some(content_hash) = Self::content_hash(inscription) else {
+      return Ok(false);
+    };
+
+    Ok(content.get(content_hash.as_byte_array())?.is_some())
+  }
+
+  /// Sats rarer than common in the inputs of `tx` that it would spend as fee,
+  /// or `None` if the index has no sat index.
+  pub(crate) fn rare_sats_spent_as_fee(
+    &self,
+    tx: &Transaction,
+    prevouts: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result<Option<Vec<Sat>>> {
+    if !self.index_sats {
+      return Ok(None);
+    }
+
+    let total_output_value = tx
+      .output
+      .iter()
+      .map(|txout| txout.value.to_sat())
+      .sum::<u64>();
+
+    let mut offset = 0;
+    let mut sats = Vec::new();
+
+    for txin in &tx.input {
+      let Some(ranges) = self.list(txin.previous_output)? else {
+        // later inputs' sats still come after this input's
+        offset += match prevouts.get(&txin.previous_output) {
+          Some(txout) => txout.value.to_sat(),
+          None => self
+            .get_transaction(txin.previous_output.txid)?
+            .and_then(|transaction| {
+              transaction
+                .output
+                .into_iter()
+                .nth(txin.previous_output.vout.into_usize())
+            })
+            .ok_or_else(|| anyhow!("input {} not found", txin.previous_output))?
+            .value
+            .to_sat(),
+        };
+        continue;
+      };
+
+      for (start, end) in ranges {
+        // sats rarer than common are always the first sat of a block subsidy,
+        // so they can only be found at the start of a range
+        if offset >= total_output_value && Sat(start).rarity() > Rarity::Common {
+          sats.push(Sat(start));
+        }
+
+        offset += end - start;
+      }
+    }
+
+    Ok(Some(sats))
+  }
+
+  /// Simulate the effect of confirming `tx` in the next block against the
+  /// current state of the index, using `prevouts` for the outputs spent by
+  /// `tx` that the index doesn't know about. Etchings are assumed to have a
+  /// valid commitment, and rune balances are only reported when the index
+  /// has a rune index.
+  pub(crate) fn simulate(
+    &self,
+    tx: &Transaction,
+    prevouts: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result<api::Simulation> {
+    let txid = tx.compute_txid();
+    let height = self.block_count()?;
+
+    let rtx = self.database.begin_read()?;
+    let outpoint_to_balances = rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+    let id_to_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let total_output_value = tx
+      .output
+      .iter()
+      .map(|txout| txout.value.to_sat())
+      .sum::<u64>();
+
+    let mut envelopes = ParsedEnvelope::from_transaction(tx).into_iter().peekable();
+    let mut id_counter = 0;
+    let mut inscriptions = Vec::new();
+    let mut total_input_value = 0;
+    let mut unallocated: HashMap<RuneId, Lot> = HashMap::new();
+
+    for (input_index, txin) in tx.input.iter().enumerate() {
+      let outpoint = txin.previous_output;
+
+      ensure!(!outpoint.is_null(), "cannot simulate coinbase transaction");
+
+      let value = match prevouts.get(&outpoint) {
+        Some(txout) => txout.value.to_sat(),
+        None => self
+          .get_transaction(outpoint.txid)?
+          .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.into_usize()))
+          .ok_or_else(|| anyhow!("input {outpoint} not found"))?
+          .value
+          .to_sat(),
+      };
+
+      for (satpoint, id) in self.get_inscriptions_on_output_with_satpoints(outpoint)? {
+        inscriptions.push((total_input_value + satpoint.offset, id, false));
+      }
+
+      if let Some(balances) = outpoint_to_balances.get(&outpoint.store())? {
+        let buffer = balances.value();
+        let mut i = 0;
+        while i < buffer.len() {
+          let ((id, balance), len) = Index::decode_rune_balance(&buffer[i..])?;
+          i += len;
+          *unallocated.entry(id).or_default() += balance;
+        }
+      }
+
+      let offset = total_input_value;
+
+      total_input_value += value;
+
+      while let Some(envelope) =
+        envelopes.next_if(|envelope| envelope.input == u32::try_from(input_index).unwrap())
+      {
+        let offset = envelope
+          .payload
+          .pointer()
+          .filter(|&pointer| pointer < total_output_value)
+          .unwrap_or(offset);
+
+        inscriptions.push((
+          offset,
+          InscriptionId {
+            txid,
+            index: id_counter,
+          },
+          true,
+        ));
+
+        id_counter += 1;
+      }
+    }
+
+    let fee = total_input_value
+      .checked_sub(total_output_value)
+      .ok_or_else(|| anyhow!("output value exceeds input value"))?;
+
+    inscriptions.sort_by_key(|(offset, ..)| *offset);
+
+    let inscriptions = inscriptions
+      .into_iter()
+      .map(|(mut offset, id, new)| {
+        let mut satpoint = None;
+
+        for (vout, txout) in tx.output.iter().enumerate() {
+          if offset < txout.value.to_sat() {
+            satpoint = Some(SatPoint {
+              outpoint: OutPoint {
+                txid,
+                vout: vout.try_into().unwrap(),
+              },
+              offset,
+            });
+            break;
+          }
+
+          offset -= txout.value.to_sat();
+        }
+
+        api::SimulatedInscription { id, new, satpoint }
+      })
+      .collect();
+
+    let artifact = Runestone::decipher(tx);
+
+    let cenotaph = matches!(artifact, Some(Artifact::Cenotaph(_)));
+
+    if !self.index_runes {
+      return Ok(api::Simulation {
+        burned: None,
+        cenotaph,
+        fee,
+        inscriptions,
+        outputs: tx
+          .output
+          .iter()
+          .map(|txout| api::SimulatedOutput {
+            runes: None,
+            value: txout.value.to_sat(),
+          })
+          .collect(),
+      });
+    }
+
+    let mut piles = HashMap::new();
+
+    if let Some(id) = artifact.as_ref().and_then(Artifact::mint) {
+      if let Some(entry) = id_to_entry.get(&id.store())? {
+        if let Ok(amount) = RuneEntry::load(entry.value()).mintable(height.into()) {
+          *unallocated.entry(id).or_default() += amount;
+        }
+      }
+    }
+
+    // the ID of an etched rune depends on the position of its etching in the
+    // block, so use an ID that no rune can have: one in the coinbase of the
+    // next block
+    let etched = RuneId {
+      block: height.into(),
+      tx: 0,
+    };
+
+    let etching = match &artifact {
+      Some(Artifact::Runestone(Runestone {
+        etching: Some(etching),
+        ..
+      })) => etching.rune.map(|rune| {
+        (
+          SpacedRune {
+            rune,
+            spacers: etching.spacers.unwrap_or_default(),
+          },
+          etching.divisibility.unwrap_or_default(),
+          etching.symbol,
+        )
+      }),
+      Some(Artifact::Cenotaph(cenotaph)) => cenotaph
+        .etching
+        .map(|rune| (SpacedRune { rune, spacers: 0 }, 0, None)),
+      _ => None,
+    };
+
+    let etching = match etching {
+      Some(etching)
+        if !etching.0.rune.is_reserved()
+          && etching.0.rune
+            >= Rune::minimum_at_height(self.settings.chain().network(), Height(height))
+          && self.rune(etching.0.rune)?.is_none() =>
+      {
+        piles.insert(etched, etching);
+        Some(etched)
+      }
+      _ => None,
+    };
+
+    let mut pile = |id: RuneId| -> Result<Option<(SpacedRune, u8, Option<char>)>> {
+      if let Some(pile) = piles.get(&id) {
+        return Ok(Some(*pile));
+      }
+
+      let Some(entry) = id_to_entry.get(&id.store())? else {
+        return Ok(None);
+      };
+
+      let entry = RuneEntry::load(entry.value());
+      let pile = (entry.spaced_rune, entry.divisibility, entry.symbol);
+      piles.insert(id, pile);
+      Ok(Some(pile))
+    };
+
+    let (allocated, mut burned) = updater::allocate(tx, artifact.as_ref(), etching, unallocated);
+
+    let mut outputs = Vec::new();
+
+    for (txout, balances) in tx.output.iter().zip(allocated) {
+      let mut runes = BTreeMap::new();
+
+      for (id, amount) in balances {
+        if txout.script_pubkey.is_op_return() {
+          *burned.entry(id).or_default() += amount;
+        } else if let Some((spaced_rune, divisibility, symbol)) = pile(id)? {
+          runes.insert(
+            spaced_rune,
+            Pile {
+              amount: amount.n(),
+              divisibility,
+              symbol,
+            },
+          );
+        }
+      }
+
+      outputs.push(api::SimulatedOutput {
+        runes: Some(runes),
+        value: txout.value.to_sat(),
+      });
+    }
+
+    let mut burned_piles = BTreeMap::new();
+
+    for (id, amount) in burned {
+      if amount == 0 {
+        continue;
+      }
+
+      if let Some((spaced_rune, divisibility, symbol)) = pile(id)? {
+        burned_piles.insert(
+          spaced_rune,
+          Pile {
+            amount: amount.n(),
+            divisibility,
+            symbol,
+          },
+        );
+      }
+    }
+
+    Ok(api::Simulation {
+      burned: Some(burned_piles),
+      cenotaph,
+      fee,
+      inscriptions,
+      outputs,
+    })
+  }
 }
 
 #[cfg(test)]
@@ -6682,4 +7220,430 @@ mod tests {
     // zero
     assert_eq!(Statistic::Schema.key(), 0);
   }
+
+  #[test]
+  fn simulate_matches_index() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    context.mine_blocks(1);
+
+    let inscription_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    let inscription_id = InscriptionId {
+      txid: inscription_txid,
+      index: 0,
+    };
+
+    let simulation = context
+      .index
+      .simulate(&context.core.mempool()[0], &BTreeMap::new())
+      .unwrap();
+
+    assert_eq!(
+      simulation.inscriptions,
+      [api::SimulatedInscription {
+        id: inscription_id,
+        new: true,
+        satpoint: Some(SatPoint {
+          outpoint: OutPoint {
+            txid: inscription_txid,
+            vout: 0,
+          },
+          offset: 0,
+        }),
+      }],
+    );
+
+    context.mine_blocks(1);
+
+    let (_, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (2, 1, 0, Default::default()),
+        (id.block.try_into().unwrap(), 1, 0, Default::default()),
+      ],
+      outputs: 2,
+      fee: 1000,
+      op_return: Some(
+        Runestone {
+          edicts: vec![
+            Edict {
+              id,
+              amount: 100,
+              output: 1,
+            },
+            Edict {
+              id,
+              amount: 50,
+              output: 2,
+            },
+          ],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    let simulation = context
+      .index
+      .simulate(&context.core.mempool()[0], &BTreeMap::new())
+      .unwrap();
+
+    assert!(!simulation.cenotaph);
+    assert_eq!(simulation.fee, 1000);
+    assert_eq!(simulation.outputs.len(), 3);
+
+    context.mine_blocks(1);
+
+    for (vout, output) in simulation.outputs.iter().enumerate() {
+      assert_eq!(
+        output.runes.as_ref().unwrap(),
+        &context
+          .index
+          .get_rune_balances_for_output(OutPoint {
+            txid,
+            vout: vout.try_into().unwrap(),
+          })
+          .unwrap(),
+      );
+    }
+
+    assert_eq!(
+      simulation.outputs[0].runes.as_ref().unwrap()[&SpacedRune::new(Rune(RUNE), 0)].amount,
+      850,
+    );
+
+    let (_, entry, _) = context.index.rune(Rune(RUNE)).unwrap().unwrap();
+
+    assert_eq!(entry.burned, 50);
+
+    assert_eq!(
+      simulation.burned.unwrap()[&entry.spaced_rune].amount,
+      entry.burned,
+    );
+
+    assert_eq!(
+      simulation.inscriptions,
+      [api::SimulatedInscription {
+        id: inscription_id,
+        new: false,
+        satpoint: context
+          .index
+          .get_inscription_satpoint_by_id(inscription_id)
+          .unwrap(),
+      }],
+    );
+  }
+
+  #[test]
+  fn simulate_cenotaph_burns_runes() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let (_, id) = context.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Default::default())],
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 0,
+            output: 5,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    let simulation = context
+      .index
+      .simulate(&context.core.mempool()[0], &BTreeMap::new())
+      .unwrap();
+
+    assert!(simulation.cenotaph);
+    assert_eq!(
+      simulation.burned.unwrap()[&SpacedRune::new(Rune(RUNE), 0)].amount,
+      1000
+    );
+    assert!(simulation.outputs[0].runes.as_ref().unwrap().is_empty());
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.rune(Rune(RUNE)).unwrap().unwrap().1.burned,
+      1000
+    );
+  }
+
+  #[test]
+  fn simulate_requires_known_inputs() {
+    let context = Context::builder().build();
+
+    let outpoint = OutPoint {
+      txid: Txid::all_zeros(),
+      vout: 0,
+    };
+
+    let tx = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint,
+        ..default()
+      }],
+      output: vec![TxOut {
+        value: Amount::from_sat(1000),
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    assert_eq!(
+      context
+        .index
+        .simulate(&tx, &BTreeMap::new())
+        .unwrap_err()
+        .to_string(),
+      format!("input {outpoint} not found"),
+    );
+
+    let simulation = context
+      .index
+      .simulate(
+        &tx,
+        &[(
+          outpoint,
+          TxOut {
+            value: Amount::from_sat(1500),
+            script_pubkey: ScriptBuf::new(),
+          },
+        )]
+        .into(),
+      )
+      .unwrap();
+
+    assert_eq!(simulation.fee, 500);
+    assert_eq!(simulation.burned, None);
+    assert_eq!(simulation.outputs[0].runes, None);
+  }
+
+  #[test]
+  fn hide_and_unhide() {
+    let context = Context::builder().build();
+
+    context.mine_blocks(1);
+
+    let first = InscriptionId {
+      txid: context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    context.mine_blocks(1);
+
+    let second = InscriptionId {
+      txid: context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    context.mine_blocks(1);
+
+    let first_inscription = context.index.get_inscription_by_id(first).unwrap().unwrap();
+    let second_inscription = context
+      .index
+      .get_inscription_by_id(second)
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(context.index.hidden().unwrap(), api::Hidden::default());
+
+    context.index.hide(first, false, "spam").unwrap();
+
+    assert!(context.index.is_hidden(first, &first_inscription).unwrap());
+    assert!(!context
+      .index
+      .is_hidden(second, &second_inscription)
+      .unwrap());
+
+    context.index.hide(first, true, "spam").unwrap();
+
+    assert!(context
+      .index
+      .is_hidden(second, &second_inscription)
+      .unwrap());
+
+    let hidden = context.index.hidden().unwrap();
+    assert_eq!(
+      hidden
+        .inscriptions
+        .keys()
+        .copied()
+        .collect::<Vec<InscriptionId>>(),
+      [first],
+    );
+    assert_eq!(
+      hidden
+        .content
+        .keys()
+        .copied()
+        .collect::<Vec<sha256::Hash>>(),
+      [sha256::Hash::hash(b"hello")],
+    );
+    assert_eq!(hidden.content.values().next().unwrap().reason, "spam");
+
+    context.index.unhide(first).unwrap();
+
+    assert!(!context.index.is_hidden(first, &first_inscription).unwrap());
+    assert!(!context
+      .index
+      .is_hidden(second, &second_inscription)
+      .unwrap());
+    assert_eq!(context.index.hidden().unwrap(), api::Hidden::default());
+
+    assert_eq!(
+      context
+        .index
+        .hide(InscriptionId::default(), true, "spam")
+        .unwrap_err()
+        .to_string(),
+      format!("inscription {} not found", InscriptionId::default()),
+    );
+  }
+
+  #[test]
+  fn hidden_inscriptions_survive_reorg() {
+    let mut context = Context::builder().build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    let id = InscriptionId {
+      txid: context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    context.mine_blocks(6);
+
+    context.index.hide(id, true, "spam").unwrap();
+
+    context.mine_blocks(1);
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    let inscription = context.index.get_inscription_by_id(id).unwrap().unwrap();
+
+    assert!(context.index.is_hidden(id, &inscription).unwrap());
+    assert_eq!(context.index.hidden().unwrap().content.len(), 1);
+  }
+
+  #[test]
+  fn rare_sats_spent_as_fee_counts_inputs_without_sat_ranges() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(1);
+
+    let unknown = OutPoint {
+      txid: txid(1),
+      vout: 0,
+    };
+
+    let tx = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![
+        TxIn {
+          previous_output: unknown,
+          ..default()
+        },
+        TxIn {
+          previous_output: OutPoint {
+            txid: context.core.tx(1, 0).compute_txid(),
+            vout: 0,
+          },
+          ..default()
+        },
+      ],
+      output: vec![TxOut {
+        value: Amount::from_sat(1000),
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    assert_eq!(
+      context
+        .index
+        .rare_sats_spent_as_fee(
+          &tx,
+          &[(
+            unknown,
+            TxOut {
+              value: Amount::from_sat(1000),
+              script_pubkey: ScriptBuf::new(),
+            },
+          )]
+          .into(),
+        )
+        .unwrap(),
+      Some(vec![Sat(50 * COIN_VALUE)]),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .rare_sats_spent_as_fee(&tx, &BTreeMap::new())
+        .unwrap_err()
+        .to_string(),
+      format!("input {unknown} not found"),
+    );
+  }
 }
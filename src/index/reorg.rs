@@ -65,8 +65,16 @@ impl Reorg {
     let oldest_savepoint =
       wtx.get_persistent_savepoint(wtx.list_persistent_savepoints()?.min().unwrap())?;
 
+    // moderation is independent of the chain, so keep it across the rollback
+    let hidden = Index::load_hidden(
+      &wtx.open_table(INSCRIPTION_ID_TO_MODERATION)?,
+      &wtx.open_table(CONTENT_HASH_TO_MODERATION)?,
+    )?;
+
     wtx.restore_savepoint(&oldest_savepoint)?;
 
+    Index::store_hidden(&wtx, &hidden)?;
+
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
@@ -11,6 +11,8 @@ use {
 mod inscription_updater;
 mod rune_updater;
 
+pub(crate) use rune_updater::allocate;
+
 pub(crate) struct BlockData {
   pub(crate) header: Header,
   pub(crate) txdata: Vec<(Transaction, Txid)>,
@@ -17,7 +17,8 @@ type Result<T> = std::result::Result<T, script::Error>;
 type RawEnvelope = Envelope<Vec<Vec<u8>>>;
 pub(crate) type ParsedEnvelope = Envelope<Inscription>;
 
-#[derive(Default, PartialEq, Clone, Serialize, Deserialize, Debug, Eq)]
+#[derive(Default, PartialEq, Clone, Serialize, Deserialize, Debug, Eq, JsonSchema)]
+#[schemars(rename = "Envelope")]
 pub struct Envelope<T> {
   pub input: u32,
   pub offset: u32,
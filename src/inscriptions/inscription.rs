@@ -8,7 +8,8 @@ use {
   std::str,
 };
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default, JsonSchema)]
+#[schemars(inline)]
 pub struct Inscription {
   pub body: Option<Vec<u8>>,
   pub content_encoding: Option<Vec<u8>>,
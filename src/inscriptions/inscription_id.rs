@@ -1,8 +1,19 @@
 use super::*;
 
 #[derive(
-  Debug, PartialEq, Copy, Clone, Hash, Eq, PartialOrd, Ord, DeserializeFromStr, SerializeDisplay,
+  Debug,
+  PartialEq,
+  Copy,
+  Clone,
+  Hash,
+  Eq,
+  PartialOrd,
+  Ord,
+  DeserializeFromStr,
+  SerializeDisplay,
+  JsonSchema,
 )]
+#[schemars(with = "String", extend("pattern" = "^[0-9a-f]{64}i[0-9]+$"), inline)]
 pub struct InscriptionId {
   pub txid: Txid,
   pub index: u32,
@@ -61,6 +61,7 @@ use {
   },
   regex::Regex,
   reqwest::Url,
+  schemars::JsonSchema,
   serde::{Deserialize, Deserializer, Serialize},
   serde_with::{DeserializeFromStr, SerializeDisplay},
   snafu::{Backtrace, ErrorCompat, Snafu},
@@ -91,7 +92,7 @@ use {
 
 pub use self::{
   chain::Chain,
-  fee_rate::FeeRate,
+  fee_rate::{FeeRate, FeeRateArg},
   index::{Index, RuneEntry},
   inscriptions::{Envelope, Inscription, InscriptionId},
   object::Object,
@@ -124,6 +125,7 @@ pub mod outgoing;
 mod re;
 mod representation;
 pub mod runes;
+mod schema;
 pub mod settings;
 pub mod subcommand;
 mod tally;
@@ -145,6 +147,7 @@ fn fund_raw_transaction(
   client: &Client,
   fee_rate: FeeRate,
   unfunded_transaction: &Transaction,
+  change_address: Option<Address>,
 ) -> Result<Vec<u8>> {
   let mut buffer = Vec::new();
 
@@ -166,7 +169,9 @@ fn fund_raw_transaction(
           // and *not* fee per vB. So, we multiply the fee rate given by the user
           // by 1000.
           fee_rate: Some(Amount::from_sat((fee_rate.n() * 1000.0).ceil() as u64)),
+          change_address,
           change_position: Some(unfunded_transaction.output.len().try_into()?),
+          replaceable: Some(true),
           ..default()
         }),
         Some(false),
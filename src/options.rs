@@ -7,6 +7,11 @@ use super::*;
     .args(&["chain_argument", "signet", "regtest", "testnet"]),
 ))]
 pub struct Options {
+  #[arg(
+    long,
+    help = "Serve admin API on `ord server` and authenticate `ord admin` commands with <ADMIN_TOKEN>."
+  )]
+  pub(crate) admin_token: Option<String>,
   #[arg(long, help = "Load Bitcoin Core data dir from <BITCOIN_DATA_DIR>.")]
   pub(crate) bitcoin_data_dir: Option<PathBuf>,
   #[arg(
@@ -66,6 +71,11 @@ pub struct Options {
     help = "Do not index inscriptions."
   )]
   pub(crate) no_index_inscriptions: bool,
+  #[arg(
+    long,
+    help = "Cache up to <RESPONSE_CACHE_SIZE> bytes of `ord server` responses to pages which only change when a new block is indexed."
+  )]
+  pub(crate) response_cache_size: Option<usize>,
   #[arg(
     long,
     help = "Require basic HTTP authentication with <SERVER_PASSWORD>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
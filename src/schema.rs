@@ -0,0 +1,52 @@
+//! JSON schemas for foreign types which appear in the JSON API, for use with
+//! `#[schemars(with = "…")]` on fields of those types.
+
+use super::*;
+
+#[derive(JsonSchema)]
+#[schemars(with = "String", inline)]
+pub(crate) struct Address;
+
+#[derive(JsonSchema)]
+#[schemars(with = "String", extend("format" = "date-time"), inline)]
+pub(crate) struct DateTime;
+
+#[derive(JsonSchema)]
+#[schemars(with = "String", extend("pattern" = "^[0-9a-f]{64}$"), inline)]
+pub(crate) struct Hash;
+
+#[derive(JsonSchema)]
+#[schemars(with = "String", extend("pattern" = "^([0-9a-f]{2})*$"), inline)]
+pub(crate) struct Hex;
+
+#[derive(JsonSchema)]
+#[schemars(with = "String", extend("pattern" = "^[0-9a-f]{64}:[0-9]+$"), inline)]
+pub(crate) struct OutPoint;
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[schemars(rename = "BitcoinTransaction")]
+pub(crate) struct Transaction {
+  version: i32,
+  lock_time: u32,
+  input: Vec<TxIn>,
+  output: Vec<TxOut>,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[schemars(rename = "BitcoinTxIn")]
+struct TxIn {
+  previous_output: OutPoint,
+  script_sig: Hex,
+  sequence: u32,
+  witness: Vec<Hex>,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[schemars(rename = "BitcoinTxOut")]
+struct TxOut {
+  value: u64,
+  script_pubkey: Hex,
+}
@@ -1,8 +1,21 @@
 use {super::*, bitcoincore_rpc::Auth};
 
+/// A named API key for `ord server`, with an optional rate limit, in cost per
+/// minute, and an optional quota, in cost per UTC day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+  pub key: String,
+  pub quota: Option<u64>,
+  pub rate_limit: Option<u64>,
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
 pub struct Settings {
+  admin_token: Option<String>,
+  anonymous_rate_limit: Option<u64>,
+  api_keys: Option<BTreeMap<String, ApiKey>>,
   bitcoin_data_dir: Option<PathBuf>,
   bitcoin_rpc_limit: Option<u32>,
   bitcoin_rpc_password: Option<String>,
@@ -25,6 +38,8 @@ pub struct Settings {
   index_transactions: bool,
   integration_test: bool,
   no_index_inscriptions: bool,
+  response_cache_size: Option<usize>,
+  route_costs: Option<BTreeMap<String, u64>>,
   server_password: Option<String>,
   server_url: Option<String>,
   server_username: Option<String>,
@@ -108,6 +123,9 @@ impl Settings {
 
   pub fn or(self, source: Settings) -> Self {
     Self {
+      admin_token: self.admin_token.or(source.admin_token),
+      anonymous_rate_limit: self.anonymous_rate_limit.or(source.anonymous_rate_limit),
+      api_keys: self.api_keys.or(source.api_keys),
       bitcoin_data_dir: self.bitcoin_data_dir.or(source.bitcoin_data_dir),
       bitcoin_rpc_limit: self.bitcoin_rpc_limit.or(source.bitcoin_rpc_limit),
       bitcoin_rpc_password: self.bitcoin_rpc_password.or(source.bitcoin_rpc_password),
@@ -138,6 +156,8 @@ impl Settings {
       index_transactions: self.index_transactions || source.index_transactions,
       integration_test: self.integration_test || source.integration_test,
       no_index_inscriptions: self.no_index_inscriptions || source.no_index_inscriptions,
+      response_cache_size: self.response_cache_size.or(source.response_cache_size),
+      route_costs: self.route_costs.or(source.route_costs),
       server_password: self.server_password.or(source.server_password),
       server_url: self.server_url.or(source.server_url),
       server_username: self.server_username.or(source.server_username),
@@ -146,6 +166,9 @@ impl Settings {
 
   pub fn from_options(options: Options) -> Self {
     Self {
+      admin_token: options.admin_token,
+      anonymous_rate_limit: None,
+      api_keys: None,
       bitcoin_data_dir: options.bitcoin_data_dir,
       bitcoin_rpc_limit: options.bitcoin_rpc_limit,
       bitcoin_rpc_password: options.bitcoin_rpc_password,
@@ -173,6 +196,8 @@ impl Settings {
       index_transactions: options.index_transactions,
       integration_test: options.integration_test,
       no_index_inscriptions: options.no_index_inscriptions,
+      response_cache_size: options.response_cache_size,
+      route_costs: None,
       server_password: options.server_password,
       server_url: None,
       server_username: options.server_username,
@@ -230,6 +255,14 @@ impl Settings {
         .with_context(|| format!("failed to parse environment variable ORD_{key} as u32"))
     };
 
+    let get_u64 = |key| {
+      env
+        .get(key)
+        .map(|int| int.parse::<u64>())
+        .transpose()
+        .with_context(|| format!("failed to parse environment variable ORD_{key} as u64"))
+    };
+
     let get_usize = |key| {
       env
         .get(key)
@@ -239,6 +272,9 @@ impl Settings {
     };
 
     Ok(Self {
+      admin_token: get_string("ADMIN_TOKEN"),
+      anonymous_rate_limit: get_u64("ANONYMOUS_RATE_LIMIT")?,
+      api_keys: None,
       bitcoin_data_dir: get_path("BITCOIN_DATA_DIR"),
       bitcoin_rpc_limit: get_u32("BITCOIN_RPC_LIMIT")?,
       bitcoin_rpc_password: get_string("BITCOIN_RPC_PASSWORD"),
@@ -261,6 +297,8 @@ impl Settings {
       index_transactions: get_bool("INDEX_TRANSACTIONS"),
       integration_test: get_bool("INTEGRATION_TEST"),
       no_index_inscriptions: get_bool("NO_INDEX_INSCRIPTIONS"),
+      response_cache_size: get_usize("RESPONSE_CACHE_SIZE")?,
+      route_costs: None,
       server_password: get_string("SERVER_PASSWORD"),
       server_url: get_string("SERVER_URL"),
       server_username: get_string("SERVER_USERNAME"),
@@ -269,6 +307,9 @@ impl Settings {
 
   pub fn for_env(dir: &Path, rpc_url: &str, server_url: &str) -> Self {
     Self {
+      admin_token: None,
+      anonymous_rate_limit: None,
+      api_keys: None,
       bitcoin_data_dir: Some(dir.into()),
       bitcoin_rpc_password: None,
       bitcoin_rpc_url: Some(rpc_url.into()),
@@ -291,6 +332,8 @@ impl Settings {
       index_transactions: false,
       integration_test: false,
       no_index_inscriptions: false,
+      response_cache_size: None,
+      route_costs: None,
       server_password: None,
       server_url: Some(server_url.into()),
       server_username: None,
@@ -331,6 +374,9 @@ impl Settings {
     };
 
     Ok(Self {
+      admin_token: self.admin_token,
+      anonymous_rate_limit: self.anonymous_rate_limit,
+      api_keys: self.api_keys,
       bitcoin_data_dir: Some(bitcoin_data_dir),
       bitcoin_rpc_limit: Some(self.bitcoin_rpc_limit.unwrap_or(12)),
       bitcoin_rpc_password: self.bitcoin_rpc_password,
@@ -365,6 +411,8 @@ impl Settings {
       index_transactions: self.index_transactions,
       integration_test: self.integration_test,
       no_index_inscriptions: self.no_index_inscriptions,
+      response_cache_size: self.response_cache_size,
+      route_costs: self.route_costs,
       server_password: self.server_password,
       server_url: self.server_url,
       server_username: self.server_username,
@@ -379,6 +427,22 @@ impl Settings {
     )
   }
 
+  pub fn admin_token(&self) -> Option<&str> {
+    self.admin_token.as_deref()
+  }
+
+  pub fn anonymous_rate_limit(&self) -> Option<u64> {
+    self.anonymous_rate_limit
+  }
+
+  pub fn api_keys(&self) -> impl Iterator<Item = (&str, &ApiKey)> {
+    self
+      .api_keys
+      .iter()
+      .flatten()
+      .map(|(name, api_key)| (name.as_str(), api_key))
+  }
+
   pub fn bitcoin_credentials(&self) -> Result<Auth> {
     if let Some((user, pass)) = &self
       .bitcoin_rpc_username
@@ -573,6 +637,18 @@ impl Settings {
     self.bitcoin_rpc_limit.unwrap()
   }
 
+  pub fn response_cache_size(&self) -> Option<usize> {
+    self.response_cache_size
+  }
+
+  pub fn route_costs(&self) -> impl Iterator<Item = (&str, u64)> {
+    self
+      .route_costs
+      .iter()
+      .flatten()
+      .map(|(route, cost)| (route.as_str(), *cost))
+  }
+
   pub fn server_url(&self) -> Option<&str> {
     self.server_url.as_deref()
   }
@@ -1000,6 +1076,8 @@ mod tests {
   #[test]
   fn from_env() {
     let env = vec![
+      ("ADMIN_TOKEN", "admin token"),
+      ("ANONYMOUS_RATE_LIMIT", "60"),
       ("BITCOIN_DATA_DIR", "/bitcoin/data/dir"),
       ("BITCOIN_RPC_LIMIT", "12"),
       ("BITCOIN_RPC_PASSWORD", "bitcoin password"),
@@ -1022,6 +1100,7 @@ mod tests {
       ("INDEX_TRANSACTIONS", "1"),
       ("INTEGRATION_TEST", "1"),
       ("NO_INDEX_INSCRIPTIONS", "1"),
+      ("RESPONSE_CACHE_SIZE", "5"),
       ("SERVER_PASSWORD", "server password"),
       ("SERVER_URL", "server url"),
       ("SERVER_USERNAME", "server username"),
@@ -1033,6 +1112,9 @@ mod tests {
     pretty_assert_eq!(
       Settings::from_env(env).unwrap(),
       Settings {
+        admin_token: Some("admin token".into()),
+        anonymous_rate_limit: Some(60),
+        api_keys: None,
         bitcoin_data_dir: Some("/bitcoin/data/dir".into()),
         bitcoin_rpc_limit: Some(12),
         bitcoin_rpc_password: Some("bitcoin password".into()),
@@ -1066,6 +1148,8 @@ mod tests {
         index_transactions: true,
         integration_test: true,
         no_index_inscriptions: true,
+        response_cache_size: Some(5),
+        route_costs: None,
         server_password: Some("server password".into()),
         server_url: Some("server url".into()),
         server_username: Some("server username".into()),
@@ -1079,6 +1163,7 @@ mod tests {
       Settings::from_options(
         Options::try_parse_from([
           "ord",
+          "--admin-token=admin token",
           "--bitcoin-data-dir=/bitcoin/data/dir",
           "--bitcoin-rpc-limit=12",
           "--bitcoin-rpc-password=bitcoin password",
@@ -1099,12 +1184,16 @@ mod tests {
           "--index=index",
           "--integration-test",
           "--no-index-inscriptions",
+          "--response-cache-size=5",
           "--server-password=server password",
           "--server-username=server username",
         ])
         .unwrap()
       ),
       Settings {
+        admin_token: Some("admin token".into()),
+        anonymous_rate_limit: None,
+        api_keys: None,
         bitcoin_data_dir: Some("/bitcoin/data/dir".into()),
         bitcoin_rpc_limit: Some(12),
         bitcoin_rpc_password: Some("bitcoin password".into()),
@@ -1127,6 +1216,8 @@ mod tests {
         index_transactions: true,
         integration_test: true,
         no_index_inscriptions: true,
+        response_cache_size: Some(5),
+        route_costs: None,
         server_password: Some("server password".into()),
         server_url: None,
         server_username: Some("server username".into()),
@@ -1,5 +1,6 @@
 use super::*;
 
+pub mod admin;
 pub mod balances;
 pub mod decode;
 pub mod env;
@@ -21,6 +22,8 @@ pub mod wallets;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
+  #[command(about = "Moderate content served by `ord server`")]
+  Admin(admin::Admin),
   #[command(about = "List all rune balances")]
   Balances,
   #[command(about = "Decode a transaction")]
@@ -62,6 +65,7 @@ pub(crate) enum Subcommand {
 impl Subcommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self {
+      Self::Admin(admin) => admin.run(settings),
       Self::Balances => balances::run(settings),
       Self::Decode(decode) => decode.run(settings),
       Self::Env(env) => env.run(),
@@ -72,10 +76,11 @@ impl Subcommand {
       Self::Parse(parse) => parse.run(),
       Self::Runes => runes::run(settings),
       Self::Server(server) => {
-        let index = Arc::new(Index::open(&settings)?);
+        let (event_sender, index_events) = server.index_events().unzip();
+        let index = Arc::new(Index::open_with_event_sender(&settings, event_sender)?);
         let handle = axum_server::Handle::new();
         LISTENERS.lock().unwrap().push(handle.clone());
-        server.run(settings, index, handle)
+        server.run(settings, index, index_events, handle)
       }
       Self::Settings => settings::run(settings),
       Self::Subsidy(subsidy) => subsidy.run(),
@@ -99,6 +104,8 @@ pub enum OutputFormat {
 
 pub trait Output: Send {
   fn print(&self, format: OutputFormat);
+
+  fn json(&self) -> serde_json::Result<serde_json::Value>;
 }
 
 impl<T> Output for T
@@ -113,6 +120,10 @@ where
     };
     println!();
   }
+
+  fn json(&self) -> serde_json::Result<serde_json::Value> {
+    serde_json::to_value(self)
+  }
 }
 
 pub(crate) type SubcommandResult = Result<Option<Box<dyn Output>>>;
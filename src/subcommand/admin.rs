@@ -0,0 +1,87 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Admin {
+  #[arg(
+    long,
+    help = "Use ord running at <SERVER_URL>. [default: http://localhost:80]"
+  )]
+  pub(crate) server_url: Option<Url>,
+  #[command(subcommand)]
+  pub(crate) subcommand: AdminSubcommand,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) enum AdminSubcommand {
+  #[command(about = "Hide inscription content")]
+  Hide(Hide),
+  #[command(about = "List hidden inscriptions and content")]
+  Hidden,
+  #[command(about = "Unhide inscription content")]
+  Unhide(Unhide),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Hide {
+  #[arg(help = "Hide <INSCRIPTION>.")]
+  inscription: InscriptionId,
+  #[arg(
+    long,
+    help = "Also hide all inscriptions with the same content as <INSCRIPTION>."
+  )]
+  content: bool,
+  #[arg(long, help = "Record <REASON> for hiding <INSCRIPTION>.")]
+  reason: String,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Unhide {
+  #[arg(help = "Unhide <INSCRIPTION>, and all inscriptions with the same content.")]
+  inscription: InscriptionId,
+}
+
+impl Admin {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let admin_token = settings
+      .admin_token()
+      .context("no admin token specified, use `--admin-token`")?;
+
+    let url = self
+      .server_url
+      .as_ref()
+      .map(Url::as_str)
+      .or(settings.server_url())
+      .unwrap_or("http://127.0.0.1:80")
+      .parse::<Url>()
+      .context("invalid server URL")?;
+
+    let client = reqwest::blocking::Client::new();
+
+    let request = match self.subcommand {
+      AdminSubcommand::Hide(hide) => client.post(url.join("admin/hide")?).json(&api::Hide {
+        content: hide.content,
+        inscription: hide.inscription,
+        reason: hide.reason,
+      }),
+      AdminSubcommand::Hidden => client.get(url.join("admin/hidden")?),
+      AdminSubcommand::Unhide(unhide) => {
+        client.post(url.join("admin/unhide")?).json(&api::Unhide {
+          inscription: unhide.inscription,
+        })
+      }
+    };
+
+    let request = match settings.credentials() {
+      Some((username, password)) => request.basic_auth(username, Some(password)),
+      None => request,
+    };
+
+    let response = request.header("ord-admin-token", admin_token).send()?;
+
+    if !response.status().is_success() {
+      bail!("admin request failed: {}", response.text()?);
+    }
+
+    Ok(Some(Box::new(response.json::<api::Hidden>()?)))
+  }
+}
@@ -1,4 +1,8 @@
-use super::*;
+use {
+  super::*,
+  base64::{engine::general_purpose, Engine},
+  bitcoin::psbt::Psbt,
+};
 
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
 pub struct CompactOutput {
@@ -6,12 +10,83 @@ pub struct CompactOutput {
   pub runestone: Option<Artifact>,
 }
 
-#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug, JsonSchema)]
+#[schemars(rename = "Decode")]
 pub struct RawOutput {
   pub inscriptions: Vec<ParsedEnvelope>,
   pub runestone: Option<Artifact>,
 }
 
+#[derive(Serialize, PartialEq, Deserialize, Debug, JsonSchema)]
+#[schemars(rename = "DecodeSimulation")]
+pub struct SimulationOutput {
+  pub inscriptions: Vec<ParsedEnvelope>,
+  pub runestone: Option<Artifact>,
+  pub simulation: api::Simulation,
+}
+
+/// A transaction to decode, either a hex-encoded raw transaction, or a
+/// base64-encoded PSBT.
+#[derive(Serialize, PartialEq, Deserialize, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoded {
+  Psbt(String),
+  Transaction(#[schemars(with = "schema::Hex")] String),
+}
+
+impl Encoded {
+  /// The decoded transaction, and the outputs it spends that are included in
+  /// the PSBT. Inputs of PSBTs which have been finalized are given their final
+  /// script sig and witness, and inputs which have not are given a witness
+  /// containing the first of their tapscripts, so that envelopes can be
+  /// parsed.
+  pub(crate) fn decode(&self) -> Result<(Transaction, BTreeMap<OutPoint, TxOut>)> {
+    match self {
+      Self::Psbt(psbt) => {
+        let psbt = Psbt::deserialize(
+          &general_purpose::STANDARD
+            .decode(psbt)
+            .context("failed to decode PSBT")?,
+        )
+        .context("failed to deserialize PSBT")?;
+
+        let mut transaction = psbt.unsigned_tx.clone();
+        let mut prevouts = BTreeMap::new();
+
+        for (txin, input) in transaction.input.iter_mut().zip(&psbt.inputs) {
+          let outpoint = txin.previous_output;
+
+          if let Some(txout) = input.witness_utxo.clone().or_else(|| {
+            input
+              .non_witness_utxo
+              .as_ref()
+              .and_then(|tx| tx.output.get(outpoint.vout.into_usize()).cloned())
+          }) {
+            prevouts.insert(outpoint, txout);
+          }
+
+          if let Some(script_sig) = &input.final_script_sig {
+            txin.script_sig = script_sig.clone();
+          }
+
+          if let Some(witness) = &input.final_script_witness {
+            txin.witness = witness.clone();
+          } else if let Some((control_block, (script, _))) = input.tap_scripts.iter().next() {
+            txin.witness = Witness::from_slice(&[script.to_bytes(), control_block.serialize()]);
+          }
+        }
+
+        Ok((transaction, prevouts))
+      }
+      Self::Transaction(hex) => Ok((
+        consensus::encode::deserialize(&hex::decode(hex).context("failed to decode transaction")?)
+          .context("failed to deserialize transaction")?,
+        BTreeMap::new(),
+      )),
+    }
+  }
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
 pub struct CompactInscription {
@@ -68,10 +143,30 @@ pub(crate) struct Decode {
     help = "Serialize inscriptions in a compact, human-readable format."
   )]
   compact: bool,
+  #[arg(
+    long,
+    conflicts_with_all = ["txid", "file", "compact"],
+    help = "Decode base64-encoded <PSBT> and simulate it against the index."
+  )]
+  psbt: Option<String>,
 }
 
 impl Decode {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    if let Some(psbt) = self.psbt {
+      let (transaction, prevouts) = Encoded::Psbt(psbt).decode()?;
+
+      let index = Index::open(&settings)?;
+
+      index.update()?;
+
+      return Ok(Some(Box::new(SimulationOutput {
+        inscriptions: ParsedEnvelope::from_transaction(&transaction),
+        runestone: Runestone::decipher(&transaction),
+        simulation: index.simulate(&transaction, &prevouts)?,
+      })));
+    }
+
     let transaction = if let Some(txid) = self.txid {
       settings
         .bitcoin_rpc_client(None)?
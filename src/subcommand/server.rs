@@ -2,7 +2,9 @@ use {
   self::{
     accept_encoding::AcceptEncoding,
     accept_json::AcceptJson,
+    cache::ResponseCache,
     error::{OptionExt, ServerError, ServerResult},
+    rate_limit::{RateLimiter, Rejection},
   },
   super::*,
   crate::templates::{
@@ -14,9 +16,12 @@ use {
   },
   axum::{
     body,
-    extract::{DefaultBodyLimit, Extension, Json, Path, Query},
+    extract::{
+      ws, ConnectInfo, DefaultBodyLimit, Extension, Json, MatchedPath, Path, Query, State,
+    },
     http::{header, HeaderValue, StatusCode, Uri},
-    response::{IntoResponse, Redirect, Response},
+    middleware::{self, Next},
+    response::{sse, IntoResponse, Redirect, Response},
     routing::{get, post},
     Router,
   },
@@ -29,10 +34,13 @@ use {
     caches::DirCache,
     AcmeConfig,
   },
-  std::{str, sync::Arc},
+  std::{net::SocketAddr, str, sync::Arc},
   tokio_stream::StreamExt,
   tower_http::{
-    compression::CompressionLayer,
+    compression::{
+      predicate::{DefaultPredicate, NotForContentType, Predicate},
+      CompressionLayer,
+    },
     cors::{Any, CorsLayer},
     set_header::SetResponseHeaderLayer,
     validate_request::ValidateRequestHeaderLayer,
@@ -43,8 +51,14 @@ pub use server_config::ServerConfig;
 
 mod accept_encoding;
 mod accept_json;
+mod cache;
 mod error;
+mod esplora;
+mod events;
+mod graphql;
+mod openapi;
 pub mod query;
+mod rate_limit;
 mod server_config;
 
 enum SpawnConfig {
@@ -53,6 +67,12 @@ enum SpawnConfig {
   Redirect(String),
 }
 
+#[derive(Deserialize)]
+pub(crate) struct BroadcastQuery {
+  #[serde(default)]
+  pub(crate) force: bool,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct OutputsQuery {
   #[serde(rename = "type")]
@@ -102,6 +122,20 @@ pub struct Server {
   pub(crate) decompress: bool,
   #[arg(long, help = "Disable JSON API.")]
   pub(crate) disable_json_api: bool,
+  #[arg(
+    long,
+    help = "Relay transactions posted to `/tx` to Bitcoin Core, unless they would lose inscriptions, burn runes, or spend rare sats as fee. Requires `--index-runes` and `--index-sats` to relay without `force`."
+  )]
+  pub(crate) enable_broadcast: bool,
+  #[arg(long, help = "Serve Esplora-compatible API at `/api`.")]
+  pub(crate) enable_esplora: bool,
+  #[arg(
+    long,
+    help = "Stream block, inscription, and rune events at `/events/stream` and `/events/websocket`."
+  )]
+  pub(crate) enable_events: bool,
+  #[arg(long, help = "Serve GraphQL API at `/graphql`.")]
+  pub(crate) enable_graphql: bool,
   #[arg(
     long,
     help = "Listen on <HTTP_PORT> for incoming HTTP requests. [default: 80]"
@@ -139,11 +173,42 @@ pub struct Server {
 }
 
 impl Server {
-  pub fn run(self, settings: Settings, index: Arc<Index>, handle: Handle) -> SubcommandResult {
+  /// Channel for the index to send events on, if events are enabled.
+  pub fn index_events(
+    &self,
+  ) -> Option<(
+    tokio::sync::mpsc::Sender<crate::index::event::Event>,
+    tokio::sync::mpsc::Receiver<crate::index::event::Event>,
+  )> {
+    self.enable_events.then(|| tokio::sync::mpsc::channel(1024))
+  }
+
+  pub fn run(
+    self,
+    settings: Settings,
+    index: Arc<Index>,
+    index_events: Option<tokio::sync::mpsc::Receiver<crate::index::event::Event>>,
+    handle: Handle,
+  ) -> SubcommandResult {
     Runtime::new()?.block_on(async {
       let index_clone = index.clone();
       let integration_test = settings.integration_test();
 
+      let (event_sender, _) = tokio::sync::broadcast::channel(1024);
+
+      let block_sender = index_events.map(|index_events| {
+        let (block_sender, block_receiver) = tokio::sync::mpsc::unbounded_channel();
+        events::forward(
+          index.clone(),
+          event_sender.clone(),
+          index_events,
+          block_receiver,
+        );
+        block_sender
+      });
+
+      let mut next_block = index.block_count()?;
+
       let index_thread = thread::spawn(move || loop {
         if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
           break;
@@ -155,6 +220,12 @@ impl Server {
           }
         }
 
+        if let Some(block_sender) = &block_sender {
+          if let Err(error) = Self::send_block_events(&index_clone, block_sender, &mut next_block) {
+            log::warn!("Sending block events: {error}");
+          }
+        }
+
         thread::sleep(if integration_test {
           Duration::from_millis(100)
         } else {
@@ -167,126 +238,39 @@ impl Server {
       let settings = Arc::new(settings);
       let acme_domains = self.acme_domains()?;
 
+      let rate_limiter = RateLimiter::new(&settings)?.map(Arc::new);
+
+      let response_cache = settings
+        .response_cache_size()
+        .map(|size| Arc::new(Mutex::new(ResponseCache::new(size))));
+
       let server_config = Arc::new(ServerConfig {
         chain: settings.chain(),
+        broadcast_enabled: self.enable_broadcast,
         csp_origin: self.csp_origin.clone(),
         decompress: self.decompress,
         domain: acme_domains.first().cloned(),
+        esplora_enabled: self.enable_esplora,
+        events_enabled: self.enable_events,
+        graphql_enabled: self.enable_graphql,
         index_sats: index.has_sat_index(),
         json_api_enabled: !self.disable_json_api,
         proxy: self.proxy.clone(),
       });
 
-      let router = Router::new()
-        .route("/", get(Self::home))
-        .route("/address/:address", get(Self::address))
-        .route("/block/:query", get(Self::block))
-        .route("/blockcount", get(Self::block_count))
-        .route("/blockhash", get(Self::block_hash))
-        .route("/blockhash/:height", get(Self::block_hash_from_height))
-        .route("/blockheight", get(Self::block_height))
-        .route("/blocks", get(Self::blocks))
-        .route("/blocktime", get(Self::block_time))
-        .route("/bounties", get(Self::bounties))
-        .route("/children/:inscription_id", get(Self::children))
-        .route(
-          "/children/:inscription_id/:page",
-          get(Self::children_paginated),
-        )
-        .route("/clock", get(Self::clock))
-        .route("/collections", get(Self::collections))
-        .route("/collections/:page", get(Self::collections_paginated))
-        .route("/content/:inscription_id", get(Self::content))
-        .route("/faq", get(Self::faq))
-        .route("/favicon.ico", get(Self::favicon))
-        .route("/feed.xml", get(Self::feed))
-        .route("/input/:block/:transaction/:input", get(Self::input))
-        .route("/inscription/:inscription_query", get(Self::inscription))
-        .route(
-          "/inscription/:inscription_query/:child",
-          get(Self::inscription_child),
-        )
-        .route("/inscriptions", get(Self::inscriptions))
-        .route("/inscriptions", post(Self::inscriptions_json))
-        .route("/inscriptions/:page", get(Self::inscriptions_paginated))
-        .route(
-          "/inscriptions/block/:height",
-          get(Self::inscriptions_in_block),
-        )
-        .route(
-          "/inscriptions/block/:height/:page",
-          get(Self::inscriptions_in_block_paginated),
-        )
-        .route("/install.sh", get(Self::install_script))
-        .route("/ordinal/:sat", get(Self::ordinal))
-        .route("/output/:output", get(Self::output))
-        .route("/outputs", post(Self::outputs))
-        .route("/outputs/:address", get(Self::outputs_address))
-        .route("/parents/:inscription_id", get(Self::parents))
-        .route(
-          "/parents/:inscription_id/:page",
-          get(Self::parents_paginated),
-        )
-        .route("/preview/:inscription_id", get(Self::preview))
-        .route("/r/blockhash", get(Self::block_hash_json))
-        .route(
-          "/r/blockhash/:height",
-          get(Self::block_hash_from_height_json),
-        )
-        .route("/r/blockheight", get(Self::block_height))
-        .route("/r/blocktime", get(Self::block_time))
-        .route("/r/blockinfo/:query", get(Self::block_info))
-        .route(
-          "/r/inscription/:inscription_id",
-          get(Self::inscription_recursive),
-        )
-        .route("/r/children/:inscription_id", get(Self::children_recursive))
-        .route(
-          "/r/children/:inscription_id/:page",
-          get(Self::children_recursive_paginated),
-        )
-        .route(
-          "/r/children/:inscription_id/inscriptions",
-          get(Self::child_inscriptions_recursive),
-        )
-        .route(
-          "/r/children/:inscription_id/inscriptions/:page",
-          get(Self::child_inscriptions_recursive_paginated),
-        )
-        .route(
-          "/r/undelegated-content/:inscription_id",
-          get(Self::undelegated_content),
-        )
-        .route("/r/metadata/:inscription_id", get(Self::metadata))
-        .route("/r/parents/:inscription_id", get(Self::parents_recursive))
-        .route(
-          "/r/parents/:inscription_id/:page",
-          get(Self::parents_recursive_paginated),
-        )
-        .route("/r/sat/:sat_number", get(Self::sat_inscriptions))
-        .route(
-          "/r/sat/:sat_number/:page",
-          get(Self::sat_inscriptions_paginated),
-        )
-        .route(
-          "/r/sat/:sat_number/at/:index",
-          get(Self::sat_inscription_at_index),
-        )
-        .route("/rare.txt", get(Self::rare_txt))
-        .route("/rune/:rune", get(Self::rune))
-        .route("/runes", get(Self::runes))
-        .route("/runes/:page", get(Self::runes_paginated))
-        .route("/sat/:sat", get(Self::sat))
-        .route("/satpoint/:satpoint", get(Self::satpoint))
-        .route("/search", get(Self::search_by_query))
-        .route("/search/*query", get(Self::search_by_path))
-        .route("/static/*path", get(Self::static_asset))
-        .route("/status", get(Self::status))
-        .route("/tx/:txid", get(Self::transaction))
-        .route("/decode/:txid", get(Self::decode))
-        .route("/update", get(Self::update))
+      let router = openapi::router()
         .fallback(Self::fallback)
+        .route_layer(middleware::from_fn_with_state(
+          (index.clone(), response_cache),
+          Self::cache,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+          rate_limiter.clone(),
+          Self::rate_limit,
+        ))
+        .layer(Extension(event_sender))
         .layer(Extension(index))
+        .layer(Extension(rate_limiter))
         .layer(Extension(server_config.clone()))
         .layer(Extension(settings.clone()))
         .layer(SetResponseHeaderLayer::if_not_present(
@@ -302,7 +286,9 @@ impl Server {
             .allow_methods([http::Method::GET])
             .allow_origin(Any),
         )
-        .layer(CompressionLayer::new())
+        .layer(CompressionLayer::new().compress_when(
+          DefaultPredicate::new().and(NotForContentType::const_new("text/event-stream")),
+        ))
         .with_state(server_config.clone());
 
       let router = if server_config.json_api_enabled {
@@ -370,6 +356,34 @@ impl Server {
     })
   }
 
+  /// Send a block event for each block indexed since the last call,
+  /// whichever thread indexed it.
+  fn send_block_events(
+    index: &Index,
+    block_sender: &tokio::sync::mpsc::UnboundedSender<api::Event>,
+    next_block: &mut u32,
+  ) -> Result {
+    let block_count = index.block_count()?;
+
+    // after a reorg, resume from the new tip
+    *next_block = (*next_block).min(block_count);
+
+    while *next_block < block_count {
+      if let Some(hash) = index.block_hash(Some(*next_block))? {
+        block_sender
+          .send(api::Event::Block {
+            hash,
+            height: *next_block,
+          })
+          .ok();
+      }
+
+      *next_block += 1;
+    }
+
+    Ok(())
+  }
+
   fn spawn(
     &self,
     settings: &Settings,
@@ -410,7 +424,7 @@ impl Server {
           axum_server::Server::bind(addr)
             .handle(handle)
             .acceptor(acceptor)
-            .serve(router.into_make_service())
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
             .await
         }
         SpawnConfig::Redirect(destination) => {
@@ -427,7 +441,7 @@ impl Server {
         SpawnConfig::Http => {
           axum_server::Server::bind(addr)
             .handle(handle)
-            .serve(router.into_make_service())
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
             .await
         }
       }
@@ -1080,6 +1094,204 @@ impl Server {
     })
   }
 
+  /// Requests to the admin API must carry the admin token in the
+  /// `Ord-Admin-Token` header, since `Authorization` may already be used for
+  /// basic authentication.
+  fn require_admin(settings: &Settings, headers: &HeaderMap) -> ServerResult<()> {
+    let Some(admin_token) = settings.admin_token() else {
+      return Err(ServerError::NotFound("admin API disabled".into()));
+    };
+
+    let token = headers
+      .get("ord-admin-token")
+      .map(HeaderValue::as_bytes)
+      .unwrap_or_default();
+
+    let mismatch = token.len() != admin_token.len()
+      || token
+        .iter()
+        .zip(admin_token.as_bytes())
+        .fold(0, |acc, (a, b)| acc | (a ^ b))
+        != 0;
+
+    if mismatch {
+      return Err(ServerError::Unauthorized("invalid admin token".into()));
+    }
+
+    Ok(())
+  }
+
+  /// Requests identify themselves with an API key in the `Ord-Api-Key`
+  /// header, and are otherwise limited per IP address.
+  async fn rate_limit<B>(
+    State(rate_limiter): State<Option<Arc<RateLimiter>>>,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    matched_path: MatchedPath,
+    request: http::Request<B>,
+    next: Next<B>,
+  ) -> ServerResult {
+    let Some(rate_limiter) = rate_limiter else {
+      return Ok(next.run(request).await);
+    };
+
+    let key = request
+      .headers()
+      .get("ord-api-key")
+      .map(|key| {
+        key
+          .to_str()
+          .map_err(|_| ServerError::Unauthorized("invalid API key".into()))
+      })
+      .transpose()?;
+
+    let cost = rate_limiter.cost(request.method(), matched_path.as_str());
+
+    let timestamp = SystemTime::now()
+      .duration_since(SystemTime::UNIX_EPOCH)
+      .map_err(|err| anyhow!(err))?
+      .as_secs();
+
+    match rate_limiter.check(key, address.ip(), cost, Instant::now(), timestamp) {
+      Ok(()) => Ok(next.run(request).await),
+      Err(Rejection::InvalidKey) => Err(ServerError::Unauthorized("invalid API key".into())),
+      Err(Rejection::QuotaExceeded { retry_after }) => Err(ServerError::TooManyRequests {
+        message: "quota exceeded".into(),
+        retry_after,
+      }),
+      Err(Rejection::RateLimited { retry_after }) => Err(ServerError::TooManyRequests {
+        message: "rate limit exceeded".into(),
+        retry_after,
+      }),
+    }
+  }
+
+  /// Responses to height-dependent routes are tagged with the hash of the
+  /// indexed tip, so requests for pages which haven't changed since the client
+  /// last saw them can be answered without rendering them again.
+  async fn cache<B>(
+    State((index, response_cache)): State<(Arc<Index>, Option<Arc<Mutex<ResponseCache>>>)>,
+    matched_path: MatchedPath,
+    request: http::Request<B>,
+    next: Next<B>,
+  ) -> ServerResult {
+    let headers = request.headers().clone();
+
+    if request.method() != http::Method::GET || !cache::is_height_dependent(matched_path.as_str()) {
+      let response = next.run(request).await;
+
+      return Ok(match response.headers().get(header::ETAG) {
+        Some(etag)
+          if response.status() == StatusCode::OK && cache::if_none_match(&headers, etag) =>
+        {
+          cache::not_modified(response.headers())
+        }
+        _ => response,
+      });
+    }
+
+    let Some(tip) = task::block_in_place(|| index.tip_header())? else {
+      return Ok(next.run(request).await);
+    };
+
+    let json = headers
+      .get(header::ACCEPT)
+      .map(|value| value == "application/json")
+      .unwrap_or_default();
+
+    let etag = cache::height_etag(&tip, json);
+
+    let mut cache_headers = HeaderMap::new();
+    cache_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    cache_headers.insert(header::ETAG, etag.clone());
+
+    if let Some(last_modified) = cache::last_modified(&tip) {
+      cache_headers.insert(header::LAST_MODIFIED, last_modified);
+    }
+
+    if cache::if_none_match(&headers, &etag) {
+      return Ok(cache::not_modified(&cache_headers));
+    }
+
+    let hash = tip.block_hash();
+    let uri = request.uri().to_string();
+
+    if let Some(response_cache) = &response_cache {
+      if let Some(response) = response_cache.lock().unwrap().get(hash, &uri, json) {
+        return Ok(response);
+      }
+    }
+
+    let response = next.run(request).await;
+
+    if response.status() != StatusCode::OK {
+      return Ok(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    parts.headers.extend(cache_headers);
+
+    let Some(response_cache) = response_cache else {
+      return Ok(Response::from_parts(parts, body));
+    };
+
+    let body = hyper::body::to_bytes(body)
+      .await
+      .map_err(|err| anyhow!(err))?;
+
+    response_cache
+      .lock()
+      .unwrap()
+      .insert(hash, &uri, json, parts.headers.clone(), body.clone());
+
+    Ok(Response::from_parts(
+      parts,
+      body::boxed(body::Full::from(body)),
+    ))
+  }
+
+  async fn admin_hidden(
+    Extension(settings): Extension<Arc<Settings>>,
+    Extension(index): Extension<Arc<Index>>,
+    headers: HeaderMap,
+  ) -> ServerResult<Json<api::Hidden>> {
+    Self::require_admin(&settings, &headers)?;
+
+    task::block_in_place(|| Ok(Json(index.hidden()?)))
+  }
+
+  async fn admin_hide(
+    Extension(settings): Extension<Arc<Settings>>,
+    Extension(index): Extension<Arc<Index>>,
+    headers: HeaderMap,
+    Json(hide): Json<api::Hide>,
+  ) -> ServerResult<Json<api::Hidden>> {
+    Self::require_admin(&settings, &headers)?;
+
+    task::block_in_place(|| {
+      index
+        .hide(hide.inscription, hide.content, &hide.reason)
+        .map_err(|err| ServerError::BadRequest(format!("{err:#}")))?;
+
+      Ok(Json(index.hidden()?))
+    })
+  }
+
+  async fn admin_unhide(
+    Extension(settings): Extension<Arc<Settings>>,
+    Extension(index): Extension<Arc<Index>>,
+    headers: HeaderMap,
+    Json(unhide): Json<api::Unhide>,
+  ) -> ServerResult<Json<api::Hidden>> {
+    Self::require_admin(&settings, &headers)?;
+
+    task::block_in_place(|| {
+      index.unhide(unhide.inscription)?;
+
+      Ok(Json(index.hidden()?))
+    })
+  }
+
   async fn decode(
     Extension(index): Extension<Arc<Index>>,
     Path(txid): Path<Txid>,
@@ -1105,6 +1317,256 @@ impl Server {
     })
   }
 
+  async fn broadcast(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<BroadcastQuery>,
+    Json(encoded): Json<super::decode::Encoded>,
+  ) -> ServerResult {
+    if !server_config.broadcast_enabled {
+      return Err(ServerError::NotFound("broadcast disabled".into()));
+    }
+
+    task::block_in_place(|| {
+      let (transaction, prevouts) = encoded
+        .decode()
+        .map_err(|err| ServerError::BadRequest(format!("{err:#}")))?;
+
+      Ok(
+        match Self::relay(&index, &transaction, &prevouts, query.force)? {
+          Ok(txid) => Json(txid).into_response(),
+          Err(rejection) => (StatusCode::UNPROCESSABLE_ENTITY, Json(rejection)).into_response(),
+        },
+      )
+    })
+  }
+
+  /// Relay `transaction` to Bitcoin Core, unless `force` is false and it
+  /// would lose inscriptions, burn runes, or spend rare sats as fee, in which
+  /// case return what would be lost. Since the assets of outputs the index
+  /// doesn't know about can't be checked, transactions spending them, or
+  /// relayed by a server without inscription, rune, and sat indexes, are
+  /// refused unless `force` is true.
+  fn relay(
+    index: &Index,
+    transaction: &Transaction,
+    prevouts: &BTreeMap<OutPoint, TxOut>,
+    force: bool,
+  ) -> ServerResult<Result<Txid, api::BroadcastRejection>> {
+    if !force {
+      let missing_indexes = [
+        ("inscriptions", index.has_inscription_index()),
+        ("runes", index.has_rune_index()),
+        ("sats", index.has_sat_index()),
+      ]
+      .into_iter()
+      .filter(|(_, indexed)| !indexed)
+      .map(|(name, _)| name.to_string())
+      .collect::<Vec<String>>();
+
+      let mut unindexed_inputs = Vec::new();
+
+      for input in &transaction.input {
+        if !index.contains_output(&input.previous_output)? {
+          unindexed_inputs.push(input.previous_output);
+        }
+      }
+
+      if !missing_indexes.is_empty() || !unindexed_inputs.is_empty() {
+        return Ok(Err(api::BroadcastRejection {
+          missing_indexes,
+          unindexed_inputs,
+          ..default()
+        }));
+      }
+
+      let simulation = index
+        .simulate(transaction, prevouts)
+        .map_err(|err| ServerError::BadRequest(format!("{err:#}")))?;
+
+      let rejection = api::BroadcastRejection {
+        burned: simulation.burned.unwrap_or_default(),
+        cenotaph: simulation.cenotaph,
+        lost_inscriptions: simulation
+          .inscriptions
+          .into_iter()
+          .filter(|inscription| match inscription.satpoint {
+            Some(satpoint) => transaction.output[satpoint.outpoint.vout.into_usize()]
+              .script_pubkey
+              .is_op_return(),
+            None => true,
+          })
+          .map(|inscription| inscription.id)
+          .collect(),
+        rare_sats_as_fee: index
+          .rare_sats_spent_as_fee(transaction, prevouts)?
+          .unwrap_or_default(),
+        ..default()
+      };
+
+      if rejection != api::BroadcastRejection::default() {
+        return Ok(Err(rejection));
+      }
+    }
+
+    Ok(Ok(
+      index
+        .client
+        .send_raw_transaction(transaction)
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?,
+    ))
+  }
+
+  async fn decode_simulate(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+    Json(encoded): Json<super::decode::Encoded>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      let (transaction, prevouts) = encoded
+        .decode()
+        .map_err(|err| ServerError::BadRequest(format!("{err:#}")))?;
+
+      let simulation = index
+        .simulate(&transaction, &prevouts)
+        .map_err(|err| ServerError::BadRequest(format!("{err:#}")))?;
+
+      Ok(
+        Json(api::DecodeSimulation {
+          inscriptions: ParsedEnvelope::from_transaction(&transaction),
+          runestone: Runestone::decipher(&transaction),
+          simulation,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  fn require_esplora(server_config: &ServerConfig) -> ServerResult<()> {
+    if server_config.esplora_enabled {
+      Ok(())
+    } else {
+      Err(ServerError::NotFound("Esplora API disabled".into()))
+    }
+  }
+
+  async fn esplora_block(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<BlockHash>,
+  ) -> ServerResult<Json<esplora::Block>> {
+    Self::require_esplora(&server_config)?;
+
+    task::block_in_place(|| {
+      Ok(Json(
+        esplora::Block::load(&index, hash)?.ok_or_not_found(|| format!("block {hash}"))?,
+      ))
+    })
+  }
+
+  async fn esplora_broadcast(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<BroadcastQuery>,
+    body: String,
+  ) -> ServerResult {
+    Self::require_esplora(&server_config)?;
+
+    if !server_config.broadcast_enabled {
+      return Err(ServerError::NotFound("broadcast disabled".into()));
+    }
+
+    task::block_in_place(|| {
+      let (transaction, prevouts) = super::decode::Encoded::Transaction(body.trim().into())
+        .decode()
+        .map_err(|err| ServerError::BadRequest(format!("{err:#}")))?;
+
+      Ok(
+        match Self::relay(&index, &transaction, &prevouts, query.force)? {
+          Ok(txid) => txid.to_string().into_response(),
+          Err(rejection) => (StatusCode::UNPROCESSABLE_ENTITY, Json(rejection)).into_response(),
+        },
+      )
+    })
+  }
+
+  async fn esplora_tip_height(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<String> {
+    Self::require_esplora(&server_config)?;
+
+    task::block_in_place(|| {
+      Ok(
+        index
+          .block_height()?
+          .ok_or_not_found(|| "blockheight")?
+          .to_string(),
+      )
+    })
+  }
+
+  async fn esplora_transaction(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+  ) -> ServerResult<Json<esplora::Transaction>> {
+    Self::require_esplora(&server_config)?;
+
+    task::block_in_place(|| {
+      Ok(Json(
+        esplora::Transaction::load(&index, server_config.chain, txid)?
+          .ok_or_not_found(|| format!("transaction {txid}"))?,
+      ))
+    })
+  }
+
+  async fn esplora_transaction_hex(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
+  ) -> ServerResult<String> {
+    Self::require_esplora(&server_config)?;
+
+    task::block_in_place(|| {
+      Ok(consensus::encode::serialize_hex(
+        &index
+          .get_transaction(txid)?
+          .ok_or_not_found(|| format!("transaction {txid}"))?,
+      ))
+    })
+  }
+
+  async fn esplora_utxos(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+  ) -> ServerResult<Json<Vec<esplora::Utxo>>> {
+    Self::require_esplora(&server_config)?;
+
+    task::block_in_place(|| {
+      if !index.has_address_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address index".to_string(),
+        ));
+      }
+
+      let address = address
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let mut outputs = index.get_address_info(&address)?;
+
+      outputs.sort();
+
+      Ok(Json(esplora::Utxo::load_all(&index, outputs)?))
+    })
+  }
+
   async fn update(
     Extension(settings): Extension<Arc<Settings>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1140,13 +1602,25 @@ impl Server {
         .metadata
         .ok_or_not_found(|| format!("inscription {inscription_id} metadata"))?;
 
-      Ok(Json(hex::encode(metadata)).into_response())
-    })
-  }
-
-  async fn inscription_recursive(
-    Extension(index): Extension<Arc<Index>>,
-    Extension(server_config): Extension<Arc<ServerConfig>>,
+      Ok(
+        (
+          [
+            (
+              header::CACHE_CONTROL,
+              HeaderValue::from_static("public, max-age=1209600, immutable"),
+            ),
+            (header::ETAG, cache::content_etag(&metadata)),
+          ],
+          Json(hex::encode(metadata)),
+        )
+          .into_response(),
+      )
+    })
+  }
+
+  async fn inscription_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Path(inscription_id): Path<InscriptionId>,
   ) -> ServerResult {
     task::block_in_place(|| {
@@ -1221,16 +1695,18 @@ impl Server {
   async fn status(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
+    Extension(rate_limiter): Extension<Option<Arc<RateLimiter>>>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
+      let mut status = index.status(server_config.json_api_enabled)?;
+
+      status.usage = rate_limiter.map(|rate_limiter| rate_limiter.usage());
+
       Ok(if accept_json {
-        Json(index.status(server_config.json_api_enabled)?).into_response()
+        Json(status).into_response()
       } else {
-        index
-          .status(server_config.json_api_enabled)?
-          .page(server_config)
-          .into_response()
+        status.page(server_config).into_response()
       })
     })
   }
@@ -1360,6 +1836,101 @@ impl Server {
     task::block_in_place(|| Ok(index.block_count()?.to_string()))
   }
 
+  async fn openapi(Extension(server_config): Extension<Arc<ServerConfig>>) -> ServerResult {
+    if !server_config.json_api_enabled {
+      return Err(ServerError::NotFound("JSON API disabled".into()));
+    }
+
+    Ok(Json(openapi::document()).into_response())
+  }
+
+  async fn graphql(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Json(request): Json<graphql::Request>,
+  ) -> ServerResult {
+    if !server_config.graphql_enabled {
+      return Err(ServerError::NotFound("GraphQL API disabled".into()));
+    }
+
+    task::block_in_place(|| {
+      Ok(Json(graphql::execute(&index, server_config.chain, request)).into_response())
+    })
+  }
+
+  async fn graphql_schema(Extension(server_config): Extension<Arc<ServerConfig>>) -> ServerResult {
+    if !server_config.graphql_enabled {
+      return Err(ServerError::NotFound("GraphQL API disabled".into()));
+    }
+
+    Ok(graphql::sdl().into_response())
+  }
+
+  async fn events_stream(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Extension(events): Extension<tokio::sync::broadcast::Sender<events::Notification>>,
+    Query(query): Query<events::EventsQuery>,
+  ) -> ServerResult {
+    let filter = Self::events_filter(&server_config, &index, query)?;
+
+    let subscription = events::Subscription::new(&events, filter);
+
+    let stream = futures::stream::unfold(subscription, |mut subscription| async move {
+      let event = subscription.next().await?;
+      Some((
+        sse::Event::default().json_data(event).map_err(Error::from),
+        subscription,
+      ))
+    });
+
+    Ok(
+      sse::Sse::new(stream)
+        .keep_alive(sse::KeepAlive::default())
+        .into_response(),
+    )
+  }
+
+  async fn events_websocket(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Extension(events): Extension<tokio::sync::broadcast::Sender<events::Notification>>,
+    Query(query): Query<events::EventsQuery>,
+    websocket: Result<ws::WebSocketUpgrade, ws::rejection::WebSocketUpgradeRejection>,
+  ) -> ServerResult {
+    let filter = Self::events_filter(&server_config, &index, query)?;
+
+    let websocket = match websocket {
+      Ok(websocket) => websocket,
+      Err(rejection) => return Ok(rejection.into_response()),
+    };
+
+    let subscription = events::Subscription::new(&events, filter);
+
+    Ok(
+      websocket
+        .max_message_size(64 * 1024)
+        .on_upgrade(|socket| events::websocket(socket, subscription))
+        .into_response(),
+    )
+  }
+
+  fn events_filter(
+    server_config: &ServerConfig,
+    index: &Index,
+    query: events::EventsQuery,
+  ) -> ServerResult<events::Filter> {
+    if !server_config.json_api_enabled {
+      return Err(ServerError::NotFound("JSON API disabled".into()));
+    }
+
+    if !server_config.events_enabled {
+      return Err(ServerError::NotFound("events disabled".into()));
+    }
+
+    task::block_in_place(|| events::Filter::new(query, index, server_config.chain))
+  }
+
   async fn block_height(Extension(index): Extension<Arc<Index>>) -> ServerResult<String> {
     task::block_in_place(|| {
       Ok(
@@ -1583,10 +2154,18 @@ impl Server {
         };
       };
 
+      if index.is_hidden(inscription_id, &inscription)? {
+        return Ok(PreviewUnknownHtml.into_response());
+      }
+
       if let Some(delegate) = inscription.delegate() {
         inscription = index
           .get_inscription_by_id(delegate)?
-          .ok_or_not_found(|| format!("delegate {inscription_id}"))?
+          .ok_or_not_found(|| format!("delegate {inscription_id}"))?;
+
+        if settings.is_hidden(delegate) || index.is_hidden(delegate, &inscription)? {
+          return Ok(PreviewUnknownHtml.into_response());
+        }
       }
 
       Ok(
@@ -1613,6 +2192,10 @@ impl Server {
         .get_inscription_by_id(inscription_id)?
         .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
+      if index.is_hidden(inscription_id, &inscription)? {
+        return Ok(PreviewUnknownHtml.into_response());
+      }
+
       Ok(
         Self::content_response(inscription, accept_encoding, &server_config)?
           .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
@@ -1675,6 +2258,8 @@ impl Server {
           .read_to_end(&mut decompressed)
           .map_err(|err| ServerError::Internal(err.into()))?;
 
+        headers.insert(header::ETAG, cache::content_etag(&decompressed));
+
         return Ok(Some((headers, decompressed)));
       } else {
         return Err(ServerError::NotAcceptable {
@@ -1688,6 +2273,8 @@ impl Server {
       return Ok(None);
     };
 
+    headers.insert(header::ETAG, cache::content_etag(&body));
+
     Ok(Some((headers, body)))
   }
 
@@ -1707,10 +2294,18 @@ impl Server {
         .get_inscription_by_id(inscription_id)?
         .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
+      if index.is_hidden(inscription_id, &inscription)? {
+        return Ok(PreviewUnknownHtml.into_response());
+      }
+
       if let Some(delegate) = inscription.delegate() {
         inscription = index
           .get_inscription_by_id(delegate)?
-          .ok_or_not_found(|| format!("delegate {inscription_id}"))?
+          .ok_or_not_found(|| format!("delegate {inscription_id}"))?;
+
+        if settings.is_hidden(delegate) || index.is_hidden(delegate, &inscription)? {
+          return Ok(PreviewUnknownHtml.into_response());
+        }
       }
 
       let media = inscription.media();
@@ -2266,6 +2861,147 @@ impl Server {
     })
   }
 
+  fn rune_recursive_entry(
+    index: &Index,
+    rune_query: &query::Rune,
+  ) -> Result<Option<(RuneId, RuneEntry, Option<InscriptionId>)>> {
+    if !index.has_rune_index() {
+      return Ok(None);
+    }
+
+    let rune = match rune_query {
+      query::Rune::Spaced(spaced_rune) => Some(spaced_rune.rune),
+      query::Rune::Id(rune_id) => index.get_rune_by_id(*rune_id)?,
+      query::Rune::Number(number) => index.get_rune_by_number(usize::try_from(*number)?)?,
+    };
+
+    match rune {
+      Some(rune) => index.rune(rune),
+      None => Ok(None),
+    }
+  }
+
+  fn rune_recursive_not_found(
+    index: &Index,
+    server_config: &ServerConfig,
+    path: String,
+    not_found: String,
+  ) -> ServerResult {
+    if let Some(proxy) = server_config.proxy.as_ref() {
+      Self::proxy(proxy, &path)
+    } else if !index.has_rune_index() {
+      Err(ServerError::NotFound(
+        "this server has no rune index".to_string(),
+      ))
+    } else {
+      Err(ServerError::NotFound(not_found))
+    }
+  }
+
+  async fn rune_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let Some((id, entry, inscription)) = Self::rune_recursive_entry(&index, &rune_query)? else {
+        return Self::rune_recursive_not_found(
+          &index,
+          &server_config,
+          format!("r/rune/{rune_query}"),
+          format!("rune {rune_query} not found"),
+        );
+      };
+
+      let block_height = index.block_height()?.unwrap_or(Height(0));
+
+      Ok(
+        Json(api::RuneRecursive {
+          mintable: entry.mintable((block_height.n() + 1).into()).is_ok(),
+          supply: entry.supply(),
+          entry,
+          id,
+          inscription,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  async fn rune_balance_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path((DeserializeFromStr(rune_query), outpoint)): Path<(
+      DeserializeFromStr<query::Rune>,
+      OutPoint,
+    )>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let path = format!("r/rune/{rune_query}/balance/{outpoint}");
+
+      let Some((_, entry, _)) = Self::rune_recursive_entry(&index, &rune_query)? else {
+        return Self::rune_recursive_not_found(
+          &index,
+          &server_config,
+          path,
+          format!("rune {rune_query} not found"),
+        );
+      };
+
+      let Some((output_info, _)) = index.get_output_info(outpoint)? else {
+        return Self::rune_recursive_not_found(
+          &index,
+          &server_config,
+          path,
+          format!("output {outpoint} not found"),
+        );
+      };
+
+      Ok(
+        Json(
+          output_info
+            .runes
+            .get(&entry.spaced_rune)
+            .copied()
+            .unwrap_or(Pile {
+              amount: 0,
+              divisibility: entry.divisibility,
+              symbol: entry.symbol,
+            }),
+        )
+        .into_response(),
+      )
+    })
+  }
+
+  async fn utxo_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path(outpoint): Path<OutPoint>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let Some((output_info, _)) = index.get_output_info(outpoint)? else {
+        return if let Some(proxy) = server_config.proxy.as_ref() {
+          Self::proxy(proxy, &format!("r/utxo/{outpoint}"))
+        } else {
+          Err(ServerError::NotFound(format!(
+            "output {outpoint} not found"
+          )))
+        };
+      };
+
+      Ok(
+        Json(api::UtxoRecursive {
+          inscriptions: output_info.inscriptions,
+          runes: index.has_rune_index().then_some(output_info.runes),
+          sat_ranges: output_info.sat_ranges,
+          value: output_info.value,
+        })
+        .into_response(),
+      )
+    })
+  }
+
   async fn redirect_http_to_https(
     Extension(mut destination): Extension<String>,
     uri: Uri,
@@ -2413,13 +3149,18 @@ mod tests {
         .or_defaults()
         .unwrap();
 
-      let index = Arc::new(Index::open(&settings).unwrap());
+      let (event_sender, index_events) = server.index_events().unzip();
+      let index = Arc::new(Index::open_with_event_sender(&settings, event_sender).unwrap());
       let ord_server_handle = Handle::new();
 
       {
         let index = index.clone();
         let ord_server_handle = ord_server_handle.clone();
-        thread::spawn(|| server.run(settings, index, ord_server_handle).unwrap());
+        thread::spawn(|| {
+          server
+            .run(settings, index, index_events, ord_server_handle)
+            .unwrap()
+        });
       }
 
       while index.statistic(crate::index::Statistic::Commits) == 0 {
@@ -5182,6 +5923,151 @@ mod tests {
     );
   }
 
+  #[test]
+  fn content_responses_support_conditional_get() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        Inscription {
+          content_type: Some("text/foo".into()),
+          body: Some("hello".into()),
+          metadata: Some(vec![0xa0]),
+          ..default()
+        }
+        .to_witness(),
+      )],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    let response = server.get(format!("/content/{id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let etag = response.headers()[header::ETAG].clone();
+    assert_eq!(
+      etag,
+      format!("W/\"{}\"", bitcoin::hashes::sha256::Hash::hash(b"hello"))
+    );
+
+    let response = reqwest::blocking::Client::new()
+      .get(server.join_url(&format!("/content/{id}")))
+      .header(header::IF_NONE_MATCH, etag.clone())
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag);
+    assert_eq!(
+      response.headers()[header::CACHE_CONTROL],
+      "public, max-age=1209600, immutable"
+    );
+    assert_eq!(response.text().unwrap(), "");
+
+    let response = server.get(format!("/r/metadata/{id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers()[header::CACHE_CONTROL],
+      "public, max-age=1209600, immutable"
+    );
+    assert_eq!(
+      response.headers()[header::ETAG],
+      format!("W/\"{}\"", bitcoin::hashes::sha256::Hash::hash(&[0xa0])),
+    );
+  }
+
+  #[test]
+  fn height_dependent_responses_are_tagged_with_tip() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    let tip = server.mine_blocks(1)[0].header;
+
+    let response = server.get("/blockcount");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    assert_eq!(
+      response.headers()[header::ETAG],
+      format!("W/\"{}\"", tip.block_hash()),
+    );
+    assert_eq!(
+      response.headers()[header::LAST_MODIFIED],
+      DateTime::from_timestamp(tip.time.into(), 0)
+        .unwrap()
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string(),
+    );
+
+    let etag = response.headers()[header::ETAG].clone();
+
+    let request = |etag: &HeaderValue, json: bool| {
+      reqwest::blocking::Client::new()
+        .get(server.join_url("/blockcount"))
+        .header(header::IF_NONE_MATCH, etag)
+        .header(
+          header::ACCEPT,
+          if json { "application/json" } else { "*/*" },
+        )
+        .send()
+        .unwrap()
+    };
+
+    let response = request(&etag, false);
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag);
+
+    let response = request(&etag, true);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers()[header::ETAG],
+      format!("W/\"{}-json\"", tip.block_hash()),
+    );
+
+    let tip = server.mine_blocks(1)[0].header;
+
+    let response = request(&etag, false);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers()[header::ETAG],
+      format!("W/\"{}\"", tip.block_hash()),
+    );
+    assert_eq!(response.text().unwrap(), "3");
+
+    assert!(server.get("/status").headers().get(header::ETAG).is_none());
+  }
+
+  #[test]
+  fn response_cache() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_option("--response-cache-size", "1000000")
+      .build();
+
+    server.mine_blocks(1);
+
+    for _ in 0..2 {
+      server.assert_response("/blockcount", StatusCode::OK, "2");
+      assert_eq!(server.get_json::<u32>("/blockheight"), 1);
+      server.assert_response_regex("/block/1", StatusCode::OK, ".*<h1>Block 1</h1>.*");
+    }
+
+    let tip = server.mine_blocks(1)[0].header;
+
+    let response = server.get("/blockcount");
+    assert_eq!(
+      response.headers()[header::ETAG],
+      format!("W/\"{}\"", tip.block_hash()),
+    );
+    assert_eq!(response.text().unwrap(), "3");
+  }
+
   #[test]
   fn error_content_responses_have_max_age_zero_cache_control_headers() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
@@ -7253,14 +8139,260 @@ next
   }
 
   #[test]
-  fn update_endpoint_is_not_available_when_not_in_integration_test_mode() {
-    let server = TestServer::builder().build();
-    server.assert_response("/update", StatusCode::NOT_FOUND, "");
+  fn admin_api_is_disabled_without_admin_token() {
+    let server = TestServer::new();
+
+    server.assert_response("/admin/hidden", StatusCode::NOT_FOUND, "admin API disabled");
   }
 
   #[test]
-  fn burned_charm() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
+  fn admin_api_requires_admin_token() {
+    let server = TestServer::builder()
+      .ord_option("--admin-token", "foo")
+      .build();
+
+    server.assert_response(
+      "/admin/hidden",
+      StatusCode::UNAUTHORIZED,
+      "invalid admin token",
+    );
+
+    let response = reqwest::blocking::Client::new()
+      .get(server.join_url("/admin/hidden"))
+      .header("ord-admin-token", "bar")
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/admin/hide"))
+      .json(&api::Hide {
+        content: false,
+        inscription: InscriptionId::default(),
+        reason: "spam".into(),
+      })
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+  }
+
+  #[test]
+  fn inscriptions_can_be_hidden_at_runtime() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_option("--admin-token", "foo")
+      .build();
+
+    server.mine_blocks(1);
+
+    let first = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks(1);
+
+    let second = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0, inscription("text/foo", "hello").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks(1);
+
+    let admin = |path: &str, body: serde_json::Value| {
+      let response = reqwest::blocking::Client::new()
+        .post(server.join_url(path))
+        .header("ord-admin-token", "foo")
+        .json(&body)
+        .send()
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+
+      response.json::<serde_json::Value>().unwrap()
+    };
+
+    server.assert_response(format!("/content/{second}"), StatusCode::OK, "hello");
+
+    let hidden = admin(
+      "/admin/hide",
+      serde_json::json!({
+        "content": true,
+        "inscription": first,
+        "reason": "spam",
+      }),
+    );
+
+    let document = openapi::document();
+
+    openapi::validate(
+      &document,
+      &document["paths"]["/admin/hide"]["post"]["responses"]["200"]["content"]["application/json"]
+        ["schema"],
+      &hidden,
+    )
+    .unwrap();
+
+    assert_eq!(hidden["inscriptions"][first.to_string()]["reason"], "spam");
+
+    for inscription in [first, second] {
+      server.assert_response_regex(format!("/inscription/{inscription}"), StatusCode::OK, ".*");
+
+      server.assert_response(
+        format!("/content/{inscription}"),
+        StatusCode::OK,
+        &PreviewUnknownHtml.to_string(),
+      );
+
+      server.assert_response(
+        format!("/preview/{inscription}"),
+        StatusCode::OK,
+        &PreviewUnknownHtml.to_string(),
+      );
+    }
+
+    let hidden = admin("/admin/unhide", serde_json::json!({ "inscription": first }));
+
+    assert_eq!(
+      hidden,
+      serde_json::json!({ "content": {}, "inscriptions": {} })
+    );
+
+    server.assert_response(format!("/content/{first}"), StatusCode::OK, "hello");
+    server.assert_response(format!("/content/{second}"), StatusCode::OK, "hello");
+  }
+
+  #[test]
+  fn hiding_content_of_missing_inscription_is_bad_request() {
+    let server = TestServer::builder()
+      .ord_option("--admin-token", "foo")
+      .build();
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/admin/hide"))
+      .header("ord-admin-token", "foo")
+      .json(&api::Hide {
+        content: true,
+        inscription: InscriptionId::default(),
+        reason: "spam".into(),
+      })
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      response.text().unwrap(),
+      format!("inscription {} not found", InscriptionId::default()),
+    );
+  }
+
+  #[test]
+  fn anonymous_requests_are_rate_limited() {
+    let server = TestServer::builder()
+      .config("anonymous_rate_limit: 2")
+      .build();
+
+    // the first request was made by the test server to check that it started
+    server.assert_response("/blockcount", StatusCode::OK, "1");
+
+    let response = server.get("/blockcount");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let retry_after = response.headers()[header::RETRY_AFTER]
+      .to_str()
+      .unwrap()
+      .parse::<u64>()
+      .unwrap();
+    assert!((1..=30).contains(&retry_after));
+
+    assert_eq!(response.text().unwrap(), "rate limit exceeded");
+  }
+
+  #[test]
+  fn api_keys_have_quotas_and_usage_is_shown_on_status_page() {
+    let server = TestServer::builder()
+      .config(
+        "
+anonymous_rate_limit: 1
+api_keys:
+  alice:
+    key: foo
+    quota: 15
+",
+      )
+      .build();
+
+    let request = |key: &str| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url("/outputs"))
+        .header(header::ACCEPT, "application/json")
+        .header("ord-api-key", key)
+        .json(&Vec::<OutPoint>::new())
+        .send()
+        .unwrap()
+    };
+
+    let response = request("bar");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.text().unwrap(), "invalid API key");
+
+    assert_eq!(request("foo").status(), StatusCode::OK);
+
+    let response = request("foo");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.text().unwrap(), "quota exceeded");
+
+    let status = |json: bool| {
+      let response = reqwest::blocking::Client::new()
+        .get(server.join_url("/status"))
+        .header(
+          header::ACCEPT,
+          if json { "application/json" } else { "*/*" },
+        )
+        .header("ord-api-key", "foo")
+        .send()
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+
+      response.text().unwrap()
+    };
+
+    pretty_assert_eq!(
+      serde_json::from_str::<api::Status>(&status(true))
+        .unwrap()
+        .usage
+        .unwrap()["alice"],
+      api::Usage {
+        cost: 11,
+        limited: 1,
+        requests: 2,
+      },
+    );
+
+    assert_regex_match!(
+      status(false),
+      r".*<dt>alice usage</dt>\s*<dd>3 requests, 12 cost, 1 limited</dd>.*",
+    );
+  }
+
+  #[test]
+  fn update_endpoint_is_not_available_when_not_in_integration_test_mode() {
+    let server = TestServer::builder().build();
+    server.assert_response("/update", StatusCode::NOT_FOUND, "");
+  }
+
+  #[test]
+  fn burned_charm() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
 
     server.mine_blocks(1);
 
@@ -7420,4 +8552,1115 @@ next
       "output 0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef:123 not found",
     );
   }
+
+  fn read_event(lines: &mut impl Iterator<Item = std::io::Result<String>>) -> api::Event {
+    loop {
+      let line = lines.next().unwrap().unwrap();
+
+      if let Some(data) = line.strip_prefix("data:") {
+        return serde_json::from_str(data.trim()).unwrap();
+      }
+    }
+  }
+
+  #[test]
+  fn events_stream_sends_inscription_and_block_events() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--enable-events")
+      .build();
+
+    server.mine_blocks(1);
+
+    let response = server.get("/events/stream");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/event-stream"
+    );
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    let blocks = server.mine_blocks(1);
+
+    let mut lines = std::io::BufRead::lines(std::io::BufReader::new(response));
+
+    assert_eq!(
+      read_event(&mut lines),
+      api::Event::InscriptionCreated {
+        block_height: 2,
+        charms: Vec::new(),
+        inscription_id: InscriptionId { txid, index: 0 },
+        location: Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        }),
+        parent_inscription_ids: Vec::new(),
+        sequence_number: 0,
+      }
+    );
+
+    assert_eq!(
+      read_event(&mut lines),
+      api::Event::Block {
+        hash: blocks[0].block_hash(),
+        height: 2,
+      }
+    );
+  }
+
+  #[test]
+  fn events_stream_filters_by_rune() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .server_flag("--enable-events")
+      .build();
+
+    let response = server.get(format!("/events/stream?rune={}", Rune(RUNE + 1)));
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let (txid, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE + 1)),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let mut lines = std::io::BufRead::lines(std::io::BufReader::new(response));
+
+    let event = iter::from_fn(|| Some(read_event(&mut lines)))
+      .find(|event| !matches!(event, api::Event::Block { .. }))
+      .unwrap();
+
+    assert_eq!(
+      event,
+      api::Event::RuneEtched {
+        block_height: id.block.try_into().unwrap(),
+        rune_id: id,
+        txid,
+      }
+    );
+  }
+
+  #[test]
+  fn events_require_json_api() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--disable-json-api")
+      .server_flag("--enable-events")
+      .build();
+
+    server.assert_response("/events/stream", StatusCode::NOT_FOUND, "JSON API disabled");
+
+    server.assert_response(
+      "/events/websocket",
+      StatusCode::NOT_FOUND,
+      "JSON API disabled",
+    );
+  }
+
+  #[test]
+  fn events_are_disabled_by_default() {
+    let server = TestServer::new();
+
+    server.assert_response("/events/stream", StatusCode::NOT_FOUND, "events disabled");
+
+    server.assert_response(
+      "/events/websocket",
+      StatusCode::NOT_FOUND,
+      "events disabled",
+    );
+  }
+
+  #[test]
+  fn events_websocket_requires_upgrade() {
+    TestServer::builder()
+      .server_flag("--enable-events")
+      .build()
+      .assert_response(
+        "/events/websocket",
+        StatusCode::BAD_REQUEST,
+        "Connection header did not include 'upgrade'",
+      );
+  }
+
+  #[test]
+  fn events_websocket_requires_version_13() {
+    let server = TestServer::builder().server_flag("--enable-events").build();
+
+    let response = reqwest::blocking::Client::new()
+      .get(server.join_url("/events/websocket"))
+      .header(header::CONNECTION, "upgrade")
+      .header(header::UPGRADE, "websocket")
+      .header(header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ==")
+      .header(header::SEC_WEBSOCKET_VERSION, "8")
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[test]
+  fn json_responses_match_openapi_document() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .index_sats()
+      .ord_flag("--index-addresses")
+      .server_flag("--enable-esplora")
+      .build();
+
+    server.mine_blocks(1);
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          terms: Some(Terms {
+            amount: Some(1),
+            cap: Some(1),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      Some(
+        Inscription {
+          content_type: Some("text/plain".into()),
+          body: Some("hello".into()),
+          rune: Some(Rune(RUNE).commitment()),
+          ..default()
+        }
+        .to_witness(),
+      ),
+    );
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let inscription = server.get_json::<api::Inscription>(format!("/inscription/{inscription_id}"));
+    let sat = inscription.sat.unwrap().n();
+    let output = server.get_json::<api::Output>(format!("/output/{txid}:0"));
+    let address = output.address.unwrap().assume_checked();
+    let height = id.block;
+
+    let document = openapi::document();
+
+    let schema = |path: &str, method: &str| {
+      let schema = document["paths"][path][method]["responses"]["200"]["content"]
+        ["application/json"]["schema"]
+        .clone();
+      assert!(!schema.is_null(), "{method} {path} has no JSON schema");
+      schema
+    };
+
+    let hash = server.get_json::<BlockHash>(format!("/r/blockhash/{height}"));
+
+    let get = [
+      ("/address/{address}", format!("/address/{address}")),
+      (
+        "/api/address/{address}/utxo",
+        format!("/api/address/{address}/utxo"),
+      ),
+      ("/api/block/{hash}", format!("/api/block/{hash}")),
+      ("/api/tx/{txid}", format!("/api/tx/{txid}")),
+      ("/block/{query}", format!("/block/{height}")),
+      ("/blocks", "/blocks".into()),
+      ("/decode/{txid}", format!("/decode/{txid}")),
+      (
+        "/inscription/{inscription_query}",
+        format!("/inscription/{inscription_id}"),
+      ),
+      ("/inscriptions", "/inscriptions".into()),
+      ("/inscriptions/{page}", "/inscriptions/0".into()),
+      (
+        "/inscriptions/block/{height}",
+        format!("/inscriptions/block/{height}"),
+      ),
+      ("/openapi.json", "/openapi.json".into()),
+      ("/output/{output}", format!("/output/{txid}:0")),
+      ("/outputs/{address}", format!("/outputs/{address}")),
+      ("/r/blockhash", "/r/blockhash".into()),
+      ("/r/blockhash/{height}", "/r/blockhash/0".into()),
+      ("/r/blockinfo/{query}", format!("/r/blockinfo/{height}")),
+      (
+        "/r/children/{inscription_id}",
+        format!("/r/children/{inscription_id}"),
+      ),
+      (
+        "/r/children/{inscription_id}/inscriptions",
+        format!("/r/children/{inscription_id}/inscriptions"),
+      ),
+      (
+        "/r/inscription/{inscription_id}",
+        format!("/r/inscription/{inscription_id}"),
+      ),
+      (
+        "/r/parents/{inscription_id}",
+        format!("/r/parents/{inscription_id}"),
+      ),
+      ("/r/rune/{rune}", format!("/r/rune/{id}")),
+      (
+        "/r/rune/{rune}/balance/{outpoint}",
+        format!("/r/rune/{id}/balance/{txid}:0"),
+      ),
+      ("/r/sat/{sat_number}", format!("/r/sat/{sat}")),
+      (
+        "/r/sat/{sat_number}/at/{index}",
+        format!("/r/sat/{sat}/at/0"),
+      ),
+      ("/r/utxo/{outpoint}", format!("/r/utxo/{txid}:0")),
+      ("/rune/{rune}", format!("/rune/{}", Rune(RUNE))),
+      ("/runes", "/runes".into()),
+      ("/runes/{page}", "/runes/0".into()),
+      ("/sat/{sat}", format!("/sat/{sat}")),
+      ("/status", "/status".into()),
+      ("/tx/{txid}", format!("/tx/{txid}")),
+    ];
+
+    for (template, path) in get {
+      let value = server.get_json::<serde_json::Value>(&path);
+
+      if let Err(err) = openapi::validate(&document, &schema(template, "get"), &value) {
+        panic!("GET {path} does not match schema: {err}\n{value:#}");
+      }
+    }
+
+    let post = [
+      (
+        "/decode",
+        serde_json::json!({
+          "transaction": consensus::encode::serialize_hex(
+            &server.core.tx(id.block.try_into().unwrap(), 1)
+          ),
+        }),
+      ),
+      ("/inscriptions", serde_json::json!([inscription_id])),
+      ("/outputs", serde_json::json!([OutPoint { txid, vout: 0 }])),
+    ];
+
+    for (path, body) in post {
+      let response = reqwest::blocking::Client::new()
+        .post(server.join_url(path))
+        .header(header::ACCEPT, "application/json")
+        .json(&body)
+        .send()
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+
+      let value = response.json::<serde_json::Value>().unwrap();
+
+      if let Err(err) = openapi::validate(&document, &schema(path, "post"), &value) {
+        panic!("POST {path} does not match schema: {err}\n{value:#}");
+      }
+    }
+  }
+
+  #[test]
+  fn graphql_is_disabled_by_default() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/graphql"))
+      .json(&serde_json::json!({ "query": "{ inscriptions { nodes { id } } }" }))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.text().unwrap(), "GraphQL API disabled");
+
+    server.assert_response(
+      "/graphql/schema",
+      StatusCode::NOT_FOUND,
+      "GraphQL API disabled",
+    );
+  }
+
+  #[test]
+  fn graphql_resolves_inscription_with_parent_output_and_sat() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .server_flag("--enable-graphql")
+      .build();
+
+    server.mine_blocks(1);
+
+    let parent_txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "parent").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let parent = InscriptionId {
+      txid: parent_txid,
+      index: 0,
+    };
+
+    let child_txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (2, 1, 0, Default::default()),
+        (
+          2,
+          0,
+          0,
+          Inscription {
+            content_type: Some("text/plain".into()),
+            body: Some("child".into()),
+            parents: vec![parent.value()],
+            ..default()
+          }
+          .to_witness(),
+        ),
+      ],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let child = InscriptionId {
+      txid: child_txid,
+      index: 0,
+    };
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/graphql"))
+      .json(&serde_json::json!({
+        "query": "query($id: String!) {
+          inscription(id: $id) {
+            id
+            parents { nodes { id children { nodes { id } } } }
+            output { outpoint }
+            sat { rarity }
+          }
+        }",
+        "variables": { "id": child },
+      }))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let sat = server
+      .get_json::<api::Inscription>(format!("/inscription/{child}"))
+      .sat
+      .unwrap();
+
+    pretty_assert_eq!(
+      response.json::<serde_json::Value>().unwrap(),
+      serde_json::json!({
+        "data": {
+          "inscription": {
+            "id": child,
+            "parents": {
+              "nodes": [{
+                "id": parent,
+                "children": { "nodes": [{ "id": child }] },
+              }],
+            },
+            "output": { "outpoint": format!("{child_txid}:0") },
+            "sat": { "rarity": sat.rarity().to_string() },
+          },
+        },
+      }),
+    );
+
+    server.assert_response_regex("/graphql/schema", StatusCode::OK, "(?s).*type Query \\{.*");
+  }
+
+  #[test]
+  fn graphql_request_size_is_limited() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--enable-graphql")
+      .build();
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/graphql"))
+      .json(&serde_json::json!({
+        "query": format!("{{ {} }}", "blockCount ".repeat(graphql::MAX_REQUEST_SIZE / 10)),
+      }))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+  }
+
+  #[test]
+  fn rune_recursive_endpoints() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          symbol: Some('%'),
+          terms: Some(Terms {
+            amount: Some(10),
+            cap: Some(5),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      Some(
+        Inscription {
+          content_type: Some("text/plain".into()),
+          body: Some("hello".into()),
+          rune: Some(Rune(RUNE).commitment()),
+          ..default()
+        }
+        .to_witness(),
+      ),
+    );
+
+    let rune = server.get_json::<api::RuneRecursive>(format!("/r/rune/{id}"));
+
+    assert_eq!(rune.id, id);
+    assert_eq!(rune.entry.spaced_rune.rune, Rune(RUNE));
+    assert_eq!(rune.entry.mints, 0);
+    assert_eq!(rune.entry.burned, 0);
+    assert_eq!(rune.inscription, Some(InscriptionId { txid, index: 0 }));
+    assert!(rune.mintable);
+    assert_eq!(rune.supply, 1000);
+
+    assert_eq!(
+      server.get_json::<api::RuneRecursive>(format!("/r/rune/{}", Rune(RUNE))),
+      rune,
+    );
+
+    assert_eq!(server.get_json::<api::RuneRecursive>("/r/rune/0"), rune);
+
+    pretty_assert_eq!(
+      server.get_json::<Pile>(format!("/r/rune/{id}/balance/{txid}:0")),
+      Pile {
+        amount: 1000,
+        divisibility: 0,
+        symbol: Some('%'),
+      },
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<Pile>(format!("/r/rune/{id}/balance/{txid}:1")),
+      Pile {
+        amount: 0,
+        divisibility: 0,
+        symbol: Some('%'),
+      },
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::UtxoRecursive>(format!("/r/utxo/{txid}:0")),
+      api::UtxoRecursive {
+        inscriptions: vec![InscriptionId { txid, index: 0 }],
+        runes: Some(
+          [(
+            rune.entry.spaced_rune,
+            Pile {
+              amount: 1000,
+              divisibility: 0,
+              symbol: Some('%'),
+            },
+          )]
+          .into(),
+        ),
+        sat_ranges: None,
+        value: 50 * COIN_VALUE,
+      },
+    );
+
+    server.assert_response(
+      "/r/rune/AAAAA",
+      StatusCode::NOT_FOUND,
+      "rune AAAAA not found",
+    );
+
+    server.assert_response(
+      format!("/r/rune/{id}/balance/{txid}:5"),
+      StatusCode::NOT_FOUND,
+      &format!("output {txid}:5 not found"),
+    );
+  }
+
+  #[test]
+  fn rune_recursive_endpoints_require_rune_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.assert_response(
+      "/r/rune/1:1",
+      StatusCode::NOT_FOUND,
+      "this server has no rune index",
+    );
+
+    let outpoint = OutPoint {
+      txid: server.mine_blocks(1)[0].txdata[0].compute_txid(),
+      vout: 0,
+    };
+
+    assert_eq!(
+      server
+        .get_json::<api::UtxoRecursive>(format!("/r/utxo/{outpoint}"))
+        .runes,
+      None,
+    );
+  }
+
+  #[test]
+  fn rune_recursive_proxy() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let server_with_proxy = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_option("--proxy", server.url.as_ref())
+      .build();
+
+    server_with_proxy.mine_blocks(1);
+
+    assert_eq!(
+      server_with_proxy.get_json::<api::RuneRecursive>(format!("/r/rune/{id}")),
+      server.get_json::<api::RuneRecursive>(format!("/r/rune/{id}")),
+    );
+
+    assert_eq!(
+      server_with_proxy
+        .get_json::<Pile>(format!("/r/rune/{id}/balance/{txid}:0"))
+        .amount,
+      1000,
+    );
+
+    assert_eq!(
+      server_with_proxy.get_json::<api::UtxoRecursive>(format!("/r/utxo/{txid}:0")),
+      server.get_json::<api::UtxoRecursive>(format!("/r/utxo/{txid}:0")),
+    );
+  }
+
+  #[test]
+  fn decode_simulates_raw_transactions_and_psbts() {
+    use {base64::Engine, bitcoin::psbt::Psbt};
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    let tx = server.core.mempool()[0].clone();
+
+    let post = |body: serde_json::Value| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url("/decode"))
+        .header(header::ACCEPT, "application/json")
+        .json(&body)
+        .send()
+        .unwrap()
+    };
+
+    let response = post(serde_json::json!({
+      "transaction": consensus::encode::serialize_hex(&tx),
+    }));
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let decoded = response.json::<api::DecodeSimulation>().unwrap();
+
+    assert_eq!(decoded.inscriptions, ParsedEnvelope::from_transaction(&tx));
+    assert_eq!(decoded.runestone, None);
+    assert_eq!(
+      decoded.simulation.inscriptions,
+      [api::SimulatedInscription {
+        id: InscriptionId { txid, index: 0 },
+        new: true,
+        satpoint: Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        }),
+      }],
+    );
+
+    let mut unsigned_tx = tx.clone();
+    unsigned_tx.input[0].witness = Witness::new();
+
+    let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).unwrap();
+    psbt.inputs[0].final_script_witness = Some(tx.input[0].witness.clone());
+
+    let response = post(serde_json::json!({
+      "psbt": base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+    }));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.json::<api::DecodeSimulation>().unwrap(), decoded);
+
+    let response = post(serde_json::json!({ "transaction": "00" }));
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response
+      .text()
+      .unwrap()
+      .starts_with("failed to deserialize transaction"));
+  }
+
+  #[test]
+  fn broadcast_is_disabled_by_default() {
+    let server = TestServer::new();
+
+    let response = reqwest::blocking::Client::new()
+      .post(server.join_url("/tx"))
+      .json(&serde_json::json!({ "transaction": "00" }))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.text().unwrap(), "broadcast disabled");
+  }
+
+  #[test]
+  fn broadcast_rejects_transactions_that_lose_assets() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .index_sats()
+      .server_flag("--enable-broadcast")
+      .build();
+
+    server.mine_blocks(2);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let burn = |previous_output| Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output,
+        ..default()
+      }],
+      output: vec![TxOut {
+        value: Amount::ZERO,
+        script_pubkey: script::Builder::new()
+          .push_opcode(opcodes::all::OP_RETURN)
+          .into_script(),
+      }],
+    };
+
+    let post = |tx: &Transaction, force: bool| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url(&format!("/tx?force={force}")))
+        .json(&serde_json::json!({
+          "transaction": consensus::encode::serialize_hex(tx),
+        }))
+        .send()
+        .unwrap()
+    };
+
+    let tx = burn(OutPoint { txid, vout: 0 });
+
+    let response = post(&tx, false);
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+      response.json::<api::BroadcastRejection>().unwrap(),
+      api::BroadcastRejection {
+        lost_inscriptions: vec![InscriptionId { txid, index: 0 }],
+        rare_sats_as_fee: vec![Sat(50 * COIN_VALUE)],
+        ..default()
+      },
+    );
+
+    assert!(server.core.mempool().is_empty());
+
+    let response = post(&tx, true);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.json::<Txid>().unwrap(), tx.compute_txid());
+    assert_eq!(server.core.mempool(), [tx]);
+
+    let tx = Transaction {
+      output: vec![TxOut {
+        value: Amount::from_sat(50 * COIN_VALUE),
+        script_pubkey: ScriptBuf::new(),
+      }],
+      ..burn(OutPoint {
+        txid: server.core.tx(2, 0).compute_txid(),
+        vout: 0,
+      })
+    };
+
+    let response = post(&tx, false);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.json::<Txid>().unwrap(), tx.compute_txid());
+  }
+
+  #[test]
+  fn broadcast_rejects_unindexed_inputs_and_missing_indexes() {
+    let post = |server: &TestServer, tx: &Transaction| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url("/tx"))
+        .json(&serde_json::json!({
+          "transaction": consensus::encode::serialize_hex(tx),
+        }))
+        .send()
+        .unwrap()
+    };
+
+    let spend = |previous_output| Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output,
+        ..default()
+      }],
+      output: vec![TxOut {
+        value: Amount::from_sat(1000),
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .server_flag("--enable-broadcast")
+      .build();
+
+    server.mine_blocks(1);
+
+    let response = post(
+      &server,
+      &spend(OutPoint {
+        txid: server.core.tx(1, 0).compute_txid(),
+        vout: 0,
+      }),
+    );
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+      response.json::<api::BroadcastRejection>().unwrap(),
+      api::BroadcastRejection {
+        missing_indexes: vec!["sats".into()],
+        ..default()
+      },
+    );
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .index_sats()
+      .server_flag("--enable-broadcast")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    let unconfirmed = OutPoint { txid, vout: 0 };
+
+    let response = post(&server, &spend(unconfirmed));
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+      response.json::<api::BroadcastRejection>().unwrap(),
+      api::BroadcastRejection {
+        unindexed_inputs: vec![unconfirmed],
+        ..default()
+      },
+    );
+
+    assert_eq!(server.core.mempool().len(), 1);
+  }
+
+  #[test]
+  fn esplora_is_disabled_by_default() {
+    let server = TestServer::new();
+
+    server.assert_response(
+      "/api/blocks/tip/height",
+      StatusCode::NOT_FOUND,
+      "Esplora API disabled",
+    );
+  }
+
+  #[test]
+  fn esplora_endpoints() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-addresses")
+      .server_flag("--enable-esplora")
+      .build();
+
+    server.mine_blocks(1);
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: 1000,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      Some(
+        Inscription {
+          content_type: Some("text/plain".into()),
+          body: Some("hello".into()),
+          rune: Some(Rune(RUNE).commitment()),
+          ..default()
+        }
+        .to_witness(),
+      ),
+    );
+
+    let height = u32::try_from(id.block).unwrap();
+    let hash = server.get_json::<BlockHash>(format!("/r/blockhash/{height}"));
+    let tx = server.core.tx(id.block.try_into().unwrap(), 1);
+
+    server.assert_response(
+      "/api/blocks/tip/height",
+      StatusCode::OK,
+      &height.to_string(),
+    );
+
+    server.assert_response(
+      format!("/api/tx/{txid}/hex"),
+      StatusCode::OK,
+      &consensus::encode::serialize_hex(&tx),
+    );
+
+    let block = server.get_json::<esplora::Block>(format!("/api/block/{hash}"));
+
+    assert_eq!(block.height, height);
+    assert_eq!(block.id, hash);
+    assert_eq!(block.tx_count, 2);
+
+    let status = esplora::Status {
+      block_hash: Some(hash),
+      block_height: Some(height),
+      block_time: Some(block.timestamp),
+      confirmed: true,
+    };
+
+    let transaction = server.get_json::<esplora::Transaction>(format!("/api/tx/{txid}"));
+
+    assert_eq!(transaction.txid, txid);
+    assert_eq!(transaction.status, status);
+    assert_eq!(transaction.vin.len(), 1);
+    assert!(!transaction.vin[0].is_coinbase);
+    assert_eq!(transaction.vin[0].txid, tx.input[0].previous_output.txid);
+    assert_eq!(
+      transaction.fee,
+      transaction.vin[0].prevout.as_ref().unwrap().value
+        - tx
+          .output
+          .iter()
+          .map(|tx_out| tx_out.value.to_sat())
+          .sum::<u64>(),
+    );
+    assert_eq!(transaction.vout.len(), tx.output.len());
+    assert_eq!(transaction.vout[1].scriptpubkey_type, "op_return");
+
+    let address = transaction.vout[0]
+      .scriptpubkey_address
+      .clone()
+      .unwrap()
+      .assume_checked();
+
+    pretty_assert_eq!(
+      server.get_json::<Vec<esplora::Utxo>>(format!("/api/address/{address}/utxo")),
+      [esplora::Utxo {
+        inscriptions: vec![InscriptionId { txid, index: 0 }],
+        runes: [(
+          SpacedRune {
+            rune: Rune(RUNE),
+            spacers: 0,
+          },
+          Pile {
+            amount: 1000,
+            divisibility: 0,
+            symbol: None,
+          },
+        )]
+        .into(),
+        status,
+        txid,
+        value: tx.output[0].value.to_sat(),
+        vout: 0,
+      }],
+    );
+  }
+
+  #[test]
+  fn esplora_broadcast() {
+    let broadcast = |server: &TestServer, tx: &Transaction, force: bool| {
+      reqwest::blocking::Client::new()
+        .post(server.join_url(&format!("/api/tx?force={force}")))
+        .body(consensus::encode::serialize_hex(tx))
+        .send()
+        .unwrap()
+    };
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--enable-esplora")
+      .build();
+
+    server.mine_blocks(1);
+
+    let tx = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: server.core.tx(1, 0).compute_txid(),
+          vout: 0,
+        },
+        ..default()
+      }],
+      output: vec![TxOut {
+        value: Amount::from_sat(50 * COIN_VALUE),
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    let response = broadcast(&server, &tx, false);
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.text().unwrap(), "broadcast disabled");
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .index_sats()
+      .server_flag("--enable-esplora")
+      .server_flag("--enable-broadcast")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let tx = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint { txid, vout: 0 },
+        ..default()
+      }],
+      output: vec![TxOut {
+        value: Amount::ZERO,
+        script_pubkey: script::Builder::new()
+          .push_opcode(opcodes::all::OP_RETURN)
+          .into_script(),
+      }],
+    };
+
+    let response = broadcast(&server, &tx, false);
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+      response.json::<api::BroadcastRejection>().unwrap(),
+      api::BroadcastRejection {
+        lost_inscriptions: vec![InscriptionId { txid, index: 0 }],
+        rare_sats_as_fee: vec![Sat(50 * COIN_VALUE)],
+        ..default()
+      },
+    );
+
+    let response = broadcast(&server, &tx, true);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().unwrap(), tx.compute_txid().to_string());
+    assert_eq!(server.core.mempool(), [tx]);
+  }
 }
@@ -0,0 +1,287 @@
+use {
+  super::*,
+  axum::body::Bytes,
+  bitcoin::{block::Header, hashes::sha256},
+  std::collections::HashMap,
+};
+
+/// Routes whose responses only change when the index advances to a new block,
+/// and so can be tagged and cached by the hash of the indexed tip.
+const HEIGHT_DEPENDENT_ROUTES: &[&str] = &[
+  "/block/:query",
+  "/blockcount",
+  "/blockhash",
+  "/blockhash/:height",
+  "/blockheight",
+  "/blocks",
+  "/blocktime",
+  "/children/:inscription_id",
+  "/children/:inscription_id/:page",
+  "/collections",
+  "/collections/:page",
+  "/inscription/:inscription_query",
+  "/inscription/:inscription_query/:child",
+  "/inscriptions",
+  "/inscriptions/:page",
+  "/inscriptions/block/:height",
+  "/inscriptions/block/:height/:page",
+  "/parents/:inscription_id",
+  "/parents/:inscription_id/:page",
+  "/r/blockhash",
+  "/r/blockhash/:height",
+  "/r/blockheight",
+  "/r/blockinfo/:query",
+  "/r/blocktime",
+  "/r/children/:inscription_id",
+  "/r/children/:inscription_id/:page",
+  "/r/children/:inscription_id/inscriptions",
+  "/r/children/:inscription_id/inscriptions/:page",
+  "/r/inscription/:inscription_id",
+  "/r/parents/:inscription_id",
+  "/r/parents/:inscription_id/:page",
+  "/r/rune/:rune",
+  "/r/rune/:rune/balance/:outpoint",
+  "/r/sat/:sat_number",
+  "/r/sat/:sat_number/:page",
+  "/r/sat/:sat_number/at/:index",
+  "/rune/:rune",
+  "/runes",
+  "/runes/:page",
+  "/sat/:sat",
+];
+
+pub(super) fn is_height_dependent(route: &str) -> bool {
+  HEIGHT_DEPENDENT_ROUTES.contains(&route)
+}
+
+/// Entity tag for a response to a height-dependent route, which differs
+/// between JSON and HTML representations of the same page. Tags are weak, since
+/// the response may be compressed with any content encoding after it is
+/// tagged, and the encoded bodies are not byte-for-byte identical.
+pub(super) fn height_etag(tip: &Header, json: bool) -> HeaderValue {
+  format!(
+    "W/\"{}{}\"",
+    tip.block_hash(),
+    if json { "-json" } else { "" }
+  )
+  .parse()
+  .unwrap()
+}
+
+/// Weak entity tag for content-addressed data, which never changes.
+pub(super) fn content_etag(body: &[u8]) -> HeaderValue {
+  format!("W/\"{}\"", sha256::Hash::hash(body))
+    .parse()
+    .unwrap()
+}
+
+pub(super) fn last_modified(tip: &Header) -> Option<HeaderValue> {
+  DateTime::from_timestamp(tip.time.into(), 0)?
+    .format("%a, %d %b %Y %H:%M:%S GMT")
+    .to_string()
+    .parse()
+    .ok()
+}
+
+/// Whether an `If-None-Match` header matches `etag`, using the weak
+/// comparison required for conditional GET.
+pub(super) fn if_none_match(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+  let Some(if_none_match) = headers
+    .get(header::IF_NONE_MATCH)
+    .and_then(|value| value.to_str().ok())
+  else {
+    return false;
+  };
+
+  let Ok(etag) = etag.to_str() else {
+    return false;
+  };
+
+  let etag = etag.trim_start_matches("W/");
+
+  if_none_match
+    .split(',')
+    .map(str::trim)
+    .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// `304 Not Modified` response, keeping the headers a cache needs to update
+/// its stored response.
+pub(super) fn not_modified(headers: &HeaderMap) -> Response {
+  let mut response = StatusCode::NOT_MODIFIED.into_response();
+
+  for name in [header::CACHE_CONTROL, header::ETAG, header::LAST_MODIFIED] {
+    if let Some(value) = headers.get(&name) {
+      response.headers_mut().insert(name, value.clone());
+    }
+  }
+
+  response
+}
+
+struct Entry {
+  body: Bytes,
+  headers: HeaderMap,
+  used: u64,
+}
+
+/// Least-recently-used cache of responses to height-dependent routes, which
+/// is cleared whenever the index advances to a new tip.
+pub(super) struct ResponseCache {
+  capacity: usize,
+  clock: u64,
+  entries: HashMap<(String, bool), Entry>,
+  size: usize,
+  tip: Option<BlockHash>,
+  used: BTreeMap<u64, (String, bool)>,
+}
+
+impl ResponseCache {
+  pub(super) fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      clock: 0,
+      entries: HashMap::new(),
+      size: 0,
+      tip: None,
+      used: BTreeMap::new(),
+    }
+  }
+
+  fn sync(&mut self, tip: BlockHash) {
+    if self.tip != Some(tip) {
+      self.entries.clear();
+      self.used.clear();
+      self.size = 0;
+      self.tip = Some(tip);
+    }
+  }
+
+  pub(super) fn get(&mut self, tip: BlockHash, uri: &str, json: bool) -> Option<Response> {
+    self.sync(tip);
+
+    let entry = self.entries.get_mut(&(uri.into(), json))?;
+
+    self.clock += 1;
+    self.used.remove(&entry.used);
+    self.used.insert(self.clock, (uri.into(), json));
+    entry.used = self.clock;
+
+    let mut response = body::boxed(body::Full::from(entry.body.clone())).into_response();
+    *response.headers_mut() = entry.headers.clone();
+
+    Some(response)
+  }
+
+  pub(super) fn insert(
+    &mut self,
+    tip: BlockHash,
+    uri: &str,
+    json: bool,
+    headers: HeaderMap,
+    body: Bytes,
+  ) {
+    self.sync(tip);
+
+    let size = uri.len() + body.len();
+
+    if size > self.capacity {
+      return;
+    }
+
+    while self.size + size > self.capacity {
+      let Some((_, key)) = self.used.pop_first() else {
+        break;
+      };
+
+      if let Some(entry) = self.entries.remove(&key) {
+        self.size -= key.0.len() + entry.body.len();
+      }
+    }
+
+    self.clock += 1;
+    self.used.insert(self.clock, (uri.into(), json));
+    self.size += size;
+
+    if let Some(previous) = self.entries.insert(
+      (uri.into(), json),
+      Entry {
+        body,
+        headers,
+        used: self.clock,
+      },
+    ) {
+      self.used.remove(&previous.used);
+      self.size -= uri.len() + previous.body.len();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn headers(if_none_match: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::IF_NONE_MATCH, if_none_match.parse().unwrap());
+    headers
+  }
+
+  #[test]
+  fn if_none_match_uses_weak_comparison() {
+    let etag = HeaderValue::from_static("\"foo\"");
+
+    assert!(if_none_match(&headers("\"foo\""), &etag));
+    assert!(if_none_match(&headers("W/\"foo\""), &etag));
+    assert!(if_none_match(&headers("\"bar\", \"foo\""), &etag));
+    assert!(if_none_match(&headers("*"), &etag));
+    assert!(!if_none_match(&headers("\"bar\""), &etag));
+    assert!(!if_none_match(&HeaderMap::new(), &etag));
+  }
+
+  #[test]
+  fn least_recently_used_responses_are_evicted() {
+    let tip = BlockHash::all_zeros();
+
+    let mut cache = ResponseCache::new(6);
+
+    cache.insert(tip, "/a", false, HeaderMap::new(), "a".into());
+    cache.insert(tip, "/b", false, HeaderMap::new(), "b".into());
+
+    assert!(cache.get(tip, "/a", false).is_some());
+    assert!(cache.get(tip, "/a", true).is_none());
+
+    cache.insert(tip, "/c", false, HeaderMap::new(), "c".into());
+
+    assert!(cache.get(tip, "/a", false).is_some());
+    assert!(cache.get(tip, "/b", false).is_none());
+    assert!(cache.get(tip, "/c", false).is_some());
+
+    cache.insert(tip, "/d", false, HeaderMap::new(), "too large".into());
+
+    assert!(cache.get(tip, "/d", false).is_none());
+    assert_eq!(cache.size, 6);
+  }
+
+  #[test]
+  fn cache_is_cleared_when_tip_changes() {
+    let mut cache = ResponseCache::new(100);
+
+    cache.insert(
+      BlockHash::all_zeros(),
+      "/a",
+      false,
+      HeaderMap::new(),
+      "a".into(),
+    );
+
+    assert!(cache.get(BlockHash::all_zeros(), "/a", false).is_some());
+
+    let tip = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+      .parse()
+      .unwrap();
+
+    assert!(cache.get(tip, "/a", false).is_none());
+    assert_eq!(cache.size, 0);
+  }
+}
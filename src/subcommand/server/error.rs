@@ -9,6 +9,11 @@ pub(super) enum ServerError {
     content_encoding: HeaderValue,
   },
   NotFound(String),
+  TooManyRequests {
+    message: String,
+    retry_after: u64,
+  },
+  Unauthorized(String),
 }
 
 pub(super) type ServerResult<T = Response> = Result<T, ServerError>;
@@ -50,6 +55,16 @@ impl IntoResponse for ServerError {
         message,
       )
         .into_response(),
+      Self::TooManyRequests {
+        message,
+        retry_after,
+      } => (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, HeaderValue::from(retry_after))],
+        message,
+      )
+        .into_response(),
+      Self::Unauthorized(message) => (StatusCode::UNAUTHORIZED, message).into_response(),
     }
   }
 }
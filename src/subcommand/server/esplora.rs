@@ -0,0 +1,289 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "EsploraBlock")]
+pub(crate) struct Block {
+  pub(crate) bits: u32,
+  pub(crate) difficulty: f64,
+  pub(crate) height: u32,
+  #[schemars(with = "schema::Hash")]
+  pub(crate) id: BlockHash,
+  pub(crate) mediantime: Option<u64>,
+  #[schemars(with = "schema::Hash")]
+  pub(crate) merkle_root: TxMerkleNode,
+  pub(crate) nonce: u32,
+  #[schemars(with = "Option<schema::Hash>")]
+  pub(crate) previousblockhash: Option<BlockHash>,
+  pub(crate) size: usize,
+  pub(crate) timestamp: u32,
+  pub(crate) tx_count: usize,
+  pub(crate) version: i32,
+  pub(crate) weight: u64,
+}
+
+impl Block {
+  pub(super) fn load(index: &Index, hash: BlockHash) -> Result<Option<Self>> {
+    let Some(block) = index.get_block_by_hash(hash)? else {
+      return Ok(None);
+    };
+
+    let Some(info) = index.block_header_info(hash)? else {
+      return Ok(None);
+    };
+
+    Ok(Some(Self {
+      bits: block.header.bits.to_consensus(),
+      difficulty: info.difficulty,
+      height: info.height.try_into()?,
+      id: hash,
+      mediantime: info.median_time.map(|time| time.try_into()).transpose()?,
+      merkle_root: block.header.merkle_root,
+      nonce: block.header.nonce,
+      previousblockhash: (block.header.prev_blockhash != BlockHash::all_zeros())
+        .then_some(block.header.prev_blockhash),
+      size: block.total_size(),
+      timestamp: block.header.time,
+      tx_count: block.txdata.len(),
+      version: block.header.version.to_consensus(),
+      weight: block.weight().to_wu(),
+    }))
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "EsploraInput")]
+pub(crate) struct Input {
+  pub(crate) is_coinbase: bool,
+  pub(crate) prevout: Option<Output>,
+  #[schemars(with = "schema::Hex")]
+  pub(crate) scriptsig: ScriptBuf,
+  pub(crate) scriptsig_asm: String,
+  pub(crate) sequence: u32,
+  #[schemars(with = "schema::Hash")]
+  pub(crate) txid: Txid,
+  pub(crate) vout: u32,
+  #[schemars(with = "Vec<schema::Hex>")]
+  pub(crate) witness: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "EsploraOutput")]
+pub(crate) struct Output {
+  #[schemars(with = "schema::Hex")]
+  pub(crate) scriptpubkey: ScriptBuf,
+  #[schemars(with = "Option<schema::Address>")]
+  pub(crate) scriptpubkey_address: Option<Address<NetworkUnchecked>>,
+  pub(crate) scriptpubkey_asm: String,
+  pub(crate) scriptpubkey_type: String,
+  pub(crate) value: u64,
+}
+
+impl Output {
+  fn new(chain: Chain, tx_out: TxOut) -> Self {
+    let script = &tx_out.script_pubkey;
+
+    let scriptpubkey_type = if script.is_p2pk() {
+      "p2pk"
+    } else if script.is_p2pkh() {
+      "p2pkh"
+    } else if script.is_p2sh() {
+      "p2sh"
+    } else if script.is_p2wpkh() {
+      "v0_p2wpkh"
+    } else if script.is_p2wsh() {
+      "v0_p2wsh"
+    } else if script.is_p2tr() {
+      "v1_p2tr"
+    } else if script.is_op_return() {
+      "op_return"
+    } else if script.is_empty() {
+      "empty"
+    } else {
+      "unknown"
+    };
+
+    Self {
+      scriptpubkey_address: chain
+        .address_from_script(script)
+        .ok()
+        .map(|address| uncheck(&address)),
+      scriptpubkey_asm: script.to_asm_string(),
+      scriptpubkey_type: scriptpubkey_type.into(),
+      value: tx_out.value.to_sat(),
+      scriptpubkey: tx_out.script_pubkey,
+    }
+  }
+}
+
+/// Confirmation status of a transaction, with the block it was confirmed in.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "EsploraStatus")]
+pub(crate) struct Status {
+  #[schemars(with = "Option<schema::Hash>")]
+  pub(crate) block_hash: Option<BlockHash>,
+  pub(crate) block_height: Option<u32>,
+  pub(crate) block_time: Option<u32>,
+  pub(crate) confirmed: bool,
+}
+
+impl Status {
+  fn load(index: &Index, txid: Txid) -> Result<Self> {
+    let unconfirmed = Self {
+      block_hash: None,
+      block_height: None,
+      block_time: None,
+      confirmed: false,
+    };
+
+    let Some(hash) = index.get_transaction_blockhash(txid)? else {
+      return Ok(unconfirmed);
+    };
+
+    let (Some(info), Some(header)) = (index.block_header_info(hash)?, index.block_header(hash)?)
+    else {
+      return Ok(unconfirmed);
+    };
+
+    Ok(Self {
+      block_hash: Some(hash),
+      block_height: Some(info.height.try_into()?),
+      block_time: Some(header.time),
+      confirmed: true,
+    })
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "EsploraTransaction")]
+pub(crate) struct Transaction {
+  pub(crate) fee: u64,
+  pub(crate) locktime: u32,
+  pub(crate) size: usize,
+  pub(crate) status: Status,
+  #[schemars(with = "schema::Hash")]
+  pub(crate) txid: Txid,
+  pub(crate) version: i32,
+  pub(crate) vin: Vec<Input>,
+  pub(crate) vout: Vec<Output>,
+  pub(crate) weight: u64,
+}
+
+impl Transaction {
+  pub(super) fn load(index: &Index, chain: Chain, txid: Txid) -> Result<Option<Self>> {
+    let Some(transaction) = index.get_transaction(txid)? else {
+      return Ok(None);
+    };
+
+    let mut input_value = 0;
+
+    let vin = transaction
+      .input
+      .iter()
+      .map(|txin| {
+        let is_coinbase = txin.previous_output.is_null();
+
+        let prevout = if is_coinbase {
+          None
+        } else {
+          let outpoint = txin.previous_output;
+
+          let tx_out = index
+            .get_transaction(outpoint.txid)?
+            .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.into_usize()))
+            .with_context(|| format!("input {outpoint} not found"))?;
+
+          input_value += tx_out.value.to_sat();
+
+          Some(Output::new(chain, tx_out))
+        };
+
+        Ok(Input {
+          is_coinbase,
+          prevout,
+          scriptsig_asm: txin.script_sig.to_asm_string(),
+          scriptsig: txin.script_sig.clone(),
+          sequence: txin.sequence.to_consensus_u32(),
+          txid: txin.previous_output.txid,
+          vout: txin.previous_output.vout,
+          witness: txin.witness.iter().map(hex::encode).collect(),
+        })
+      })
+      .collect::<Result<Vec<Input>>>()?;
+
+    let output_value = transaction
+      .output
+      .iter()
+      .map(|tx_out| tx_out.value.to_sat())
+      .sum::<u64>();
+
+    Ok(Some(Self {
+      fee: if transaction.is_coinbase() {
+        0
+      } else {
+        input_value.saturating_sub(output_value)
+      },
+      locktime: transaction.lock_time.to_consensus_u32(),
+      size: transaction.total_size(),
+      status: Status::load(index, txid)?,
+      txid,
+      version: transaction.version.0,
+      vin,
+      weight: transaction.weight().to_wu(),
+      vout: transaction
+        .output
+        .into_iter()
+        .map(|tx_out| Output::new(chain, tx_out))
+        .collect(),
+    }))
+  }
+}
+
+/// An unspent output, with the inscriptions and rune balances it holds in
+/// addition to the fields returned by Esplora.
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "EsploraUtxo")]
+pub(crate) struct Utxo {
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) runes: BTreeMap<SpacedRune, Pile>,
+  pub(crate) status: Status,
+  #[schemars(with = "schema::Hash")]
+  pub(crate) txid: Txid,
+  pub(crate) value: u64,
+  pub(crate) vout: u32,
+}
+
+impl Utxo {
+  pub(super) fn load_all(index: &Index, outpoints: Vec<OutPoint>) -> Result<Vec<Self>> {
+    let mut statuses = BTreeMap::new();
+
+    outpoints
+      .into_iter()
+      .map(|outpoint| {
+        let status = match statuses.get(&outpoint.txid) {
+          Some(status) => *status,
+          None => {
+            let status = Status::load(index, outpoint.txid)?;
+            statuses.insert(outpoint.txid, status);
+            status
+          }
+        };
+
+        let value = index
+          .get_transaction(outpoint.txid)?
+          .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.into_usize()))
+          .with_context(|| format!("output {outpoint} not found"))?
+          .value
+          .to_sat();
+
+        Ok(Self {
+          inscriptions: index.get_inscriptions_for_output(outpoint)?,
+          runes: index.get_rune_balances_for_output(outpoint)?,
+          status,
+          txid: outpoint.txid,
+          value,
+          vout: outpoint.vout,
+        })
+      })
+      .collect()
+  }
+}
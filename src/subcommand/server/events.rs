@@ -0,0 +1,491 @@
+use {
+  super::*,
+  axum::extract::ws::{Message, WebSocket},
+  std::collections::{HashMap, VecDeque},
+  tokio::sync::{broadcast, mpsc},
+};
+
+/// Index events held back until their block is committed. Beyond this, the
+/// oldest are dropped.
+const MAX_PENDING: usize = 16 * 1024;
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct EventsQuery {
+  address: Option<Address<NetworkUnchecked>>,
+  inscription: Option<InscriptionId>,
+  rune: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum RuneFilter {
+  Id(RuneId),
+  Rune(Rune),
+}
+
+/// An event together with the script pubkey of the output it moved to and the
+/// name of its rune, looked up once when the event is forwarded so that
+/// subscriptions can be filtered without touching the index.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Notification {
+  event: api::Event,
+  rune: Option<Rune>,
+  script_pubkey: Option<ScriptBuf>,
+}
+
+impl Notification {
+  fn new(
+    event: api::Event,
+    index: &Index,
+    transactions: &mut HashMap<Txid, Option<Transaction>>,
+  ) -> Result<Self> {
+    let rune = rune_id(&event)
+      .map(|rune_id| index.get_rune_by_id(rune_id))
+      .transpose()?
+      .flatten();
+
+    let script_pubkey = match outpoint(&event) {
+      Some(outpoint) => {
+        let transaction = match transactions.get(&outpoint.txid) {
+          Some(transaction) => transaction,
+          None => transactions
+            .entry(outpoint.txid)
+            .or_insert(index.get_transaction(outpoint.txid)?),
+        };
+
+        transaction.as_ref().and_then(|transaction| {
+          transaction
+            .output
+            .get(usize::try_from(outpoint.vout).unwrap())
+            .map(|output| output.script_pubkey.clone())
+        })
+      }
+      None => None,
+    };
+
+    Ok(Self {
+      event,
+      rune,
+      script_pubkey,
+    })
+  }
+}
+
+/// Selects inscription and rune events matching all of the address,
+/// inscription, and rune given in the query. Block events always match.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct Filter {
+  address: Option<ScriptBuf>,
+  inscription: Option<InscriptionId>,
+  rune: Option<RuneFilter>,
+}
+
+impl Filter {
+  pub(super) fn new(query: EventsQuery, index: &Index, chain: Chain) -> ServerResult<Self> {
+    let address = query
+      .address
+      .map(|address| {
+        address
+          .require_network(chain.network())
+          .map(|address| address.script_pubkey())
+          .map_err(|err| ServerError::BadRequest(err.to_string()))
+      })
+      .transpose()?;
+
+    let rune = query
+      .rune
+      .map(|rune| -> ServerResult<RuneFilter> {
+        Ok(
+          match rune
+            .parse::<query::Rune>()
+            .map_err(|err| ServerError::BadRequest(err.to_string()))?
+          {
+            query::Rune::Id(id) => RuneFilter::Id(id),
+            query::Rune::Spaced(spaced_rune) => RuneFilter::Rune(spaced_rune.rune),
+            query::Rune::Number(number) => RuneFilter::Rune(
+              index
+                .get_rune_by_number(usize::try_from(number).unwrap())?
+                .ok_or_not_found(|| format!("rune number {number}"))?,
+            ),
+          },
+        )
+      })
+      .transpose()?;
+
+    Ok(Self {
+      address,
+      inscription: query.inscription,
+      rune,
+    })
+  }
+
+  fn matches(&self, notification: &Notification) -> bool {
+    let inscriptions = match &notification.event {
+      api::Event::Block { .. } => return true,
+      api::Event::InscriptionCreated {
+        inscription_id,
+        parent_inscription_ids,
+        ..
+      } => [inscription_id]
+        .into_iter()
+        .chain(parent_inscription_ids)
+        .collect(),
+      api::Event::InscriptionTransferred { inscription_id, .. } => vec![inscription_id],
+      _ => Vec::new(),
+    };
+
+    if let Some(inscription) = self.inscription {
+      if !inscriptions.contains(&&inscription) {
+        return false;
+      }
+    }
+
+    if let Some(rune) = &self.rune {
+      let matches = match rune {
+        RuneFilter::Id(id) => rune_id(&notification.event) == Some(*id),
+        RuneFilter::Rune(rune) => notification.rune == Some(*rune),
+      };
+
+      if !matches {
+        return false;
+      }
+    }
+
+    if let Some(script_pubkey) = &self.address {
+      if notification.script_pubkey.as_ref() != Some(script_pubkey) {
+        return false;
+      }
+    }
+
+    true
+  }
+}
+
+pub(super) struct Subscription {
+  filter: Filter,
+  receiver: broadcast::Receiver<Notification>,
+}
+
+impl Subscription {
+  pub(super) fn new(events: &broadcast::Sender<Notification>, filter: Filter) -> Self {
+    Self {
+      filter,
+      receiver: events.subscribe(),
+    }
+  }
+
+  /// Wait for the next matching event. Events missed because the subscriber
+  /// fell behind are skipped.
+  pub(super) async fn next(&mut self) -> Option<api::Event> {
+    loop {
+      match self.receiver.recv().await {
+        Ok(notification) => {
+          if self.filter.matches(&notification) {
+            return Some(notification.event);
+          }
+        }
+        Err(broadcast::error::RecvError::Lagged(_)) => {}
+        Err(broadcast::error::RecvError::Closed) => return None,
+      }
+    }
+  }
+}
+
+/// Forward index events and block events to subscribers. Index events are
+/// emitted before their block is committed, so they are held back until the
+/// block event for their height, which is only sent once the block is
+/// committed, and forwarded just before it. Index events are dropped while
+/// there are no subscribers.
+pub(super) fn forward(
+  index: Arc<Index>,
+  sender: broadcast::Sender<Notification>,
+  mut index_events: mpsc::Receiver<crate::index::event::Event>,
+  mut block_events: mpsc::UnboundedReceiver<api::Event>,
+) -> task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut pending = VecDeque::<api::Event>::new();
+
+    loop {
+      let block = tokio::select! {
+        biased;
+        Some(event) = index_events.recv() => {
+          if sender.receiver_count() == 0 {
+            pending.clear();
+          } else {
+            if pending.len() == MAX_PENDING {
+              pending.pop_front();
+            }
+
+            pending.push_back(event.into());
+          }
+          continue;
+        }
+        Some(event) = block_events.recv() => event,
+        else => break,
+      };
+
+      let api::Event::Block { height, .. } = block else {
+        continue;
+      };
+
+      let (committed, uncommitted) = pending.drain(..).partition::<Vec<api::Event>, _>(|event| {
+        block_height(event).is_some_and(|block_height| block_height <= height)
+      });
+
+      pending = uncommitted.into();
+
+      if sender.receiver_count() == 0 {
+        continue;
+      }
+
+      let notifications = task::block_in_place(|| -> Result<Vec<Notification>> {
+        let mut transactions = HashMap::new();
+
+        committed
+          .into_iter()
+          .chain([block])
+          .map(|event| Notification::new(event, &index, &mut transactions))
+          .collect()
+      });
+
+      match notifications {
+        // sending fails if all subscribers have gone away
+        Ok(notifications) => {
+          for notification in notifications {
+            sender.send(notification).ok();
+          }
+        }
+        Err(err) => log::warn!("Resolving events for block {height}: {err}"),
+      }
+    }
+  })
+}
+
+fn block_height(event: &api::Event) -> Option<u32> {
+  match event {
+    api::Event::Block { .. } => None,
+    api::Event::InscriptionCreated { block_height, .. }
+    | api::Event::InscriptionTransferred { block_height, .. }
+    | api::Event::RuneBurned { block_height, .. }
+    | api::Event::RuneEtched { block_height, .. }
+    | api::Event::RuneMinted { block_height, .. }
+    | api::Event::RuneTransferred { block_height, .. } => Some(*block_height),
+  }
+}
+
+fn outpoint(event: &api::Event) -> Option<OutPoint> {
+  match event {
+    api::Event::InscriptionCreated { location, .. } => location.map(|location| location.outpoint),
+    api::Event::InscriptionTransferred { new_location, .. } => Some(new_location.outpoint),
+    api::Event::RuneTransferred { outpoint, .. } => Some(*outpoint),
+    api::Event::Block { .. }
+    | api::Event::RuneBurned { .. }
+    | api::Event::RuneEtched { .. }
+    | api::Event::RuneMinted { .. } => None,
+  }
+}
+
+fn rune_id(event: &api::Event) -> Option<RuneId> {
+  match event {
+    api::Event::RuneBurned { rune_id, .. }
+    | api::Event::RuneEtched { rune_id, .. }
+    | api::Event::RuneMinted { rune_id, .. }
+    | api::Event::RuneTransferred { rune_id, .. } => Some(*rune_id),
+    api::Event::Block { .. }
+    | api::Event::InscriptionCreated { .. }
+    | api::Event::InscriptionTransferred { .. } => None,
+  }
+}
+
+/// Send matching events to the client as JSON text messages until either side
+/// closes the connection. Client data messages are ignored.
+pub(super) async fn websocket(mut socket: WebSocket, mut subscription: Subscription) {
+  loop {
+    tokio::select! {
+      message = socket.recv() => match message {
+        Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+        Some(Ok(_)) => {}
+      },
+      event = subscription.next() => {
+        let Some(event) = event else {
+          socket.send(Message::Close(None)).await.ok();
+          break;
+        };
+
+        if socket
+          .send(Message::Text(serde_json::to_string(&event).unwrap()))
+          .await
+          .is_err()
+        {
+          break;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::testing::Context};
+
+  fn notification(event: api::Event) -> Notification {
+    Notification {
+      event,
+      rune: None,
+      script_pubkey: None,
+    }
+  }
+
+  #[test]
+  fn filter_matches_inscription_and_parent() {
+    let filter = Filter {
+      inscription: Some(inscription_id(1)),
+      ..default()
+    };
+
+    let created = |inscription_id, parent_inscription_ids| {
+      notification(api::Event::InscriptionCreated {
+        block_height: 0,
+        charms: Vec::new(),
+        inscription_id,
+        location: None,
+        parent_inscription_ids,
+        sequence_number: 0,
+      })
+    };
+
+    assert!(filter.matches(&created(inscription_id(1), Vec::new())));
+
+    assert!(filter.matches(&created(inscription_id(2), vec![inscription_id(1)])));
+
+    assert!(!filter.matches(&created(inscription_id(2), Vec::new())));
+
+    assert!(filter.matches(&notification(api::Event::Block {
+      hash: BlockHash::all_zeros(),
+      height: 0,
+    })));
+  }
+
+  #[test]
+  fn filter_matches_rune_id() {
+    let filter = Filter {
+      rune: Some(RuneFilter::Id(RuneId { block: 1, tx: 1 })),
+      ..default()
+    };
+
+    let minted = |rune_id| {
+      notification(api::Event::RuneMinted {
+        amount: 1,
+        block_height: 0,
+        rune_id,
+        txid: txid(1),
+      })
+    };
+
+    assert!(filter.matches(&minted(RuneId { block: 1, tx: 1 })));
+
+    assert!(!filter.matches(&minted(RuneId { block: 1, tx: 2 })));
+
+    assert!(
+      !filter.matches(&notification(api::Event::InscriptionTransferred {
+        block_height: 0,
+        inscription_id: inscription_id(1),
+        new_location: SatPoint::default(),
+        old_location: SatPoint::default(),
+        sequence_number: 0,
+      }))
+    );
+  }
+
+  #[test]
+  fn filter_matches_rune_name_and_address() {
+    let filter = Filter {
+      address: Some(ScriptBuf::from_bytes(vec![1])),
+      rune: Some(RuneFilter::Rune(Rune(1))),
+      ..default()
+    };
+
+    let transferred = |rune, script_pubkey| Notification {
+      event: api::Event::RuneTransferred {
+        amount: 1,
+        block_height: 0,
+        outpoint: OutPoint::null(),
+        rune_id: RuneId { block: 1, tx: 1 },
+        txid: txid(1),
+      },
+      rune,
+      script_pubkey,
+    };
+
+    assert!(filter.matches(&transferred(
+      Some(Rune(1)),
+      Some(ScriptBuf::from_bytes(vec![1]))
+    )));
+
+    assert!(!filter.matches(&transferred(
+      Some(Rune(2)),
+      Some(ScriptBuf::from_bytes(vec![1]))
+    )));
+
+    assert!(!filter.matches(&transferred(
+      Some(Rune(1)),
+      Some(ScriptBuf::from_bytes(vec![2]))
+    )));
+
+    assert!(!filter.matches(&transferred(Some(Rune(1)), None)));
+  }
+
+  #[test]
+  fn notification_resolves_script_pubkey() {
+    let context = Context::builder().build();
+
+    let coinbase = context.mine_blocks(1)[0].txdata[0].clone();
+
+    let notification = Notification::new(
+      api::Event::InscriptionTransferred {
+        block_height: 1,
+        inscription_id: inscription_id(1),
+        new_location: SatPoint {
+          outpoint: OutPoint {
+            txid: coinbase.compute_txid(),
+            vout: 0,
+          },
+          offset: 0,
+        },
+        old_location: SatPoint::default(),
+        sequence_number: 0,
+      },
+      &context.index,
+      &mut HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      notification.script_pubkey,
+      Some(coinbase.output[0].script_pubkey.clone())
+    );
+    assert_eq!(notification.rune, None);
+  }
+
+  #[test]
+  fn filter_rejects_address_on_wrong_network() {
+    let context = Context::builder().build();
+
+    let Err(ServerError::BadRequest(message)) = Filter::new(
+      EventsQuery {
+        address: Some(
+          "tb1qsgx55dp6gn53tsmyjjv4c2ye403hgxynxs0dnm"
+            .parse()
+            .unwrap(),
+        ),
+        ..default()
+      },
+      &context.index,
+      Chain::Mainnet,
+    ) else {
+      panic!("expected bad request");
+    };
+
+    assert_eq!(message, "validation error");
+  }
+}
@@ -0,0 +1,810 @@
+use {
+  super::*,
+  base64::{engine::general_purpose, Engine},
+  objects::{Object, Query, Resolved},
+  parser::{Document, Field, InputValue, Operation, Selection},
+  schema::Type,
+  serde_json::{json, Map, Value},
+  std::{fmt::Write, rc::Rc},
+};
+
+mod objects;
+mod parser;
+mod schema;
+
+pub(super) use schema::sdl;
+
+/// Maximum nesting depth of selection sets, counting inline fragments.
+const MAX_DEPTH: usize = 12;
+
+/// Maximum size of a request body posted to `/graphql`.
+pub(super) const MAX_REQUEST_SIZE: usize = 64 * 1024;
+
+/// Maximum estimated number of fields a query may resolve. Fields of
+/// connections count once per requested node, and fields of lists count
+/// `DEFAULT_PAGE_SIZE` times.
+const MAX_COMPLEXITY: u64 = 10_000;
+
+const DEFAULT_PAGE_SIZE: usize = 10;
+
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(rename = "GraphQLRequest")]
+pub(super) struct Request {
+  pub(super) query: String,
+  #[serde(default)]
+  pub(super) operation_name: Option<String>,
+  #[serde(default)]
+  pub(super) variables: Option<Map<String, Value>>,
+}
+
+pub(super) struct Context<'a> {
+  chain: Chain,
+  index: &'a Index,
+}
+
+pub(super) struct Arguments(BTreeMap<String, Value>);
+
+impl Arguments {
+  fn get(&self, name: &str) -> Option<&Value> {
+    self.0.get(name).filter(|value| !value.is_null())
+  }
+
+  fn string(&self, name: &str) -> Option<&str> {
+    self.get(name).and_then(Value::as_str)
+  }
+
+  fn int(&self, name: &str) -> Option<i64> {
+    self.get(name).and_then(Value::as_i64)
+  }
+
+  fn big_int(&self, name: &str) -> Result<Option<u128>> {
+    self
+      .get(name)
+      .map(|value| match value {
+        Value::String(string) => string
+          .parse()
+          .map_err(|_| anyhow!("invalid `{name}` argument: {string}")),
+        value => value
+          .as_u64()
+          .map(u128::from)
+          .ok_or_else(|| anyhow!("invalid `{name}` argument: {value}")),
+      })
+      .transpose()
+  }
+
+  fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>>
+  where
+    T::Err: Display,
+  {
+    self
+      .string(name)
+      .map(|string| {
+        string
+          .parse()
+          .map_err(|err| anyhow!("invalid `{name}` argument: {err}"))
+      })
+      .transpose()
+  }
+
+  fn page(&self) -> Result<Page> {
+    let first = match self.int("first") {
+      Some(first) => usize::try_from(first)
+        .ok()
+        .filter(|first| *first <= MAX_PAGE_SIZE)
+        .ok_or_else(|| anyhow!("`first` must be between 0 and {MAX_PAGE_SIZE}"))?,
+      None => DEFAULT_PAGE_SIZE,
+    };
+
+    let offset = match self.string("after") {
+      Some(cursor) => Page::decode_cursor(cursor)? + 1,
+      None => 0,
+    };
+
+    Ok(Page { first, offset })
+  }
+}
+
+/// A page of a connection, starting at `offset` and containing at most
+/// `first` nodes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Page {
+  first: usize,
+  offset: usize,
+}
+
+impl Page {
+  fn encode_cursor(offset: usize) -> String {
+    general_purpose::STANDARD.encode(format!("cursor:{offset}"))
+  }
+
+  fn decode_cursor(cursor: &str) -> Result<usize> {
+    general_purpose::STANDARD
+      .decode(cursor)
+      .ok()
+      .and_then(|cursor| String::from_utf8(cursor).ok())
+      .and_then(|cursor| cursor.strip_prefix("cursor:")?.parse().ok())
+      .ok_or_else(|| anyhow!("invalid cursor `{cursor}`"))
+  }
+
+  /// Select this page from `items`, which contains all items up to and
+  /// including this page. Returns the selected items and whether more items
+  /// follow.
+  fn select<T>(self, items: impl IntoIterator<Item = T>) -> (Vec<T>, bool) {
+    let mut items = items
+      .into_iter()
+      .skip(self.offset)
+      .take(self.first.saturating_add(1))
+      .collect::<Vec<T>>();
+
+    let more = items.len() > self.first;
+
+    items.truncate(self.first);
+
+    (items, more)
+  }
+}
+
+pub(super) fn execute(index: &Index, chain: Chain, request: Request) -> Value {
+  let document = match parser::parse(&request.query) {
+    Ok(document) => document,
+    Err(err) => return json!({ "errors": [{ "message": err.to_string() }] }),
+  };
+
+  let (operation, variables) = match prepare(&document, &request) {
+    Ok(prepared) => prepared,
+    Err(err) => return json!({ "errors": [{ "message": err.to_string() }] }),
+  };
+
+  let mut executor = Executor {
+    context: Context { chain, index },
+    document: &document,
+    errors: Vec::new(),
+    variables,
+  };
+
+  let data = executor.selection_set(
+    &Query,
+    operation.selection_set.iter().collect(),
+    &mut Vec::new(),
+  );
+
+  if executor.errors.is_empty() {
+    json!({ "data": data })
+  } else {
+    json!({ "data": data, "errors": executor.errors })
+  }
+}
+
+/// Select the operation to execute, collect its variables, and validate it
+/// against the schema and the depth and complexity limits.
+fn prepare<'a>(
+  document: &'a Document,
+  request: &Request,
+) -> Result<(&'a Operation, Map<String, Value>)> {
+  let operation = match &request.operation_name {
+    Some(name) => document
+      .operations
+      .iter()
+      .find(|operation| operation.name.as_ref() == Some(name))
+      .ok_or_else(|| anyhow!("unknown operation `{name}`"))?,
+    None => {
+      ensure!(
+        document.operations.len() == 1,
+        "operation name required for document with multiple operations"
+      );
+      &document.operations[0]
+    }
+  };
+
+  let mut variables = Map::new();
+
+  for definition in &operation.variables {
+    let value = match request
+      .variables
+      .as_ref()
+      .and_then(|variables| variables.get(&definition.name))
+    {
+      Some(value) => value.clone(),
+      None => match &definition.default {
+        Some(default) => input_value(default, &Map::new())?,
+        None => Value::Null,
+      },
+    };
+
+    variables.insert(definition.name.clone(), value);
+  }
+
+  let complexity = Validator {
+    document,
+    fragments: Vec::new(),
+    variables: &variables,
+  }
+  .selection_set(
+    schema::object("Query").unwrap(),
+    &operation.selection_set,
+    1,
+  )?;
+
+  ensure!(
+    complexity <= MAX_COMPLEXITY,
+    "query complexity of {complexity} exceeds maximum of {MAX_COMPLEXITY}"
+  );
+
+  Ok((operation, variables))
+}
+
+fn input_value(value: &InputValue, variables: &Map<String, Value>) -> Result<Value> {
+  Ok(match value {
+    InputValue::Boolean(boolean) => Value::Bool(*boolean),
+    InputValue::Enum(name) => Value::String(name.clone()),
+    InputValue::Float(float) => json!(float),
+    InputValue::Int(int) => json!(int),
+    InputValue::List(list) => Value::Array(
+      list
+        .iter()
+        .map(|value| input_value(value, variables))
+        .collect::<Result<Vec<Value>>>()?,
+    ),
+    InputValue::Null => Value::Null,
+    InputValue::Object(object) => Value::Object(
+      object
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), input_value(value, variables)?)))
+        .collect::<Result<Map<String, Value>>>()?,
+    ),
+    InputValue::String(string) => Value::String(string.clone()),
+    InputValue::Variable(name) => variables
+      .get(name)
+      .cloned()
+      .ok_or_else(|| anyhow!("variable `${name}` is not defined"))?,
+  })
+}
+
+fn arguments(field: &Field, variables: &Map<String, Value>) -> Result<Arguments> {
+  Ok(Arguments(
+    field
+      .arguments
+      .iter()
+      .map(|(name, value)| Ok((name.clone(), input_value(value, variables)?)))
+      .collect::<Result<BTreeMap<String, Value>>>()?,
+  ))
+}
+
+fn argument_matches(ty: &str, value: &Value) -> bool {
+  match (ty.trim_end_matches('!'), value) {
+    (_, Value::Null) => !ty.ends_with('!'),
+    ("BigInt", Value::Number(number)) => number.as_u64().is_some(),
+    ("BigInt", Value::String(string)) => string.parse::<u128>().is_ok(),
+    ("Boolean", Value::Bool(_)) => true,
+    ("Int", Value::Number(number)) => number
+      .as_i64()
+      .is_some_and(|number| i32::try_from(number).is_ok()),
+    ("String", Value::String(_)) => true,
+    _ => false,
+  }
+}
+
+struct Validator<'a> {
+  document: &'a Document,
+  fragments: Vec<&'a str>,
+  variables: &'a Map<String, Value>,
+}
+
+impl<'a> Validator<'a> {
+  /// Validate `selections` on `ty`, returning their complexity. Fragments are
+  /// validated again each time they are spread, so validation stops as soon as
+  /// the complexity of any selection set passes `MAX_COMPLEXITY`, which the
+  /// complexity of the whole query is at least.
+  fn selection_set(&mut self, ty: &Type, selections: &'a [Selection], depth: usize) -> Result<u64> {
+    ensure!(
+      depth <= MAX_DEPTH,
+      "query exceeds maximum depth of {MAX_DEPTH}"
+    );
+
+    let mut complexity = 0u64;
+
+    for selection in selections {
+      complexity = complexity.saturating_add(match selection {
+        Selection::Field(field) => self.field(ty, field, depth)?,
+        Selection::FragmentSpread(name) => {
+          let fragment = self
+            .document
+            .fragments
+            .get(name)
+            .ok_or_else(|| anyhow!("unknown fragment `{name}`"))?;
+
+          ensure!(
+            fragment.type_condition == ty.name,
+            "fragment `{name}` on `{}` cannot be spread on `{}`",
+            fragment.type_condition,
+            ty.name,
+          );
+
+          ensure!(
+            !self.fragments.contains(&name.as_str()),
+            "fragment `{name}` spreads itself"
+          );
+
+          self.fragments.push(name);
+          let complexity = self.selection_set(ty, &fragment.selection_set, depth)?;
+          self.fragments.pop();
+          complexity
+        }
+        Selection::InlineFragment {
+          selection_set,
+          type_condition,
+        } => {
+          if let Some(type_condition) = type_condition {
+            ensure!(
+              type_condition == ty.name,
+              "fragment on `{type_condition}` cannot be spread on `{}`",
+              ty.name,
+            );
+          }
+
+          self.selection_set(ty, selection_set, depth + 1)?
+        }
+      });
+
+      ensure!(
+        complexity <= MAX_COMPLEXITY,
+        "query complexity of {complexity} exceeds maximum of {MAX_COMPLEXITY}"
+      );
+    }
+
+    Ok(complexity)
+  }
+
+  fn field(&mut self, ty: &Type, field: &'a Field, depth: usize) -> Result<u64> {
+    if field.name == "__typename" {
+      ensure!(
+        field.arguments.is_empty() && field.selection_set.is_empty(),
+        "field `__typename` takes no arguments or selections"
+      );
+      return Ok(1);
+    }
+
+    let definition = ty
+      .fields
+      .iter()
+      .find(|definition| definition.name == field.name)
+      .ok_or_else(|| anyhow!("cannot query field `{}` on type `{}`", field.name, ty.name))?;
+
+    let arguments = arguments(field, self.variables)?;
+
+    for (name, _) in &field.arguments {
+      ensure!(
+        definition
+          .arguments
+          .iter()
+          .any(|(argument, _)| argument == name),
+        "unknown argument `{name}` on field `{}.{}`",
+        ty.name,
+        field.name,
+      );
+    }
+
+    for (name, argument_ty) in definition.arguments {
+      ensure!(
+        argument_matches(argument_ty, arguments.0.get(*name).unwrap_or(&Value::Null)),
+        "argument `{name}` of field `{}.{}` must be of type `{argument_ty}`",
+        ty.name,
+        field.name,
+      );
+    }
+
+    let Some(object) = schema::object(definition.named_type()) else {
+      ensure!(
+        field.selection_set.is_empty(),
+        "field `{}` of type `{}` must not have a selection",
+        field.name,
+        definition.ty,
+      );
+      return Ok(1);
+    };
+
+    ensure!(
+      !field.selection_set.is_empty(),
+      "field `{}` of type `{}` must have a selection of subfields",
+      field.name,
+      definition.ty,
+    );
+
+    // the nodes of a connection are already counted by the connection field,
+    // and selections under `first: 0` still count once, so that the complexity
+    // of a selection set bounds the work of validating it
+    let multiplier = if definition.is_connection() {
+      arguments.page()?.first.max(1)
+    } else if definition.is_list() && !ty.name.ends_with("Connection") {
+      DEFAULT_PAGE_SIZE
+    } else {
+      1
+    };
+
+    let complexity = self.selection_set(object, &field.selection_set, depth + 1)?;
+
+    Ok(
+      u64::try_from(multiplier)
+        .unwrap()
+        .saturating_mul(complexity)
+        .saturating_add(1),
+    )
+  }
+}
+
+struct Executor<'a> {
+  context: Context<'a>,
+  document: &'a Document,
+  errors: Vec<Value>,
+  variables: Map<String, Value>,
+}
+
+impl<'a> Executor<'a> {
+  /// Group fields by response key, merging the selections of fields with the
+  /// same key.
+  fn collect(&self, selections: &'a [Selection], fields: &mut Vec<(&'a str, Vec<&'a Field>)>) {
+    for selection in selections {
+      match selection {
+        Selection::Field(field) => {
+          match fields
+            .iter_mut()
+            .find(|(key, _)| *key == field.response_key())
+          {
+            Some((_, group)) => group.push(field),
+            None => fields.push((field.response_key(), vec![field])),
+          }
+        }
+        Selection::FragmentSpread(name) => {
+          self.collect(&self.document.fragments[name].selection_set, fields);
+        }
+        Selection::InlineFragment { selection_set, .. } => self.collect(selection_set, fields),
+      }
+    }
+  }
+
+  fn selection_set(
+    &mut self,
+    object: &dyn Object,
+    selections: Vec<&'a Selection>,
+    path: &mut Vec<Value>,
+  ) -> Value {
+    let mut fields = Vec::new();
+
+    for selection in selections {
+      self.collect(std::slice::from_ref(selection), &mut fields);
+    }
+
+    let mut map = Map::new();
+
+    for (key, group) in fields {
+      path.push(key.into());
+
+      let field = group[0];
+
+      let value = if field.name == "__typename" {
+        object.type_name().into()
+      } else {
+        match arguments(field, &self.variables)
+          .and_then(|arguments| object.resolve(&self.context, &field.name, &arguments))
+        {
+          Ok(resolved) => self.complete(resolved, &group, path),
+          Err(err) => {
+            self
+              .errors
+              .push(json!({ "message": err.to_string(), "path": path }));
+            Value::Null
+          }
+        }
+      };
+
+      path.pop();
+
+      map.insert(key.into(), value);
+    }
+
+    Value::Object(map)
+  }
+
+  fn complete(&mut self, resolved: Resolved, fields: &[&'a Field], path: &mut Vec<Value>) -> Value {
+    match resolved {
+      Resolved::List(items) => Value::Array(
+        items
+          .into_iter()
+          .enumerate()
+          .map(|(i, item)| {
+            path.push(i.into());
+            let value = self.complete(item, fields, path);
+            path.pop();
+            value
+          })
+          .collect(),
+      ),
+      Resolved::Object(object) => self.selection_set(
+        &*object,
+        fields
+          .iter()
+          .flat_map(|field| &field.selection_set)
+          .collect(),
+        path,
+      ),
+      Resolved::Value(value) => value,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::testing::Context as IndexContext};
+
+  const RUNE: u128 = 99246114928149462;
+
+  fn request(query: &str) -> Request {
+    Request {
+      query: query.into(),
+      operation_name: None,
+      variables: None,
+    }
+  }
+
+  #[track_caller]
+  fn error(query: &str, expected: &str) {
+    let context = IndexContext::builder().build();
+
+    assert_eq!(
+      execute(&context.index, Chain::Regtest, request(query)),
+      json!({ "errors": [{ "message": expected }] }),
+    );
+  }
+
+  #[test]
+  fn cursors_round_trip() {
+    assert_eq!(Page::encode_cursor(7), "Y3Vyc29yOjc=");
+    assert_eq!(Page::decode_cursor("Y3Vyc29yOjc=").unwrap(), 7);
+    assert_eq!(
+      Page::decode_cursor("foo").unwrap_err().to_string(),
+      "invalid cursor `foo`"
+    );
+  }
+
+  #[test]
+  fn page_select() {
+    let page = Page {
+      first: 2,
+      offset: 1,
+    };
+
+    assert_eq!(page.select([0, 1, 2, 3]), (vec![1, 2], true));
+    assert_eq!(page.select([0, 1, 2]), (vec![1, 2], false));
+    assert_eq!(page.select([0]), (Vec::<i32>::new(), false));
+  }
+
+  #[test]
+  fn validation_errors() {
+    error("{ foo }", "cannot query field `foo` on type `Query`");
+    error(
+      "{ sat(number: 0, bar: 1) { name } }",
+      "unknown argument `bar` on field `Query.sat`",
+    );
+    error(
+      "{ sat { name } }",
+      "argument `number` of field `Query.sat` must be of type `BigInt!`",
+    );
+    error(
+      "{ sat(number: \"a\") { name } }",
+      "argument `number` of field `Query.sat` must be of type `BigInt!`",
+    );
+    error(
+      "{ sat(number: 0) }",
+      "field `sat` of type `Sat` must have a selection of subfields",
+    );
+    error(
+      "{ sat(number: 0) { name { foo } } }",
+      "field `name` of type `String!` must not have a selection",
+    );
+    error(
+      "{ sat(number: 0) { ...F } } fragment F on Block { height }",
+      "fragment `F` on `Block` cannot be spread on `Sat`",
+    );
+    error(
+      "{ sat(number: 0) { ...F } } fragment F on Sat { ...F }",
+      "fragment `F` spreads itself",
+    );
+    error("{ sat(number: 0) { ...G } }", "unknown fragment `G`");
+    error(
+      "{ sat(number: $n) { name } }",
+      "variable `$n` is not defined",
+    );
+    error(
+      "query A { status } query B { status }",
+      "operation name required for document with multiple operations",
+    );
+    error(
+      "{ inscriptions(first: 101) { nodes { id } } }",
+      "`first` must be between 0 and 100",
+    );
+  }
+
+  #[test]
+  fn depth_limit() {
+    error(
+      &format!(
+        "{{ block(height: 0) {{ {} height {} }} }}",
+        "previous { ".repeat(MAX_DEPTH),
+        "}".repeat(MAX_DEPTH),
+      ),
+      "query exceeds maximum depth of 12",
+    );
+  }
+
+  #[test]
+  fn complexity_limit() {
+    error(
+      "{
+        inscriptions(first: 100) {
+          nodes {
+            children(first: 100) {
+              nodes { id }
+            }
+          }
+        }
+      }",
+      "query complexity of 20201 exceeds maximum of 10000",
+    );
+  }
+
+  #[test]
+  fn complexity_limit_stops_fragment_spread_chains() {
+    let mut query = "{ block(height: 0) { ...F0 } }".to_string();
+
+    for i in 0..40 {
+      query.push_str(&format!(
+        " fragment F{i} on Block {{ ...F{} ...F{} }}",
+        i + 1,
+        i + 1
+      ));
+    }
+
+    query.push_str(" fragment F40 on Block { height }");
+
+    error(&query, "query complexity of 16384 exceeds maximum of 10000");
+  }
+
+  #[test]
+  fn execute_with_variables_aliases_and_fragments() {
+    let context = IndexContext::builder().build();
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      execute(
+        &context.index,
+        Chain::Regtest,
+        Request {
+          query: "
+            query Sats($first: BigInt!, $last: BigInt = \"2099999997689999\") {
+              first: sat(number: $first) { ...Sat }
+              last: sat(number: $last) { ...Sat }
+            }
+
+            fragment Sat on Sat {
+              __typename
+              name
+              rarity
+              block { height }
+              ... on Sat { number }
+            }
+          "
+          .into(),
+          operation_name: Some("Sats".into()),
+          variables: Some(json!({ "first": 0 }).as_object().unwrap().clone()),
+        },
+      ),
+      json!({
+        "data": {
+          "first": {
+            "__typename": "Sat",
+            "name": "nvtdijuwxlp",
+            "rarity": "mythic",
+            "block": { "height": 0 },
+            "number": "0",
+          },
+          "last": {
+            "__typename": "Sat",
+            "name": "a",
+            "rarity": "uncommon",
+            "block": null,
+            "number": "2099999997689999",
+          },
+        },
+      }),
+    );
+  }
+
+  #[test]
+  fn field_errors_are_reported_with_path() {
+    let context = IndexContext::builder().build();
+
+    assert_eq!(
+      execute(
+        &context.index,
+        Chain::Regtest,
+        request("{ a: runes { nodes { name } } b: sat(number: 0) { number } }"),
+      ),
+      json!({
+        "data": {
+          "a": null,
+          "b": { "number": "0" },
+        },
+        "errors": [{
+          "message": "this server has no rune index",
+          "path": ["a"],
+        }],
+      }),
+    );
+  }
+
+  #[test]
+  fn connections_paginate_with_cursors() {
+    let context = IndexContext::builder().arg("--index-runes").build();
+
+    context.mine_blocks(1);
+
+    for i in 0..3 {
+      context.etch(
+        Runestone {
+          etching: Some(Etching {
+            rune: Some(Rune(RUNE + i)),
+            ..default()
+          }),
+          ..default()
+        },
+        1,
+      );
+    }
+
+    let page = |after: Option<&str>| {
+      execute(
+        &context.index,
+        Chain::Regtest,
+        Request {
+          query: "query ($after: String) {
+            runes(first: 2, after: $after) {
+              edges { cursor node { name } }
+              pageInfo { endCursor hasNextPage }
+            }
+          }"
+          .into(),
+          operation_name: None,
+          variables: Some(json!({ "after": after }).as_object().unwrap().clone()),
+        },
+      )["data"]["runes"]
+        .clone()
+    };
+
+    let first = page(None);
+
+    assert_eq!(
+      first,
+      json!({
+        "edges": [
+          { "cursor": Page::encode_cursor(0), "node": { "name": Rune(RUNE + 2).to_string() } },
+          { "cursor": Page::encode_cursor(1), "node": { "name": Rune(RUNE + 1).to_string() } },
+        ],
+        "pageInfo": { "endCursor": Page::encode_cursor(1), "hasNextPage": true },
+      }),
+    );
+
+    assert_eq!(
+      page(first["pageInfo"]["endCursor"].as_str()),
+      json!({
+        "edges": [
+          { "cursor": Page::encode_cursor(2), "node": { "name": Rune(RUNE).to_string() } },
+        ],
+        "pageInfo": { "endCursor": Page::encode_cursor(2), "hasNextPage": false },
+      }),
+    );
+  }
+}
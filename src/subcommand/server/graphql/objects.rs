@@ -0,0 +1,836 @@
+use {super::*, crate::index::entry::InscriptionEntry};
+
+/// The result of resolving a field, completed by the executor using the
+/// field's selection set.
+pub(crate) enum Resolved {
+  List(Vec<Resolved>),
+  Object(Rc<dyn Object>),
+  Value(Value),
+}
+
+impl Resolved {
+  fn object(object: impl Object + 'static) -> Self {
+    Self::Object(Rc::new(object))
+  }
+
+  fn optional(object: Option<impl Object + 'static>) -> Self {
+    match object {
+      Some(object) => Self::object(object),
+      None => Self::Value(Value::Null),
+    }
+  }
+
+  fn list<T: Object + 'static>(objects: impl IntoIterator<Item = T>) -> Self {
+    Self::List(objects.into_iter().map(Self::object).collect())
+  }
+
+  fn connection(
+    name: &'static str,
+    page: Page,
+    nodes: Vec<Rc<dyn Object>>,
+    has_next_page: bool,
+  ) -> Self {
+    Self::object(Connection {
+      has_next_page,
+      name,
+      nodes,
+      offset: page.offset,
+    })
+  }
+}
+
+impl<T: Serialize> From<T> for Resolved {
+  fn from(value: T) -> Self {
+    Self::Value(serde_json::to_value(value).unwrap())
+  }
+}
+
+/// `BigInt` values are serialized as strings, since they may exceed what
+/// JSON numbers can represent precisely.
+fn big_int(n: impl Into<u128>) -> Resolved {
+  n.into().to_string().into()
+}
+
+pub(crate) trait Object {
+  fn type_name(&self) -> &'static str;
+
+  /// Resolve `field`, which has been validated against the schema.
+  fn resolve(&self, context: &Context, field: &str, arguments: &Arguments) -> Result<Resolved>;
+}
+
+fn unknown(ty: &str, field: &str) -> ! {
+  panic!("unhandled field `{ty}.{field}`")
+}
+
+struct Connection {
+  has_next_page: bool,
+  name: &'static str,
+  nodes: Vec<Rc<dyn Object>>,
+  offset: usize,
+}
+
+impl Object for Connection {
+  fn type_name(&self) -> &'static str {
+    self.name
+  }
+
+  fn resolve(&self, _: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    Ok(match field {
+      "edges" => Resolved::List(
+        self
+          .nodes
+          .iter()
+          .enumerate()
+          .map(|(i, node)| {
+            Resolved::object(Edge {
+              cursor: Page::encode_cursor(self.offset + i),
+              name: schema::object(self.name)
+                .unwrap()
+                .fields
+                .iter()
+                .find(|field| field.name == "edges")
+                .unwrap()
+                .named_type(),
+              node: node.clone(),
+            })
+          })
+          .collect(),
+      ),
+      "nodes" => Resolved::List(self.nodes.iter().cloned().map(Resolved::Object).collect()),
+      "pageInfo" => Resolved::object(PageInfo {
+        end_cursor: self
+          .nodes
+          .len()
+          .checked_sub(1)
+          .map(|last| Page::encode_cursor(self.offset + last)),
+        has_next_page: self.has_next_page,
+      }),
+      _ => unknown(self.name, field),
+    })
+  }
+}
+
+struct Edge {
+  cursor: String,
+  name: &'static str,
+  node: Rc<dyn Object>,
+}
+
+impl Object for Edge {
+  fn type_name(&self) -> &'static str {
+    self.name
+  }
+
+  fn resolve(&self, _: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    Ok(match field {
+      "cursor" => self.cursor.clone().into(),
+      "node" => Resolved::Object(self.node.clone()),
+      _ => unknown(self.name, field),
+    })
+  }
+}
+
+struct PageInfo {
+  end_cursor: Option<String>,
+  has_next_page: bool,
+}
+
+impl Object for PageInfo {
+  fn type_name(&self) -> &'static str {
+    "PageInfo"
+  }
+
+  fn resolve(&self, _: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    Ok(match field {
+      "endCursor" => self.end_cursor.clone().into(),
+      "hasNextPage" => self.has_next_page.into(),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+fn inscriptions(
+  context: &Context,
+  page: Page,
+  ids: Vec<InscriptionId>,
+  has_next_page: bool,
+) -> Result<Resolved> {
+  Ok(Resolved::connection(
+    "InscriptionConnection",
+    page,
+    ids
+      .into_iter()
+      .map(|id| Ok(Rc::new(InscriptionObject::get(context, id)?) as Rc<dyn Object>))
+      .collect::<Result<Vec<Rc<dyn Object>>>>()?,
+    has_next_page,
+  ))
+}
+
+fn runes(context: &Context, page: Page, runes: Vec<Rune>, has_next_page: bool) -> Result<Resolved> {
+  Ok(Resolved::connection(
+    "RuneConnection",
+    page,
+    runes
+      .into_iter()
+      .map(|rune| {
+        Ok(Rc::new(
+          RuneObject::load(context, rune)?.ok_or_else(|| anyhow!("rune {rune} not found"))?,
+        ) as Rc<dyn Object>)
+      })
+      .collect::<Result<Vec<Rc<dyn Object>>>>()?,
+    has_next_page,
+  ))
+}
+
+pub(crate) struct Query;
+
+impl Object for Query {
+  fn type_name(&self) -> &'static str {
+    "Query"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, arguments: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+
+    Ok(match field {
+      "address" => {
+        ensure!(
+          index.has_address_index(),
+          "this server has no address index"
+        );
+
+        let address = arguments
+          .parse::<Address<NetworkUnchecked>>("address")?
+          .unwrap()
+          .require_network(context.chain.network())?;
+
+        Resolved::object(AddressObject(address))
+      }
+      "block" => {
+        let hash = arguments.parse::<BlockHash>("hash")?;
+        let height = arguments.int("height");
+
+        match (hash, height) {
+          (Some(hash), None) => Resolved::optional(
+            index
+              .block_header_info(hash)?
+              .filter(|info| info.confirmations >= 0)
+              .map(|info| BlockObject {
+                hash,
+                height: info.height.try_into().unwrap(),
+              }),
+          ),
+          (None, Some(height)) => {
+            Resolved::optional(BlockObject::load(context, u32::try_from(height).ok())?)
+          }
+          _ => bail!("exactly one of `hash` and `height` must be given"),
+        }
+      }
+      "inscription" => {
+        let query = match (
+          arguments.parse::<InscriptionId>("id")?,
+          arguments.int("number"),
+        ) {
+          (Some(id), None) => query::Inscription::Id(id),
+          (None, Some(number)) => query::Inscription::Number(number.try_into().unwrap()),
+          _ => bail!("exactly one of `id` and `number` must be given"),
+        };
+
+        Resolved::optional(InscriptionObject::load(context, query)?)
+      }
+      "inscriptions" => {
+        let page = arguments.page()?;
+
+        let (ids, more) = index.get_inscriptions_by_offset(
+          u32::try_from(page.offset).unwrap_or(u32::MAX),
+          page.first.try_into().unwrap(),
+        )?;
+
+        inscriptions(context, page, ids, more)?
+      }
+      "output" => Resolved::optional(OutputObject::load(
+        context,
+        arguments.parse::<OutPoint>("outpoint")?.unwrap(),
+      )?),
+      "rune" => {
+        ensure!(index.has_rune_index(), "this server has no rune index");
+
+        let rune = match (
+          arguments.parse::<RuneId>("id")?,
+          arguments.parse::<SpacedRune>("name")?,
+        ) {
+          (Some(id), None) => index.get_rune_by_id(id)?,
+          (None, Some(spaced_rune)) => Some(spaced_rune.rune),
+          _ => bail!("exactly one of `id` and `name` must be given"),
+        };
+
+        match rune {
+          Some(rune) => Resolved::optional(RuneObject::load(context, rune)?),
+          None => Resolved::Value(Value::Null),
+        }
+      }
+      "runes" => {
+        ensure!(index.has_rune_index(), "this server has no rune index");
+
+        let page = arguments.page()?;
+
+        let (entries, _) = index.runes_paginated(page.offset.saturating_add(page.first), 0)?;
+
+        let (entries, more) = page.select(entries);
+
+        Resolved::connection(
+          "RuneConnection",
+          page,
+          entries
+            .into_iter()
+            .map(|(id, entry)| Rc::new(RuneObject { entry, id }) as Rc<dyn Object>)
+            .collect(),
+          more,
+        )
+      }
+      "sat" => {
+        let number = arguments.big_int("number")?.unwrap();
+
+        ensure!(
+          number < Sat::SUPPLY.into(),
+          "sat number {number} exceeds supply"
+        );
+
+        Resolved::object(SatObject(Sat(number.try_into().unwrap())))
+      }
+      "transaction" => Resolved::optional(TransactionObject::load(
+        context,
+        arguments.parse::<Txid>("txid")?.unwrap(),
+      )?),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct AddressObject(Address);
+
+impl Object for AddressObject {
+  fn type_name(&self) -> &'static str {
+    "Address"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, arguments: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+
+    Ok(match field {
+      "address" => self.0.to_string().into(),
+      "inscriptions" => {
+        let page = arguments.page()?;
+        let outputs = index.get_address_info(&self.0)?;
+        let (ids, more) = page.select(index.get_inscriptions_for_outputs(&outputs)?);
+        inscriptions(context, page, ids, more)?
+      }
+      "outputs" => {
+        let page = arguments.page()?;
+
+        let (outputs, more) = page.select(index.get_address_info(&self.0)?);
+
+        Resolved::connection(
+          "OutputConnection",
+          page,
+          outputs
+            .into_iter()
+            .map(|outpoint| {
+              Ok(Rc::new(
+                OutputObject::load(context, outpoint)?
+                  .ok_or_else(|| anyhow!("output {outpoint} not found"))?,
+              ) as Rc<dyn Object>)
+            })
+            .collect::<Result<Vec<Rc<dyn Object>>>>()?,
+          more,
+        )
+      }
+      "runeBalances" => {
+        let outputs = index.get_address_info(&self.0)?;
+
+        Resolved::list(
+          index
+            .get_aggregated_rune_balances_for_outputs(&outputs)?
+            .into_iter()
+            .map(|(spaced_rune, decimal, _symbol)| RuneBalanceObject {
+              amount: decimal.value,
+              rune: spaced_rune.rune,
+            }),
+        )
+      }
+      "satBalance" => {
+        let outputs = index.get_address_info(&self.0)?;
+        big_int(index.get_sat_balances_for_outputs(&outputs)?)
+      }
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct BlockObject {
+  hash: BlockHash,
+  height: u32,
+}
+
+impl BlockObject {
+  fn load(context: &Context, height: Option<u32>) -> Result<Option<Self>> {
+    let Some(height) = height else {
+      return Ok(None);
+    };
+
+    Ok(
+      context
+        .index
+        .block_hash(Some(height))?
+        .map(|hash| Self { hash, height }),
+    )
+  }
+}
+
+impl Object for BlockObject {
+  fn type_name(&self) -> &'static str {
+    "Block"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, arguments: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+
+    Ok(match field {
+      "hash" => self.hash.into(),
+      "height" => self.height.into(),
+      "inscriptions" => {
+        let page = arguments.page()?;
+        let (ids, more) = page.select(index.get_inscriptions_in_block(self.height)?);
+        inscriptions(context, page, ids, more)?
+      }
+      "previous" => Resolved::optional(BlockObject::load(context, self.height.checked_sub(1))?),
+      "runes" => {
+        let page = arguments.page()?;
+
+        let (spaced_runes, more) = page.select(index.get_runes_in_block(self.height.into())?);
+
+        runes(
+          context,
+          page,
+          spaced_runes
+            .into_iter()
+            .map(|spaced_rune| spaced_rune.rune)
+            .collect(),
+          more,
+        )?
+      }
+      "timestamp" => index
+        .block_header(self.hash)?
+        .map(|header| header.time)
+        .into(),
+      "transactionCount" => index
+        .get_block_by_hash(self.hash)?
+        .map(|block| block.txdata.len())
+        .into(),
+      "transactions" => {
+        let page = arguments.page()?;
+
+        let Some(block) = index.get_block_by_hash(self.hash)? else {
+          return Ok(Resolved::Value(Value::Null));
+        };
+
+        let (transactions, more) = page.select(block.txdata);
+
+        Resolved::connection(
+          "TransactionConnection",
+          page,
+          transactions
+            .into_iter()
+            .map(|transaction| {
+              Rc::new(TransactionObject {
+                txid: transaction.compute_txid(),
+                transaction,
+              }) as Rc<dyn Object>
+            })
+            .collect(),
+          more,
+        )
+      }
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct InscriptionObject(api::Inscription);
+
+impl InscriptionObject {
+  fn load(context: &Context, query: query::Inscription) -> Result<Option<Self>> {
+    Ok(
+      context
+        .index
+        .inscription_info(query, None)?
+        .map(|(inscription, _output, _inscription)| Self(inscription)),
+    )
+  }
+
+  fn get(context: &Context, id: InscriptionId) -> Result<Self> {
+    Self::load(context, query::Inscription::Id(id))?
+      .ok_or_else(|| anyhow!("inscription {id} not found"))
+  }
+
+  fn entry(&self, context: &Context) -> Result<InscriptionEntry> {
+    context
+      .index
+      .get_inscription_entry(self.0.id)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.0.id))
+  }
+}
+
+impl Object for InscriptionObject {
+  fn type_name(&self) -> &'static str {
+    "Inscription"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, arguments: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+    let inscription = &self.0;
+
+    Ok(match field {
+      "address" => inscription.address.clone().into(),
+      "block" => Resolved::optional(BlockObject::load(context, Some(inscription.height))?),
+      "charms" => inscription
+        .charms
+        .iter()
+        .map(Charm::to_string)
+        .collect::<Vec<String>>()
+        .into(),
+      "children" => {
+        let page = arguments.page()?;
+
+        let (ids, more) = index.get_children_by_sequence_number_paginated(
+          self.entry(context)?.sequence_number,
+          page.offset.saturating_add(page.first),
+          0,
+        )?;
+
+        inscriptions(
+          context,
+          page,
+          ids.into_iter().skip(page.offset).collect(),
+          more,
+        )?
+      }
+      "contentLength" => inscription.content_length.into(),
+      "contentType" => inscription.content_type.clone().into(),
+      "fee" => big_int(inscription.fee),
+      "height" => inscription.height.into(),
+      "id" => inscription.id.into(),
+      "metaprotocol" => inscription.metaprotocol.clone().into(),
+      "number" => inscription.number.into(),
+      "output" => Resolved::optional(OutputObject::load(context, inscription.satpoint.outpoint)?),
+      "parents" => {
+        let page = arguments.page()?;
+
+        let (parents, more) = page.select(self.entry(context)?.parents);
+
+        let (ids, _) = index.get_parents_by_sequence_number_paginated(parents, 0)?;
+
+        inscriptions(context, page, ids, more)?
+      }
+      "rune" => match inscription.rune {
+        Some(spaced_rune) => Resolved::optional(RuneObject::load(context, spaced_rune.rune)?),
+        None => Resolved::Value(Value::Null),
+      },
+      "sat" => Resolved::optional(inscription.sat.map(SatObject)),
+      "satpoint" => inscription.satpoint.into(),
+      "timestamp" => inscription.timestamp.into(),
+      "transaction" => Resolved::optional(TransactionObject::load(context, inscription.id.txid)?),
+      "value" => match inscription.value {
+        Some(value) => big_int(value),
+        None => Resolved::Value(Value::Null),
+      },
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct OutputObject(api::Output);
+
+impl OutputObject {
+  fn load(context: &Context, outpoint: OutPoint) -> Result<Option<Self>> {
+    Ok(
+      context
+        .index
+        .get_output_info(outpoint)?
+        .map(|(output, _txout)| Self(output)),
+    )
+  }
+}
+
+impl Object for OutputObject {
+  fn type_name(&self) -> &'static str {
+    "Output"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    let output = &self.0;
+
+    Ok(match field {
+      "address" => output.address.clone().into(),
+      "inscriptions" => Resolved::list(
+        output
+          .inscriptions
+          .iter()
+          .map(|id| InscriptionObject::get(context, *id))
+          .collect::<Result<Vec<InscriptionObject>>>()?,
+      ),
+      "outpoint" => output.outpoint.into(),
+      "runeBalances" => {
+        Resolved::list(
+          output
+            .runes
+            .iter()
+            .map(|(spaced_rune, pile)| RuneBalanceObject {
+              amount: pile.amount,
+              rune: spaced_rune.rune,
+            }),
+        )
+      }
+      "satRanges" => match &output.sat_ranges {
+        Some(sat_ranges) => Resolved::list(sat_ranges.iter().copied().map(SatRangeObject)),
+        None => Resolved::Value(Value::Null),
+      },
+      "scriptPubkey" => output.script_pubkey.to_hex_string().into(),
+      "spent" => output.spent.into(),
+      "transaction" => Resolved::optional(TransactionObject::load(context, output.transaction)?),
+      "value" => big_int(output.value),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct RuneObject {
+  entry: RuneEntry,
+  id: RuneId,
+}
+
+impl RuneObject {
+  fn load(context: &Context, rune: Rune) -> Result<Option<Self>> {
+    Ok(
+      context
+        .index
+        .rune(rune)?
+        .map(|(id, entry, _parent)| Self { entry, id }),
+    )
+  }
+}
+
+impl Object for RuneObject {
+  fn type_name(&self) -> &'static str {
+    "Rune"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+    let entry = &self.entry;
+
+    Ok(match field {
+      "block" => Resolved::optional(BlockObject::load(context, entry.block.try_into().ok())?),
+      "burned" => big_int(entry.burned),
+      "divisibility" => entry.divisibility.into(),
+      "etching" => Resolved::optional(TransactionObject::load(context, entry.etching)?),
+      "id" => self.id.into(),
+      "mintable" => {
+        let height = index.block_height()?.unwrap_or(Height(0));
+        entry.mintable((height.n() + 1).into()).is_ok().into()
+      }
+      "mints" => big_int(entry.mints),
+      "name" => entry.spaced_rune.into(),
+      "number" => big_int(entry.number),
+      "parent" => match index
+        .rune(entry.spaced_rune.rune)?
+        .and_then(|(_id, _entry, parent)| parent)
+      {
+        Some(parent) => Resolved::object(InscriptionObject::get(context, parent)?),
+        None => Resolved::Value(Value::Null),
+      },
+      "premine" => big_int(entry.premine),
+      "supply" => big_int(entry.supply()),
+      "symbol" => entry.symbol.into(),
+      "timestamp" => entry.timestamp.into(),
+      "turbo" => entry.turbo.into(),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct RuneBalanceObject {
+  amount: u128,
+  rune: Rune,
+}
+
+impl Object for RuneBalanceObject {
+  fn type_name(&self) -> &'static str {
+    "RuneBalance"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    Ok(match field {
+      "amount" => big_int(self.amount),
+      "rune" => Resolved::optional(RuneObject::load(context, self.rune)?),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct SatObject(Sat);
+
+impl Object for SatObject {
+  fn type_name(&self) -> &'static str {
+    "Sat"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, arguments: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+    let sat = self.0;
+
+    Ok(match field {
+      "block" => Resolved::optional(BlockObject::load(context, Some(sat.height().n()))?),
+      "charms" => Charm::charms(sat.charms())
+        .iter()
+        .map(Charm::to_string)
+        .collect::<Vec<String>>()
+        .into(),
+      "decimal" => sat.decimal().to_string().into(),
+      "degree" => sat.degree().to_string().into(),
+      "inscriptions" => {
+        let page = arguments.page()?;
+
+        let (ids, more) = index.get_inscription_ids_by_sat_paginated(
+          sat,
+          page.offset.saturating_add(page.first).try_into().unwrap(),
+          0,
+        )?;
+
+        inscriptions(
+          context,
+          page,
+          ids.into_iter().skip(page.offset).collect(),
+          more,
+        )?
+      }
+      "name" => sat.name().into(),
+      "number" => big_int(sat.n()),
+      "percentile" => sat.percentile().into(),
+      "rarity" => sat.rarity().into(),
+      "satpoint" => {
+        ensure!(index.has_sat_index(), "this server has no sat index");
+        index.find(sat)?.into()
+      }
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct SatRangeObject((u64, u64));
+
+impl Object for SatRangeObject {
+  fn type_name(&self) -> &'static str {
+    "SatRange"
+  }
+
+  fn resolve(&self, _: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    let (start, end) = self.0;
+
+    Ok(match field {
+      "end" => big_int(end),
+      "start" => big_int(start),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct TransactionObject {
+  transaction: Transaction,
+  txid: Txid,
+}
+
+impl TransactionObject {
+  fn load(context: &Context, txid: Txid) -> Result<Option<Self>> {
+    Ok(
+      context
+        .index
+        .get_transaction(txid)?
+        .map(|transaction| Self { transaction, txid }),
+    )
+  }
+}
+
+impl Object for TransactionObject {
+  fn type_name(&self) -> &'static str {
+    "Transaction"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    let index = context.index;
+
+    Ok(match field {
+      "etching" => match index.get_etching(self.txid)? {
+        Some(spaced_rune) => Resolved::optional(RuneObject::load(context, spaced_rune.rune)?),
+        None => Resolved::Value(Value::Null),
+      },
+      "inputs" => Resolved::list(
+        self
+          .transaction
+          .input
+          .iter()
+          .map(|input| TransactionInputObject(input.previous_output)),
+      ),
+      "inscriptions" => Resolved::list(
+        (0..index.inscription_count(self.txid)?)
+          .map(|index| {
+            InscriptionObject::get(
+              context,
+              InscriptionId {
+                txid: self.txid,
+                index,
+              },
+            )
+          })
+          .collect::<Result<Vec<InscriptionObject>>>()?,
+      ),
+      "outputs" => Resolved::list(
+        (0..self.transaction.output.len())
+          .map(|vout| {
+            let outpoint = OutPoint {
+              txid: self.txid,
+              vout: vout.try_into().unwrap(),
+            };
+
+            OutputObject::load(context, outpoint)?
+              .ok_or_else(|| anyhow!("output {outpoint} not found"))
+          })
+          .collect::<Result<Vec<OutputObject>>>()?,
+      ),
+      "txid" => self.txid.into(),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
+
+struct TransactionInputObject(OutPoint);
+
+impl Object for TransactionInputObject {
+  fn type_name(&self) -> &'static str {
+    "TransactionInput"
+  }
+
+  fn resolve(&self, context: &Context, field: &str, _: &Arguments) -> Result<Resolved> {
+    Ok(match field {
+      "output" => {
+        if self.0.is_null() {
+          Resolved::Value(Value::Null)
+        } else {
+          Resolved::optional(OutputObject::load(context, self.0)?)
+        }
+      }
+      "previousOutput" => self.0.into(),
+      _ => unknown(self.type_name(), field),
+    })
+  }
+}
@@ -0,0 +1,689 @@
+use super::*;
+
+/// Maximum nesting depth of input values and variable types.
+const MAX_VALUE_DEPTH: usize = 32;
+
+/// An executable GraphQL document, containing operations and the fragments
+/// they reference.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Document {
+  pub(crate) fragments: BTreeMap<String, Fragment>,
+  pub(crate) operations: Vec<Operation>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Operation {
+  pub(crate) name: Option<String>,
+  pub(crate) selection_set: Vec<Selection>,
+  pub(crate) variables: Vec<VariableDefinition>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct VariableDefinition {
+  pub(crate) default: Option<InputValue>,
+  pub(crate) name: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Fragment {
+  pub(crate) selection_set: Vec<Selection>,
+  pub(crate) type_condition: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Selection {
+  Field(Field),
+  FragmentSpread(String),
+  InlineFragment {
+    selection_set: Vec<Selection>,
+    type_condition: Option<String>,
+  },
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Field {
+  pub(crate) alias: Option<String>,
+  pub(crate) arguments: Vec<(String, InputValue)>,
+  pub(crate) name: String,
+  pub(crate) selection_set: Vec<Selection>,
+}
+
+impl Field {
+  pub(crate) fn response_key(&self) -> &str {
+    self.alias.as_deref().unwrap_or(&self.name)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum InputValue {
+  Boolean(bool),
+  Enum(String),
+  Float(f64),
+  Int(i64),
+  List(Vec<InputValue>),
+  Null,
+  Object(Vec<(String, InputValue)>),
+  String(String),
+  Variable(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  Float(f64),
+  Int(i64),
+  Name(String),
+  Punctuator(char),
+  Spread,
+  String(String),
+}
+
+impl Display for Token {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Float(float) => write!(f, "{float}"),
+      Self::Int(int) => write!(f, "{int}"),
+      Self::Name(name) => write!(f, "`{name}`"),
+      Self::Punctuator(punctuator) => write!(f, "`{punctuator}`"),
+      Self::Spread => write!(f, "`...`"),
+      Self::String(string) => write!(f, "{string:?}"),
+    }
+  }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+  let mut tokens = Vec::new();
+  let mut chars = source.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\u{FEFF}' | ' ' | '\t' | '\n' | '\r' | ',' => {}
+      '#' => while chars.next_if(|c| *c != '\n' && *c != '\r').is_some() {},
+      '!' | '$' | '&' | '(' | ')' | ':' | '=' | '@' | '[' | ']' | '{' | '|' | '}' => {
+        tokens.push(Token::Punctuator(c));
+      }
+      '.' => {
+        ensure!(
+          chars.next() == Some('.') && chars.next() == Some('.'),
+          "syntax error: unexpected `.`"
+        );
+        tokens.push(Token::Spread);
+      }
+      '"' => {
+        let mut string = String::new();
+
+        loop {
+          match chars.next() {
+            Some('"') => break,
+            Some('\\') => match chars.next() {
+              Some('"') => string.push('"'),
+              Some('\\') => string.push('\\'),
+              Some('/') => string.push('/'),
+              Some('b') => string.push('\u{8}'),
+              Some('f') => string.push('\u{C}'),
+              Some('n') => string.push('\n'),
+              Some('r') => string.push('\r'),
+              Some('t') => string.push('\t'),
+              Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+                string.push(
+                  u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| anyhow!("syntax error: invalid unicode escape `\\u{hex}`"))?,
+                );
+              }
+              _ => bail!("syntax error: invalid escape sequence in string"),
+            },
+            Some('\n' | '\r') | None => bail!("syntax error: unterminated string"),
+            Some(c) => string.push(c),
+          }
+        }
+
+        tokens.push(Token::String(string));
+      }
+      '-' | '0'..='9' => {
+        let mut number = c.to_string();
+        let mut float = false;
+
+        while let Some(c) =
+          chars.next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'))
+        {
+          float |= matches!(c, '.' | 'e' | 'E');
+          number.push(c);
+        }
+
+        tokens.push(if float {
+          Token::Float(
+            number
+              .parse()
+              .map_err(|_| anyhow!("syntax error: invalid number `{number}`"))?,
+          )
+        } else {
+          Token::Int(
+            number
+              .parse()
+              .map_err(|_| anyhow!("syntax error: invalid number `{number}`"))?,
+          )
+        });
+      }
+      'a'..='z' | 'A'..='Z' | '_' => {
+        let mut name = c.to_string();
+
+        while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+          name.push(c);
+        }
+
+        tokens.push(Token::Name(name));
+      }
+      c => bail!("syntax error: unexpected character `{c}`"),
+    }
+  }
+
+  Ok(tokens)
+}
+
+struct Parser {
+  tokens: Vec<Token>,
+  next: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.next)
+  }
+
+  fn advance(&mut self) -> Result<Token> {
+    let token = self
+      .tokens
+      .get(self.next)
+      .cloned()
+      .ok_or_else(|| anyhow!("syntax error: unexpected end of document"))?;
+    self.next += 1;
+    Ok(token)
+  }
+
+  fn accept(&mut self, punctuator: char) -> bool {
+    if self.peek() == Some(&Token::Punctuator(punctuator)) {
+      self.next += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn expect(&mut self, punctuator: char) -> Result {
+    match self.advance()? {
+      Token::Punctuator(c) if c == punctuator => Ok(()),
+      token => bail!("syntax error: expected `{punctuator}`, found {token}"),
+    }
+  }
+
+  fn name(&mut self) -> Result<String> {
+    match self.advance()? {
+      Token::Name(name) => Ok(name),
+      token => bail!("syntax error: expected name, found {token}"),
+    }
+  }
+
+  fn peek_name(&self, name: &str) -> bool {
+    matches!(self.peek(), Some(Token::Name(next)) if next == name)
+  }
+
+  fn document(&mut self) -> Result<Document> {
+    let mut document = Document::default();
+
+    while let Some(token) = self.peek() {
+      match token {
+        Token::Punctuator('{') => document.operations.push(Operation {
+          name: None,
+          selection_set: self.selection_set(1)?,
+          variables: Vec::new(),
+        }),
+        Token::Name(keyword) if keyword == "query" => {
+          self.next += 1;
+          document.operations.push(self.operation()?);
+        }
+        Token::Name(keyword) if keyword == "mutation" || keyword == "subscription" => {
+          bail!("{keyword} operations are not supported")
+        }
+        Token::Name(keyword) if keyword == "fragment" => {
+          self.next += 1;
+          let name = self.name()?;
+          ensure!(name != "on", "syntax error: unexpected `on`");
+          ensure!(
+            self.name()? == "on",
+            "syntax error: expected `on` after fragment name"
+          );
+          let type_condition = self.name()?;
+          self.directives()?;
+          let selection_set = self.selection_set(1)?;
+
+          ensure!(
+            document
+              .fragments
+              .insert(
+                name.clone(),
+                Fragment {
+                  selection_set,
+                  type_condition,
+                },
+              )
+              .is_none(),
+            "there can be only one fragment named `{name}`"
+          );
+        }
+        token => bail!("syntax error: unexpected {token}"),
+      }
+    }
+
+    ensure!(
+      !document.operations.is_empty(),
+      "document does not contain any operations"
+    );
+
+    Ok(document)
+  }
+
+  fn operation(&mut self) -> Result<Operation> {
+    let name = match self.peek() {
+      Some(Token::Name(_)) => Some(self.name()?),
+      _ => None,
+    };
+
+    let mut variables = Vec::new();
+
+    if self.accept('(') {
+      while !self.accept(')') {
+        self.expect('$')?;
+        let name = self.name()?;
+        self.expect(':')?;
+        self.variable_type(1)?;
+        let default = if self.accept('=') {
+          Some(self.value(true, 1)?)
+        } else {
+          None
+        };
+        self.directives()?;
+        variables.push(VariableDefinition { default, name });
+      }
+    }
+
+    self.directives()?;
+
+    Ok(Operation {
+      name,
+      selection_set: self.selection_set(1)?,
+      variables,
+    })
+  }
+
+  fn variable_type(&mut self, depth: usize) -> Result {
+    ensure!(
+      depth <= MAX_VALUE_DEPTH,
+      "variable type exceeds maximum depth of {MAX_VALUE_DEPTH}"
+    );
+
+    if self.accept('[') {
+      self.variable_type(depth + 1)?;
+      self.expect(']')?;
+    } else {
+      self.name()?;
+    }
+
+    self.accept('!');
+
+    Ok(())
+  }
+
+  fn directives(&mut self) -> Result {
+    if self.peek() == Some(&Token::Punctuator('@')) {
+      bail!("directives are not supported");
+    }
+
+    Ok(())
+  }
+
+  /// Parse a selection set nested `depth` deep. Both fields and inline
+  /// fragments add a level of nesting.
+  fn selection_set(&mut self, depth: usize) -> Result<Vec<Selection>> {
+    ensure!(
+      depth <= MAX_DEPTH,
+      "query exceeds maximum depth of {MAX_DEPTH}"
+    );
+
+    self.expect('{')?;
+
+    let mut selections = Vec::new();
+
+    while !self.accept('}') {
+      if self.peek() == Some(&Token::Spread) {
+        self.next += 1;
+
+        if self.peek_name("on") {
+          self.next += 1;
+          let type_condition = Some(self.name()?);
+          self.directives()?;
+          selections.push(Selection::InlineFragment {
+            selection_set: self.selection_set(depth + 1)?,
+            type_condition,
+          });
+        } else if let Some(Token::Name(_)) = self.peek() {
+          selections.push(Selection::FragmentSpread(self.name()?));
+          self.directives()?;
+        } else {
+          self.directives()?;
+          selections.push(Selection::InlineFragment {
+            selection_set: self.selection_set(depth + 1)?,
+            type_condition: None,
+          });
+        }
+
+        continue;
+      }
+
+      let mut name = self.name()?;
+
+      let alias = if self.accept(':') {
+        Some(mem::replace(&mut name, self.name()?))
+      } else {
+        None
+      };
+
+      let mut arguments = Vec::new();
+
+      if self.accept('(') {
+        while !self.accept(')') {
+          let name = self.name()?;
+          self.expect(':')?;
+          let value = self.value(false, 1)?;
+          ensure!(
+            arguments.iter().all(|(argument, _)| *argument != name),
+            "there can be only one argument named `{name}`"
+          );
+          arguments.push((name, value));
+        }
+      }
+
+      self.directives()?;
+
+      let selection_set = if self.peek() == Some(&Token::Punctuator('{')) {
+        self.selection_set(depth + 1)?
+      } else {
+        Vec::new()
+      };
+
+      selections.push(Selection::Field(Field {
+        alias,
+        arguments,
+        name,
+        selection_set,
+      }));
+    }
+
+    ensure!(!selections.is_empty(), "syntax error: empty selection set");
+
+    Ok(selections)
+  }
+
+  fn value(&mut self, constant: bool, depth: usize) -> Result<InputValue> {
+    ensure!(
+      depth <= MAX_VALUE_DEPTH,
+      "input value exceeds maximum depth of {MAX_VALUE_DEPTH}"
+    );
+
+    Ok(match self.advance()? {
+      Token::Punctuator('$') if !constant => InputValue::Variable(self.name()?),
+      Token::Punctuator('[') => {
+        let mut list = Vec::new();
+        while !self.accept(']') {
+          list.push(self.value(constant, depth + 1)?);
+        }
+        InputValue::List(list)
+      }
+      Token::Punctuator('{') => {
+        let mut object = Vec::new();
+        while !self.accept('}') {
+          let name = self.name()?;
+          self.expect(':')?;
+          object.push((name, self.value(constant, depth + 1)?));
+        }
+        InputValue::Object(object)
+      }
+      Token::Int(int) => InputValue::Int(int),
+      Token::Float(float) => InputValue::Float(float),
+      Token::String(string) => InputValue::String(string),
+      Token::Name(name) => match name.as_str() {
+        "true" => InputValue::Boolean(true),
+        "false" => InputValue::Boolean(false),
+        "null" => InputValue::Null,
+        _ => InputValue::Enum(name),
+      },
+      token => bail!("syntax error: unexpected {token}"),
+    })
+  }
+}
+
+pub(crate) fn parse(source: &str) -> Result<Document> {
+  Parser {
+    tokens: tokenize(source)?,
+    next: 0,
+  }
+  .document()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn field(name: &str, selection_set: Vec<Selection>) -> Selection {
+    Selection::Field(Field {
+      alias: None,
+      arguments: Vec::new(),
+      name: name.into(),
+      selection_set,
+    })
+  }
+
+  #[test]
+  fn parse_shorthand_query() {
+    assert_eq!(
+      parse("{ status { height } }").unwrap(),
+      Document {
+        fragments: BTreeMap::new(),
+        operations: vec![Operation {
+          name: None,
+          selection_set: vec![field("status", vec![field("height", Vec::new())])],
+          variables: Vec::new(),
+        }],
+      }
+    );
+  }
+
+  #[test]
+  fn parse_operation_with_variables_aliases_and_fragments() {
+    let document = parse(
+      r#"
+        # comment
+        query Page($id: String!, $first: Int = 5, $ids: [String!]) {
+          a: inscription(id: $id) { ...Fields }
+          b: inscription(id: "A\n", number: -1) { ... on Inscription { id } }
+        }
+
+        fragment Fields on Inscription {
+          children(first: $first) { nodes { id } }
+        }
+      "#,
+    )
+    .unwrap();
+
+    let operation = &document.operations[0];
+
+    assert_eq!(operation.name.as_deref(), Some("Page"));
+    assert_eq!(
+      operation.variables,
+      vec![
+        VariableDefinition {
+          default: None,
+          name: "id".into(),
+        },
+        VariableDefinition {
+          default: Some(InputValue::Int(5)),
+          name: "first".into(),
+        },
+        VariableDefinition {
+          default: None,
+          name: "ids".into(),
+        },
+      ]
+    );
+
+    assert_eq!(
+      operation.selection_set,
+      vec![
+        Selection::Field(Field {
+          alias: Some("a".into()),
+          arguments: vec![("id".into(), InputValue::Variable("id".into()))],
+          name: "inscription".into(),
+          selection_set: vec![Selection::FragmentSpread("Fields".into())],
+        }),
+        Selection::Field(Field {
+          alias: Some("b".into()),
+          arguments: vec![
+            ("id".into(), InputValue::String("A\n".into())),
+            ("number".into(), InputValue::Int(-1)),
+          ],
+          name: "inscription".into(),
+          selection_set: vec![Selection::InlineFragment {
+            selection_set: vec![field("id", Vec::new())],
+            type_condition: Some("Inscription".into()),
+          }],
+        }),
+      ]
+    );
+
+    assert_eq!(document.fragments["Fields"].type_condition, "Inscription");
+  }
+
+  #[test]
+  fn parse_values() {
+    let document = parse(r#"{ a(b: [1, 2.5, true, null, FOO, { c: "d" }]) }"#).unwrap();
+
+    let Selection::Field(field) = &document.operations[0].selection_set[0] else {
+      panic!();
+    };
+
+    assert_eq!(
+      field.arguments[0].1,
+      InputValue::List(vec![
+        InputValue::Int(1),
+        InputValue::Float(2.5),
+        InputValue::Boolean(true),
+        InputValue::Null,
+        InputValue::Enum("FOO".into()),
+        InputValue::Object(vec![("c".into(), InputValue::String("d".into()))]),
+      ])
+    );
+  }
+
+  #[test]
+  fn parse_errors() {
+    #[track_caller]
+    fn case(source: &str, expected: &str) {
+      assert_eq!(parse(source).unwrap_err().to_string(), expected);
+    }
+
+    case("", "document does not contain any operations");
+    case("{ a", "syntax error: unexpected end of document");
+    case("{ }", "syntax error: empty selection set");
+    case(
+      "{ a(b: 1, b: 2) }",
+      "there can be only one argument named `b`",
+    );
+    case("{ a(b: $c) @skip }", "directives are not supported");
+    case("mutation { a }", "mutation operations are not supported");
+    case("{ a(b: \"c) }", "syntax error: unterminated string");
+    case("{ a(b: 1x) }", "syntax error: invalid number `1x`");
+    case("{ a } ~", "syntax error: unexpected character `~`");
+    case(
+      "fragment A on B { a } fragment A on B { a } { a }",
+      "there can be only one fragment named `A`",
+    );
+    case("query ($a: Int = $b) { a }", "syntax error: unexpected `$`");
+  }
+
+  #[test]
+  fn depth_limits() {
+    #[track_caller]
+    fn case(source: &str, expected: &str) {
+      assert_eq!(parse(source).unwrap_err().to_string(), expected);
+    }
+
+    assert!(parse(&format!(
+      "{{ {} a {} }}",
+      "a { ".repeat(MAX_DEPTH - 1),
+      "}".repeat(MAX_DEPTH - 1),
+    ))
+    .is_ok());
+
+    case(
+      &format!(
+        "{{ {} a {} }}",
+        "a { ".repeat(MAX_DEPTH),
+        "}".repeat(MAX_DEPTH)
+      ),
+      "query exceeds maximum depth of 12",
+    );
+
+    case(
+      &format!(
+        "{{ {} a {} }}",
+        "... { ".repeat(MAX_DEPTH),
+        "}".repeat(MAX_DEPTH)
+      ),
+      "query exceeds maximum depth of 12",
+    );
+
+    case(
+      &format!(
+        "fragment A on B {{ {} a {} }} {{ a }}",
+        "... on B { ".repeat(MAX_DEPTH),
+        "}".repeat(MAX_DEPTH)
+      ),
+      "query exceeds maximum depth of 12",
+    );
+
+    assert!(parse(&format!(
+      "{{ a(b: {}1{}) }}",
+      "[".repeat(MAX_VALUE_DEPTH - 1),
+      "]".repeat(MAX_VALUE_DEPTH - 1),
+    ))
+    .is_ok());
+
+    case(
+      &format!(
+        "{{ a(b: {}1{}) }}",
+        "[".repeat(100_000),
+        "]".repeat(100_000)
+      ),
+      "input value exceeds maximum depth of 32",
+    );
+
+    case(
+      &format!(
+        "{{ a(b: {}1{}) }}",
+        "{a: ".repeat(100_000),
+        "}".repeat(100_000)
+      ),
+      "input value exceeds maximum depth of 32",
+    );
+
+    case(
+      &format!(
+        "query ($a: {}Int{}) {{ a }}",
+        "[".repeat(100_000),
+        "]".repeat(100_000)
+      ),
+      "variable type exceeds maximum depth of 32",
+    );
+  }
+}
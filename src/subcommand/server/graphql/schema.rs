@@ -0,0 +1,333 @@
+use super::*;
+
+pub(crate) struct Type {
+  pub(crate) description: &'static str,
+  pub(crate) fields: &'static [FieldDefinition],
+  pub(crate) name: &'static str,
+}
+
+pub(crate) struct FieldDefinition {
+  pub(crate) arguments: &'static [(&'static str, &'static str)],
+  pub(crate) description: &'static str,
+  pub(crate) name: &'static str,
+  pub(crate) ty: &'static str,
+}
+
+impl FieldDefinition {
+  /// The named type of this field, without list and non-null wrappers.
+  pub(crate) fn named_type(&self) -> &'static str {
+    self.ty.trim_matches(|c| matches!(c, '[' | ']' | '!'))
+  }
+
+  pub(crate) fn is_connection(&self) -> bool {
+    self.named_type().ends_with("Connection")
+  }
+
+  pub(crate) fn is_list(&self) -> bool {
+    self.ty.starts_with('[')
+  }
+}
+
+const PAGE: &[(&str, &str)] = &[("after", "String"), ("first", "Int")];
+
+macro_rules! field {
+  ($name:literal: $ty:expr, $description:expr) => {
+    field!($name(): $ty, $description)
+  };
+  ($name:literal(PAGE): $ty:expr, $description:expr) => {
+    FieldDefinition {
+      arguments: PAGE,
+      description: $description,
+      name: $name,
+      ty: $ty,
+    }
+  };
+  ($name:literal($($argument:literal: $argument_ty:literal),*): $ty:expr, $description:expr) => {
+    FieldDefinition {
+      arguments: &[$(($argument, $argument_ty)),*],
+      description: $description,
+      name: $name,
+      ty: $ty,
+    }
+  };
+}
+
+macro_rules! connection {
+  ($node:literal) => {
+    [
+      Type {
+        description: concat!("A page of `", $node, "` objects."),
+        fields: &[
+          field!("edges": concat!("[", $node, "Edge!]!"), "Nodes in this page, with their cursors."),
+          field!("nodes": concat!("[", $node, "!]!"), "Nodes in this page."),
+          field!("pageInfo": "PageInfo!", "Information for fetching the next page."),
+        ],
+        name: concat!($node, "Connection"),
+      },
+      Type {
+        description: concat!("A `", $node, "` in a page, with its cursor."),
+        fields: &[
+          field!("cursor": "String!", "Pass as `after` to fetch the nodes following this one."),
+          field!("node": concat!($node, "!"), "The node."),
+        ],
+        name: concat!($node, "Edge"),
+      },
+    ]
+  };
+}
+
+const OBJECTS: &[Type] = &[
+  Type {
+    name: "Query",
+    description: "Entry points into the index.",
+    fields: &[
+      field!("address"("address": "String!"): "Address", "Address. Requires `--index-addresses`."),
+      field!("block"("hash": "String", "height": "Int"): "Block", "Block by hash or height."),
+      field!("inscription"("id": "String", "number": "Int"): "Inscription", "Inscription by ID or number."),
+      field!("inscriptions"(PAGE): "InscriptionConnection", "Inscriptions, newest first."),
+      field!("output"("outpoint": "String!"): "Output", "Output by outpoint."),
+      field!("rune"("id": "String", "name": "String"): "Rune", "Rune by ID or name. Requires `--index-runes`."),
+      field!("runes"(PAGE): "RuneConnection", "Runes, newest first. Requires `--index-runes`."),
+      field!("sat"("number": "BigInt!"): "Sat", "Sat by number."),
+      field!("transaction"("txid": "String!"): "Transaction", "Transaction by ID."),
+    ],
+  },
+  Type {
+    name: "Address",
+    description: "An address and the outputs it holds.",
+    fields: &[
+      field!("address": "String!", "Address."),
+      field!("inscriptions"(PAGE): "InscriptionConnection", "Inscriptions on outputs held by this address."),
+      field!("outputs"(PAGE): "OutputConnection", "Unspent outputs held by this address."),
+      field!("runeBalances": "[RuneBalance!]", "Rune balances, summed over outputs held by this address."),
+      field!("satBalance": "BigInt", "Value of outputs held by this address, in sats."),
+    ],
+  },
+  Type {
+    name: "Block",
+    description: "A block in the active chain.",
+    fields: &[
+      field!("hash": "String!", "Block hash."),
+      field!("height": "Int!", "Block height."),
+      field!("inscriptions"(PAGE): "InscriptionConnection", "Inscriptions revealed in this block."),
+      field!("previous": "Block", "Previous block."),
+      field!("runes"(PAGE): "RuneConnection", "Runes etched in this block."),
+      field!("timestamp": "Int", "Block timestamp, in seconds since the Unix epoch."),
+      field!("transactionCount": "Int", "Number of transactions in this block."),
+      field!("transactions"(PAGE): "TransactionConnection", "Transactions in this block."),
+    ],
+  },
+  Type {
+    name: "Inscription",
+    description: "An inscription.",
+    fields: &[
+      field!("address": "String", "Address of the output holding this inscription."),
+      field!("block": "Block", "Block this inscription was revealed in."),
+      field!("charms": "[String!]!", "Charms."),
+      field!("children"(PAGE): "InscriptionConnection", "Children of this inscription."),
+      field!("contentLength": "Int", "Content length in bytes."),
+      field!("contentType": "String", "Content type."),
+      field!("fee": "BigInt!", "Fee paid by the reveal transaction, in sats."),
+      field!("height": "Int!", "Height of the block this inscription was revealed in."),
+      field!("id": "String!", "Inscription ID."),
+      field!("metaprotocol": "String", "Metaprotocol."),
+      field!("number": "Int!", "Inscription number."),
+      field!("output": "Output", "Output holding this inscription."),
+      field!("parents"(PAGE): "InscriptionConnection", "Parents of this inscription."),
+      field!("rune": "Rune", "Rune etched alongside this inscription."),
+      field!("sat": "Sat", "Sat this inscription is on. Requires `--index-sats`."),
+      field!("satpoint": "String!", "Current location."),
+      field!("timestamp": "Int!", "Timestamp of the block this inscription was revealed in."),
+      field!("transaction": "Transaction", "Reveal transaction."),
+      field!("value": "BigInt", "Value of the output holding this inscription, in sats."),
+    ],
+  },
+  Type {
+    name: "Output",
+    description: "A transaction output.",
+    fields: &[
+      field!("address": "String", "Address of the output's script pubkey."),
+      field!("inscriptions": "[Inscription!]", "Inscriptions on this output."),
+      field!("outpoint": "String!", "Outpoint."),
+      field!("runeBalances": "[RuneBalance!]!", "Runes held by this output."),
+      field!("satRanges": "[SatRange!]", "Sat ranges in this output. Requires `--index-sats`."),
+      field!("scriptPubkey": "String!", "Script pubkey, hex encoded."),
+      field!("spent": "Boolean!", "Whether this output has been spent."),
+      field!("transaction": "Transaction", "Transaction that created this output."),
+      field!("value": "BigInt!", "Value in sats."),
+    ],
+  },
+  Type {
+    name: "PageInfo",
+    description: "Information for fetching the next page of a connection.",
+    fields: &[
+      field!("endCursor": "String", "Cursor of the last node in this page."),
+      field!("hasNextPage": "Boolean!", "Whether there are more nodes after this page."),
+    ],
+  },
+  Type {
+    name: "Rune",
+    description: "A rune.",
+    fields: &[
+      field!("block": "Block", "Block this rune was etched in."),
+      field!("burned": "BigInt!", "Amount burned, in the rune's smallest unit."),
+      field!("divisibility": "Int!", "Number of decimal places."),
+      field!("etching": "Transaction", "Etching transaction."),
+      field!("id": "String!", "Rune ID."),
+      field!("mintable": "Boolean!", "Whether this rune can be minted in the next block."),
+      field!("mints": "BigInt!", "Number of mints."),
+      field!("name": "String!", "Spaced rune name."),
+      field!("number": "BigInt!", "Rune number."),
+      field!("parent": "Inscription", "Inscription etched alongside this rune."),
+      field!("premine": "BigInt!", "Premine, in the rune's smallest unit."),
+      field!("supply": "BigInt!", "Supply, in the rune's smallest unit."),
+      field!("symbol": "String", "Currency symbol."),
+      field!("timestamp": "Int!", "Timestamp of the block this rune was etched in."),
+      field!("turbo": "Boolean!", "Whether this rune opted into future protocol changes."),
+    ],
+  },
+  Type {
+    name: "RuneBalance",
+    description: "An amount of a rune.",
+    fields: &[
+      field!("amount": "BigInt!", "Amount, in the rune's smallest unit."),
+      field!("rune": "Rune", "Rune."),
+    ],
+  },
+  Type {
+    name: "Sat",
+    description: "A sat.",
+    fields: &[
+      field!("block": "Block", "Block this sat was mined in."),
+      field!("charms": "[String!]!", "Charms."),
+      field!("decimal": "String!", "Decimal notation."),
+      field!("degree": "String!", "Degree notation."),
+      field!("inscriptions"(PAGE): "InscriptionConnection", "Inscriptions on this sat."),
+      field!("name": "String!", "Name."),
+      field!("number": "BigInt!", "Sat number."),
+      field!("percentile": "String!", "Percentile notation."),
+      field!("rarity": "String!", "Rarity."),
+      field!("satpoint": "String", "Current location. Requires `--index-sats`."),
+    ],
+  },
+  Type {
+    name: "SatRange",
+    description: "A half-open range of sats.",
+    fields: &[
+      field!("end": "BigInt!", "First sat after this range."),
+      field!("start": "BigInt!", "First sat in this range."),
+    ],
+  },
+  Type {
+    name: "Transaction",
+    description: "A transaction.",
+    fields: &[
+      field!("etching": "Rune", "Rune etched by this transaction."),
+      field!("inputs": "[TransactionInput!]!", "Inputs."),
+      field!("inscriptions": "[Inscription!]", "Inscriptions revealed by this transaction."),
+      field!("outputs": "[Output!]", "Outputs."),
+      field!("txid": "String!", "Transaction ID."),
+    ],
+  },
+  Type {
+    name: "TransactionInput",
+    description: "A transaction input.",
+    fields: &[
+      field!("output": "Output", "Output spent by this input. Null for coinbase inputs."),
+      field!("previousOutput": "String!", "Outpoint spent by this input."),
+    ],
+  },
+];
+
+const CONNECTIONS: [[Type; 2]; 4] = [
+  connection!("Inscription"),
+  connection!("Output"),
+  connection!("Rune"),
+  connection!("Transaction"),
+];
+
+pub(crate) fn types() -> impl Iterator<Item = &'static Type> {
+  OBJECTS.iter().chain(CONNECTIONS.iter().flatten())
+}
+
+pub(crate) fn object(name: &str) -> Option<&'static Type> {
+  types().find(|ty| ty.name == name)
+}
+
+/// Render the schema in GraphQL schema definition language.
+pub(crate) fn sdl() -> String {
+  let mut sdl = String::from(
+    "\"\"\"\nUnsigned integer, serialized as a decimal string. Accepts strings and integers as input.\n\"\"\"\nscalar BigInt\n",
+  );
+
+  for ty in types() {
+    writeln!(sdl, "\n\"{}\"\ntype {} {{", ty.description, ty.name).unwrap();
+
+    for field in ty.fields {
+      write!(sdl, "  \"{}\"\n  {}", field.description, field.name).unwrap();
+
+      if !field.arguments.is_empty() {
+        let arguments = field
+          .arguments
+          .iter()
+          .map(|(name, ty)| format!("{name}: {ty}"))
+          .collect::<Vec<String>>()
+          .join(", ");
+
+        write!(sdl, "({arguments})").unwrap();
+      }
+
+      writeln!(sdl, ": {}", field.ty).unwrap();
+    }
+
+    sdl.push_str("}\n");
+  }
+
+  sdl
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SCALARS: &[&str] = &["BigInt", "Boolean", "Int", "String"];
+
+  #[test]
+  fn field_types_are_defined() {
+    for ty in types() {
+      for field in ty.fields {
+        assert!(
+          SCALARS.contains(&field.named_type()) || object(field.named_type()).is_some(),
+          "{}.{} has undefined type {}",
+          ty.name,
+          field.name,
+          field.ty,
+        );
+
+        for (argument, argument_ty) in field.arguments {
+          assert!(
+            SCALARS.contains(&argument_ty.trim_end_matches('!')),
+            "{}.{}({argument}) has non-scalar type {argument_ty}",
+            ty.name,
+            field.name,
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn sdl() {
+    let sdl = super::sdl();
+
+    assert!(sdl.contains(
+      "type Query {\n  \"Address. Requires `--index-addresses`.\"\n  address(address: String!): Address\n"
+    ));
+
+    assert!(sdl.contains(
+      "type InscriptionConnection {\n  \"Nodes in this page, with their cursors.\"\n  edges: [InscriptionEdge!]!\n"
+    ));
+
+    assert!(sdl.contains("  children(after: String, first: Int): InscriptionConnection\n"));
+  }
+}
@@ -0,0 +1,1022 @@
+use {
+  super::*,
+  axum::{handler::Handler, routing::MethodRouter},
+  schemars::{generate::SchemaSettings, json_schema, Schema, SchemaGenerator},
+  serde_json::{json, Map, Value},
+};
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+fn schema<T: JsonSchema + ?Sized>(generator: &mut SchemaGenerator) -> Schema {
+  generator.subschema_for::<T>()
+}
+
+#[derive(Clone, Copy)]
+enum Body {
+  Events,
+  Html,
+  HtmlOrJson(SchemaFn),
+  Json(SchemaFn),
+  Media(&'static str),
+  Redirect,
+  WebSocket,
+}
+
+/// A route served by `ord server`, with the handler that serves it and the
+/// documentation that describes it.
+struct Endpoint {
+  body: Body,
+  method: &'static str,
+  path: &'static str,
+  query: &'static [(&'static str, &'static str)],
+  request: Option<(&'static str, SchemaFn)>,
+  route: MethodRouter<Arc<ServerConfig>>,
+  summary: &'static str,
+}
+
+impl Endpoint {
+  fn get<H: Handler<T, Arc<ServerConfig>>, T: 'static>(
+    path: &'static str,
+    summary: &'static str,
+    body: Body,
+    handler: H,
+  ) -> Self {
+    Self {
+      body,
+      method: "get",
+      path,
+      query: &[],
+      request: None,
+      route: get(handler),
+      summary,
+    }
+  }
+
+  fn post<H: Handler<T, Arc<ServerConfig>>, T: 'static>(
+    path: &'static str,
+    summary: &'static str,
+    request: SchemaFn,
+    body: Body,
+    handler: H,
+  ) -> Self {
+    Self {
+      body,
+      method: "post",
+      path,
+      query: &[],
+      request: Some(("application/json", request)),
+      route: post(handler),
+      summary,
+    }
+  }
+
+  fn post_text<H: Handler<T, Arc<ServerConfig>>, T: 'static>(
+    path: &'static str,
+    summary: &'static str,
+    body: Body,
+    handler: H,
+  ) -> Self {
+    Self {
+      body,
+      method: "post",
+      path,
+      query: &[],
+      request: Some(("text/plain", schema::<String>)),
+      route: post(handler),
+      summary,
+    }
+  }
+
+  fn body_limit(self, limit: usize) -> Self {
+    Self {
+      route: self.route.layer(DefaultBodyLimit::max(limit)),
+      ..self
+    }
+  }
+
+  fn query(self, query: &'static [(&'static str, &'static str)]) -> Self {
+    Self { query, ..self }
+  }
+
+  /// The path in OpenAPI syntax, with `{name}` in place of axum's `:name`
+  /// and `*name` parameters.
+  fn openapi_path(&self) -> String {
+    self
+      .path
+      .split('/')
+      .map(|segment| match segment.strip_prefix([':', '*']) {
+        Some(name) => format!("{{{name}}}"),
+        None => segment.into(),
+      })
+      .collect::<Vec<String>>()
+      .join("/")
+  }
+
+  /// The operation object for this endpoint. Request bodies are described
+  /// by `requests`, which describes how they are deserialized, and responses
+  /// by `responses`, which describes how they are serialized.
+  fn operation(&self, requests: &mut SchemaGenerator, responses: &mut SchemaGenerator) -> Value {
+    let mut parameters = self
+      .path
+      .split('/')
+      .filter_map(|segment| segment.strip_prefix([':', '*']))
+      .map(|name| {
+        json!({
+          "name": name,
+          "in": "path",
+          "required": true,
+          "schema": { "type": "string" },
+        })
+      })
+      .collect::<Vec<Value>>();
+
+    for (name, description) in self.query {
+      parameters.push(json!({
+        "name": name,
+        "in": "query",
+        "description": description,
+        "schema": { "type": "string" },
+      }));
+    }
+
+    let (status, response) = match self.body {
+      Body::Events => (
+        "200",
+        json!({
+          "description": "Stream of server-sent events, each with an event as JSON in its `data` field",
+          "content": { "text/event-stream": { "schema": schema::<api::Event>(responses) } },
+        }),
+      ),
+      Body::Html => (
+        "200",
+        json!({
+          "description": "HTML page",
+          "content": { "text/html": {} },
+        }),
+      ),
+      Body::HtmlOrJson(schema) => (
+        "200",
+        json!({
+          "description": "HTML page, or JSON if requested with `Accept: application/json`",
+          "content": {
+            "text/html": {},
+            "application/json": { "schema": schema(responses) },
+          },
+        }),
+      ),
+      Body::Json(schema) => (
+        "200",
+        json!({
+          "description": "JSON",
+          "content": { "application/json": { "schema": schema(responses) } },
+        }),
+      ),
+      Body::Media(media_type) => (
+        "200",
+        json!({
+          "description": "Content",
+          "content": { media_type: {} },
+        }),
+      ),
+      Body::Redirect => (
+        "303",
+        json!({
+          "description": "Redirect",
+          "headers": { "Location": { "schema": { "type": "string" } } },
+        }),
+      ),
+      Body::WebSocket => {
+        schema::<api::Event>(responses);
+        (
+          "101",
+          json!({
+            "description": "WebSocket connection, sending each event as a JSON text message",
+          }),
+        )
+      }
+    };
+
+    let mut operation = Map::new();
+
+    operation.insert("summary".into(), self.summary.into());
+
+    if !parameters.is_empty() {
+      operation.insert("parameters".into(), parameters.into());
+    }
+
+    if let Some((media_type, request)) = self.request {
+      operation.insert(
+        "requestBody".into(),
+        json!({
+          "required": true,
+          "content": { media_type: { "schema": request(requests) } },
+        }),
+      );
+    }
+
+    operation.insert("responses".into(), json!({ status: response }));
+
+    operation.into()
+  }
+}
+
+/// Every route served by `ord server`. Both the router and the OpenAPI
+/// document are built from this table, so every route is documented.
+fn endpoints() -> Vec<Endpoint> {
+  [
+    Endpoint::get("/", "Home page", Body::Html, Server::home),
+    Endpoint::get(
+      "/address/:address",
+      "Outputs, inscriptions, sat balance, and rune balances of an address. Requires index with `--index-addresses`",
+      Body::HtmlOrJson(schema::<api::AddressInfo>),
+      Server::address,
+    ),
+    Endpoint::get(
+      "/admin/hidden",
+      "Inscriptions and content hidden with `ord admin hide`. Requires admin token in `Ord-Admin-Token` header",
+      Body::Json(schema::<api::Hidden>),
+      Server::admin_hidden,
+    ),
+    Endpoint::post(
+      "/admin/hide",
+      "Hide inscription, and optionally all inscriptions with the same content. Requires admin token in `Ord-Admin-Token` header",
+      schema::<api::Hide>,
+      Body::Json(schema::<api::Hidden>),
+      Server::admin_hide,
+    ),
+    Endpoint::post(
+      "/admin/unhide",
+      "Unhide inscription, and all inscriptions with the same content. Requires admin token in `Ord-Admin-Token` header",
+      schema::<api::Unhide>,
+      Body::Json(schema::<api::Hidden>),
+      Server::admin_unhide,
+    ),
+    Endpoint::get(
+      "/api/address/:address/utxo",
+      "Esplora-compatible unspent outputs of address, with their inscriptions and rune balances. Requires `--enable-esplora` and index with `--index-addresses`",
+      Body::Json(schema::<Vec<esplora::Utxo>>),
+      Server::esplora_utxos,
+    ),
+    Endpoint::get(
+      "/api/block/:hash",
+      "Esplora-compatible block. Requires `--enable-esplora`",
+      Body::Json(schema::<esplora::Block>),
+      Server::esplora_block,
+    ),
+    Endpoint::get(
+      "/api/blocks/tip/height",
+      "Esplora-compatible height of latest block. Requires `--enable-esplora`",
+      Body::Media("text/plain"),
+      Server::esplora_tip_height,
+    ),
+    Endpoint::post_text(
+      "/api/tx",
+      "Esplora-compatible broadcast of hex-encoded raw transaction, unless it would lose inscriptions, burn runes, or spend rare sats as fee. Requires `--enable-esplora` and `--enable-broadcast`",
+      Body::Media("text/plain"),
+      Server::esplora_broadcast,
+    )
+    .query(&[(
+      "force",
+      "Broadcast even if the transaction would lose inscriptions, burn runes, or spend rare sats as fee",
+    )]),
+    Endpoint::get(
+      "/api/tx/:txid",
+      "Esplora-compatible transaction. Requires `--enable-esplora`",
+      Body::Json(schema::<esplora::Transaction>),
+      Server::esplora_transaction,
+    ),
+    Endpoint::get(
+      "/api/tx/:txid/hex",
+      "Esplora-compatible hex-encoded raw transaction. Requires `--enable-esplora`",
+      Body::Media("text/plain"),
+      Server::esplora_transaction_hex,
+    ),
+    Endpoint::get(
+      "/block/:query",
+      "Block by height or hash",
+      Body::HtmlOrJson(schema::<api::Block>),
+      Server::block,
+    ),
+    Endpoint::get(
+      "/blockcount",
+      "Number of blocks indexed",
+      Body::Media("text/plain"),
+      Server::block_count,
+    ),
+    Endpoint::get(
+      "/blockhash",
+      "Hash of latest block",
+      Body::Media("text/plain"),
+      Server::block_hash,
+    ),
+    Endpoint::get(
+      "/blockhash/:height",
+      "Hash of block at height",
+      Body::Media("text/plain"),
+      Server::block_hash_from_height,
+    ),
+    Endpoint::get(
+      "/blockheight",
+      "Height of latest block",
+      Body::Media("text/plain"),
+      Server::block_height,
+    ),
+    Endpoint::get(
+      "/blocks",
+      "Latest blocks and their featured inscriptions",
+      Body::HtmlOrJson(schema::<api::Blocks>),
+      Server::blocks,
+    ),
+    Endpoint::get(
+      "/blocktime",
+      "UNIX timestamp of latest block",
+      Body::Media("text/plain"),
+      Server::block_time,
+    ),
+    Endpoint::get("/bounties", "Bounties documentation", Body::Redirect, Server::bounties),
+    Endpoint::get(
+      "/children/:inscription_id",
+      "Children of inscription",
+      Body::Html,
+      Server::children,
+    ),
+    Endpoint::get(
+      "/children/:inscription_id/:page",
+      "Page of children of inscription",
+      Body::Html,
+      Server::children_paginated,
+    ),
+    Endpoint::get(
+      "/clock",
+      "Clock showing latest block height",
+      Body::Media("image/svg+xml"),
+      Server::clock,
+    ),
+    Endpoint::get("/collections", "Inscriptions with children", Body::Html, Server::collections),
+    Endpoint::get(
+      "/collections/:page",
+      "Page of inscriptions with children",
+      Body::Html,
+      Server::collections_paginated,
+    ),
+    Endpoint::get(
+      "/content/:inscription_id",
+      "Content of inscription, or of its delegate",
+      Body::Media("*/*"),
+      Server::content,
+    ),
+    Endpoint::post(
+      "/decode",
+      "Inscriptions and runestone in raw transaction or PSBT, and the effect of confirming it",
+      schema::<decode::Encoded>,
+      Body::Json(schema::<api::DecodeSimulation>),
+      Server::decode_simulate,
+    ),
+    Endpoint::get(
+      "/decode/:txid",
+      "Inscriptions and runestone in transaction",
+      Body::Json(schema::<api::Decode>),
+      Server::decode,
+    ),
+    Endpoint::get(
+      "/events/stream",
+      "Subscribe to block, inscription, and rune events. Requires `--enable-events`",
+      Body::Events,
+      Server::events_stream,
+    )
+    .query(EVENT_FILTERS),
+    Endpoint::get(
+      "/events/websocket",
+      "Subscribe to block, inscription, and rune events over WebSocket. Requires `--enable-events`",
+      Body::WebSocket,
+      Server::events_websocket,
+    )
+    .query(EVENT_FILTERS),
+    Endpoint::get("/faq", "Frequently asked questions", Body::Redirect, Server::faq),
+    Endpoint::get("/favicon.ico", "Favicon", Body::Media("image/png"), Server::favicon),
+    Endpoint::get(
+      "/feed.xml",
+      "Feed of latest inscriptions",
+      Body::Media("application/rss+xml"),
+      Server::feed,
+    ),
+    Endpoint::post(
+      "/graphql",
+      "Execute GraphQL query. Requires `--enable-graphql`",
+      schema::<graphql::Request>,
+      Body::Json(graphql_response),
+      Server::graphql,
+    )
+    .body_limit(graphql::MAX_REQUEST_SIZE),
+    Endpoint::get(
+      "/graphql/schema",
+      "GraphQL schema definition. Requires `--enable-graphql`",
+      Body::Media("text/plain"),
+      Server::graphql_schema,
+    ),
+    Endpoint::get(
+      "/input/:block/:transaction/:input",
+      "Transaction input",
+      Body::Html,
+      Server::input,
+    ),
+    Endpoint::get(
+      "/inscription/:inscription_query",
+      "Inscription by ID, number, or sat",
+      Body::HtmlOrJson(schema::<api::Inscription>),
+      Server::inscription,
+    ),
+    Endpoint::get(
+      "/inscription/:inscription_query/:child",
+      "Child of inscription by index",
+      Body::HtmlOrJson(schema::<api::Inscription>),
+      Server::inscription_child,
+    ),
+    Endpoint::get(
+      "/inscriptions",
+      "Latest inscriptions",
+      Body::HtmlOrJson(schema::<api::Inscriptions>),
+      Server::inscriptions,
+    ),
+    Endpoint::post(
+      "/inscriptions",
+      "Inscriptions by ID",
+      schema::<Vec<InscriptionId>>,
+      Body::Json(schema::<Vec<api::Inscription>>),
+      Server::inscriptions_json,
+    ),
+    Endpoint::get(
+      "/inscriptions/:page",
+      "Page of inscriptions",
+      Body::HtmlOrJson(schema::<api::Inscriptions>),
+      Server::inscriptions_paginated,
+    ),
+    Endpoint::get(
+      "/inscriptions/block/:height",
+      "Inscriptions in block",
+      Body::HtmlOrJson(schema::<api::Inscriptions>),
+      Server::inscriptions_in_block,
+    ),
+    Endpoint::get(
+      "/inscriptions/block/:height/:page",
+      "Page of inscriptions in block",
+      Body::HtmlOrJson(schema::<api::Inscriptions>),
+      Server::inscriptions_in_block_paginated,
+    ),
+    Endpoint::get("/install.sh", "Install script", Body::Redirect, Server::install_script),
+    Endpoint::get("/openapi.json", "This document", Body::Json(schema::<Value>), Server::openapi),
+    Endpoint::get("/ordinal/:sat", "Sat", Body::Redirect, Server::ordinal),
+    Endpoint::get(
+      "/output/:output",
+      "Output",
+      Body::HtmlOrJson(schema::<api::Output>),
+      Server::output,
+    ),
+    Endpoint::post(
+      "/outputs",
+      "Outputs by outpoint",
+      schema::<Vec<crate::schema::OutPoint>>,
+      Body::Json(schema::<Vec<api::Output>>),
+      Server::outputs,
+    ),
+    Endpoint::get(
+      "/outputs/:address",
+      "Outputs of address. Requires index with `--index-addresses`",
+      Body::Json(schema::<Vec<api::Output>>),
+      Server::outputs_address,
+    )
+    .query(&[(
+      "type",
+      "Only return `any`, `cardinal`, `inscribed`, or `runic` outputs",
+    )]),
+    Endpoint::get(
+      "/parents/:inscription_id",
+      "Parents of inscription",
+      Body::Html,
+      Server::parents,
+    ),
+    Endpoint::get(
+      "/parents/:inscription_id/:page",
+      "Page of parents of inscription",
+      Body::Html,
+      Server::parents_paginated,
+    ),
+    Endpoint::get(
+      "/preview/:inscription_id",
+      "Preview of inscription content",
+      Body::Html,
+      Server::preview,
+    ),
+    Endpoint::get(
+      "/r/blockhash",
+      "Hash of latest block",
+      Body::Json(schema::<String>),
+      Server::block_hash_json,
+    ),
+    Endpoint::get(
+      "/r/blockhash/:height",
+      "Hash of block at height",
+      Body::Json(schema::<String>),
+      Server::block_hash_from_height_json,
+    ),
+    Endpoint::get(
+      "/r/blockheight",
+      "Height of latest block",
+      Body::Media("text/plain"),
+      Server::block_height,
+    ),
+    Endpoint::get(
+      "/r/blockinfo/:query",
+      "Block info by height or hash",
+      Body::Json(schema::<api::BlockInfo>),
+      Server::block_info,
+    ),
+    Endpoint::get(
+      "/r/blocktime",
+      "UNIX timestamp of latest block",
+      Body::Media("text/plain"),
+      Server::block_time,
+    ),
+    Endpoint::get(
+      "/r/children/:inscription_id",
+      "First 100 child inscription IDs",
+      Body::Json(schema::<api::Children>),
+      Server::children_recursive,
+    ),
+    Endpoint::get(
+      "/r/children/:inscription_id/:page",
+      "Page of child inscription IDs",
+      Body::Json(schema::<api::Children>),
+      Server::children_recursive_paginated,
+    ),
+    Endpoint::get(
+      "/r/children/:inscription_id/inscriptions",
+      "First 100 child inscriptions",
+      Body::Json(schema::<api::ChildInscriptions>),
+      Server::child_inscriptions_recursive,
+    ),
+    Endpoint::get(
+      "/r/children/:inscription_id/inscriptions/:page",
+      "Page of child inscriptions",
+      Body::Json(schema::<api::ChildInscriptions>),
+      Server::child_inscriptions_recursive_paginated,
+    ),
+    Endpoint::get(
+      "/r/inscription/:inscription_id",
+      "Inscription",
+      Body::Json(schema::<api::InscriptionRecursive>),
+      Server::inscription_recursive,
+    ),
+    Endpoint::get(
+      "/r/metadata/:inscription_id",
+      "Hex-encoded CBOR metadata of inscription",
+      Body::Json(schema::<String>),
+      Server::metadata,
+    ),
+    Endpoint::get(
+      "/r/parents/:inscription_id",
+      "First 100 parent inscription IDs",
+      Body::Json(schema::<api::Inscriptions>),
+      Server::parents_recursive,
+    ),
+    Endpoint::get(
+      "/r/parents/:inscription_id/:page",
+      "Page of parent inscription IDs",
+      Body::Json(schema::<api::Inscriptions>),
+      Server::parents_recursive_paginated,
+    ),
+    Endpoint::get(
+      "/r/rune/:rune",
+      "Rune by name, ID, or number, with supply. Requires index with `--index-runes`",
+      Body::Json(schema::<api::RuneRecursive>),
+      Server::rune_recursive,
+    ),
+    Endpoint::get(
+      "/r/rune/:rune/balance/:outpoint",
+      "Balance of rune in output. Requires index with `--index-runes`",
+      Body::Json(schema::<Pile>),
+      Server::rune_balance_recursive,
+    ),
+    Endpoint::get(
+      "/r/sat/:sat_number",
+      "First 100 inscription IDs on sat. Requires index with `--index-sats`",
+      Body::Json(schema::<api::SatInscriptions>),
+      Server::sat_inscriptions,
+    ),
+    Endpoint::get(
+      "/r/sat/:sat_number/:page",
+      "Page of inscription IDs on sat. Requires index with `--index-sats`",
+      Body::Json(schema::<api::SatInscriptions>),
+      Server::sat_inscriptions_paginated,
+    ),
+    Endpoint::get(
+      "/r/sat/:sat_number/at/:index",
+      "Inscription ID on sat at index. Requires index with `--index-sats`",
+      Body::Json(schema::<api::SatInscription>),
+      Server::sat_inscription_at_index,
+    ),
+    Endpoint::get(
+      "/r/undelegated-content/:inscription_id",
+      "Content of inscription, ignoring delegate",
+      Body::Media("*/*"),
+      Server::undelegated_content,
+    ),
+    Endpoint::get(
+      "/r/utxo/:outpoint",
+      "Value, inscriptions, and rune balances of output",
+      Body::Json(schema::<api::UtxoRecursive>),
+      Server::utxo_recursive,
+    ),
+    Endpoint::get(
+      "/rare.txt",
+      "Rare sats and their locations",
+      Body::Media("text/plain"),
+      Server::rare_txt,
+    ),
+    Endpoint::get(
+      "/rune/:rune",
+      "Rune by name, ID, or number",
+      Body::HtmlOrJson(schema::<api::Rune>),
+      Server::rune,
+    ),
+    Endpoint::get(
+      "/runes",
+      "Latest runes",
+      Body::HtmlOrJson(schema::<api::Runes>),
+      Server::runes,
+    ),
+    Endpoint::get(
+      "/runes/:page",
+      "Page of runes",
+      Body::HtmlOrJson(schema::<api::Runes>),
+      Server::runes_paginated,
+    ),
+    Endpoint::get("/sat/:sat", "Sat", Body::HtmlOrJson(schema::<api::Sat>), Server::sat),
+    Endpoint::get("/satpoint/:satpoint", "Sat at satpoint", Body::Redirect, Server::satpoint),
+    Endpoint::get("/search", "Search", Body::Redirect, Server::search_by_query)
+      .query(&[("query", "Search query")]),
+    Endpoint::get("/search/*query", "Search", Body::Redirect, Server::search_by_path),
+    Endpoint::get("/static/*path", "Static asset", Body::Media("*/*"), Server::static_asset),
+    Endpoint::get(
+      "/status",
+      "Server status",
+      Body::HtmlOrJson(schema::<api::Status>),
+      Server::status,
+    ),
+    Endpoint::post(
+      "/tx",
+      "Broadcast raw transaction or finalized PSBT, unless it would lose inscriptions, burn runes, or spend rare sats as fee. Requires `--enable-broadcast`",
+      schema::<decode::Encoded>,
+      Body::Json(schema::<crate::schema::Hash>),
+      Server::broadcast,
+    )
+    .query(&[(
+      "force",
+      "Broadcast even if the transaction would lose inscriptions, burn runes, or spend rare sats as fee",
+    )]),
+    Endpoint::get(
+      "/tx/:txid",
+      "Transaction",
+      Body::HtmlOrJson(schema::<api::Transaction>),
+      Server::transaction,
+    ),
+    Endpoint::get(
+      "/update",
+      "Update the index. Only available in integration tests",
+      Body::Media("text/plain"),
+      Server::update,
+    ),
+  ]
+  .into()
+}
+
+const EVENT_FILTERS: &[(&str, &str)] = &[
+  (
+    "address",
+    "Only send events for inscriptions and runes moved to address",
+  ),
+  (
+    "inscription",
+    "Only send events for inscription and its children",
+  ),
+  ("rune", "Only send events for rune name, ID, or number"),
+];
+
+/// GraphQL responses contain `data`, `errors`, or both, with the shape of
+/// `data` determined by the query.
+fn graphql_response(_: &mut SchemaGenerator) -> Schema {
+  json_schema!({
+    "type": "object",
+    "properties": {
+      "data": { "type": ["object", "null"] },
+      "errors": {
+        "type": "array",
+        "items": {
+          "type": "object",
+          "properties": {
+            "message": { "type": "string" },
+            "path": {
+              "type": "array",
+              "items": { "anyOf": [{ "type": "string" }, { "type": "integer", "minimum": 0 }] },
+            },
+          },
+          "required": ["message"],
+          "additionalProperties": false,
+        },
+      },
+    },
+    "additionalProperties": false,
+  })
+}
+
+/// The router serving every endpoint.
+pub(super) fn router() -> Router<Arc<ServerConfig>> {
+  endpoints()
+    .into_iter()
+    .fold(Router::new(), |router, endpoint| {
+      router.route(endpoint.path, endpoint.route)
+    })
+}
+
+/// The OpenAPI document describing every endpoint, with schemas derived from
+/// the request and response types.
+pub(super) fn document() -> Value {
+  let settings = SchemaSettings::draft2020_12().with(|settings| {
+    settings.definitions_path = "/components/schemas".into();
+    settings.meta_schema = None;
+  });
+
+  let mut requests = settings.clone().for_deserialize().into_generator();
+  let mut responses = settings.for_serialize().into_generator();
+
+  let mut paths = Map::new();
+
+  for endpoint in endpoints() {
+    let operation = endpoint.operation(&mut requests, &mut responses);
+
+    paths
+      .entry(endpoint.openapi_path())
+      .or_insert_with(|| Value::Object(Map::new()))
+      .as_object_mut()
+      .unwrap()
+      .insert(endpoint.method.into(), operation);
+  }
+
+  let mut schemas = responses.take_definitions(true);
+
+  for (name, schema) in requests.take_definitions(true) {
+    if let Some(previous) = schemas.insert(name.clone(), schema.clone()) {
+      assert_eq!(
+        previous, schema,
+        "request schema {name} differs from response schema"
+      );
+    }
+  }
+
+  json!({
+    "openapi": "3.1.0",
+    "info": {
+      "title": "ord",
+      "description": "Ordinal explorer and JSON API",
+      "version": env!("CARGO_PKG_VERSION"),
+    },
+    "paths": paths,
+    "components": { "schemas": schemas },
+  })
+}
+
+/// Check `value` against `schema`, resolving references in `document`.
+/// Supports the subset of JSON Schema used by `document`.
+#[cfg(test)]
+pub(super) fn validate(document: &Value, schema: &Value, value: &Value) -> Result<(), String> {
+  validate_at(document, schema, value, "$")
+}
+
+#[cfg(test)]
+fn validate_at(document: &Value, schema: &Value, value: &Value, at: &str) -> Result<(), String> {
+  if let Some(reference) = schema["$ref"].as_str() {
+    let name = reference.strip_prefix("#/components/schemas/").unwrap();
+    return validate_at(
+      document,
+      &document["components"]["schemas"][name],
+      value,
+      at,
+    );
+  }
+
+  if let Some(any_of) = schema["anyOf"].as_array() {
+    return any_of
+      .iter()
+      .any(|schema| validate_at(document, schema, value, at).is_ok())
+      .then_some(())
+      .ok_or_else(|| format!("{at}: {value} matches none of {schema}"));
+  }
+
+  if let Some(one_of) = schema["oneOf"].as_array() {
+    let matches = one_of
+      .iter()
+      .filter(|schema| validate_at(document, schema, value, at).is_ok())
+      .count();
+
+    return (matches == 1)
+      .then_some(())
+      .ok_or_else(|| format!("{at}: {value} matches {matches} of {schema}"));
+  }
+
+  if let Some(constant) = schema.get("const") {
+    if value != constant {
+      return Err(format!("{at}: expected {constant}, got {value}"));
+    }
+  }
+
+  if let Some(variants) = schema["enum"].as_array() {
+    if !variants.contains(value) {
+      return Err(format!("{at}: {value} is not one of {variants:?}"));
+    }
+  }
+
+  let types = match &schema["type"] {
+    Value::String(ty) => vec![ty.as_str()],
+    Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+    _ => return Ok(()),
+  };
+
+  let matches = types.iter().any(|ty| match *ty {
+    "array" => value.is_array(),
+    "boolean" => value.is_boolean(),
+    "integer" => value.is_i64() || value.is_u64(),
+    "null" => value.is_null(),
+    "number" => value.is_number(),
+    "object" => value.is_object(),
+    "string" => value.is_string(),
+    _ => panic!("unsupported type {ty}"),
+  });
+
+  if !matches {
+    return Err(format!(
+      "{at}: expected {}, got {value}",
+      types.join(" or ")
+    ));
+  }
+
+  if let Some(minimum) = schema["minimum"].as_i64() {
+    if value.as_i64().is_some_and(|value| value < minimum) {
+      return Err(format!("{at}: {value} is less than {minimum}"));
+    }
+  }
+
+  if let (Some(pattern), Some(string)) = (schema["pattern"].as_str(), value.as_str()) {
+    if !Regex::new(pattern).unwrap().is_match(string) {
+      return Err(format!("{at}: `{string}` does not match `{pattern}`"));
+    }
+  }
+
+  if let Some(array) = value.as_array() {
+    if let Some(min) = schema["minItems"].as_u64() {
+      if (array.len() as u64) < min {
+        return Err(format!("{at}: fewer than {min} items"));
+      }
+    }
+
+    if let Some(max) = schema["maxItems"].as_u64() {
+      if array.len() as u64 > max {
+        return Err(format!("{at}: more than {max} items"));
+      }
+    }
+
+    let prefix_items = schema["prefixItems"]
+      .as_array()
+      .cloned()
+      .unwrap_or_default();
+
+    for (i, item) in array.iter().enumerate() {
+      let item_schema = prefix_items.get(i).unwrap_or(&schema["items"]);
+      validate_at(document, item_schema, item, &format!("{at}[{i}]"))?;
+    }
+  }
+
+  if let Some(object) = value.as_object() {
+    for required in schema["required"].as_array().into_iter().flatten() {
+      if !object.contains_key(required.as_str().unwrap()) {
+        return Err(format!("{at}: missing field {required}"));
+      }
+    }
+
+    for (key, value) in object {
+      let at = format!("{at}.{key}");
+
+      let pattern_property = schema["patternProperties"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .find(|(pattern, _)| Regex::new(pattern).unwrap().is_match(key));
+
+      if let Some(property) = schema["properties"].get(key) {
+        validate_at(document, property, value, &at)?;
+      } else if let Some((_, property)) = pattern_property {
+        validate_at(document, property, value, &at)?;
+      } else {
+        match &schema["additionalProperties"] {
+          Value::Bool(false) => return Err(format!("{at}: unexpected field")),
+          Value::Object(_) => validate_at(document, &schema["additionalProperties"], value, &at)?,
+          _ => {}
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn endpoints_are_unique() {
+    let endpoints = endpoints();
+
+    assert!(endpoints.len() > 50);
+
+    let routes = endpoints
+      .iter()
+      .map(|endpoint| (endpoint.path, endpoint.method))
+      .collect::<BTreeSet<(&str, &str)>>();
+
+    assert_eq!(routes.len(), endpoints.len());
+
+    let document = document();
+
+    assert_eq!(
+      document["paths"]
+        .as_object()
+        .unwrap()
+        .values()
+        .map(|path| path.as_object().unwrap().len())
+        .sum::<usize>(),
+      endpoints.len(),
+    );
+  }
+
+  #[test]
+  fn openapi_paths() {
+    assert_eq!(
+      Endpoint::get("/r/sat/:sat_number/at/:index", "", Body::Html, Server::home).openapi_path(),
+      "/r/sat/{sat_number}/at/{index}",
+    );
+
+    assert_eq!(
+      Endpoint::get("/static/*path", "", Body::Html, Server::static_asset).openapi_path(),
+      "/static/{path}",
+    );
+  }
+
+  #[test]
+  fn document_references_are_defined() {
+    let document = document();
+
+    let schemas = document["components"]["schemas"].as_object().unwrap();
+
+    let references = Regex::new(r##""\$ref":"#/components/schemas/(\w+)""##)
+      .unwrap()
+      .captures_iter(&document.to_string())
+      .map(|captures| captures[1].to_string())
+      .collect::<BTreeSet<String>>();
+
+    assert!(!references.is_empty());
+
+    for reference in references {
+      assert!(schemas.contains_key(&reference), "{reference}");
+    }
+
+    assert!(schemas.values().all(|schema| !schema.is_null()));
+  }
+
+  #[test]
+  fn validate_rejects_mismatches() {
+    let document = document();
+
+    let schema = json!({ "$ref": "#/components/schemas/Inscriptions" });
+
+    assert!(validate(
+      &document,
+      &schema,
+      &json!({ "ids": [], "more": false, "page_index": 0 }),
+    )
+    .is_ok());
+
+    assert_eq!(
+      validate(&document, &schema, &json!({ "ids": [], "more": false })),
+      Err("$: missing field \"page_index\"".into()),
+    );
+
+    assert_eq!(
+      validate(
+        &document,
+        &schema,
+        &json!({ "ids": ["foo"], "more": false, "page_index": 0, "bar": 1 }),
+      ),
+      Err("$.ids[0]: `foo` does not match `^[0-9a-f]{64}i[0-9]+$`".into()),
+    );
+  }
+}
@@ -70,3 +70,13 @@ impl FromStr for Rune {
     }
   }
 }
+
+impl Display for Rune {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Spaced(spaced_rune) => write!(f, "{spaced_rune}"),
+      Self::Id(id) => write!(f, "{id}"),
+      Self::Number(number) => write!(f, "{number}"),
+    }
+  }
+}
@@ -0,0 +1,372 @@
+use {
+  super::*,
+  axum::http::Method,
+  std::{collections::HashMap, net::IpAddr},
+};
+
+/// Costs of routes which do more work per request than most, keyed by method
+/// and route. Overridden by `route_costs` in settings.
+const DEFAULT_ROUTE_COSTS: &[(&str, u64)] = &[("POST /inscriptions", 10), ("POST /outputs", 10)];
+
+const ANONYMOUS: &str = "anonymous";
+
+const MAX_CLIENTS: usize = 100_000;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Rejection {
+  InvalidKey,
+  QuotaExceeded { retry_after: u64 },
+  RateLimited { retry_after: u64 },
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Client {
+  Anonymous(IpAddr),
+  Key(String),
+}
+
+struct Bucket {
+  day: u64,
+  quota_used: u64,
+  refilled: Instant,
+  tokens: f64,
+}
+
+struct Key {
+  name: String,
+  quota: Option<u64>,
+  rate_limit: Option<u64>,
+}
+
+/// Per-client token buckets refilled at each client's rate limit, in cost per
+/// minute, along with daily quotas for API keys.
+pub struct RateLimiter {
+  anonymous_rate_limit: Option<u64>,
+  buckets: Mutex<HashMap<Client, Bucket>>,
+  costs: HashMap<String, u64>,
+  keys: HashMap<String, Key>,
+  usage: Mutex<BTreeMap<String, api::Usage>>,
+}
+
+impl RateLimiter {
+  /// Returns `None` if neither API keys nor an anonymous rate limit are
+  /// configured, in which case requests are not limited.
+  pub(super) fn new(settings: &Settings) -> Result<Option<Self>> {
+    let mut keys = HashMap::new();
+
+    for (name, api_key) in settings.api_keys() {
+      ensure!(
+        name != ANONYMOUS,
+        "API key name `{ANONYMOUS}` is reserved for anonymous clients"
+      );
+
+      ensure!(
+        api_key.rate_limit != Some(0),
+        "API key `{name}` has rate limit of zero"
+      );
+
+      let previous = keys.insert(
+        api_key.key.clone(),
+        Key {
+          name: name.into(),
+          quota: api_key.quota,
+          rate_limit: api_key.rate_limit,
+        },
+      );
+
+      ensure!(previous.is_none(), "API key `{name}` is not unique");
+    }
+
+    ensure!(
+      settings.anonymous_rate_limit() != Some(0),
+      "anonymous rate limit of zero"
+    );
+
+    if keys.is_empty() && settings.anonymous_rate_limit().is_none() {
+      return Ok(None);
+    }
+
+    let mut costs = DEFAULT_ROUTE_COSTS
+      .iter()
+      .map(|(route, cost)| (route.to_string(), *cost))
+      .collect::<HashMap<String, u64>>();
+
+    for (route, cost) in settings.route_costs() {
+      costs.insert(route.into(), cost);
+    }
+
+    Ok(Some(Self {
+      anonymous_rate_limit: settings.anonymous_rate_limit(),
+      buckets: Mutex::new(HashMap::new()),
+      costs,
+      keys,
+      usage: Mutex::new(BTreeMap::new()),
+    }))
+  }
+
+  pub(super) fn cost(&self, method: &Method, route: &str) -> u64 {
+    self
+      .costs
+      .get(&format!("{method} {route}"))
+      .copied()
+      .unwrap_or(1)
+  }
+
+  /// Charge `cost` to the client identified by `key`, or by `ip` for
+  /// anonymous clients. `now` is used to refill rate limit buckets, and
+  /// `timestamp`, in seconds since the unix epoch, to reset daily quotas.
+  pub(super) fn check(
+    &self,
+    key: Option<&str>,
+    ip: IpAddr,
+    cost: u64,
+    now: Instant,
+    timestamp: u64,
+  ) -> Result<(), Rejection> {
+    let (client, name, rate_limit, quota) = match key {
+      Some(key) => {
+        let key = self.keys.get(key).ok_or(Rejection::InvalidKey)?;
+        (
+          Client::Key(key.name.clone()),
+          key.name.as_str(),
+          key.rate_limit,
+          key.quota,
+        )
+      }
+      None => (
+        Client::Anonymous(ip),
+        ANONYMOUS,
+        self.anonymous_rate_limit,
+        None,
+      ),
+    };
+
+    let result = if rate_limit.is_none() && quota.is_none() {
+      Ok(())
+    } else {
+      self.charge(client, rate_limit, quota, cost, now, timestamp)
+    };
+
+    let mut usage = self.usage.lock().unwrap();
+    let usage = usage.entry(name.into()).or_default();
+
+    if result.is_ok() {
+      usage.cost += cost;
+      usage.requests += 1;
+    } else {
+      usage.limited += 1;
+    }
+
+    result
+  }
+
+  fn charge(
+    &self,
+    client: Client,
+    rate_limit: Option<u64>,
+    quota: Option<u64>,
+    cost: u64,
+    now: Instant,
+    timestamp: u64,
+  ) -> Result<(), Rejection> {
+    let mut buckets = self.buckets.lock().unwrap();
+
+    if buckets.len() >= MAX_CLIENTS && !buckets.contains_key(&client) {
+      // anonymous buckets untouched for a minute have refilled completely, so
+      // forgetting them doesn't change how their clients are limited
+      buckets.retain(|client, bucket| {
+        matches!(client, Client::Key(_))
+          || now.saturating_duration_since(bucket.refilled) < Duration::from_secs(60)
+      });
+    }
+
+    let day = timestamp / SECONDS_PER_DAY;
+
+    let bucket = buckets.entry(client).or_insert(Bucket {
+      day,
+      quota_used: 0,
+      refilled: now,
+      tokens: rate_limit.unwrap_or_default() as f64,
+    });
+
+    if bucket.day != day {
+      bucket.day = day;
+      bucket.quota_used = 0;
+    }
+
+    if let Some(quota) = quota {
+      if bucket.quota_used + cost > quota {
+        return Err(Rejection::QuotaExceeded {
+          retry_after: (day + 1) * SECONDS_PER_DAY - timestamp,
+        });
+      }
+    }
+
+    if let Some(rate_limit) = rate_limit {
+      let rate_limit = rate_limit as f64;
+
+      bucket.tokens = (bucket.tokens
+        + now.saturating_duration_since(bucket.refilled).as_secs_f64() * rate_limit / 60.0)
+        .min(rate_limit);
+      bucket.refilled = now;
+
+      if bucket.tokens < cost as f64 {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let retry_after = ((cost as f64 - bucket.tokens) * 60.0 / rate_limit).ceil() as u64;
+
+        return Err(Rejection::RateLimited { retry_after });
+      }
+
+      bucket.tokens -= cost as f64;
+    }
+
+    bucket.quota_used += cost;
+
+    Ok(())
+  }
+
+  pub(super) fn usage(&self) -> BTreeMap<String, api::Usage> {
+    self.usage.lock().unwrap().clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+  fn rate_limiter(config: &str) -> RateLimiter {
+    RateLimiter::new(
+      &serde_yaml::from_str::<Settings>(config)
+        .unwrap()
+        .or_defaults()
+        .unwrap(),
+    )
+    .unwrap()
+    .unwrap()
+  }
+
+  #[test]
+  fn disabled_without_keys_or_anonymous_rate_limit() {
+    assert!(
+      RateLimiter::new(&Settings::default().or_defaults().unwrap())
+        .unwrap()
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn anonymous_clients_are_limited_per_ip() {
+    let rate_limiter = rate_limiter("anonymous_rate_limit: 2");
+
+    let now = Instant::now();
+
+    assert_eq!(rate_limiter.check(None, IP, 1, now, 0), Ok(()));
+    assert_eq!(rate_limiter.check(None, IP, 1, now, 0), Ok(()));
+    assert_eq!(
+      rate_limiter.check(None, IP, 1, now, 0),
+      Err(Rejection::RateLimited { retry_after: 30 }),
+    );
+
+    assert_eq!(
+      rate_limiter.check(None, IpAddr::V4(std::net::Ipv4Addr::BROADCAST), 1, now, 0),
+      Ok(()),
+    );
+
+    assert_eq!(
+      rate_limiter.check(None, IP, 1, now + Duration::from_secs(30), 0),
+      Ok(()),
+    );
+
+    assert_eq!(
+      rate_limiter.usage(),
+      [(
+        ANONYMOUS.into(),
+        api::Usage {
+          cost: 4,
+          limited: 1,
+          requests: 4,
+        }
+      )]
+      .into(),
+    );
+  }
+
+  #[test]
+  fn api_keys_have_rate_limits_and_quotas() {
+    let rate_limiter = rate_limiter(
+      "
+anonymous_rate_limit: 1
+api_keys:
+  alice:
+    key: foo
+    rate_limit: 60
+    quota: 15
+  bob:
+    key: bar
+",
+    );
+
+    let now = Instant::now();
+
+    assert_eq!(
+      rate_limiter.check(Some("baz"), IP, 1, now, 0),
+      Err(Rejection::InvalidKey),
+    );
+
+    assert_eq!(rate_limiter.check(Some("foo"), IP, 10, now, 0), Ok(()));
+    assert_eq!(
+      rate_limiter.check(Some("foo"), IP, 10, now, 0),
+      Err(Rejection::QuotaExceeded {
+        retry_after: SECONDS_PER_DAY
+      }),
+    );
+    assert_eq!(rate_limiter.check(Some("foo"), IP, 5, now, 0), Ok(()));
+
+    assert_eq!(
+      rate_limiter.check(Some("foo"), IP, 1, now, SECONDS_PER_DAY),
+      Ok(())
+    );
+
+    for _ in 0..100 {
+      assert_eq!(rate_limiter.check(Some("bar"), IP, 10, now, 0), Ok(()));
+    }
+
+    assert_eq!(rate_limiter.usage()["alice"].cost, 16);
+    assert_eq!(rate_limiter.usage()["bob"].requests, 100);
+  }
+
+  #[test]
+  fn route_costs_can_be_configured() {
+    let rate_limiter = rate_limiter(
+      "
+anonymous_rate_limit: 1
+route_costs:
+  GET /status: 5
+",
+    );
+
+    assert_eq!(rate_limiter.cost(&Method::GET, "/status"), 5);
+    assert_eq!(rate_limiter.cost(&Method::GET, "/blockcount"), 1);
+    assert_eq!(rate_limiter.cost(&Method::POST, "/outputs"), 10);
+  }
+
+  #[test]
+  fn anonymous_name_is_reserved() {
+    assert_eq!(
+      RateLimiter::new(
+        &serde_yaml::from_str::<Settings>("api_keys: {anonymous: {key: foo}}")
+          .unwrap()
+          .or_defaults()
+          .unwrap(),
+      )
+      .err()
+      .unwrap()
+      .to_string(),
+      "API key name `anonymous` is reserved for anonymous clients",
+    );
+  }
+}
@@ -2,10 +2,14 @@ use {super::*, axum::http::HeaderName};
 
 #[derive(Default)]
 pub struct ServerConfig {
+  pub broadcast_enabled: bool,
   pub chain: Chain,
   pub csp_origin: Option<String>,
   pub decompress: bool,
   pub domain: Option<String>,
+  pub esplora_enabled: bool,
+  pub events_enabled: bool,
+  pub graphql_enabled: bool,
   pub index_sats: bool,
   pub json_api_enabled: bool,
   pub proxy: Option<Url>,
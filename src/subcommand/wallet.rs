@@ -1,39 +1,81 @@
 use {
   super::*,
-  crate::wallet::{batch, wallet_constructor::WalletConstructor, ListDescriptorsResult, Wallet},
+  crate::wallet::{
+    batch, postage, protection::Protection, wallet_constructor::WalletConstructor,
+    ListDescriptorsResult, Wallet,
+  },
+  coin_control::CoinControl,
   shared_args::SharedArgs,
 };
 
 pub mod addresses;
 pub mod balance;
 mod batch_command;
+pub mod bump;
 pub mod burn;
 pub mod cardinals;
+mod coin_control;
 pub mod create;
 pub mod dump;
+pub mod extract;
 pub mod inscribe;
 pub mod inscriptions;
-mod label;
+pub mod label;
 pub mod mint;
+pub mod offer;
 pub mod outputs;
 pub mod pending;
 pub mod receive;
+pub mod resize_postage;
 pub mod restore;
 pub mod resume;
 pub mod runics;
 pub mod sats;
 pub mod send;
+pub mod server;
 mod shared_args;
 pub mod sign;
 pub mod split;
+pub mod sweep;
 pub mod transactions;
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Backend {
+  #[value(help = "Bitcoin Core wallet")]
+  Core,
+  #[value(
+    help = "Keys stored unencrypted in ord wallet database, outputs from ord server with \
+    `--index-addresses`"
+  )]
+  Ord,
+}
+
 #[derive(Debug, Parser)]
 pub(crate) struct WalletCommand {
+  #[arg(
+    long,
+    help = "Abort if transaction fees exceed <MAX_FEE>.",
+    value_name = "MAX_FEE"
+  )]
+  pub(crate) max_fee: Option<Amount>,
+  #[arg(
+    long,
+    help = "Abort if transaction fee rates exceed <MAX_FEE_RATE> sats/vB."
+  )]
+  pub(crate) max_fee_rate: Option<FeeRate>,
   #[arg(long, default_value = "ord", help = "Use wallet named <WALLET>.")]
   pub(crate) name: String,
   #[arg(long, alias = "nosync", help = "Do not update index.")]
   pub(crate) no_sync: bool,
+  #[arg(long, help = "Do not spend sats with <CHARM>.", value_name = "CHARM")]
+  pub(crate) protect_charm: Vec<Charm>,
+  #[arg(long, help = "Do not spend sats of <RARITY> or rarer.")]
+  pub(crate) protect_rarity: Option<Rarity>,
+  #[arg(
+    long,
+    help = "Do not spend sats listed in first column of tab-separated value file <TSV>."
+  )]
+  pub(crate) protect_sats: Option<PathBuf>,
   #[arg(
     long,
     help = "Use ord running at <SERVER_URL>. [default: http://localhost:80]"
@@ -52,7 +94,9 @@ pub(crate) enum Subcommand {
   Balance,
   #[command(about = "Create inscriptions and runes")]
   Batch(batch_command::Batch),
-  #[command(about = "Burn an inscription")]
+  #[command(about = "Bump fee of an unconfirmed transaction")]
+  Bump(bump::Bump),
+  #[command(about = "Burn an inscription or runes")]
   Burn(burn::Burn),
   #[command(about = "List unspent cardinal outputs in wallet")]
   Cardinals,
@@ -60,20 +104,26 @@ pub(crate) enum Subcommand {
   Create(create::Create),
   #[command(about = "Dump wallet descriptors")]
   Dump,
+  #[command(about = "Extract sats into dedicated outputs")]
+  Extract(extract::Extract),
   #[command(about = "Create inscription")]
   Inscribe(inscribe::Inscribe),
   #[command(about = "List wallet inscriptions")]
   Inscriptions,
-  #[command(about = "Export output labels")]
-  Label,
+  #[command(about = "Export and import BIP-329 labels")]
+  Label(label::Label),
   #[command(about = "Mint a rune")]
   Mint(mint::Mint),
+  #[command(subcommand, about = "Offer commands")]
+  Offer(offer::Offer),
   #[command(about = "List all unspent outputs in wallet")]
   Outputs(outputs::Outputs),
   #[command(about = "List pending etchings")]
   Pending(pending::Pending),
   #[command(about = "Generate receive address")]
   Receive(receive::Receive),
+  #[command(about = "Resize postage of inscription outputs")]
+  ResizePostage(resize_postage::ResizePostage),
   #[command(about = "Restore wallet")]
   Restore(restore::Restore),
   #[command(about = "Resume pending etchings")]
@@ -84,14 +134,32 @@ pub(crate) enum Subcommand {
   Sats(sats::Sats),
   #[command(about = "Send sat or inscription")]
   Send(send::Send),
+  #[command(about = "Run wallet JSON API server")]
+  Server(server::Server),
   #[command(about = "Sign message")]
   Sign(sign::Sign),
   #[command(about = "Split outputs")]
   Split(split::Split),
+  #[command(about = "Sweep inscriptions, runes, and sats from a private key into wallet")]
+  Sweep(sweep::Sweep),
   #[command(about = "See wallet transactions")]
   Transactions(transactions::Transactions),
 }
 
+impl Subcommand {
+  /// Subcommands which need Bitcoin Core's wallet transaction history, which
+  /// wallets using the ord backend do not have.
+  fn requires_core_wallet(&self) -> Option<&'static str> {
+    match self {
+      Self::Bump(_) => Some("bump"),
+      Self::Label(_) => Some("label"),
+      Self::Offer(_) => Some("offer"),
+      Self::Transactions(_) => Some("transactions"),
+      _ => None,
+    }
+  }
+}
+
 impl WalletCommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self.subcommand {
@@ -100,45 +168,95 @@ impl WalletCommand {
       _ => {}
     };
 
-    let wallet = WalletConstructor::construct(
-      self.name.clone(),
-      self.no_sync,
-      settings.clone(),
-      self
-        .server_url
-        .as_ref()
-        .map(Url::as_str)
-        .or(settings.server_url())
-        .unwrap_or("http://127.0.0.1:80")
-        .parse::<Url>()
-        .context("invalid server URL")?,
-    )?;
+    let name = self.name.clone();
+    let no_sync = self.no_sync;
+    let rpc_url = self
+      .server_url
+      .as_ref()
+      .map(Url::as_str)
+      .or(settings.server_url())
+      .unwrap_or("http://127.0.0.1:80")
+      .parse::<Url>()
+      .context("invalid server URL")?;
+    let protection = self.protection()?;
+    let max_fee = self.max_fee;
+    let max_fee_rate = self.max_fee_rate;
+
+    let construct = {
+      let settings = settings.clone();
+      move || {
+        WalletConstructor::construct(
+          name.clone(),
+          no_sync,
+          settings.clone(),
+          rpc_url.clone(),
+          protection.clone(),
+          max_fee,
+          max_fee_rate,
+        )
+      }
+    };
+
+    if let Subcommand::Server(server) = self.subcommand {
+      return server.run(&settings, construct);
+    }
+
+    let wallet = construct()?;
+
+    if wallet.has_keychain() {
+      if let Some(subcommand) = self.subcommand.requires_core_wallet() {
+        bail!("`ord wallet {subcommand}` is not supported by wallets using the ord backend");
+      }
+    }
 
     match self.subcommand {
       Subcommand::Addresses => addresses::run(wallet),
       Subcommand::Balance => balance::run(wallet),
       Subcommand::Batch(batch) => batch.run(wallet),
+      Subcommand::Bump(bump) => bump.run(wallet),
       Subcommand::Burn(burn) => burn.run(wallet),
       Subcommand::Cardinals => cardinals::run(wallet),
       Subcommand::Create(_) | Subcommand::Restore(_) => unreachable!(),
       Subcommand::Dump => dump::run(wallet),
+      Subcommand::Extract(extract) => extract.run(wallet),
       Subcommand::Inscribe(inscribe) => inscribe.run(wallet),
       Subcommand::Inscriptions => inscriptions::run(wallet),
-      Subcommand::Label => label::run(wallet),
+      Subcommand::Label(label) => label.run(wallet),
       Subcommand::Mint(mint) => mint.run(wallet),
+      Subcommand::Offer(offer) => offer.run(wallet),
       Subcommand::Outputs(outputs) => outputs.run(wallet),
       Subcommand::Pending(pending) => pending.run(wallet),
       Subcommand::Receive(receive) => receive.run(wallet),
+      Subcommand::ResizePostage(resize_postage) => resize_postage.run(wallet),
       Subcommand::Resume(resume) => resume.run(wallet),
       Subcommand::Runics => runics::run(wallet),
       Subcommand::Sats(sats) => sats.run(wallet),
       Subcommand::Send(send) => send.run(wallet),
+      Subcommand::Server(_) => unreachable!(),
       Subcommand::Sign(sign) => sign.run(wallet),
       Subcommand::Split(split) => split.run(wallet),
+      Subcommand::Sweep(sweep) => sweep.run(wallet),
       Subcommand::Transactions(transactions) => transactions.run(wallet),
     }
   }
 
+  fn protection(&self) -> Result<Protection> {
+    let sats = match &self.protect_sats {
+      Some(path) => {
+        let tsv = fs::read_to_string(path)
+          .with_context(|| format!("I/O error reading `{}`", path.display()))?;
+
+        sats::Sats::needles(&tsv)?
+          .into_iter()
+          .map(|(sat, _value)| sat)
+          .collect()
+      }
+      None => BTreeSet::new(),
+    };
+
+    Protection::new(self.protect_rarity, self.protect_charm.clone(), sats)
+  }
+
   fn parse_metadata(cbor: Option<PathBuf>, json: Option<PathBuf>) -> Result<Option<Vec<u8>>> {
     match (cbor, json) {
       (None, None) => Ok(None),
@@ -1,6 +1,11 @@
 use super::*;
 
 #[derive(Debug, Parser)]
+#[clap(group(
+  ArgGroup::new("source")
+    .required(true)
+    .args(&["batch", "dir"]))
+)]
 pub(crate) struct Batch {
   #[command(flatten)]
   shared: SharedArgs,
@@ -9,14 +14,56 @@ pub(crate) struct Batch {
     help = "Inscribe multiple inscriptions and rune defined in YAML <BATCH_FILE>.",
     value_name = "BATCH_FILE"
   )]
-  pub(crate) batch: PathBuf,
+  pub(crate) batch: Option<PathBuf>,
+  #[arg(
+    long,
+    conflicts_with = "batch",
+    help = "Send inscriptions to destinations in CSV file <DESTINATIONS> with lines of the form \
+    `<FILE>,<DESTINATION>`. Inscriptions without a destination are sent to the wallet.",
+    value_name = "DESTINATIONS"
+  )]
+  pub(crate) destinations: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Inscribe files in <DIR> sorted by name, with metadata from `<NAME>.json` or \
+    `<NAME>.cbor` sidecar files. Inscriptions are split into multiple commit and reveal \
+    transactions if necessary, and a failed run resumes after the last inscribed file.",
+    value_name = "DIR"
+  )]
+  pub(crate) dir: Option<PathBuf>,
+  #[arg(
+    long = "parent",
+    conflicts_with = "batch",
+    help = "Make inscriptions children of <PARENT>.",
+    value_name = "PARENT"
+  )]
+  pub(crate) parents: Vec<InscriptionId>,
 }
 
 impl Batch {
+  const OUTPUT_WEIGHT: u64 = 172;
+  const PARENT_WEIGHT: u64 = 500;
+  const REVEAL_WEIGHT: u64 = 1_000;
+
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    self.inscribe(wallet, true)
+  }
+
+  /// Like `run`, but return as soon as a rune etching's commit transaction is
+  /// broadcast, leaving the pending etching to be revealed by `resume` once
+  /// the commit transaction matures.
+  pub(crate) fn run_without_waiting(self, wallet: Wallet) -> SubcommandResult {
+    self.inscribe(wallet, false)
+  }
+
+  fn inscribe(self, wallet: Wallet, wait_for_maturation: bool) -> SubcommandResult {
+    let Some(batch) = &self.batch else {
+      return self.inscribe_directory(wallet);
+    };
+
     let utxos = wallet.utxos();
 
-    let batchfile = batch::File::load(&self.batch)?;
+    let batchfile = batch::File::load(batch)?;
 
     let parent_info = wallet.get_parent_info(&batchfile.parents)?;
 
@@ -30,23 +77,28 @@ impl Batch {
       self.shared.compress,
     )?;
 
-    let mut locked_utxos = wallet.locked_utxos().clone();
+    self.shared.coin_control.check(&wallet, false)?;
+
+    let mut locked_utxos = self.shared.coin_control.get_unspendable_outputs(&wallet)?;
 
     locked_utxos.extend(
       reveal_satpoints
         .iter()
-        .map(|(satpoint, txout)| (satpoint.outpoint, txout.clone())),
+        .map(|(satpoint, _txout)| satpoint.outpoint),
     );
 
     if let Some(etching) = batchfile.etching {
       Self::check_etching(&wallet, &etching)?;
     }
 
-    batch::Plan {
-      commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
+    let plan = batch::Plan {
+      change_address: self.shared.coin_control.change_address(&wallet)?,
+      commit_fee_rate: wallet
+        .fee_rate(self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate))?,
       destinations,
       dry_run: self.shared.dry_run,
       etching: batchfile.etching,
+      include_outputs: self.shared.coin_control.include_output.clone(),
       inscriptions,
       mode: batchfile.mode,
       no_backup: self.shared.no_backup,
@@ -54,20 +106,177 @@ impl Batch {
       parent_info,
       postages,
       reinscribe: batchfile.reinscribe,
-      reveal_fee_rate: self.shared.fee_rate,
+      reveal_fee_rate: wallet.fee_rate(self.shared.fee_rate)?,
       reveal_satpoints,
       satpoint: if let Some(sat) = batchfile.sat {
         Some(wallet.find_sat_in_outputs(sat)?)
       } else {
         batchfile.satpoint
       },
+    };
+
+    let runic_utxos = wallet.get_runic_outputs()?;
+
+    if wait_for_maturation {
+      plan.inscribe(&locked_utxos, runic_utxos, utxos, &wallet)
+    } else {
+      let (_commit, _reveal, output) = plan.execute(&locked_utxos, runic_utxos, utxos, &wallet)?;
+      Ok(Some(Box::new(output)))
     }
-    .inscribe(
-      &locked_utxos.into_keys().collect(),
-      wallet.get_runic_outputs()?,
-      utxos,
-      &wallet,
-    )
+  }
+
+  fn inscribe_directory(self, mut wallet: Wallet) -> SubcommandResult {
+    let directory =
+      batch::Directory::load(self.dir.as_ref().unwrap(), self.destinations.as_deref())?;
+
+    let key = directory.key();
+
+    let progress = wallet.batch_progress(&key)?;
+
+    let mut items = directory
+      .items
+      .iter()
+      .filter(|item| progress.as_ref().map_or(true, |last| item.name > *last))
+      .peekable();
+
+    ensure!(
+      items.peek().is_some(),
+      "all files in `{key}` have already been inscribed",
+    );
+
+    self.shared.coin_control.check(&wallet, false)?;
+
+    let mut locked_utxos = self.shared.coin_control.get_unspendable_outputs(&wallet)?;
+
+    let mut parent_info = wallet.get_parent_info(&self.parents)?;
+
+    let change_address = self.shared.coin_control.change_address(&wallet)?;
+
+    let commit_fee_rate =
+      wallet.fee_rate(self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate))?;
+
+    let reveal_fee_rate = wallet.fee_rate(self.shared.fee_rate)?;
+
+    let mut outputs = Vec::new();
+
+    while items.peek().is_some() {
+      let mut pointer = parent_info
+        .iter()
+        .map(|info| info.tx_out.value.to_sat())
+        .sum::<u64>();
+
+      let mut weight =
+        Self::REVEAL_WEIGHT + u64::try_from(parent_info.len())? * Self::PARENT_WEIGHT;
+
+      let mut inscriptions = Vec::new();
+      let mut destinations = Vec::new();
+      let mut last = None;
+
+      // fill reveal transaction up to MAX_STANDARD_TX_WEIGHT
+      while let Some(item) = items.peek().copied() {
+        let inscription = Inscription::new(
+          wallet.chain(),
+          self.shared.compress,
+          None,
+          WalletCommand::parse_metadata(item.cbor_metadata.clone(), item.json_metadata.clone())
+            .with_context(|| format!("invalid metadata for `{}`", item.name))?,
+          None,
+          self.parents.clone(),
+          Some(item.file.clone()),
+          Some(pointer),
+          None,
+        )?;
+
+        weight += u64::try_from(
+          inscription
+            .append_reveal_script_to_builder(script::Builder::new())
+            .as_script()
+            .len(),
+        )? + Self::OUTPUT_WEIGHT;
+
+        if !self.shared.no_limit
+          && !inscriptions.is_empty()
+          && weight > MAX_STANDARD_TX_WEIGHT.into()
+        {
+          break;
+        }
+
+        inscriptions.push(inscription);
+
+        destinations.push(match &item.destination {
+          Some(destination) => destination
+            .clone()
+            .require_network(wallet.chain().network())?,
+          None => wallet.get_change_address()?,
+        });
+
+        pointer += TARGET_POSTAGE.to_sat();
+
+        last = Some(item);
+
+        items.next();
+      }
+
+      let (commit, reveal, output) = batch::Plan {
+        change_address: change_address.clone(),
+        commit_fee_rate,
+        destinations,
+        dry_run: self.shared.dry_run,
+        etching: None,
+        include_outputs: if outputs.is_empty() {
+          self.shared.coin_control.include_output.clone()
+        } else {
+          Vec::new()
+        },
+        postages: vec![TARGET_POSTAGE; inscriptions.len()],
+        inscriptions,
+        mode: batch::Mode::SeparateOutputs,
+        no_backup: self.shared.no_backup,
+        no_limit: self.shared.no_limit,
+        parent_info: parent_info.clone(),
+        reinscribe: false,
+        reveal_fee_rate,
+        reveal_satpoints: Vec::new(),
+        satpoint: None,
+      }
+      .execute(
+        &locked_utxos,
+        wallet.get_runic_outputs()?,
+        wallet.utxos(),
+        &wallet,
+      )?;
+
+      // later chunks spend change from earlier chunks, and must not spend
+      // their inscriptions
+      wallet.apply_transaction(&commit)?;
+      wallet.apply_transaction(&reveal)?;
+
+      let reveal_txid = reveal.compute_txid();
+
+      locked_utxos.extend((0..reveal.output.len()).map(|vout| OutPoint {
+        txid: reveal_txid,
+        vout: vout.try_into().unwrap(),
+      }));
+
+      for (vout, info) in parent_info.iter_mut().enumerate() {
+        info.location = SatPoint {
+          outpoint: OutPoint {
+            txid: reveal_txid,
+            vout: vout.try_into().unwrap(),
+          },
+          offset: info.location.offset,
+        };
+        info.tx_out = reveal.output[vout].clone();
+      }
+
+      if !self.shared.dry_run {
+        wallet.save_batch_progress(&key, &last.unwrap().name)?;
+      }
+
+      outputs.push(output);
+    }
+
+    Ok(Some(Box::new(outputs)))
   }
 
   fn check_etching(wallet: &Wallet, etching: &batch::Etching) -> Result {
@@ -252,6 +461,48 @@ inscriptions:
     );
   }
 
+  #[test]
+  fn batch_and_dir_flags_conflict() {
+    assert_regex_match!(
+      Arguments::try_parse_from([
+        "ord",
+        "wallet",
+        "batch",
+        "--fee-rate",
+        "1",
+        "--batch",
+        "batch.yaml",
+        "--dir",
+        "collection",
+      ])
+      .unwrap_err()
+      .to_string(),
+      ".*--batch.*cannot be used with.*--dir.*"
+    );
+  }
+
+  #[test]
+  fn destinations_and_parent_flags_conflict_with_batch() {
+    for flag in ["--destinations", "--parent"] {
+      assert_regex_match!(
+        Arguments::try_parse_from([
+          "ord",
+          "wallet",
+          "batch",
+          "--fee-rate",
+          "1",
+          "--batch",
+          "batch.yaml",
+          flag,
+          "8d363b28528b0cb86b5fd48615493fb175bdf132d2a3d20b4251bba3f130a5abi0",
+        ])
+        .unwrap_err()
+        .to_string(),
+        ".*cannot be used with.*--batch.*"
+      );
+    }
+  }
+
   #[test]
   fn batch_with_unknown_field_throws_error() {
     let tempdir = TempDir::new().unwrap();
@@ -0,0 +1,381 @@
+use {super::*, bitcoin::psbt::Psbt};
+
+// Bitcoin Core's default `-limitancestorcount`, which bounds how many
+// unconfirmed parents we may need to walk to find an input's assets.
+const MAX_UNCONFIRMED_ANCESTORS: usize = 25;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Bump {
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
+  #[arg(help = "Bump fee of transaction <TXID>.")]
+  txid: Txid,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Method {
+  Cpfp,
+  Rbf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Output {
+  pub method: Method,
+  pub original: Txid,
+  pub txid: Txid,
+  pub fee: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Assets {
+  inscriptions: Vec<u64>,
+  runes: bool,
+}
+
+impl Assets {
+  fn is_cardinal(&self) -> bool {
+    self.inscriptions.is_empty() && !self.runes
+  }
+}
+
+impl Bump {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
+    let transaction = bitcoin_client
+      .get_transaction(&self.txid, None)
+      .with_context(|| format!("transaction {} not found in wallet", self.txid))?;
+
+    ensure!(
+      transaction.info.confirmations == 0,
+      "transaction {} is already confirmed",
+      self.txid,
+    );
+
+    let transaction = transaction.transaction()?;
+
+    let mut input_value = Amount::ZERO;
+    for txin in &transaction.input {
+      input_value += Self::previous_output(&wallet, txin.previous_output)?.value;
+    }
+
+    let original_fee = input_value
+      - transaction
+        .output
+        .iter()
+        .map(|txout| txout.value)
+        .sum::<Amount>();
+
+    let assets = Self::output_assets(&wallet, &transaction, 0)?;
+
+    // replacing a reveal would require re-signing its script path spends,
+    // which the wallet cannot do, so reveals are always bumped with a child
+    let reveal = !ParsedEnvelope::from_transaction(&transaction).is_empty();
+
+    let (method, (unsigned_transaction, fee)) = if transaction.is_explicitly_rbf() && !reveal {
+      (
+        Method::Rbf,
+        self.replace(
+          &wallet,
+          &transaction,
+          &assets,
+          input_value,
+          original_fee,
+          fee_rate,
+        )?,
+      )
+    } else {
+      (
+        Method::Cpfp,
+        self.child(&wallet, &transaction, &assets, original_fee, fee_rate)?,
+      )
+    };
+
+    wallet.check_fee(&[(&unsigned_transaction, fee)])?;
+
+    let signed_transaction = wallet
+      .finalize_psbt(&wallet.sign_psbt(&Psbt::from_unsigned_tx(unsigned_transaction)?, None)?)?;
+
+    let txid = wallet.send_raw_transaction(&signed_transaction)?;
+
+    Ok(Some(Box::new(Output {
+      method,
+      original: self.txid,
+      txid,
+      fee: fee.to_sat(),
+    })))
+  }
+
+  fn replace(
+    &self,
+    wallet: &Wallet,
+    transaction: &Transaction,
+    assets: &[Assets],
+    input_value: Amount,
+    original_fee: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<(Transaction, Amount)> {
+    ensure!(
+      fee_rate.fee(transaction.vsize()) > original_fee,
+      "transaction {} already pays a fee rate of at least {} sat/vB",
+      self.txid,
+      fee_rate.n(),
+    );
+
+    // only the last cardinal output may shrink, since changing the value of
+    // an output moves every sat in the outputs that follow it
+    let mut change = None;
+    for (vout, (txout, assets)) in transaction.output.iter().zip(assets).enumerate().rev() {
+      if !assets.inscriptions.is_empty() {
+        break;
+      }
+
+      if !assets.runes
+        && !txout.script_pubkey.is_op_return()
+        && wallet.is_mine(&txout.script_pubkey)?
+      {
+        change = Some(vout);
+        break;
+      }
+    }
+
+    // appending a change output leaves existing sats in place, but would
+    // change which outputs a runestone's edicts and pointer refer to
+    ensure!(
+      change.is_some() || Runestone::decipher(transaction).is_none(),
+      "transaction {} has no cardinal change output to pay for replacement",
+      self.txid
+    );
+
+    // BIP 125 requires the replacement to pay for its own relay on top of
+    // the fee of the transaction it replaces
+    Ok(Self::transaction_builder(wallet, fee_rate)?.build_fee_bump(
+      transaction,
+      input_value,
+      change,
+      |vbytes| {
+        fee_rate
+          .fee(vbytes)
+          .max(original_fee + Amount::from_sat(vbytes.try_into().unwrap()))
+      },
+    )?)
+  }
+
+  fn child(
+    &self,
+    wallet: &Wallet,
+    transaction: &Transaction,
+    assets: &[Assets],
+    parent_fee: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<(Transaction, Amount)> {
+    ensure!(
+      fee_rate.fee(transaction.vsize()) > parent_fee,
+      "transaction {} already pays a fee rate of at least {} sat/vB",
+      self.txid,
+      fee_rate.n(),
+    );
+
+    let mut cardinal = None;
+    let mut non_cardinal = None;
+    for (vout, (txout, assets)) in transaction.output.iter().zip(assets).enumerate() {
+      if txout.script_pubkey.is_op_return() || !wallet.is_mine(&txout.script_pubkey)? {
+        continue;
+      }
+
+      if !assets.is_cardinal() {
+        non_cardinal = non_cardinal.or(Some(vout));
+      } else if cardinal
+        .map(|cardinal: usize| txout.value > transaction.output[cardinal].value)
+        .unwrap_or(true)
+      {
+        cardinal = Some(vout);
+      }
+    }
+
+    let Some(vout) = cardinal.or(non_cardinal) else {
+      bail!(
+        "transaction {} cannot be replaced and has no output to spend for CPFP",
+        self.txid
+      );
+    };
+
+    let parent_output = &transaction.output[vout];
+
+    // a non-cardinal output is passed through unchanged as the child's first
+    // output, which keeps its inscriptions at the same offsets and receives
+    // its runes
+    let child = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: self.txid,
+          vout: vout.try_into().unwrap(),
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: if cardinal.is_some() {
+        Vec::new()
+      } else {
+        vec![parent_output.clone()]
+      },
+    };
+
+    let parent_vsize = transaction.vsize();
+
+    Ok(Self::transaction_builder(wallet, fee_rate)?.build_fee_bump(
+      &child,
+      parent_output.value,
+      None,
+      |vbytes| {
+        fee_rate
+          .fee(parent_vsize + vbytes)
+          .checked_sub(parent_fee)
+          .unwrap_or_default()
+      },
+    )?)
+  }
+
+  fn transaction_builder(wallet: &Wallet, fee_rate: FeeRate) -> Result<TransactionBuilder> {
+    Ok(TransactionBuilder::fee_bump(
+      wallet.inscriptions().clone(),
+      wallet.utxos().clone(),
+      wallet.get_unspendable_outputs()?,
+      wallet.get_runic_outputs()?,
+      wallet.get_change_address()?,
+      fee_rate,
+      wallet.chain().network(),
+    ))
+  }
+
+  fn previous_output(wallet: &Wallet, outpoint: OutPoint) -> Result<TxOut> {
+    wallet
+      .bitcoin_client()
+      .get_raw_transaction(&outpoint.txid, None)?
+      .output
+      .into_iter()
+      .nth(outpoint.vout.into_usize())
+      .ok_or_else(|| anyhow!("output {outpoint} not found"))
+  }
+
+  fn input_assets(wallet: &Wallet, outpoint: OutPoint, depth: usize) -> Result<Assets> {
+    if let Some(info) = wallet.get_output_info(outpoint)? {
+      return Ok(Assets {
+        inscriptions: info
+          .inscriptions
+          .iter()
+          .map(|id| Ok(wallet.get_inscription(*id)?.satpoint.offset))
+          .collect::<Result<Vec<u64>>>()?,
+        runes: !info.runes.is_empty(),
+      });
+    }
+
+    ensure!(
+      depth < MAX_UNCONFIRMED_ANCESTORS,
+      "output {outpoint} has too many unconfirmed ancestors"
+    );
+
+    let parent = wallet
+      .bitcoin_client()
+      .get_raw_transaction(&outpoint.txid, None)?;
+
+    Self::output_assets(wallet, &parent, depth + 1)?
+      .into_iter()
+      .nth(outpoint.vout.into_usize())
+      .ok_or_else(|| anyhow!("output {outpoint} not found"))
+  }
+
+  fn output_assets(
+    wallet: &Wallet,
+    transaction: &Transaction,
+    depth: usize,
+  ) -> Result<Vec<Assets>> {
+    let mut outputs = vec![Assets::default(); transaction.output.len()];
+
+    let mut runic = false;
+    let mut input_offset = 0;
+    for txin in &transaction.input {
+      let assets = Self::input_assets(wallet, txin.previous_output, depth)?;
+
+      runic |= assets.runes;
+
+      for offset in assets.inscriptions {
+        let mut offset = input_offset + offset;
+        for (output, txout) in outputs.iter_mut().zip(&transaction.output) {
+          if offset < txout.value.to_sat() {
+            output.inscriptions.push(offset);
+            break;
+          }
+          offset -= txout.value.to_sat();
+        }
+      }
+
+      input_offset += Self::previous_output(wallet, txin.previous_output)?
+        .value
+        .to_sat();
+    }
+
+    // new inscriptions may be pointed anywhere, so treat every output of a
+    // reveal as inscribed
+    if !ParsedEnvelope::from_transaction(transaction).is_empty() {
+      for (output, txout) in outputs.iter_mut().zip(&transaction.output) {
+        if !txout.script_pubkey.is_op_return() {
+          output.inscriptions.push(0);
+        }
+      }
+    }
+
+    let first_non_op_return = transaction
+      .output
+      .iter()
+      .position(|txout| !txout.script_pubkey.is_op_return());
+
+    match Runestone::decipher(transaction) {
+      Some(Artifact::Cenotaph(_)) => {}
+      Some(Artifact::Runestone(runestone)) => {
+        if runic || runestone.mint.is_some() || runestone.etching.is_some() {
+          for edict in &runestone.edicts {
+            let vout = edict.output.into_usize();
+            if vout == transaction.output.len() {
+              for (output, txout) in outputs.iter_mut().zip(&transaction.output) {
+                if !txout.script_pubkey.is_op_return() {
+                  output.runes = true;
+                }
+              }
+            } else if let Some(output) = outputs.get_mut(vout) {
+              output.runes = true;
+            }
+          }
+
+          if let Some(vout) = runestone
+            .pointer
+            .map(|pointer| pointer.into_usize())
+            .or(first_non_op_return)
+          {
+            if let Some(output) = outputs.get_mut(vout) {
+              output.runes = true;
+            }
+          }
+        }
+      }
+      None => {
+        if runic {
+          if let Some(vout) = first_non_op_return {
+            outputs[vout].runes = true;
+          }
+        }
+      }
+    }
+
+    Ok(outputs)
+  }
+}
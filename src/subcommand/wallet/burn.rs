@@ -9,10 +9,15 @@ pub struct Burn {
     value_name = "PATH"
   )]
   cbor_metadata: Option<PathBuf>,
+  #[command(flatten)]
+  coin_control: CoinControl,
   #[arg(long, help = "Don't sign or broadcast transaction.")]
   dry_run: bool,
-  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
-  fee_rate: FeeRate,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
   #[arg(
     long,
     help = "Include JSON from <PATH> converted to CBOR in OP_RETURN.",
@@ -34,19 +39,56 @@ pub struct Burn {
     value_name = "AMOUNT"
   )]
   postage: Option<Amount>,
-  inscription: InscriptionId,
+  #[arg(help = "Burn <OUTGOING>, an inscription ID or `<DECIMAL>:<RUNE>`.")]
+  outgoing: Outgoing,
 }
 
 impl Burn {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
+    let metadata = WalletCommand::parse_metadata(self.cbor_metadata, self.json_metadata)?;
+
+    self
+      .coin_control
+      .check(&wallet, matches!(self.outgoing, Outgoing::Rune { .. }))?;
+
+    let inscription = match self.outgoing {
+      Outgoing::InscriptionId(id) => id,
+      Outgoing::Rune { decimal, rune } => {
+        ensure!(
+          metadata.is_none(),
+          "metadata may not be included when burning runes"
+        );
+
+        let unsigned_transaction = Self::create_unsigned_rune_burn_transaction(
+          &wallet,
+          &self.coin_control,
+          rune,
+          decimal,
+          self.postage.unwrap_or(TARGET_POSTAGE),
+          fee_rate,
+        )?;
+
+        let (txid, psbt, fee) =
+          wallet.sign_and_broadcast_transaction(unsigned_transaction, self.dry_run)?;
+
+        return Ok(Some(Box::new(send::Output {
+          txid,
+          psbt,
+          outgoing: self.outgoing,
+          fee,
+        })));
+      }
+      _ => bail!("only inscriptions and runes may be burned"),
+    };
+
     let inscription_info = wallet
       .inscription_info()
-      .get(&self.inscription)
-      .ok_or_else(|| anyhow!("inscription {} not found", self.inscription))?
+      .get(&inscription)
+      .ok_or_else(|| anyhow!("inscription {inscription} not found"))?
       .clone();
 
-    let metadata = WalletCommand::parse_metadata(self.cbor_metadata, self.json_metadata)?;
-
     let Some(value) = inscription_info.value else {
       bail!("Cannot burn unbound inscription");
     };
@@ -87,9 +129,10 @@ impl Burn {
 
     let unsigned_transaction = Self::create_unsigned_burn_transaction(
       &wallet,
+      &self.coin_control,
       inscription_info.satpoint,
       self.postage,
-      self.fee_rate,
+      fee_rate,
       script_pubkey,
     )?;
 
@@ -99,13 +142,164 @@ impl Burn {
     Ok(Some(Box::new(send::Output {
       txid,
       psbt,
-      outgoing: Outgoing::InscriptionId(self.inscription),
+      outgoing: self.outgoing,
       fee,
     })))
   }
 
+  fn create_unsigned_rune_burn_transaction(
+    wallet: &Wallet,
+    coin_control: &CoinControl,
+    spaced_rune: SpacedRune,
+    decimal: Decimal,
+    postage: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    ensure!(
+      wallet.has_rune_index(),
+      "burning runes with `ord wallet burn` requires index created with `--index-runes` flag",
+    );
+
+    wallet.lock_non_cardinal_outputs()?;
+
+    let (id, entry, _parent) = wallet
+      .get_rune(spaced_rune.rune)?
+      .with_context(|| format!("rune `{}` has not been etched", spaced_rune.rune))?;
+
+    let amount = decimal.to_integer(entry.divisibility)?;
+
+    ensure!(amount > 0, "burn amount must be greater than zero");
+
+    let inscribed_outputs = wallet
+      .inscriptions()
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<HashSet<OutPoint>>();
+
+    let mut inputs = Vec::new();
+    let mut input_rune_balances: BTreeMap<Rune, u128> = BTreeMap::new();
+
+    let (included, runic_outputs): (Vec<_>, Vec<_>) = wallet
+      .get_runic_outputs()?
+      .into_iter()
+      .filter(|output| {
+        !inscribed_outputs.contains(output) && !coin_control.exclude_output.contains(output)
+      })
+      .partition(|output| coin_control.include_output.contains(output));
+
+    for output in included {
+      for (spaced_rune, pile) in wallet.get_runes_balances_in_output(&output)? {
+        *input_rune_balances.entry(spaced_rune.rune).or_default() += pile.amount;
+      }
+
+      inputs.push(output);
+    }
+
+    for output in runic_outputs {
+      if !inputs.is_empty()
+        && input_rune_balances
+          .get(&spaced_rune.rune)
+          .copied()
+          .unwrap_or_default()
+          >= amount
+      {
+        break;
+      }
+
+      let balances = wallet.get_runes_balances_in_output(&output)?;
+
+      if !balances
+        .keys()
+        .any(|balance| balance.rune == spaced_rune.rune)
+      {
+        continue;
+      }
+
+      for (spaced_rune, pile) in balances {
+        *input_rune_balances.entry(spaced_rune.rune).or_default() += pile.amount;
+      }
+
+      inputs.push(output);
+
+      if input_rune_balances[&spaced_rune.rune] >= amount {
+        break;
+      }
+    }
+
+    let input_rune_balance = input_rune_balances
+      .get(&spaced_rune.rune)
+      .copied()
+      .unwrap_or_default();
+
+    ensure! {
+      input_rune_balance >= amount,
+      "insufficient `{}` balance, only {} in wallet",
+      spaced_rune,
+      Pile {
+        amount: input_rune_balance,
+        divisibility: entry.divisibility,
+        symbol: entry.symbol
+      },
+    }
+
+    let needs_runes_change_output = input_rune_balance > amount || input_rune_balances.len() > 1;
+
+    // the edict targets the runestone's own OP_RETURN output, which burns the
+    // runes without producing a cenotaph, and any remaining runes are pointed
+    // at change
+    let runestone = Runestone {
+      edicts: vec![Edict {
+        amount,
+        id,
+        output: 0,
+      }],
+      pointer: needs_runes_change_output.then_some(1),
+      ..default()
+    };
+
+    let mut output = vec![TxOut {
+      script_pubkey: runestone.encipher(),
+      value: Amount::from_sat(0),
+    }];
+
+    if needs_runes_change_output {
+      output.push(TxOut {
+        script_pubkey: coin_control.get_change_address(wallet)?.script_pubkey(),
+        value: postage,
+      });
+    }
+
+    let unfunded_transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: inputs
+        .into_iter()
+        .map(|previous_output| TxIn {
+          previous_output,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .chain(coin_control.cardinal_inputs(wallet)?)
+        .collect(),
+      output,
+    };
+
+    let unsigned_transaction = coin_control.fund(wallet, fee_rate, &unfunded_transaction)?;
+
+    assert_eq!(
+      Runestone::decipher(&unsigned_transaction),
+      Some(Artifact::Runestone(runestone)),
+    );
+
+    wallet.check_protected_sats(&unsigned_transaction, None)?;
+
+    Ok(unsigned_transaction)
+  }
+
   fn create_unsigned_burn_transaction(
     wallet: &Wallet,
+    coin_control: &CoinControl,
     satpoint: SatPoint,
     postage: Option<Amount>,
     fee_rate: FeeRate,
@@ -122,20 +316,24 @@ impl Burn {
 
     let postage = postage.map(Target::ExactPostage).unwrap_or(Target::Postage);
 
-    Ok(
-      TransactionBuilder::new(
-        satpoint,
-        wallet.inscriptions().clone(),
-        wallet.utxos().clone(),
-        wallet.locked_utxos().clone().into_keys().collect(),
-        runic_outputs,
-        script_pubkey,
-        change,
-        fee_rate,
-        postage,
-        wallet.chain().network(),
-      )
-      .build_transaction()?,
+    let unsigned_transaction = TransactionBuilder::new(
+      satpoint,
+      wallet.inscriptions().clone(),
+      wallet.utxos().clone(),
+      coin_control.get_unspendable_outputs(wallet)?,
+      runic_outputs,
+      script_pubkey,
+      change,
+      fee_rate,
+      postage,
+      wallet.chain().network(),
     )
+    .change_address(coin_control.change_address(wallet)?)
+    .include_outputs(coin_control.include_output.clone())
+    .build_transaction()?;
+
+    wallet.check_protected_sats(&unsigned_transaction, Some(satpoint.outpoint))?;
+
+    Ok(unsigned_transaction)
   }
 }
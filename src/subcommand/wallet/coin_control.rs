@@ -0,0 +1,168 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct CoinControl {
+  #[arg(long, help = "Send change to <CHANGE_ADDRESS>.")]
+  pub(crate) change_address: Option<Address<NetworkUnchecked>>,
+  #[arg(
+    long,
+    help = "Do not spend <OUTPOINT>. May be given multiple times.",
+    value_name = "OUTPOINT"
+  )]
+  pub(crate) exclude_output: Vec<OutPoint>,
+  #[arg(
+    long,
+    help = "Spend <OUTPOINT>. May be given multiple times.",
+    value_name = "OUTPOINT"
+  )]
+  pub(crate) include_output: Vec<OutPoint>,
+}
+
+impl CoinControl {
+  /// Check that included outputs may be spent. Outputs containing runes may
+  /// only be included if `runes` is true, since only transactions with a
+  /// runestone can account for them.
+  pub(crate) fn check(&self, wallet: &Wallet, runes: bool) -> Result {
+    let unspendable = wallet.get_unspendable_outputs()?;
+
+    let runic = wallet.get_runic_outputs()?;
+
+    for outpoint in &self.include_output {
+      ensure!(
+        !self.exclude_output.contains(outpoint),
+        "output {outpoint} cannot be both included and excluded",
+      );
+
+      ensure!(
+        wallet.utxos().contains_key(outpoint),
+        "included output {outpoint} not in wallet",
+      );
+
+      ensure!(
+        !wallet
+          .inscriptions()
+          .keys()
+          .any(|satpoint| satpoint.outpoint == *outpoint),
+        "included output {outpoint} contains inscriptions",
+      );
+
+      ensure!(
+        runes || !runic.contains(outpoint),
+        "included output {outpoint} contains runes",
+      );
+
+      ensure!(
+        !unspendable.contains(outpoint),
+        "included output {outpoint} is locked",
+      );
+    }
+
+    self.change_address(wallet)?;
+
+    Ok(())
+  }
+
+  pub(crate) fn change_address(&self, wallet: &Wallet) -> Result<Option<Address>> {
+    Ok(
+      self
+        .change_address
+        .clone()
+        .map(|address| address.require_network(wallet.chain().network()))
+        .transpose()?,
+    )
+  }
+
+  /// Returns `--change-address` if given, otherwise a new wallet change
+  /// address.
+  pub(crate) fn get_change_address(&self, wallet: &Wallet) -> Result<Address> {
+    match self.change_address(wallet)? {
+      Some(address) => Ok(address),
+      None => wallet.get_change_address(),
+    }
+  }
+
+  pub(crate) fn get_unspendable_outputs(&self, wallet: &Wallet) -> Result<BTreeSet<OutPoint>> {
+    let mut unspendable = wallet.get_unspendable_outputs()?;
+    unspendable.extend(&self.exclude_output);
+    Ok(unspendable)
+  }
+
+  /// Included outputs which do not contain runes.
+  pub(crate) fn cardinal_inputs(&self, wallet: &Wallet) -> Result<Vec<TxIn>> {
+    let runic = wallet.get_runic_outputs()?;
+
+    Ok(
+      self
+        .include_output
+        .iter()
+        .filter(|outpoint| !runic.contains(outpoint))
+        .map(|outpoint| TxIn {
+          previous_output: *outpoint,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+    )
+  }
+
+  /// Fund `unfunded_transaction` with `fundrawtransaction`, sending change to
+  /// `--change-address` if given. Must be called after
+  /// `Wallet::lock_non_cardinal_outputs`. Excluded outputs which are not
+  /// already locked are locked while funding so that Bitcoin Core does not
+  /// select them. Wallets using the ord backend select inputs themselves.
+  pub(crate) fn fund(
+    &self,
+    wallet: &Wallet,
+    fee_rate: FeeRate,
+    unfunded_transaction: &Transaction,
+  ) -> Result<Transaction> {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let change_address = self.change_address(wallet)?;
+
+    if wallet.has_keychain() {
+      return wallet.fund_transaction(
+        fee_rate,
+        unfunded_transaction,
+        change_address,
+        &self.exclude_output,
+      );
+    }
+
+    let mut locked = wallet.get_unspendable_outputs()?;
+    locked.extend(wallet.get_runic_outputs()?);
+    locked.extend(
+      wallet
+        .inscriptions()
+        .keys()
+        .map(|satpoint| satpoint.outpoint),
+    );
+
+    let excluded = self
+      .exclude_output
+      .iter()
+      .filter(|outpoint| !locked.contains(outpoint))
+      .copied()
+      .collect::<BTreeSet<OutPoint>>()
+      .into_iter()
+      .collect::<Vec<OutPoint>>();
+
+    if !excluded.is_empty() && !bitcoin_client.lock_unspent(&excluded)? {
+      bail!("failed to lock UTXOs");
+    }
+
+    let result = fund_raw_transaction(
+      bitcoin_client,
+      fee_rate,
+      unfunded_transaction,
+      change_address,
+    );
+
+    if !excluded.is_empty() && !bitcoin_client.unlock_unspent(&excluded)? {
+      bail!("failed to unlock UTXOs");
+    }
+
+    Ok(consensus::encode::deserialize(&result?)?)
+  }
+}
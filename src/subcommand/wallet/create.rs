@@ -11,6 +11,13 @@ pub struct Output {
 
 #[derive(Debug, Parser)]
 pub(crate) struct Create {
+  #[arg(
+    long,
+    value_enum,
+    default_value = "core",
+    help = "Store keys and track outputs with <BACKEND>."
+  )]
+  pub(crate) backend: Backend,
   #[arg(
     long,
     default_value = "",
@@ -26,12 +33,18 @@ impl Create {
 
     let mnemonic = Mnemonic::from_entropy(&entropy)?;
 
-    Wallet::initialize(
-      name,
-      settings,
-      mnemonic.to_seed(&self.passphrase),
-      bitcoincore_rpc::json::Timestamp::Now,
-    )?;
+    let seed = mnemonic.to_seed(&self.passphrase);
+
+    match self.backend {
+      Backend::Core => {
+        Wallet::initialize(name, settings, seed, bitcoincore_rpc::json::Timestamp::Now)?
+      }
+      Backend::Ord => Wallet::initialize_keychain(
+        name,
+        settings,
+        Wallet::derive_descriptors(settings.chain().network(), seed)?,
+      )?,
+    }
 
     Ok(Some(Box::new(Output {
       mnemonic,
@@ -8,9 +8,5 @@ pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
 =========================================="
   );
 
-  Ok(Some(Box::new(
-    wallet
-      .bitcoin_client()
-      .call::<ListDescriptorsResult>("listdescriptors", &[serde_json::to_value(true)?])?,
-  )))
+  Ok(Some(Box::new(wallet.list_descriptors()?)))
 }
@@ -0,0 +1,288 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+#[clap(group(
+  ArgGroup::new("target")
+    .required(true)
+    .args(&["sat", "rarity"]))
+)]
+pub(crate) struct Extract {
+  #[arg(long, help = "Don't sign or broadcast transaction.")]
+  dry_run: bool,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
+  #[arg(
+    long,
+    help = "Extract sats into outputs of <AMOUNT> postage. [default: 10000 sat]",
+    value_name = "AMOUNT"
+  )]
+  postage: Option<Amount>,
+  #[arg(long, help = "Extract all sats of <RARITY> or rarer.")]
+  rarity: Option<Rarity>,
+  #[arg(long, num_args = 1.., help = "Extract <SAT>.")]
+  sat: Vec<Sat>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExtractedSat {
+  pub sat: Sat,
+  pub output: OutPoint,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub txid: Txid,
+  pub psbt: String,
+  pub fee: u64,
+  pub extracted: Vec<ExtractedSat>,
+}
+
+impl Extract {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
+    ensure!(
+      wallet.has_sat_index(),
+      "extracting sats requires index created with `--index-sats` flag"
+    );
+
+    let postage = self.postage.unwrap_or(TARGET_POSTAGE);
+
+    let inscribed = wallet
+      .inscriptions()
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let runic = wallet.get_runic_outputs()?;
+
+    let splittable = |output: &OutPoint| {
+      !inscribed.contains(output)
+        && !runic.contains(output)
+        && !wallet.locked_utxos().contains_key(output)
+    };
+
+    let mut targets = BTreeMap::<OutPoint, BTreeMap<u64, Sat>>::new();
+
+    for sat in &self.sat {
+      let satpoint = wallet.find_sat_in_outputs(*sat)?;
+
+      ensure!(
+        splittable(&satpoint.outpoint),
+        "output {} containing sat {sat} holds inscriptions or runes, or is locked",
+        satpoint.outpoint,
+      );
+
+      targets
+        .entry(satpoint.outpoint)
+        .or_default()
+        .insert(satpoint.offset, *sat);
+    }
+
+    if let Some(rarity) = self.rarity {
+      for (output, ranges) in wallet.get_wallet_sat_ranges()? {
+        if !splittable(&output) {
+          continue;
+        }
+
+        let mut offset = 0;
+        for (start, end) in ranges {
+          let sat = Sat(start);
+
+          if sat.rarity() >= rarity && sat.rarity() > Rarity::Common {
+            targets.entry(output).or_default().insert(offset, sat);
+          }
+
+          offset += end - start;
+        }
+      }
+    }
+
+    // sats already alone at the start of a postage-sized output have nothing
+    // left to extract
+    targets.retain(|output, sats| {
+      !(sats.len() == 1
+        && sats.contains_key(&0)
+        && wallet.utxos()[output].value <= TransactionBuilder::MAX_POSTAGE)
+    });
+
+    ensure!(!targets.is_empty(), "no sats to extract");
+
+    let unsigned_transaction =
+      Self::create_unsigned_extract_transaction(&wallet, &targets, postage, fee_rate)?;
+
+    wallet.check_protected_sats(&unsigned_transaction, None)?;
+
+    let mut extracted = Vec::new();
+    let mut offset = 0;
+    let mut sats = Vec::new();
+
+    for input in &unsigned_transaction.input {
+      for (sat_offset, sat) in targets.get(&input.previous_output).into_iter().flatten() {
+        sats.push((offset + sat_offset, *sat));
+      }
+
+      offset += wallet.utxos()[&input.previous_output].value.to_sat();
+    }
+
+    let (txid, psbt, fee) =
+      wallet.sign_and_broadcast_transaction(unsigned_transaction.clone(), self.dry_run)?;
+
+    let mut output_start = 0;
+    for (vout, output) in unsigned_transaction.output.iter().enumerate() {
+      for (offset, sat) in &sats {
+        if *offset == output_start {
+          extracted.push(ExtractedSat {
+            sat: *sat,
+            output: OutPoint {
+              txid,
+              vout: vout.try_into().unwrap(),
+            },
+          });
+        }
+      }
+
+      output_start += output.value.to_sat();
+    }
+
+    Ok(Some(Box::new(Output {
+      txid,
+      psbt,
+      fee,
+      extracted,
+    })))
+  }
+
+  fn create_unsigned_extract_transaction(
+    wallet: &Wallet,
+    targets: &BTreeMap<OutPoint, BTreeMap<u64, Sat>>,
+    postage: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    let change = wallet.get_change_address()?.script_pubkey();
+
+    let dust_value = change.minimal_non_dust().to_sat();
+
+    let mut cardinals = wallet
+      .get_cardinal_utxos()?
+      .into_iter()
+      .filter(|(output, _)| !targets.contains_key(output))
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    cardinals.sort_by_key(|(_, txout)| txout.value);
+
+    let mut inputs = Vec::new();
+
+    // sats in the first input that precede the first target must land in a
+    // change output, so if there are too few of them, pad with a cardinal
+    let first_offset = *targets.values().next().unwrap().keys().next().unwrap();
+
+    if first_offset > 0 && first_offset < dust_value {
+      ensure!(
+        !cardinals.is_empty(),
+        "wallet contains no cardinal utxos to pad sat at offset {first_offset}"
+      );
+
+      inputs.push(cardinals.remove(0));
+    }
+
+    for output in targets.keys() {
+      inputs.push((*output, wallet.utxos()[output].clone()));
+    }
+
+    let mut cardinals = cardinals.into_iter().rev();
+
+    loop {
+      let mut offsets = Vec::new();
+      let mut total = 0;
+
+      for (output, txout) in &inputs {
+        for offset in targets.get(output).into_iter().flat_map(|sats| sats.keys()) {
+          offsets.push(total + offset);
+        }
+
+        total += txout.value.to_sat();
+      }
+
+      let mut values = Vec::new();
+      let mut position = 0;
+
+      for (i, offset) in offsets.iter().enumerate() {
+        let gap = offset - position;
+
+        if gap >= dust_value {
+          values.push(gap);
+        } else if gap > 0 {
+          *values
+            .last_mut()
+            .expect("first target should be preceded by padding") += gap;
+        }
+
+        let end = match offsets.get(i + 1) {
+          Some(next) => {
+            ensure!(
+              next - offset >= dust_value,
+              "sats at offsets {offset} and {next} are too close to extract into separate outputs",
+            );
+
+            (offset + postage.to_sat()).min(*next)
+          }
+          None => offset + postage.to_sat(),
+        };
+
+        values.push(end - offset);
+
+        position = end;
+      }
+
+      let mut unsigned_transaction = Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: inputs
+          .iter()
+          .map(|(previous_output, _)| TxIn {
+            previous_output: *previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::from_slice(&[&[0; 64]]),
+          })
+          .collect(),
+        output: values
+          .iter()
+          .chain([&0])
+          .map(|value| TxOut {
+            script_pubkey: change.clone(),
+            value: Amount::from_sat(*value),
+          })
+          .collect(),
+      };
+
+      let fee = fee_rate.fee(unsigned_transaction.vsize()).to_sat();
+
+      if let Some(remainder) = total.checked_sub(position + fee) {
+        if remainder >= dust_value {
+          unsigned_transaction.output.last_mut().unwrap().value = Amount::from_sat(remainder);
+
+          for input in &mut unsigned_transaction.input {
+            input.witness = Witness::new();
+          }
+
+          for output in &mut unsigned_transaction.output {
+            output.script_pubkey = wallet.get_change_address()?.script_pubkey();
+          }
+
+          return Ok(unsigned_transaction);
+        }
+      }
+
+      let Some(cardinal) = cardinals.next() else {
+        bail!("wallet does not contain enough cardinal UTXOs to pay for extraction");
+      };
+
+      inputs.push(cardinal);
+    }
+  }
+}
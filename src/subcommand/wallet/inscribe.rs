@@ -60,14 +60,19 @@ impl Inscribe {
       }
     }
 
+    self.shared.coin_control.check(&wallet, false)?;
+
     batch::Plan {
-      commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
+      change_address: self.shared.coin_control.change_address(&wallet)?,
+      commit_fee_rate: wallet
+        .fee_rate(self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate))?,
       destinations: vec![match self.destination.clone() {
         Some(destination) => destination.require_network(chain.network())?,
         None => wallet.get_change_address()?,
       }],
       dry_run: self.shared.dry_run,
       etching: None,
+      include_outputs: self.shared.coin_control.include_output.clone(),
       inscriptions: vec![Inscription::new(
         chain,
         self.shared.compress,
@@ -85,7 +90,7 @@ impl Inscribe {
       parent_info: wallet.get_parent_info(self.parent.as_slice())?,
       postages: vec![self.postage.unwrap_or(TARGET_POSTAGE)],
       reinscribe: self.reinscribe,
-      reveal_fee_rate: self.shared.fee_rate,
+      reveal_fee_rate: wallet.fee_rate(self.shared.fee_rate)?,
       reveal_satpoints: Vec::new(),
       satpoint: if let Some(sat) = self.sat {
         Some(wallet.find_sat_in_outputs(sat)?)
@@ -94,7 +99,7 @@ impl Inscribe {
       },
     }
     .inscribe(
-      &wallet.locked_utxos().clone().into_keys().collect(),
+      &self.shared.coin_control.get_unspendable_outputs(&wallet)?,
       wallet.get_runic_outputs()?,
       wallet.utxos(),
       &wallet,
@@ -6,6 +6,8 @@ pub struct Output {
   pub location: SatPoint,
   pub explorer: String,
   pub postage: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub label: Option<String>,
 }
 
 pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
@@ -16,6 +18,8 @@ pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
     Chain::Testnet => "https://testnet.ordinals.com/inscription/",
   };
 
+  let labels = wallet.output_labels()?;
+
   let mut output = Vec::new();
 
   for (location, inscriptions) in wallet.inscriptions() {
@@ -26,6 +30,7 @@ pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
           inscription: *inscription,
           explorer: format!("{explorer}{inscription}"),
           postage: txout.value.to_sat(),
+          label: labels.get(&location.outpoint).cloned(),
         })
       }
     }
@@ -1,71 +1,323 @@
-use super::*;
+use {
+  super::*,
+  bitcoin::secp256k1::Secp256k1,
+  miniscript::{
+    descriptor::{Descriptor, DescriptorPublicKey},
+    ForEachKey,
+  },
+};
 
-#[derive(Serialize)]
-struct Label {
-  first_sat: SatLabel,
+const TYPES: &[&str] = &["addr", "input", "output", "pubkey", "tx", "xpub"];
+
+#[derive(Debug, Parser)]
+pub(crate) struct Label {
+  #[command(subcommand)]
+  subcommand: Option<LabelSubcommand>,
+}
+
+#[derive(Debug, Parser)]
+enum LabelSubcommand {
+  #[command(about = "Export BIP-329 labels")]
+  Export,
+  #[command(about = "Import BIP-329 labels")]
+  Import(Import),
+}
+
+#[derive(Debug, Parser)]
+struct Import {
+  #[arg(help = "Import labels from JSON lines <FILE>.")]
+  file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportOutput {
+  pub labels: usize,
+  pub locked: usize,
+  pub unlocked: usize,
+}
+
+/// Label exported for outputs without a user label. Deserialized on import to
+/// recognize and skip these labels, so that importing an export doesn't turn
+/// them into user labels.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OutputLabel {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  first_sat: Option<SatLabel>,
   inscriptions: BTreeMap<u64, BTreeSet<InscriptionId>>,
+  runes: BTreeMap<SpacedRune, Decimal>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct SatLabel {
   name: String,
   number: u64,
   rarity: Rarity,
 }
 
-#[derive(Serialize)]
-struct Line {
-  label: String,
-  r#ref: String,
-  r#type: String,
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+  pub r#type: String,
+  pub r#ref: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub label: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub origin: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub spendable: Option<bool>,
 }
 
-pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
-  let mut lines: Vec<Line> = Vec::new();
+impl Label {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    match self.subcommand {
+      None | Some(LabelSubcommand::Export) => Self::export(wallet),
+      Some(LabelSubcommand::Import(import)) => import.run(wallet),
+    }
+  }
 
-  let sat_ranges = wallet.get_wallet_sat_ranges()?;
+  fn export(wallet: Wallet) -> SubcommandResult {
+    let mut labels = wallet.labels()?;
 
-  let mut inscriptions_by_output: BTreeMap<OutPoint, BTreeMap<u64, Vec<InscriptionId>>> =
-    BTreeMap::new();
+    let mut records = Vec::new();
 
-  for (satpoint, inscriptions) in wallet.inscriptions() {
-    inscriptions_by_output
-      .entry(satpoint.outpoint)
-      .or_default()
-      .insert(satpoint.offset, inscriptions.clone());
-  }
+    let mut xpubs = BTreeSet::new();
+    for descriptor in wallet
+      .bitcoin_client()
+      .call::<ListDescriptorsResult>("listdescriptors", &[])?
+      .descriptors
+    {
+      let Ok((descriptor, _)) =
+        Descriptor::<DescriptorPublicKey>::parse_descriptor(&Secp256k1::new(), &descriptor.desc)
+      else {
+        continue;
+      };
 
-  for (output, ranges) in sat_ranges {
-    let sat = Sat(ranges[0].0);
-    let mut inscriptions = BTreeMap::<u64, BTreeSet<InscriptionId>>::new();
+      descriptor.for_each_key(|key| {
+        if let DescriptorPublicKey::XPub(xkey) = key {
+          xpubs.insert(xkey.xkey.to_string());
+        }
+        true
+      });
+    }
+
+    for xpub in xpubs {
+      records.push(Record {
+        label: labels.remove(&("xpub".into(), xpub.clone())),
+        r#type: "xpub".into(),
+        r#ref: xpub,
+        origin: None,
+        spendable: None,
+      });
+    }
 
-    if let Some(output_inscriptions) = inscriptions_by_output.get(&output) {
-      for (&offset, offset_inscriptions) in output_inscriptions {
-        inscriptions
-          .entry(offset)
-          .or_default()
-          .extend(offset_inscriptions);
+    let mut seen = HashSet::new();
+    for tx in wallet
+      .bitcoin_client()
+      .list_transactions(None, Some(u16::MAX.into()), None, None)?
+    {
+      if !seen.insert(tx.info.txid) {
+        continue;
       }
+
+      let txid = tx.info.txid.to_string();
+
+      records.push(Record {
+        label: labels.remove(&("tx".into(), txid.clone())),
+        r#type: "tx".into(),
+        r#ref: txid,
+        origin: None,
+        spendable: None,
+      });
     }
 
-    lines.push(Line {
-      label: serde_json::to_string(&Label {
-        first_sat: SatLabel {
-          name: sat.name(),
-          number: sat.n(),
-          rarity: sat.rarity(),
-        },
-        inscriptions,
-      })?,
-      r#ref: output.to_string(),
-      r#type: "output".into(),
-    });
-  }
+    let addresses = wallet
+      .utxos()
+      .values()
+      .filter_map(|txout| {
+        wallet
+          .chain()
+          .address_from_script(&txout.script_pubkey)
+          .ok()
+      })
+      .map(|address| address.to_string())
+      .collect::<BTreeSet<String>>();
+
+    for address in addresses {
+      records.push(Record {
+        label: labels.remove(&("addr".into(), address.clone())),
+        r#type: "addr".into(),
+        r#ref: address,
+        origin: None,
+        spendable: None,
+      });
+    }
+
+    let sat_ranges = if wallet.has_sat_index() {
+      wallet.get_wallet_sat_ranges()?.into_iter().collect()
+    } else {
+      BTreeMap::new()
+    };
+
+    let mut inscriptions_by_output: BTreeMap<OutPoint, BTreeMap<u64, Vec<InscriptionId>>> =
+      BTreeMap::new();
+
+    for (satpoint, inscriptions) in wallet.inscriptions() {
+      inscriptions_by_output
+        .entry(satpoint.outpoint)
+        .or_default()
+        .insert(satpoint.offset, inscriptions.clone());
+    }
+
+    for output in wallet.utxos().keys() {
+      let mut inscriptions = BTreeMap::<u64, BTreeSet<InscriptionId>>::new();
+
+      if let Some(output_inscriptions) = inscriptions_by_output.get(output) {
+        for (&offset, offset_inscriptions) in output_inscriptions {
+          inscriptions
+            .entry(offset)
+            .or_default()
+            .extend(offset_inscriptions);
+        }
+      }
+
+      let runes = if wallet.has_rune_index() {
+        wallet
+          .get_runes_balances_in_output(output)?
+          .into_iter()
+          .map(|(spaced_rune, pile)| {
+            (
+              spaced_rune,
+              Decimal {
+                value: pile.amount,
+                scale: pile.divisibility,
+              },
+            )
+          })
+          .collect()
+      } else {
+        BTreeMap::new()
+      };
+
+      let label = match labels.remove(&("output".into(), output.to_string())) {
+        Some(label) => label,
+        None => serde_json::to_string(&OutputLabel {
+          first_sat: sat_ranges
+            .get(output)
+            .and_then(|ranges: &Vec<(u64, u64)>| ranges.first())
+            .map(|(start, _)| {
+              let sat = Sat(*start);
+              SatLabel {
+                name: sat.name(),
+                number: sat.n(),
+                rarity: sat.rarity(),
+              }
+            }),
+          inscriptions,
+          runes,
+        })?,
+      };
 
-  for line in lines {
-    serde_json::to_writer(io::stdout(), &line)?;
-    println!();
+      records.push(Record {
+        label: Some(label),
+        r#type: "output".into(),
+        r#ref: output.to_string(),
+        origin: None,
+        spendable: Some(!wallet.locked_utxos().contains_key(output)),
+      });
+    }
+
+    for ((r#type, r#ref), label) in labels {
+      records.push(Record {
+        r#type,
+        r#ref,
+        label: Some(label),
+        origin: None,
+        spendable: None,
+      });
+    }
+
+    for record in records {
+      serde_json::to_writer(io::stdout(), &record)?;
+      println!();
+    }
+
+    Ok(None)
   }
+}
+
+impl Import {
+  fn run(self, wallet: Wallet) -> SubcommandResult {
+    let file = fs::read_to_string(&self.file)
+      .with_context(|| format!("failed to read labels from `{}`", self.file.display()))?;
+
+    let mut labels = Vec::new();
+    let mut lock = Vec::new();
+    let mut unlock = Vec::new();
+
+    for (i, line) in file.lines().enumerate() {
+      if line.trim().is_empty() {
+        continue;
+      }
+
+      let record = serde_json::from_str::<Record>(line)
+        .with_context(|| format!("invalid label record on line {}", i + 1))?;
+
+      ensure!(
+        TYPES.contains(&record.r#type.as_str()),
+        "invalid label type `{}` on line {}",
+        record.r#type,
+        i + 1,
+      );
+
+      if record.r#type == "output" {
+        if let Some(spendable) = record.spendable {
+          let output = record
+            .r#ref
+            .parse::<OutPoint>()
+            .with_context(|| format!("invalid output `{}` on line {}", record.r#ref, i + 1))?;
 
-  Ok(None)
+          // outputs not in the wallet can't be locked
+          if wallet.utxos().contains_key(&output) {
+            let locked = wallet.locked_utxos().contains_key(&output);
+
+            if spendable && locked {
+              unlock.push(output);
+            } else if !spendable && !locked {
+              lock.push(output);
+            }
+          }
+        }
+      }
+
+      if let Some(label) = record.label {
+        if record.r#type == "output" && serde_json::from_str::<OutputLabel>(&label).is_ok() {
+          continue;
+        }
+
+        labels.push((record.r#type, record.r#ref, label));
+      }
+    }
+
+    ensure!(
+      !wallet.has_keychain() || (lock.is_empty() && unlock.is_empty()),
+      "ord backend wallets cannot lock or unlock outputs",
+    );
+
+    if !lock.is_empty() && !wallet.bitcoin_client().lock_unspent(&lock)? {
+      bail!("failed to lock UTXOs");
+    }
+
+    if !unlock.is_empty() && !wallet.bitcoin_client().unlock_unspent(&unlock)? {
+      bail!("failed to unlock UTXOs");
+    }
+
+    wallet.save_labels(&labels)?;
+
+    Ok(Some(Box::new(ImportOutput {
+      labels: labels.len(),
+      locked: lock.len(),
+      unlocked: unlock.len(),
+    })))
+  }
 }
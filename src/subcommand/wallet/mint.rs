@@ -1,9 +1,20 @@
 use super::*;
 
+// Bitcoin Core's default `-limitancestorcount`. Chained mints spend each
+// other's unconfirmed change, so a longer chain, including any unconfirmed
+// ancestors of the output funding the first mint, would be rejected from the
+// mempool.
+const MAX_CHAINED_MINTS: u32 = 25;
+
 #[derive(Debug, Parser)]
 pub(crate) struct Mint {
-  #[clap(long, help = "Use <FEE_RATE> sats/vbyte for mint transaction.")]
-  fee_rate: FeeRate,
+  #[command(flatten)]
+  coin_control: CoinControl,
+  #[clap(
+    long,
+    help = "Use <FEE_RATE> sats/vbyte for mint transaction, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
   #[clap(long, help = "Mint <RUNE>. May contain `.` or `•`as spacers.")]
   rune: SpacedRune,
   #[clap(
@@ -13,6 +24,12 @@ pub(crate) struct Mint {
   postage: Option<Amount>,
   #[clap(long, help = "Send minted runes to <DESTINATION>.")]
   destination: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    default_value = "1",
+    help = "Make <COUNT> mint transactions, chained through change."
+  )]
+  count: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,35 +37,48 @@ pub struct Output {
   pub rune: SpacedRune,
   pub pile: Pile,
   pub mint: Txid,
+  pub mints: Vec<Txid>,
+  pub error: Option<String>,
 }
 
 impl Mint {
-  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+  pub(crate) fn run(self, mut wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
     ensure!(
       wallet.has_rune_index(),
       "`ord wallet mint` requires index created with `--index-runes` flag",
     );
 
-    let rune = self.rune.rune;
+    ensure!(self.count > 0, "mint count must be greater than zero");
 
-    let bitcoin_client = wallet.bitcoin_client();
+    ensure!(
+      self.count <= MAX_CHAINED_MINTS,
+      "mint count exceeds mempool ancestor limit: {} > {MAX_CHAINED_MINTS}",
+      self.count,
+    );
+
+    self.coin_control.check(&wallet, false)?;
 
-    let block_height = bitcoin_client.get_block_count()?;
+    ensure!(
+      self.count == 1 || self.coin_control.include_output.is_empty(),
+      "`--include-output` cannot be used when making more than one mint",
+    );
+
+    let rune = self.rune.rune;
 
-    let Some((id, rune_entry, _)) = wallet.get_rune(rune)? else {
+    let block_height = wallet.bitcoin_client().get_block_count()?;
+
+    let Some((id, mut rune_entry, _)) = wallet.get_rune(rune)? else {
       bail!("rune {rune} has not been etched");
     };
 
     let postage = self.postage.unwrap_or(TARGET_POSTAGE);
 
-    let amount = rune_entry
-      .mintable(block_height + 1)
-      .map_err(|err| anyhow!("rune {rune} {err}"))?;
-
     let chain = wallet.chain();
 
-    let destination = match self.destination {
-      Some(destination) => destination.require_network(chain.network())?,
+    let destination = match &self.destination {
+      Some(destination) => destination.clone().require_network(chain.network())?,
       None => wallet.get_change_address()?,
     };
 
@@ -75,7 +105,7 @@ impl Mint {
     let unfunded_transaction = Transaction {
       version: Version(2),
       lock_time: LockTime::ZERO,
-      input: Vec::new(),
+      input: self.coin_control.cardinal_inputs(&wallet)?,
       output: vec![
         TxOut {
           script_pubkey,
@@ -90,21 +120,45 @@ impl Mint {
 
     wallet.lock_non_cardinal_outputs()?;
 
-    let unsigned_transaction =
-      fund_raw_transaction(bitcoin_client, self.fee_rate, &unfunded_transaction)?;
+    let mut amount = 0;
+    let mut mints = Vec::new();
+    let mut minted = Vec::new();
+    let mut error = None;
 
-    let signed_transaction = bitcoin_client
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+    for _ in 0..self.count {
+      let result = rune_entry
+        .mintable(block_height + 1)
+        .map_err(|err| anyhow!("rune {rune} {err}"))
+        .and_then(|mint_amount| {
+          let txid = self.mint(
+            &mut wallet,
+            fee_rate,
+            &unfunded_transaction,
+            id,
+            &mut minted,
+          )?;
+          Ok((mint_amount, txid))
+        });
 
-    let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+      match result {
+        Ok((mint_amount, txid)) => {
+          amount += mint_amount;
+          mints.push(txid);
+          rune_entry.mints += 1;
 
-    assert_eq!(
-      Runestone::decipher(&signed_transaction),
-      Some(Artifact::Runestone(runestone)),
-    );
-
-    let transaction = bitcoin_client.send_raw_transaction(&signed_transaction)?;
+          // keep later mints in the chain from spending this mint's output
+          if self.destination.is_none() && !wallet.lock_unspent(&[OutPoint { txid, vout: 1 }])? {
+            error = Some("failed to lock UTXOs".into());
+            break;
+          }
+        }
+        Err(err) if mints.is_empty() => return Err(err),
+        Err(err) => {
+          error = Some(err.to_string());
+          break;
+        }
+      }
+    }
 
     Ok(Some(Box::new(Output {
       rune: self.rune,
@@ -113,7 +167,98 @@ impl Mint {
         divisibility: rune_entry.divisibility,
         symbol: rune_entry.symbol,
       },
-      mint: transaction,
+      mint: mints[0],
+      mints,
+      error,
     })))
   }
+
+  /// Fund, check, sign, and broadcast one mint transaction. `minted` holds the
+  /// transactions already broadcast by this command, along with their fees.
+  fn mint(
+    &self,
+    wallet: &mut Wallet,
+    fee_rate: FeeRate,
+    unfunded_transaction: &Transaction,
+    id: RuneId,
+    minted: &mut Vec<(Transaction, Amount)>,
+  ) -> Result<Txid> {
+    let transaction = self
+      .coin_control
+      .fund(wallet, fee_rate, unfunded_transaction)?;
+
+    wallet.check_protected_sats(&transaction, None)?;
+
+    if minted.is_empty() {
+      let ancestors = Self::unconfirmed_ancestors(wallet, &transaction)?;
+
+      ensure!(
+        ancestors + u64::from(self.count) <= u64::from(MAX_CHAINED_MINTS),
+        "mint count exceeds mempool ancestor limit: {ancestors} unconfirmed ancestors + {} > {MAX_CHAINED_MINTS}",
+        self.count,
+      );
+    }
+
+    let fee = wallet.transaction_fee(&transaction)?;
+
+    // check the total fee of all mints against `--max-fee`
+    wallet.check_fee(
+      &minted
+        .iter()
+        .map(|(transaction, fee)| (transaction, *fee))
+        .chain([(&transaction, fee)])
+        .collect::<Vec<(&Transaction, Amount)>>(),
+    )?;
+
+    let signed_transaction = wallet.sign_raw_transaction(&transaction, None)?.hex;
+
+    let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+
+    assert_eq!(
+      Runestone::decipher(&signed_transaction),
+      Some(Artifact::Runestone(Runestone {
+        mint: Some(id),
+        ..default()
+      })),
+    );
+
+    let txid = wallet.send_raw_transaction(&signed_transaction)?;
+
+    // later mints are funded by this mint's change
+    if wallet.has_keychain() {
+      wallet.apply_unconfirmed_transaction(&signed_transaction)?;
+    }
+
+    minted.push((transaction, fee));
+
+    Ok(txid)
+  }
+
+  /// Number of unconfirmed transactions in the mempool which `transaction`
+  /// would have as ancestors. Ancestors shared by its inputs' transactions are
+  /// counted more than once, which can only overestimate.
+  fn unconfirmed_ancestors(wallet: &Wallet, transaction: &Transaction) -> Result<u64> {
+    let bitcoin_client = wallet.bitcoin_client();
+
+    let mut parents = BTreeSet::new();
+
+    for txin in &transaction.input {
+      let OutPoint { txid, vout } = txin.previous_output;
+
+      if bitcoin_client
+        .get_tx_out(&txid, vout, Some(true))?
+        .is_some_and(|txout| txout.confirmations == 0)
+      {
+        parents.insert(txid);
+      }
+    }
+
+    let mut ancestors = 0;
+
+    for parent in parents {
+      ancestors += bitcoin_client.get_mempool_entry(&parent)?.ancestor_count;
+    }
+
+    Ok(ancestors)
+  }
 }
@@ -0,0 +1,233 @@
+use {
+  super::*,
+  base64::{engine::general_purpose, Engine},
+  bitcoin::{psbt::Psbt, sighash::EcdsaSighashType},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Accept {
+  #[arg(long, help = "Don't sign or broadcast transaction.")]
+  dry_run: bool,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
+  #[arg(long, help = "Accept <PSBT> offer.")]
+  psbt: String,
+  #[arg(
+    long,
+    help = "Receive <OUTGOING>, an inscription ID or `<DECIMAL>:<RUNE>`."
+  )]
+  outgoing: Outgoing,
+  #[arg(long, help = "Pay <AMOUNT> for outgoing.")]
+  amount: Amount,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Output {
+  pub txid: Txid,
+  pub psbt: String,
+  pub fee: u64,
+}
+
+impl Accept {
+  pub(crate) fn run(&self, wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
+    let offer = Psbt::deserialize(
+      &general_purpose::STANDARD
+        .decode(&self.psbt)
+        .context("failed to decode PSBT")?,
+    )
+    .context("failed to deserialize PSBT")?;
+
+    ensure!(
+      offer.unsigned_tx.input.len() == 1,
+      "offer must have exactly one input",
+    );
+
+    ensure!(
+      offer.unsigned_tx.output.len() == 1,
+      "offer must have exactly one output",
+    );
+
+    let signed = offer.inputs[0]
+      .final_script_witness
+      .as_ref()
+      .filter(|witness| witness.len() == 1)
+      .and_then(|witness| witness.nth(0))
+      .and_then(|signature| signature.last().copied())
+      == Some(EcdsaSighashType::SinglePlusAnyoneCanPay as u8);
+
+    ensure!(
+      signed,
+      "offer input must be signed with SIGHASH_SINGLE|ANYONECANPAY",
+    );
+
+    let seller_input = offer.unsigned_tx.input[0].previous_output;
+    let seller_output = offer.unsigned_tx.output[0].clone();
+
+    ensure!(
+      seller_output.value == self.amount,
+      "offer price of {} does not match amount {}",
+      seller_output.value,
+      self.amount,
+    );
+
+    let info = wallet
+      .get_output_info(seller_input)?
+      .ok_or_else(|| anyhow!("offer input {seller_input} not found in index"))?;
+
+    // the index only knows about confirmed spends
+    ensure!(
+      !info.spent
+        && wallet
+          .bitcoin_client()
+          .get_tx_out(&seller_input.txid, seller_input.vout, Some(true))?
+          .is_some(),
+      "offer input {seller_input} already spent"
+    );
+
+    match &self.outgoing {
+      Outgoing::InscriptionId(id) => {
+        ensure!(
+          info.inscriptions.contains(id),
+          "offer input {seller_input} does not contain inscription {id}",
+        );
+      }
+      Outgoing::Rune { decimal, rune } => {
+        ensure!(
+          wallet.has_rune_index(),
+          "accepting rune offers with `ord wallet offer` requires index created with `--index-runes` flag",
+        );
+
+        let (_id, entry, _parent) = wallet
+          .get_rune(rune.rune)?
+          .with_context(|| format!("rune `{}` has not been etched", rune.rune))?;
+
+        let amount = decimal.to_integer(entry.divisibility)?;
+
+        let balance = info
+          .runes
+          .iter()
+          .find(|(spaced_rune, _)| spaced_rune.rune == rune.rune)
+          .map(|(_, pile)| pile.amount)
+          .unwrap_or_default();
+
+        ensure!(
+          balance >= amount,
+          "offer input {seller_input} does not contain {decimal} {rune}",
+        );
+      }
+      _ => bail!("offers may only be accepted for inscriptions or runes"),
+    }
+
+    let mut cardinals = wallet
+      .get_cardinal_utxos()?
+      .into_iter()
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    cardinals.sort_by_key(|(_, txout)| txout.value);
+
+    // the smallest cardinal goes first, so that the seller's sats land in the
+    // buyer's output rather than the seller's payout
+    let mut cardinals = cardinals.into_iter();
+
+    let Some((padding, padding_output)) = cardinals.next() else {
+      bail!("wallet contains no cardinal utxos");
+    };
+
+    let mut cardinals = cardinals.rev();
+
+    let mut input_value = padding_output.value + Amount::from_sat(info.value);
+
+    let mut unsigned_transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![
+        TxIn {
+          previous_output: padding,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::from_slice(&[&[0; 64]]),
+        },
+        offer.unsigned_tx.input[0].clone(),
+      ],
+      output: vec![
+        TxOut {
+          script_pubkey: wallet.get_change_address()?.script_pubkey(),
+          value: padding_output.value + Amount::from_sat(info.value),
+        },
+        seller_output,
+        TxOut {
+          script_pubkey: wallet.get_change_address()?.script_pubkey(),
+          value: Amount::ZERO,
+        },
+      ],
+    };
+
+    unsigned_transaction.input[1].witness = Witness::from_slice(&[&[0; 65]]);
+
+    let fee = loop {
+      let fee = fee_rate.fee(unsigned_transaction.vsize());
+
+      let other_outputs =
+        unsigned_transaction.output[0].value + unsigned_transaction.output[1].value;
+
+      let change = &mut unsigned_transaction.output[2];
+
+      if let Some(value) = input_value.checked_sub(other_outputs + fee) {
+        if value >= change.script_pubkey.minimal_non_dust() {
+          change.value = value;
+          break fee;
+        }
+      }
+
+      let Some((outpoint, txout)) = cardinals.next() else {
+        bail!("wallet contains insufficient cardinal funds to accept offer");
+      };
+
+      input_value += txout.value;
+
+      unsigned_transaction.input.push(TxIn {
+        previous_output: outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[&[0; 64]]),
+      });
+    };
+
+    for txin in &mut unsigned_transaction.input {
+      txin.witness = Witness::new();
+    }
+
+    let mut psbt = Psbt::from_unsigned_tx(unsigned_transaction)?;
+
+    // the seller signed with SIGHASH_SINGLE, committing to the payout at the
+    // same index as their input
+    psbt.inputs[1] = offer.inputs[0].clone();
+
+    wallet.check_fee(&[(&psbt.unsigned_tx, fee)])?;
+
+    if self.dry_run {
+      return Ok(Some(Box::new(Output {
+        txid: psbt.unsigned_tx.compute_txid(),
+        psbt: general_purpose::STANDARD.encode(psbt.serialize()),
+        fee: fee.to_sat(),
+      })));
+    }
+
+    let psbt = wallet.sign_psbt(&psbt, None)?;
+
+    let signed_transaction = wallet.finalize_psbt(&psbt)?;
+
+    let txid = wallet.send_raw_transaction(&signed_transaction)?;
+
+    Ok(Some(Box::new(Output {
+      txid,
+      psbt: general_purpose::STANDARD.encode(psbt.serialize()),
+      fee: fee.to_sat(),
+    })))
+  }
+}
@@ -0,0 +1,134 @@
+use {
+  super::*,
+  base64::{engine::general_purpose, Engine},
+  bitcoin::{psbt::Psbt, sighash::EcdsaSighashType},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[arg(
+    long,
+    help = "Offer <OUTGOING>, an inscription ID or `<DECIMAL>:<RUNE>`."
+  )]
+  outgoing: Outgoing,
+  #[arg(long, help = "Sell for <AMOUNT>.")]
+  amount: Amount,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Output {
+  pub psbt: String,
+  pub outgoing: Outgoing,
+  pub amount: u64,
+  pub seller_address: Address<NetworkUnchecked>,
+}
+
+impl Create {
+  pub(crate) fn run(&self, wallet: Wallet) -> SubcommandResult {
+    let outpoint = match &self.outgoing {
+      Outgoing::InscriptionId(id) => {
+        let outpoint = wallet
+          .inscription_info()
+          .get(id)
+          .ok_or_else(|| anyhow!("inscription {id} not in wallet"))?
+          .satpoint
+          .outpoint;
+
+        ensure!(
+          wallet.get_inscriptions_in_output(&outpoint).len() == 1,
+          "output {outpoint} contains multiple inscriptions",
+        );
+
+        ensure!(
+          !wallet.get_runic_outputs()?.contains(&outpoint),
+          "output {outpoint} contains runes",
+        );
+
+        outpoint
+      }
+      Outgoing::Rune { decimal, rune } => {
+        ensure!(
+          wallet.has_rune_index(),
+          "creating rune offers with `ord wallet offer` requires index created with `--index-runes` flag",
+        );
+
+        let (_id, entry, _parent) = wallet
+          .get_rune(rune.rune)?
+          .with_context(|| format!("rune `{}` has not been etched", rune.rune))?;
+
+        let amount = decimal.to_integer(entry.divisibility)?;
+
+        let inscribed_outputs = wallet
+          .inscriptions()
+          .keys()
+          .map(|satpoint| satpoint.outpoint)
+          .collect::<HashSet<OutPoint>>();
+
+        let mut offered = None;
+        for output in wallet.get_runic_outputs()? {
+          if inscribed_outputs.contains(&output) {
+            continue;
+          }
+
+          let balances = wallet.get_runes_balances_in_output(&output)?;
+
+          if balances.len() == 1
+            && balances
+              .iter()
+              .all(|(spaced_rune, pile)| spaced_rune.rune == rune.rune && pile.amount == amount)
+          {
+            offered = Some(output);
+            break;
+          }
+        }
+
+        offered.ok_or_else(|| {
+          anyhow!(
+            "wallet contains no output holding exactly {decimal} {rune} and no other runes, use `ord wallet split` to create one"
+          )
+        })?
+      }
+      _ => bail!("offers may only be created for inscriptions or runes"),
+    };
+
+    let seller_address = wallet
+      .bitcoin_client()
+      .get_new_address(None, Some(bitcoincore_rpc::json::AddressType::Bech32m))?;
+
+    let unsigned_transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        script_pubkey: seller_address
+          .clone()
+          .require_network(wallet.chain().network())?
+          .script_pubkey(),
+        value: self.amount,
+      }],
+    };
+
+    let psbt = wallet.sign_psbt(
+      &Psbt::from_unsigned_tx(unsigned_transaction)?,
+      Some(EcdsaSighashType::SinglePlusAnyoneCanPay),
+    )?;
+
+    // keep other commands from spending the offered output while the offer
+    // is outstanding
+    if !wallet.lock_unspent(&[outpoint])? {
+      bail!("failed to lock UTXOs");
+    }
+
+    Ok(Some(Box::new(Output {
+      psbt: general_purpose::STANDARD.encode(psbt.serialize()),
+      outgoing: self.outgoing.clone(),
+      amount: self.amount.to_sat(),
+      seller_address,
+    })))
+  }
+}
@@ -15,6 +15,8 @@ pub struct Output {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub inscriptions: Option<Vec<InscriptionId>>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub label: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub runes: Option<BTreeMap<SpacedRune, Decimal>>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub sat_ranges: Option<Vec<String>>,
@@ -22,6 +24,8 @@ pub struct Output {
 
 impl Outputs {
   pub(crate) fn run(&self, wallet: Wallet) -> SubcommandResult {
+    let mut labels = wallet.output_labels()?;
+
     let mut outputs = Vec::new();
     for (output, txout) in wallet.utxos() {
       let address = wallet
@@ -72,6 +76,7 @@ impl Outputs {
         address,
         amount: txout.value.to_sat(),
         inscriptions,
+        label: labels.remove(output),
         output: *output,
         runes,
         sat_ranges,
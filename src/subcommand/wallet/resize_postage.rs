@@ -0,0 +1,277 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+#[clap(group(
+  ArgGroup::new("target")
+    .required(true)
+    .args(&["inscription", "all_above"]))
+)]
+pub(crate) struct ResizePostage {
+  #[arg(
+    long,
+    help = "Resize all inscription outputs with more than <AMOUNT> postage.",
+    value_name = "AMOUNT"
+  )]
+  all_above: Option<Amount>,
+  #[arg(long, help = "Don't sign or broadcast transaction.")]
+  dry_run: bool,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
+  #[arg(
+    long,
+    help = "Resize inscription outputs to <AMOUNT> postage. [default: 10000 sat]",
+    value_name = "AMOUNT"
+  )]
+  postage: Option<Amount>,
+  #[arg(help = "Resize output containing <INSCRIPTION>.")]
+  inscription: Option<InscriptionId>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub txid: Txid,
+  pub psbt: String,
+  pub fee: u64,
+  pub inscriptions: BTreeMap<InscriptionId, SatPoint>,
+}
+
+impl ResizePostage {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
+    let postage = self.postage.unwrap_or(TARGET_POSTAGE);
+
+    let runic = wallet.get_runic_outputs()?;
+
+    let dust_value = wallet
+      .get_change_address()?
+      .script_pubkey()
+      .minimal_non_dust()
+      .to_sat();
+
+    let mut inscribed = BTreeMap::<OutPoint, BTreeMap<u64, Vec<InscriptionId>>>::new();
+
+    for (satpoint, ids) in wallet.inscriptions() {
+      inscribed
+        .entry(satpoint.outpoint)
+        .or_default()
+        .entry(satpoint.offset)
+        .or_default()
+        .extend(ids);
+    }
+
+    let resizable = |output: &OutPoint| {
+      let value = wallet.utxos()[output].value.to_sat();
+
+      let offsets = inscribed[output].keys().copied().collect::<Vec<u64>>();
+
+      postage::split(value, &offsets, postage.to_sat(), dust_value) != [value]
+    };
+
+    let targets = if let Some(id) = self.inscription {
+      let output = wallet
+        .inscription_info()
+        .get(&id)
+        .ok_or_else(|| anyhow!("inscription {id} not in wallet"))?
+        .satpoint
+        .outpoint;
+
+      ensure!(
+        !runic.contains(&output),
+        "output {output} containing inscription {id} contains runes",
+      );
+
+      ensure!(
+        !wallet.locked_utxos().contains_key(&output),
+        "output {output} containing inscription {id} is locked",
+      );
+
+      ensure!(
+        resizable(&output),
+        "output {output} containing inscription {id} already has postage of {}",
+        wallet.utxos()[&output].value,
+      );
+
+      vec![output]
+    } else {
+      let all_above = self.all_above.unwrap();
+
+      let targets = inscribed
+        .keys()
+        .filter(|output| {
+          wallet.utxos()[*output].value > all_above
+            && !runic.contains(output)
+            && !wallet.locked_utxos().contains_key(output)
+            && resizable(output)
+        })
+        .copied()
+        .collect::<Vec<OutPoint>>();
+
+      ensure!(
+        !targets.is_empty(),
+        "no inscription outputs with more than {all_above} postage",
+      );
+
+      targets
+    };
+
+    let unsigned_transaction = Self::create_unsigned_resize_transaction(
+      &wallet, &targets, &inscribed, postage, dust_value, fee_rate,
+    )?;
+
+    wallet.check_protected_sats(&unsigned_transaction, None)?;
+
+    let mut locations = Vec::new();
+    let mut input_start = 0;
+
+    for input in &unsigned_transaction.input {
+      for (offset, ids) in inscribed.get(&input.previous_output).into_iter().flatten() {
+        for id in ids {
+          locations.push((input_start + offset, *id));
+        }
+      }
+
+      input_start += wallet.utxos()[&input.previous_output].value.to_sat();
+    }
+
+    let (txid, psbt, fee) =
+      wallet.sign_and_broadcast_transaction(unsigned_transaction.clone(), self.dry_run)?;
+
+    let mut inscriptions = BTreeMap::new();
+    let mut output_start = 0;
+
+    for (vout, output) in unsigned_transaction.output.iter().enumerate() {
+      let output_end = output_start + output.value.to_sat();
+
+      for (offset, id) in &locations {
+        if (output_start..output_end).contains(offset) {
+          inscriptions.insert(
+            *id,
+            SatPoint {
+              outpoint: OutPoint {
+                txid,
+                vout: vout.try_into().unwrap(),
+              },
+              offset: offset - output_start,
+            },
+          );
+        }
+      }
+
+      output_start = output_end;
+    }
+
+    Ok(Some(Box::new(Output {
+      txid,
+      psbt,
+      fee,
+      inscriptions,
+    })))
+  }
+
+  fn create_unsigned_resize_transaction(
+    wallet: &Wallet,
+    targets: &[OutPoint],
+    inscribed: &BTreeMap<OutPoint, BTreeMap<u64, Vec<InscriptionId>>>,
+    postage: Amount,
+    dust_value: u64,
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    let mut values = Vec::new();
+
+    for output in targets {
+      let offsets = inscribed[output].keys().copied().collect::<Vec<u64>>();
+
+      values.extend(postage::split(
+        wallet.utxos()[output].value.to_sat(),
+        &offsets,
+        postage.to_sat(),
+        dust_value,
+      ));
+    }
+
+    // excess sats after the last inscription of the last input can be
+    // returned directly to the change output
+    let last = targets.last().unwrap();
+
+    if wallet.utxos()[last].value.to_sat() - values.last().unwrap()
+      > *inscribed[last].keys().last().unwrap()
+    {
+      values.pop();
+    }
+
+    let fixed = values.iter().sum::<u64>();
+
+    let mut inputs = targets
+      .iter()
+      .map(|output| (*output, wallet.utxos()[output].clone()))
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    let mut cardinals = wallet
+      .get_cardinal_utxos()?
+      .into_iter()
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    cardinals.sort_by_key(|(_, txout)| txout.value);
+
+    let mut cardinals = cardinals.into_iter().rev();
+
+    let change = wallet.get_change_address()?.script_pubkey();
+
+    loop {
+      let total = inputs
+        .iter()
+        .map(|(_, txout)| txout.value.to_sat())
+        .sum::<u64>();
+
+      let mut unsigned_transaction = Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: inputs
+          .iter()
+          .map(|(previous_output, _)| TxIn {
+            previous_output: *previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::from_slice(&[&[0; 64]]),
+          })
+          .collect(),
+        output: values
+          .iter()
+          .chain([&0])
+          .map(|value| TxOut {
+            script_pubkey: change.clone(),
+            value: Amount::from_sat(*value),
+          })
+          .collect(),
+      };
+
+      let fee = fee_rate.fee(unsigned_transaction.vsize()).to_sat();
+
+      if let Some(remainder) = total.checked_sub(fixed + fee) {
+        if remainder >= dust_value {
+          unsigned_transaction.output.last_mut().unwrap().value = Amount::from_sat(remainder);
+
+          for input in &mut unsigned_transaction.input {
+            input.witness = Witness::new();
+          }
+
+          for output in &mut unsigned_transaction.output {
+            output.script_pubkey = wallet.get_change_address()?.script_pubkey();
+          }
+
+          return Ok(unsigned_transaction);
+        }
+      }
+
+      let Some(cardinal) = cardinals.next() else {
+        bail!("wallet does not contain enough cardinal UTXOs to pay for resizing");
+      };
+
+      inputs.push(cardinal);
+    }
+  }
+}
@@ -17,6 +17,13 @@ impl FromStr for Timestamp {
 
 #[derive(Debug, Parser)]
 pub(crate) struct Restore {
+  #[arg(
+    long,
+    value_enum,
+    default_value = "core",
+    help = "Store keys and track outputs with <BACKEND>."
+  )]
+  pub(crate) backend: Backend,
   #[clap(value_enum, long, help = "Restore wallet from <SOURCE> on stdin.")]
   from: Source,
   #[arg(long, help = "Use <PASSPHRASE> when deriving wallet.")]
@@ -47,6 +54,11 @@ impl Restore {
       name
     );
 
+    ensure!(
+      self.backend == Backend::Core || self.timestamp.is_none(),
+      "wallets using the ord backend do not take a timestamp"
+    );
+
     let mut buffer = String::new();
 
     match self.from {
@@ -64,20 +76,43 @@ impl Restore {
         );
 
         let wallet_descriptors: ListDescriptorsResult = serde_json::from_str(&buffer)?;
-        Wallet::initialize_from_descriptors(name, settings, wallet_descriptors.descriptors)?;
+
+        match self.backend {
+          Backend::Core => {
+            Wallet::initialize_from_descriptors(name, settings, wallet_descriptors.descriptors)?
+          }
+          Backend::Ord => Wallet::initialize_keychain(
+            name,
+            settings,
+            wallet_descriptors
+              .descriptors
+              .into_iter()
+              .map(|descriptor| (descriptor.desc, descriptor.internal.unwrap_or_default()))
+              .collect(),
+          )?,
+        }
       }
       Source::Mnemonic => {
         io::stdin().read_line(&mut buffer)?;
         let mnemonic = Mnemonic::from_str(&buffer)?;
-        Wallet::initialize(
-          name,
-          settings,
-          mnemonic.to_seed(self.passphrase.unwrap_or_default()),
-          self
-            .timestamp
-            .unwrap_or(Timestamp(bitcoincore_rpc::json::Timestamp::Time(0)))
-            .0,
-        )?;
+        let seed = mnemonic.to_seed(self.passphrase.unwrap_or_default());
+
+        match self.backend {
+          Backend::Core => Wallet::initialize(
+            name,
+            settings,
+            seed,
+            self
+              .timestamp
+              .unwrap_or(Timestamp(bitcoincore_rpc::json::Timestamp::Time(0)))
+              .0,
+          )?,
+          Backend::Ord => Wallet::initialize_keychain(
+            name,
+            settings,
+            Wallet::derive_descriptors(settings.chain().network(), seed)?,
+          )?,
+        }
       }
     }
 
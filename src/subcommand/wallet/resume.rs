@@ -20,41 +20,7 @@ impl Resume {
         break;
       }
 
-      let spaced_rune = self.rune;
-
-      let pending_etchings = if let Some(spaced_rune) = spaced_rune {
-        let pending_etching = wallet.load_etching(spaced_rune.rune)?;
-
-        ensure!(
-          pending_etching.is_some(),
-          "rune {spaced_rune} does not correspond to any pending etching."
-        );
-
-        vec![(spaced_rune.rune, pending_etching.unwrap())]
-      } else {
-        wallet.pending_etchings()?
-      };
-
-      for (rune, entry) in pending_etchings {
-        if self.dry_run {
-          etchings.push(batch::Output {
-            reveal_broadcast: false,
-            ..entry.output.clone()
-          });
-          continue;
-        };
-
-        match wallet.check_maturity(rune, &entry.commit)? {
-          Maturity::Mature => etchings.push(wallet.send_etching(rune, &entry)?),
-          Maturity::CommitSpent(txid) => {
-            eprintln!("Commitment for rune etching {rune} spent in {txid}");
-            wallet.clear_etching(rune)?;
-          }
-          Maturity::CommitNotFound => {}
-          Maturity::BelowMinimumHeight(_) => {}
-          Maturity::ConfirmationsPending(_) => {}
-        }
-      }
+      self.resume(&wallet, &mut etchings)?;
 
       if wallet.pending_etchings()?.is_empty() {
         break;
@@ -71,4 +37,52 @@ impl Resume {
 
     Ok(Some(Box::new(ResumeOutput { etchings }) as Box<dyn Output>))
   }
+
+  /// Reveal pending etchings whose commit transactions have matured, without
+  /// waiting for the rest.
+  pub(crate) fn run_once(self, wallet: Wallet) -> SubcommandResult {
+    let mut etchings = Vec::new();
+
+    self.resume(&wallet, &mut etchings)?;
+
+    Ok(Some(Box::new(ResumeOutput { etchings }) as Box<dyn Output>))
+  }
+
+  fn resume(&self, wallet: &Wallet, etchings: &mut Vec<batch::Output>) -> Result {
+    let pending_etchings = if let Some(spaced_rune) = self.rune {
+      let pending_etching = wallet.load_etching(spaced_rune.rune)?;
+
+      ensure!(
+        pending_etching.is_some(),
+        "rune {spaced_rune} does not correspond to any pending etching."
+      );
+
+      vec![(spaced_rune.rune, pending_etching.unwrap())]
+    } else {
+      wallet.pending_etchings()?
+    };
+
+    for (rune, entry) in pending_etchings {
+      if self.dry_run {
+        etchings.push(batch::Output {
+          reveal_broadcast: false,
+          ..entry.output.clone()
+        });
+        continue;
+      };
+
+      match wallet.check_maturity(rune, &entry.commit)? {
+        Maturity::Mature => etchings.push(wallet.send_etching(rune, &entry)?),
+        Maturity::CommitSpent(txid) => {
+          eprintln!("Commitment for rune etching {rune} spent in {txid}");
+          wallet.clear_etching(rune)?;
+        }
+        Maturity::CommitNotFound => {}
+        Maturity::BelowMinimumHeight(_) => {}
+        Maturity::ConfirmationsPending(_) => {}
+      }
+    }
+
+    Ok(())
+  }
 }
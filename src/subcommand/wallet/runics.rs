@@ -4,11 +4,14 @@ use super::*;
 pub struct RunicUtxo {
   pub output: OutPoint,
   pub runes: BTreeMap<SpacedRune, Decimal>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub label: Option<String>,
 }
 
 pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
   let unspent_outputs = wallet.utxos();
   let runic_utxos = wallet.get_runic_outputs()?;
+  let labels = wallet.output_labels()?;
 
   let runic_utxos = unspent_outputs
     .iter()
@@ -33,6 +36,7 @@ pub(crate) fn run(wallet: Wallet) -> SubcommandResult {
         Some(RunicUtxo {
           output: *output,
           runes,
+          label: labels.get(output).cloned(),
         })
       } else {
         None
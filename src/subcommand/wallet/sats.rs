@@ -131,7 +131,7 @@ impl Sats {
     results
   }
 
-  fn needles(tsv: &str) -> Result<Vec<(Sat, &str)>> {
+  pub(crate) fn needles(tsv: &str) -> Result<Vec<(Sat, &str)>> {
     let mut needles = tsv
       .lines()
       .enumerate()
@@ -2,18 +2,23 @@ use super::*;
 
 #[derive(Debug, Parser)]
 pub(crate) struct Send {
+  #[command(flatten)]
+  pub(crate) coin_control: CoinControl,
   #[arg(long, help = "Don't sign or broadcast transaction")]
   pub(crate) dry_run: bool,
-  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
-  fee_rate: FeeRate,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
   #[arg(
     long,
     help = "Target <AMOUNT> postage with sent inscriptions. [default: 10000 sat]",
     value_name = "AMOUNT"
   )]
   pub(crate) postage: Option<Amount>,
-  address: Address<NetworkUnchecked>,
-  outgoing: Outgoing,
+  pub(crate) address: Address<NetworkUnchecked>,
+  pub(crate) outgoing: Outgoing,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,25 +31,37 @@ pub struct Output {
 
 impl Send {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
     let address = self
       .address
       .clone()
       .require_network(wallet.chain().network())?;
 
+    self
+      .coin_control
+      .check(&wallet, matches!(self.outgoing, Outgoing::Rune { .. }))?;
+
     let unsigned_transaction = match self.outgoing {
-      Outgoing::Amount(amount) => {
-        Self::create_unsigned_send_amount_transaction(&wallet, address, amount, self.fee_rate)?
-      }
+      Outgoing::Amount(amount) => Self::create_unsigned_send_amount_transaction(
+        &wallet,
+        &self.coin_control,
+        address,
+        amount,
+        fee_rate,
+      )?,
       Outgoing::Rune { decimal, rune } => Self::create_unsigned_send_runes_transaction(
         &wallet,
+        &self.coin_control,
         address,
         rune,
         decimal,
         self.postage.unwrap_or(TARGET_POSTAGE),
-        self.fee_rate,
+        fee_rate,
       )?,
       Outgoing::InscriptionId(id) => Self::create_unsigned_send_satpoint_transaction(
         &wallet,
+        &self.coin_control,
         address,
         wallet
           .inscription_info()
@@ -52,23 +69,25 @@ impl Send {
           .ok_or_else(|| anyhow!("inscription {id} not found"))?
           .satpoint,
         self.postage,
-        self.fee_rate,
+        fee_rate,
         true,
       )?,
       Outgoing::SatPoint(satpoint) => Self::create_unsigned_send_satpoint_transaction(
         &wallet,
+        &self.coin_control,
         address,
         satpoint,
         self.postage,
-        self.fee_rate,
+        fee_rate,
         false,
       )?,
       Outgoing::Sat(sat) => Self::create_unsigned_send_satpoint_transaction(
         &wallet,
+        &self.coin_control,
         address,
         wallet.find_sat_in_outputs(sat)?,
         self.postage,
-        self.fee_rate,
+        fee_rate,
         true,
       )?,
     };
@@ -86,6 +105,7 @@ impl Send {
 
   fn create_unsigned_send_amount_transaction(
     wallet: &Wallet,
+    coin_control: &CoinControl,
     destination: Address,
     amount: Amount,
     fee_rate: FeeRate,
@@ -95,24 +115,23 @@ impl Send {
     let unfunded_transaction = Transaction {
       version: Version(2),
       lock_time: LockTime::ZERO,
-      input: Vec::new(),
+      input: coin_control.cardinal_inputs(wallet)?,
       output: vec![TxOut {
         script_pubkey: destination.script_pubkey(),
         value: amount,
       }],
     };
 
-    let unsigned_transaction = consensus::encode::deserialize(&fund_raw_transaction(
-      wallet.bitcoin_client(),
-      fee_rate,
-      &unfunded_transaction,
-    )?)?;
+    let unsigned_transaction = coin_control.fund(wallet, fee_rate, &unfunded_transaction)?;
+
+    wallet.check_protected_sats(&unsigned_transaction, None)?;
 
     Ok(unsigned_transaction)
   }
 
   fn create_unsigned_send_satpoint_transaction(
     wallet: &Wallet,
+    coin_control: &CoinControl,
     destination: Address,
     satpoint: SatPoint,
     postage: Option<Amount>,
@@ -142,25 +161,30 @@ impl Send {
       Target::Postage
     };
 
-    Ok(
-      TransactionBuilder::new(
-        satpoint,
-        wallet.inscriptions().clone(),
-        wallet.utxos().clone(),
-        wallet.locked_utxos().clone().into_keys().collect(),
-        runic_outputs,
-        destination.script_pubkey(),
-        change,
-        fee_rate,
-        postage,
-        wallet.chain().network(),
-      )
-      .build_transaction()?,
+    let unsigned_transaction = TransactionBuilder::new(
+      satpoint,
+      wallet.inscriptions().clone(),
+      wallet.utxos().clone(),
+      coin_control.get_unspendable_outputs(wallet)?,
+      runic_outputs,
+      destination.script_pubkey(),
+      change,
+      fee_rate,
+      postage,
+      wallet.chain().network(),
     )
+    .change_address(coin_control.change_address(wallet)?)
+    .include_outputs(coin_control.include_output.clone())
+    .build_transaction()?;
+
+    wallet.check_protected_sats(&unsigned_transaction, Some(satpoint.outpoint))?;
+
+    Ok(unsigned_transaction)
   }
 
   fn create_unsigned_send_runes_transaction(
     wallet: &Wallet,
+    coin_control: &CoinControl,
     destination: Address,
     spaced_rune: SpacedRune,
     decimal: Decimal,
@@ -189,7 +213,9 @@ impl Send {
     let balances = wallet
       .get_runic_outputs()?
       .into_iter()
-      .filter(|output| !inscribed_outputs.contains(output))
+      .filter(|output| {
+        !inscribed_outputs.contains(output) && !coin_control.exclude_output.contains(output)
+      })
       .map(|output| {
         wallet.get_runes_balances_in_output(&output).map(|balance| {
           (
@@ -206,7 +232,29 @@ impl Send {
     let mut inputs = Vec::new();
     let mut input_rune_balances: BTreeMap<Rune, u128> = BTreeMap::new();
 
+    let (included, balances): (Vec<_>, Vec<_>) = balances
+      .into_iter()
+      .partition(|(output, _runes)| coin_control.include_output.contains(output));
+
+    for (output, runes) in included {
+      for (rune, balance) in runes {
+        *input_rune_balances.entry(rune).or_default() += balance;
+      }
+
+      inputs.push(output);
+    }
+
     for (output, runes) in balances {
+      if !inputs.is_empty()
+        && input_rune_balances
+          .get(&spaced_rune.rune)
+          .cloned()
+          .unwrap_or_default()
+          >= amount
+      {
+        break;
+      }
+
       if let Some(balance) = runes.get(&spaced_rune.rune) {
         if *balance > 0 {
           for (rune, balance) in runes {
@@ -262,9 +310,10 @@ impl Send {
         .map(|previous_output| TxIn {
           previous_output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         })
+        .chain(coin_control.cardinal_inputs(wallet)?)
         .collect(),
       output: if needs_runes_change_output {
         vec![
@@ -273,7 +322,7 @@ impl Send {
             value: Amount::from_sat(0),
           },
           TxOut {
-            script_pubkey: wallet.get_change_address()?.script_pubkey(),
+            script_pubkey: coin_control.get_change_address(wallet)?.script_pubkey(),
             value: postage,
           },
           TxOut {
@@ -289,10 +338,7 @@ impl Send {
       },
     };
 
-    let unsigned_transaction =
-      fund_raw_transaction(wallet.bitcoin_client(), fee_rate, &unfunded_transaction)?;
-
-    let unsigned_transaction = consensus::encode::deserialize(&unsigned_transaction)?;
+    let unsigned_transaction = coin_control.fund(wallet, fee_rate, &unfunded_transaction)?;
 
     if needs_runes_change_output {
       assert_eq!(
@@ -301,6 +347,8 @@ impl Send {
       );
     }
 
+    wallet.check_protected_sats(&unsigned_transaction, None)?;
+
     Ok(unsigned_transaction)
   }
 }
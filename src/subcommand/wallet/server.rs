@@ -0,0 +1,362 @@
+use {
+  super::*,
+  axum::{
+    extract::{Extension, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+  },
+  std::sync::Arc,
+  tower_http::validate_request::ValidateRequestHeaderLayer,
+};
+
+type Body = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Server {
+  #[arg(
+    long,
+    default_value = "127.0.0.1",
+    help = "Listen on <ADDRESS> for incoming requests."
+  )]
+  address: String,
+  #[arg(
+    long,
+    default_value = "8080",
+    help = "Listen on <HTTP_PORT> for incoming requests."
+  )]
+  http_port: u16,
+  #[arg(long, help = "Require basic HTTP authentication with <PASSWORD>.")]
+  password: String,
+  #[arg(long, help = "Require basic HTTP authentication with <USERNAME>.")]
+  username: String,
+}
+
+struct State {
+  lock: Mutex<()>,
+  wallet: Box<dyn Fn() -> Result<Wallet> + Send + Sync>,
+}
+
+impl Server {
+  pub(crate) fn run(
+    self,
+    settings: &Settings,
+    wallet: impl Fn() -> Result<Wallet> + Send + Sync + 'static,
+  ) -> SubcommandResult {
+    let state = Arc::new(State {
+      lock: Mutex::new(()),
+      wallet: Box::new(wallet),
+    });
+
+    let router = Router::new()
+      .route("/balance", get(Self::balance))
+      .route("/batch", post(Self::batch))
+      .route("/inscriptions", get(Self::inscriptions))
+      .route("/mint", post(Self::mint))
+      .route("/pending", get(Self::pending))
+      .route("/receive", post(Self::receive))
+      .route("/resume", post(Self::resume))
+      .route("/runics", get(Self::runics))
+      .route("/send", post(Self::send))
+      .route("/split", post(Self::split))
+      .layer(Extension(state))
+      .layer(ValidateRequestHeaderLayer::basic(
+        &self.username,
+        &self.password,
+      ));
+
+    let addr = (self.address.as_str(), self.http_port)
+      .to_socket_addrs()?
+      .next()
+      .ok_or_else(|| anyhow!("failed to get socket addrs"))?;
+
+    if !settings.integration_test() {
+      eprintln!("Listening on http://{addr}");
+    }
+
+    let handle = axum_server::Handle::new();
+
+    LISTENERS.lock().unwrap().push(handle.clone());
+
+    Runtime::new()?.block_on(async {
+      axum_server::Server::bind(addr)
+        .handle(handle)
+        .serve(router.into_make_service())
+        .await
+    })?;
+
+    Ok(None)
+  }
+
+  /// Parse a JSON object into subcommand `T`. Keys are argument names, as
+  /// given in the subcommand's field names, and values are either strings,
+  /// numbers, booleans for flags, or arrays for arguments that may be given
+  /// multiple times.
+  fn parse<T: Parser>(body: Body) -> Result<T> {
+    let mut command = T::command();
+    command.build();
+
+    let mut args = vec![command.get_name().to_string()];
+    let mut positionals = Vec::new();
+
+    for (key, value) in body {
+      let arg = command
+        .get_arguments()
+        .find(|arg| arg.get_id() == key.as_str() && !arg.is_hide_set())
+        .ok_or_else(|| anyhow!("unknown argument `{key}`"))?;
+
+      let values = match value {
+        serde_json::Value::Array(values) => values,
+        value => vec![value],
+      };
+
+      for value in values {
+        let value = match value {
+          serde_json::Value::Bool(flag) if !arg.get_action().takes_values() => {
+            if flag {
+              args.push(format!("--{}", arg.get_long().unwrap()));
+            }
+            continue;
+          }
+          serde_json::Value::Bool(value) => value.to_string(),
+          serde_json::Value::Number(value) => value.to_string(),
+          serde_json::Value::String(value) => value,
+          _ => bail!("invalid value for argument `{key}`"),
+        };
+
+        match arg.get_long() {
+          Some(long) => args.push(format!("--{long}={value}")),
+          None => positionals.push((arg.get_index().unwrap(), value)),
+        }
+      }
+    }
+
+    positionals.sort_by_key(|(index, _)| *index);
+
+    args.push("--".into());
+    args.extend(positionals.into_iter().map(|(_, value)| value));
+
+    Ok(T::try_parse_from(args)?)
+  }
+
+  async fn execute(
+    state: Arc<State>,
+    f: impl FnOnce(Wallet) -> SubcommandResult + Send + 'static,
+  ) -> Response {
+    let result = task::spawn_blocking(move || {
+      let _guard = state.lock.lock().unwrap();
+
+      let wallet = (state.wallet)().map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+      f(wallet).map_err(|err| (Self::status(&err), err))
+    })
+    .await;
+
+    match result {
+      Ok(Ok(Some(output))) => match output.json() {
+        Ok(json) => Json(json).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+      },
+      Ok(Ok(None)) => StatusCode::NO_CONTENT.into_response(),
+      Ok(Err((status, err))) => (status, format!("{err:#}")).into_response(),
+      Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+  }
+
+  /// Status code for an error returned by a subcommand. Failures to reach
+  /// Bitcoin Core, the ord server, or the wallet database are internal errors,
+  /// while everything else, including requests rejected by Bitcoin Core, is
+  /// the client's fault.
+  fn status(err: &Error) -> StatusCode {
+    for cause in err.chain() {
+      if let Some(err) = cause.downcast_ref::<bitcoincore_rpc::Error>() {
+        return match err {
+          bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(_)) => {
+            StatusCode::BAD_REQUEST
+          }
+          _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+      }
+
+      if cause.is::<io::Error>()
+        || cause.is::<reqwest::Error>()
+        || cause.is::<redb::Error>()
+        || cause.is::<redb::CommitError>()
+        || cause.is::<redb::DatabaseError>()
+        || cause.is::<redb::StorageError>()
+        || cause.is::<redb::TableError>()
+        || cause.is::<redb::TransactionError>()
+      {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+      }
+    }
+
+    StatusCode::BAD_REQUEST
+  }
+
+  async fn execute_with_body<T: Parser + Send + 'static>(
+    state: Arc<State>,
+    body: Body,
+    f: impl FnOnce(T, Wallet) -> SubcommandResult + Send + 'static,
+  ) -> Response {
+    match Self::parse::<T>(body) {
+      Ok(subcommand) => Self::execute(state, move |wallet| f(subcommand, wallet)).await,
+      Err(err) => (StatusCode::BAD_REQUEST, format!("{err:#}")).into_response(),
+    }
+  }
+
+  async fn balance(Extension(state): Extension<Arc<State>>) -> Response {
+    Self::execute(state, balance::run).await
+  }
+
+  async fn batch(Extension(state): Extension<Arc<State>>, Json(body): Json<Body>) -> Response {
+    Self::execute_with_body(state, body, batch_command::Batch::run_without_waiting).await
+  }
+
+  async fn inscriptions(Extension(state): Extension<Arc<State>>) -> Response {
+    Self::execute(state, inscriptions::run).await
+  }
+
+  async fn mint(Extension(state): Extension<Arc<State>>, Json(body): Json<Body>) -> Response {
+    Self::execute_with_body(state, body, mint::Mint::run).await
+  }
+
+  async fn pending(Extension(state): Extension<Arc<State>>) -> Response {
+    Self::execute(state, |wallet| pending::Pending {}.run(wallet)).await
+  }
+
+  async fn receive(Extension(state): Extension<Arc<State>>, body: Option<Json<Body>>) -> Response {
+    Self::execute_with_body(
+      state,
+      body.map(|Json(body)| body).unwrap_or_default(),
+      receive::Receive::run,
+    )
+    .await
+  }
+
+  async fn resume(Extension(state): Extension<Arc<State>>, body: Option<Json<Body>>) -> Response {
+    Self::execute_with_body(
+      state,
+      body.map(|Json(body)| body).unwrap_or_default(),
+      resume::Resume::run_once,
+    )
+    .await
+  }
+
+  async fn runics(Extension(state): Extension<Arc<State>>) -> Response {
+    Self::execute(state, runics::run).await
+  }
+
+  async fn send(Extension(state): Extension<Arc<State>>, Json(body): Json<Body>) -> Response {
+    Self::execute_with_body(state, body, send::Send::run).await
+  }
+
+  async fn split(Extension(state): Extension<Arc<State>>, Json(body): Json<Body>) -> Response {
+    Self::execute_with_body(state, body, split::Split::run).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn body(json: serde_json::Value) -> Body {
+    match json {
+      serde_json::Value::Object(body) => body,
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_options_flags_and_positionals() {
+    let send = Server::parse::<send::Send>(body(serde_json::json!({
+      "outgoing": "1btc",
+      "fee_rate": 1,
+      "dry_run": true,
+      "address": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      "exclude_output": [
+        "0000000000000000000000000000000000000000000000000000000000000000:0",
+        "0000000000000000000000000000000000000000000000000000000000000000:1",
+      ],
+    })))
+    .unwrap();
+
+    assert!(send.dry_run);
+    assert_eq!(
+      send.outgoing,
+      Outgoing::Amount(Amount::from_sat(100_000_000))
+    );
+    assert_eq!(
+      send.address,
+      "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+    );
+    assert_eq!(send.coin_control.exclude_output.len(), 2);
+  }
+
+  #[test]
+  fn parse_false_flag_is_omitted() {
+    let send = Server::parse::<send::Send>(body(serde_json::json!({
+      "address": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      "dry_run": false,
+      "fee_rate": "1",
+      "outgoing": "1btc",
+    })))
+    .unwrap();
+
+    assert!(!send.dry_run);
+  }
+
+  #[test]
+  fn parse_unknown_argument_is_an_error() {
+    assert_eq!(
+      Server::parse::<send::Send>(body(serde_json::json!({ "foo": "bar" })))
+        .unwrap_err()
+        .to_string(),
+      "unknown argument `foo`",
+    );
+  }
+
+  #[test]
+  fn only_infrastructure_errors_are_internal() {
+    assert_eq!(
+      Server::status(&anyhow!("insufficient funds")),
+      StatusCode::BAD_REQUEST,
+    );
+
+    assert_eq!(
+      Server::status(
+        &Error::from(bitcoincore_rpc::Error::JsonRpc(
+          bitcoincore_rpc::jsonrpc::Error::Rpc(bitcoincore_rpc::jsonrpc::error::RpcError {
+            code: -26,
+            message: "min relay fee not met".into(),
+            data: None,
+          })
+        ))
+        .context("failed to broadcast transaction")
+      ),
+      StatusCode::BAD_REQUEST,
+    );
+
+    assert_eq!(
+      Server::status(&Error::from(bitcoincore_rpc::Error::ReturnedError(
+        "foo".into()
+      ))),
+      StatusCode::INTERNAL_SERVER_ERROR,
+    );
+
+    assert_eq!(
+      Server::status(&Error::from(io::Error::from(
+        io::ErrorKind::ConnectionRefused
+      ))),
+      StatusCode::INTERNAL_SERVER_ERROR,
+    );
+  }
+
+  #[test]
+  fn parse_missing_argument_is_an_error() {
+    assert!(Server::parse::<send::Send>(body(serde_json::json!({ "fee_rate": 1 }))).is_err());
+  }
+}
@@ -2,15 +2,20 @@ use super::*;
 
 #[derive(Debug, Parser)]
 pub(super) struct SharedArgs {
+  #[command(flatten)]
+  pub(crate) coin_control: CoinControl,
   #[arg(
     long,
-    help = "Use <COMMIT_FEE_RATE> sats/vbyte for commit transaction.\nDefaults to <FEE_RATE> if unset."
+    help = "Use <COMMIT_FEE_RATE> sats/vbyte for commit transaction, or `auto[:<CONF_TARGET>]` to estimate.\nDefaults to <FEE_RATE> if unset."
   )]
-  pub(crate) commit_fee_rate: Option<FeeRate>,
+  pub(crate) commit_fee_rate: Option<FeeRateArg>,
   #[arg(long, help = "Compress inscription content with brotli.")]
   pub(crate) compress: bool,
-  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
-  pub(crate) fee_rate: FeeRate,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  pub(crate) fee_rate: FeeRateArg,
   #[arg(long, help = "Don't sign or broadcast transactions.")]
   pub(crate) dry_run: bool,
   #[arg(long, alias = "nobackup", help = "Do not back up recovery key.")]
@@ -44,7 +44,7 @@ impl Sign {
 
     let to_sign = bip322::create_to_sign(&to_spend, None)?;
 
-    let result = wallet.bitcoin_client().sign_raw_transaction_with_wallet(
+    let result = wallet.sign_raw_transaction(
       &to_sign.extract_tx()?,
       Some(&[bitcoincore_rpc::json::SignRawTransactionInput {
         txid: to_spend.compute_txid(),
@@ -53,7 +53,6 @@ impl Sign {
         redeem_script: None,
         amount: Some(Amount::ZERO),
       }]),
-      None,
     )?;
 
     let mut buffer = Vec::new();
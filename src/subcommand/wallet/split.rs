@@ -61,10 +61,15 @@ impl std::error::Error for Error {}
 
 #[derive(Debug, Parser)]
 pub(crate) struct Split {
+  #[command(flatten)]
+  coin_control: CoinControl,
   #[arg(long, help = "Don't sign or broadcast transaction")]
   pub(crate) dry_run: bool,
-  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
-  fee_rate: FeeRate,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
   #[arg(
     long,
     help = "Include <AMOUNT> postage with change output. [default: 10000 sat]",
@@ -96,11 +101,15 @@ pub struct Output {
 
 impl Split {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
     ensure!(
       wallet.has_rune_index(),
       "`ord wallet split` requires index created with `--index-runes`",
     );
 
+    self.coin_control.check(&wallet, true)?;
+
     wallet.lock_non_cardinal_outputs()?;
 
     let splits = Splitfile::load(&self.splits, &wallet)?;
@@ -114,7 +123,9 @@ impl Split {
     let balances = wallet
       .get_runic_outputs()?
       .into_iter()
-      .filter(|output| !inscribed_outputs.contains(output))
+      .filter(|output| {
+        !inscribed_outputs.contains(output) && !self.coin_control.exclude_output.contains(output)
+      })
       .map(|output| {
         wallet.get_runes_balances_in_output(&output).map(|balance| {
           (
@@ -128,21 +139,24 @@ impl Split {
       })
       .collect::<Result<BTreeMap<OutPoint, BTreeMap<Rune, u128>>>>()?;
 
-    let unfunded_transaction = Self::build_transaction(
+    let mut unfunded_transaction = Self::build_transaction(
       self.no_limit,
       balances,
-      &wallet.get_change_address()?,
+      &self.coin_control.include_output.iter().copied().collect(),
+      &self.coin_control.get_change_address(&wallet)?,
       self.postage,
       &splits,
     )?;
 
-    let unsigned_transaction = fund_raw_transaction(
-      wallet.bitcoin_client(),
-      self.fee_rate,
-      &unfunded_transaction,
-    )?;
+    unfunded_transaction
+      .input
+      .extend(self.coin_control.cardinal_inputs(&wallet)?);
 
-    let unsigned_transaction = consensus::encode::deserialize(&unsigned_transaction)?;
+    let unsigned_transaction = self
+      .coin_control
+      .fund(&wallet, fee_rate, &unfunded_transaction)?;
+
+    wallet.check_protected_sats(&unsigned_transaction, None)?;
 
     let (txid, psbt, fee) =
       wallet.sign_and_broadcast_transaction(unsigned_transaction, self.dry_run)?;
@@ -153,6 +167,7 @@ impl Split {
   fn build_transaction(
     no_runestone_limit: bool,
     balances: BTreeMap<OutPoint, BTreeMap<Rune, u128>>,
+    included: &BTreeSet<OutPoint>,
     change_address: &Address,
     postage: Option<Amount>,
     splits: &Splitfile,
@@ -193,6 +208,20 @@ impl Split {
 
     let mut inputs = Vec::new();
 
+    let (included, balances): (Vec<_>, Vec<_>) = balances
+      .into_iter()
+      .partition(|(output, _runes)| included.contains(output));
+
+    // included outputs are always spent, so their runes must be split or
+    // returned to change
+    for (output, runes) in included {
+      for (rune, balance) in &runes {
+        *input_rune_balances.entry(*rune).or_default() += balance;
+      }
+
+      inputs.push(output);
+    }
+
     for (output, runes) in balances {
       for (rune, required) in &input_runes_required {
         if input_rune_balances.get(rune).copied().unwrap_or_default() >= *required {
@@ -305,7 +334,7 @@ impl Split {
         .map(|previous_output| TxIn {
           previous_output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         })
         .collect(),
@@ -335,6 +364,7 @@ mod tests {
       Split::build_transaction(
         false,
         BTreeMap::new(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -353,6 +383,7 @@ mod tests {
       Split::build_transaction(
         false,
         BTreeMap::new(),
+        &BTreeSet::new(),
         &change(0),
         Some(Amount::from_sat(100)),
         &Splitfile {
@@ -378,6 +409,7 @@ mod tests {
       Split::build_transaction(
         false,
         BTreeMap::new(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -415,6 +447,7 @@ mod tests {
       Split::build_transaction(
         false,
         BTreeMap::new(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -462,6 +495,7 @@ mod tests {
       Split::build_transaction(
         false,
         BTreeMap::new(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -508,6 +542,7 @@ mod tests {
       Split::build_transaction(
         false,
         [(outpoint(0), [(Rune(0), 1000)].into())].into(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -557,6 +592,7 @@ mod tests {
       Split::build_transaction(
         false,
         [(outpoint(0), [(Rune(0), 1000)].into())].into(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -592,6 +628,7 @@ mod tests {
       Split::build_transaction(
         false,
         [(outpoint(0), [(Rune(0), 2000)].into())].into(),
+        &BTreeSet::new(),
         &change(0),
         None,
         &Splitfile {
@@ -661,7 +698,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
+    let tx = Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+      .unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -671,7 +709,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -729,7 +767,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change, None, &splits).unwrap();
+    let tx =
+      Split::build_transaction(false, balances, &BTreeSet::new(), &change, None, &splits).unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -739,7 +778,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -804,6 +843,7 @@ mod tests {
     let tx = Split::build_transaction(
       false,
       balances,
+      &BTreeSet::new(),
       &change,
       Some(Amount::from_sat(500)),
       &splits,
@@ -818,7 +858,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -878,7 +918,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change, None, &splits).unwrap();
+    let tx =
+      Split::build_transaction(false, balances, &BTreeSet::new(), &change, None, &splits).unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -888,7 +929,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -952,7 +993,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
+    let tx = Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+      .unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -962,7 +1004,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -1023,7 +1065,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
+    let tx = Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+      .unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -1033,7 +1076,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -1093,7 +1136,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
+    let tx = Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+      .unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -1104,13 +1148,13 @@ mod tests {
           TxIn {
             previous_output: outpoint(0),
             script_sig: ScriptBuf::new(),
-            sequence: Sequence::MAX,
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
             witness: Witness::new(),
           },
           TxIn {
             previous_output: outpoint(1),
             script_sig: ScriptBuf::new(),
-            sequence: Sequence::MAX,
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
             witness: Witness::new(),
           },
         ],
@@ -1174,7 +1218,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
+    let tx = Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+      .unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -1184,7 +1229,7 @@ mod tests {
         input: vec![TxIn {
           previous_output: output,
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: vec![
@@ -1267,7 +1312,8 @@ mod tests {
       .into(),
     };
 
-    let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
+    let tx = Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+      .unwrap();
 
     pretty_assert_eq!(
       tx,
@@ -1278,13 +1324,13 @@ mod tests {
           TxIn {
             previous_output: outpoint(0),
             script_sig: ScriptBuf::new(),
-            sequence: Sequence::MAX,
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
             witness: Witness::new(),
           },
           TxIn {
             previous_output: outpoint(1),
             script_sig: ScriptBuf::new(),
-            sequence: Sequence::MAX,
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
             witness: Witness::new(),
           },
         ],
@@ -1347,7 +1393,8 @@ mod tests {
     };
 
     assert_eq!(
-      Split::build_transaction(false, balances, &change(0), None, &splits).unwrap_err(),
+      Split::build_transaction(false, balances, &BTreeSet::new(), &change(0), None, &splits)
+        .unwrap_err(),
       Error::RunestoneSize { size: 85 },
     );
   }
@@ -1380,14 +1427,15 @@ mod tests {
     };
 
     pretty_assert_eq!(
-      Split::build_transaction(true, balances, &change(0), None, &splits).unwrap(),
+      Split::build_transaction(true, balances, &BTreeSet::new(), &change(0), None, &splits)
+        .unwrap(),
       Transaction {
         version: Version(2),
         lock_time: LockTime::ZERO,
         input: vec![TxIn {
           previous_output: outpoint(0),
           script_sig: ScriptBuf::new(),
-          sequence: Sequence::MAX,
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         }],
         output: (0..11)
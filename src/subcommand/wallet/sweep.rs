@@ -0,0 +1,742 @@
+use {
+  super::*,
+  bitcoin::{
+    bip32::ChildNumber,
+    ecdsa,
+    key::{CompressedPublicKey, Keypair, TapTweak},
+    script::PushBytesBuf,
+    secp256k1::{All, Message, Secp256k1},
+    sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType},
+    taproot, NetworkKind, PrivateKey,
+  },
+  bitcoincore_rpc::json::SignRawTransactionInput,
+  miniscript::descriptor::{Descriptor, DescriptorSecretKey, DescriptorType, Wildcard},
+};
+
+#[derive(Debug, Parser)]
+#[clap(group(
+  ArgGroup::new("key")
+    .required(true)
+    .args(&["wif", "descriptor"]))
+)]
+pub(crate) struct Sweep {
+  #[arg(
+    long,
+    help = "Sweep outputs of single-key private <DESCRIPTOR>. Ranged descriptors are scanned until \
+    20 consecutive unused addresses are found."
+  )]
+  descriptor: Option<String>,
+  #[arg(long, help = "Don't broadcast transaction.")]
+  dry_run: bool,
+  #[arg(
+    long,
+    help = "Use fee rate of <FEE_RATE> sats/vB, or `auto[:<CONF_TARGET>]` to estimate."
+  )]
+  fee_rate: FeeRateArg,
+  #[arg(
+    long,
+    help = "Sweep inscriptions and runes into outputs of <AMOUNT> postage. [default: 10000 sat]",
+    value_name = "AMOUNT"
+  )]
+  postage: Option<Amount>,
+  #[arg(long, help = "Sweep outputs of WIF-encoded private key <WIF>.")]
+  wif: Option<PrivateKey>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub txid: Txid,
+  pub fee: u64,
+  pub inscriptions: BTreeMap<InscriptionId, SatPoint>,
+  pub runes: BTreeMap<SpacedRune, Decimal>,
+  pub swept: Vec<OutPoint>,
+  pub transaction: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+  P2pkh,
+  P2shP2wpkh,
+  P2tr,
+  P2wpkh,
+}
+
+struct Key {
+  address: Address,
+  kind: Kind,
+  private_key: PrivateKey,
+}
+
+impl Key {
+  fn new(
+    secp: &Secp256k1<All>,
+    kind: Kind,
+    private_key: PrivateKey,
+    network: Network,
+  ) -> Result<Self> {
+    let public_key = private_key.public_key(secp);
+
+    let address = match kind {
+      Kind::P2pkh => Address::p2pkh(public_key, network),
+      Kind::P2shP2wpkh => Address::p2shwpkh(&CompressedPublicKey::try_from(public_key)?, network),
+      Kind::P2tr => Address::p2tr(secp, public_key.inner.x_only_public_key().0, None, network),
+      Kind::P2wpkh => Address::p2wpkh(&CompressedPublicKey::try_from(public_key)?, network),
+    };
+
+    Ok(Self {
+      address,
+      kind,
+      private_key,
+    })
+  }
+
+  /// Script sig and witness of the same size as a real signature, used to
+  /// estimate transaction size.
+  fn dummy_signature(&self) -> (ScriptBuf, Witness) {
+    let public_key = if self.private_key.compressed {
+      vec![0; 33]
+    } else {
+      vec![0; 65]
+    };
+
+    match self.kind {
+      Kind::P2pkh => (
+        script::Builder::new()
+          .push_slice([0; 72])
+          .push_slice(PushBytesBuf::try_from(public_key).unwrap())
+          .into_script(),
+        Witness::new(),
+      ),
+      Kind::P2shP2wpkh => (
+        script::Builder::new().push_slice([0; 22]).into_script(),
+        Witness::from_slice(&[vec![0; 72], public_key]),
+      ),
+      Kind::P2tr => (ScriptBuf::new(), Witness::from_slice(&[[0; 64]])),
+      Kind::P2wpkh => (
+        ScriptBuf::new(),
+        Witness::from_slice(&[vec![0; 72], public_key]),
+      ),
+    }
+  }
+
+  fn redeem_script(&self, secp: &Secp256k1<All>) -> Result<Option<ScriptBuf>> {
+    Ok(match self.kind {
+      Kind::P2shP2wpkh => Some(ScriptBuf::new_p2wpkh(
+        &CompressedPublicKey::try_from(self.private_key.public_key(secp))?.wpubkey_hash(),
+      )),
+      Kind::P2pkh | Kind::P2tr | Kind::P2wpkh => None,
+    })
+  }
+
+  fn sign(
+    &self,
+    secp: &Secp256k1<All>,
+    cache: &mut SighashCache<&Transaction>,
+    input: usize,
+    prevouts: &[TxOut],
+  ) -> Result<(ScriptBuf, Witness)> {
+    let public_key = self.private_key.public_key(secp);
+
+    let ecdsa = |digest: [u8; 32]| ecdsa::Signature {
+      signature: secp.sign_ecdsa(&Message::from_digest(digest), &self.private_key.inner),
+      sighash_type: EcdsaSighashType::All,
+    };
+
+    match self.kind {
+      Kind::P2pkh => {
+        let sighash = cache.legacy_signature_hash(
+          input,
+          &prevouts[input].script_pubkey,
+          EcdsaSighashType::All.to_u32(),
+        )?;
+
+        let signature = ecdsa(sighash.to_byte_array());
+
+        Ok((
+          script::Builder::new()
+            .push_slice(signature.serialize())
+            .push_key(&public_key)
+            .into_script(),
+          Witness::new(),
+        ))
+      }
+      Kind::P2shP2wpkh | Kind::P2wpkh => {
+        let script_code =
+          ScriptBuf::new_p2wpkh(&CompressedPublicKey::try_from(public_key)?.wpubkey_hash());
+
+        let sighash = cache.p2wpkh_signature_hash(
+          input,
+          &script_code,
+          prevouts[input].value,
+          EcdsaSighashType::All,
+        )?;
+
+        let signature = ecdsa(sighash.to_byte_array());
+
+        let script_sig = if self.kind == Kind::P2shP2wpkh {
+          script::Builder::new()
+            .push_slice(PushBytesBuf::try_from(script_code.into_bytes())?)
+            .into_script()
+        } else {
+          ScriptBuf::new()
+        };
+
+        Ok((script_sig, Witness::p2wpkh(&signature, &public_key.inner)))
+      }
+      Kind::P2tr => {
+        let sighash = cache.taproot_key_spend_signature_hash(
+          input,
+          &Prevouts::All(prevouts),
+          TapSighashType::Default,
+        )?;
+
+        let keypair = Keypair::from_secret_key(secp, &self.private_key.inner)
+          .tap_tweak(secp, None)
+          .to_inner();
+
+        let signature =
+          secp.sign_schnorr_no_aux_rand(&Message::from_digest(sighash.to_byte_array()), &keypair);
+
+        Ok((
+          ScriptBuf::new(),
+          Witness::p2tr_key_spend(&taproot::Signature {
+            signature,
+            sighash_type: TapSighashType::Default,
+          }),
+        ))
+      }
+    }
+  }
+}
+
+struct Sweepable {
+  key: usize,
+  offsets: Vec<u64>,
+  output: api::Output,
+}
+
+impl Sweepable {
+  fn txout(&self) -> TxOut {
+    TxOut {
+      script_pubkey: self.output.script_pubkey.clone(),
+      value: Amount::from_sat(self.output.value),
+    }
+  }
+}
+
+impl Sweep {
+  const GAP_LIMIT: u32 = 20;
+
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      wallet.has_address_index(),
+      "sweeping requires index created with `--index-addresses` flag",
+    );
+
+    ensure!(
+      wallet.has_rune_index(),
+      "sweeping requires index created with `--index-runes` flag",
+    );
+
+    let fee_rate = wallet.fee_rate(self.fee_rate)?;
+
+    let postage = self.postage.unwrap_or(TARGET_POSTAGE);
+
+    let secp = Secp256k1::new();
+
+    let network = wallet.chain().network();
+
+    let mut keys = Vec::new();
+    let mut sweepables = Vec::new();
+
+    if let Some(wif) = self.wif {
+      ensure!(
+        wif.network == NetworkKind::from(network),
+        "WIF network does not match wallet network {network}",
+      );
+
+      let kinds = if wif.compressed {
+        [Kind::P2pkh, Kind::P2shP2wpkh, Kind::P2tr, Kind::P2wpkh].as_slice()
+      } else {
+        [Kind::P2pkh].as_slice()
+      };
+
+      for kind in kinds {
+        let key = Key::new(&secp, *kind, wif, network)?;
+        Self::scan(&wallet, &key, keys.len(), &mut sweepables)?;
+        keys.push(key);
+      }
+    }
+
+    if let Some(descriptor) = &self.descriptor {
+      let (descriptor, key_map) = Descriptor::parse_descriptor(&secp, descriptor)?;
+
+      ensure!(
+        key_map.len() == 1,
+        "descriptor must contain exactly one private key",
+      );
+
+      let kind = match descriptor.desc_type() {
+        DescriptorType::Pkh => Kind::P2pkh,
+        DescriptorType::ShWpkh => Kind::P2shP2wpkh,
+        DescriptorType::Tr if matches!(&descriptor, Descriptor::Tr(tr) if tr.tap_tree().is_none()) => {
+          Kind::P2tr
+        }
+        DescriptorType::Wpkh => Kind::P2wpkh,
+        _ => {
+          bail!("unsupported descriptor `{descriptor}`, only single-key descriptors can be swept")
+        }
+      };
+
+      let secret_key = key_map.values().next().unwrap();
+
+      let mut unused = 0;
+
+      for index in 0.. {
+        let private_key = match secret_key {
+          DescriptorSecretKey::Single(single) => single.key,
+          DescriptorSecretKey::XPrv(xprv) => {
+            let path = match xprv.wildcard {
+              Wildcard::None => xprv.derivation_path.clone(),
+              Wildcard::Unhardened => xprv
+                .derivation_path
+                .child(ChildNumber::from_normal_idx(index)?),
+              Wildcard::Hardened => xprv
+                .derivation_path
+                .child(ChildNumber::from_hardened_idx(index)?),
+            };
+
+            PrivateKey::new(xprv.xkey.derive_priv(&secp, &path)?.private_key, network)
+          }
+          DescriptorSecretKey::MultiXPrv(_) => bail!("multipath descriptors cannot be swept"),
+        };
+
+        let key = Key::new(&secp, kind, private_key, network)?;
+
+        ensure!(
+          key.address == descriptor.at_derivation_index(index)?.address(network)?,
+          "private key does not match descriptor at index {index}",
+        );
+
+        let found = sweepables.len();
+
+        Self::scan(&wallet, &key, keys.len(), &mut sweepables)?;
+
+        keys.push(key);
+
+        if !descriptor.has_wildcard() {
+          break;
+        }
+
+        if sweepables.len() == found {
+          unused += 1;
+
+          if unused == Self::GAP_LIMIT {
+            break;
+          }
+        } else {
+          unused = 0;
+        }
+      }
+    }
+
+    ensure!(!sweepables.is_empty(), "no outputs to sweep");
+
+    sweepables.sort_by_key(|sweepable| {
+      (
+        sweepable.output.inscriptions.is_empty(),
+        sweepable.output.runes.is_empty(),
+        sweepable.output.outpoint,
+      )
+    });
+
+    let (unsigned_transaction, funding) =
+      Self::create_unsigned_sweep_transaction(&wallet, &keys, &sweepables, postage, fee_rate)?;
+
+    let prevouts = sweepables
+      .iter()
+      .map(Sweepable::txout)
+      .chain(funding)
+      .collect::<Vec<TxOut>>();
+
+    let fee = prevouts.iter().map(|txout| txout.value).sum::<Amount>()
+      - unsigned_transaction
+        .output
+        .iter()
+        .map(|txout| txout.value)
+        .sum::<Amount>();
+
+    let foreign = sweepables
+      .iter()
+      .map(|sweepable| {
+        Ok(SignRawTransactionInput {
+          txid: sweepable.output.outpoint.txid,
+          vout: sweepable.output.outpoint.vout,
+          script_pub_key: sweepable.output.script_pubkey.clone(),
+          redeem_script: keys[sweepable.key].redeem_script(&secp)?,
+          amount: Some(Amount::from_sat(sweepable.output.value)),
+        })
+      })
+      .collect::<Result<Vec<SignRawTransactionInput>>>()?;
+
+    let mut signed_transaction = wallet
+      .sign_raw_transaction(&unsigned_transaction, Some(&foreign))?
+      .transaction()?;
+
+    let mut cache = SighashCache::new(&unsigned_transaction);
+
+    for (input, sweepable) in sweepables.iter().enumerate() {
+      let (script_sig, witness) = keys[sweepable.key].sign(&secp, &mut cache, input, &prevouts)?;
+      signed_transaction.input[input].script_sig = script_sig;
+      signed_transaction.input[input].witness = witness;
+    }
+
+    wallet.check_fee(&[(&signed_transaction, fee)])?;
+
+    let txid = if self.dry_run {
+      signed_transaction.compute_txid()
+    } else {
+      wallet.send_raw_transaction(&signed_transaction)?
+    };
+
+    let mut inscriptions = BTreeMap::new();
+    let mut runes = BTreeMap::<SpacedRune, Decimal>::new();
+    let mut input_start = 0;
+
+    for sweepable in &sweepables {
+      for (id, offset) in sweepable.output.inscriptions.iter().zip(&sweepable.offsets) {
+        let mut output_start = 0;
+
+        for (vout, txout) in signed_transaction.output.iter().enumerate() {
+          let output_end = output_start + txout.value.to_sat();
+
+          if (output_start..output_end).contains(&(input_start + offset)) {
+            inscriptions.insert(
+              *id,
+              SatPoint {
+                outpoint: OutPoint {
+                  txid,
+                  vout: vout.try_into().unwrap(),
+                },
+                offset: input_start + offset - output_start,
+              },
+            );
+            break;
+          }
+
+          output_start = output_end;
+        }
+      }
+
+      for (spaced_rune, pile) in &sweepable.output.runes {
+        let decimal = runes.entry(*spaced_rune).or_insert(Decimal {
+          value: 0,
+          scale: pile.divisibility,
+        });
+
+        decimal.value += pile.amount;
+      }
+
+      input_start += sweepable.output.value;
+    }
+
+    Ok(Some(Box::new(Output {
+      txid,
+      fee: fee.to_sat(),
+      inscriptions,
+      runes,
+      swept: sweepables
+        .iter()
+        .map(|sweepable| sweepable.output.outpoint)
+        .collect(),
+      transaction: consensus::encode::serialize_hex(&signed_transaction),
+    })))
+  }
+
+  fn scan(wallet: &Wallet, key: &Key, index: usize, sweepables: &mut Vec<Sweepable>) -> Result {
+    for output in wallet.get_address_outputs(&key.address)? {
+      if output.spent || wallet.utxos().contains_key(&output.outpoint) {
+        continue;
+      }
+
+      let mut offsets = Vec::new();
+
+      for id in &output.inscriptions {
+        let satpoint = wallet.get_inscription(*id)?.satpoint;
+
+        ensure!(
+          satpoint.outpoint == output.outpoint,
+          "inscription {id} not in output {}",
+          output.outpoint,
+        );
+
+        offsets.push(satpoint.offset);
+      }
+
+      sweepables.push(Sweepable {
+        key: index,
+        offsets,
+        output,
+      });
+    }
+
+    Ok(())
+  }
+
+  fn create_unsigned_sweep_transaction(
+    wallet: &Wallet,
+    keys: &[Key],
+    sweepables: &[Sweepable],
+    postage: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<(Transaction, Vec<TxOut>)> {
+    let change = wallet.get_change_address()?.script_pubkey();
+
+    let dust_value = change.minimal_non_dust().to_sat();
+
+    let mut values = Vec::new();
+
+    for sweepable in sweepables {
+      if !sweepable.offsets.is_empty() {
+        values.extend(postage::split(
+          sweepable.output.value,
+          &sweepable.offsets,
+          postage.to_sat(),
+          dust_value,
+        ));
+      }
+    }
+
+    let runestone = sweepables
+      .iter()
+      .any(|sweepable| !sweepable.output.runes.is_empty())
+      .then(|| {
+        values.push(postage.to_sat());
+
+        Runestone {
+          pointer: Some((values.len() - 1).try_into().unwrap()),
+          ..default()
+        }
+      });
+
+    let swept = sweepables
+      .iter()
+      .map(|sweepable| sweepable.output.value)
+      .sum::<u64>();
+
+    let fixed = values.iter().sum::<u64>();
+
+    let mut cardinals = wallet
+      .get_cardinal_utxos()?
+      .into_iter()
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    cardinals.sort_by_key(|(_, txout)| txout.value);
+
+    let mut cardinals = cardinals.into_iter().rev();
+
+    let mut funding = Vec::new();
+
+    loop {
+      let total = swept
+        + funding
+          .iter()
+          .map(|(_, txout): &(OutPoint, TxOut)| txout.value.to_sat())
+          .sum::<u64>();
+
+      let mut unsigned_transaction = Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: sweepables
+          .iter()
+          .map(|sweepable| {
+            let (script_sig, witness) = keys[sweepable.key].dummy_signature();
+            (sweepable.output.outpoint, script_sig, witness)
+          })
+          .chain(
+            funding
+              .iter()
+              .map(|(outpoint, _)| (*outpoint, ScriptBuf::new(), Witness::from_slice(&[[0; 64]]))),
+          )
+          .map(|(previous_output, script_sig, witness)| TxIn {
+            previous_output,
+            script_sig,
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness,
+          })
+          .collect(),
+        output: values
+          .iter()
+          .chain([&0])
+          .map(|value| TxOut {
+            script_pubkey: change.clone(),
+            value: Amount::from_sat(*value),
+          })
+          .chain(runestone.as_ref().map(|runestone| TxOut {
+            script_pubkey: runestone.encipher(),
+            value: Amount::ZERO,
+          }))
+          .collect(),
+      };
+
+      let fee = fee_rate.fee(unsigned_transaction.vsize()).to_sat();
+
+      if let Some(remainder) = total.checked_sub(fixed + fee) {
+        if remainder >= dust_value {
+          unsigned_transaction.output[values.len()].value = Amount::from_sat(remainder);
+
+          for input in &mut unsigned_transaction.input {
+            input.script_sig = ScriptBuf::new();
+            input.witness = Witness::new();
+          }
+
+          for output in unsigned_transaction
+            .output
+            .iter_mut()
+            .take(values.len() + 1)
+          {
+            output.script_pubkey = wallet.get_change_address()?.script_pubkey();
+          }
+
+          return Ok((
+            unsigned_transaction,
+            funding.into_iter().map(|(_, txout)| txout).collect(),
+          ));
+        }
+      }
+
+      let Some(cardinal) = cardinals.next() else {
+        bail!("wallet does not contain enough cardinal UTXOs to pay for sweep");
+      };
+
+      funding.push(cardinal);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn signatures_are_valid() {
+    let secp = Secp256k1::new();
+
+    let private_key = PrivateKey::from_slice(&[1; 32], Network::Regtest).unwrap();
+
+    let public_key = private_key.public_key(&secp);
+
+    for kind in [Kind::P2pkh, Kind::P2shP2wpkh, Kind::P2tr, Kind::P2wpkh] {
+      let key = Key::new(&secp, kind, private_key, Network::Regtest).unwrap();
+
+      let prevouts = [TxOut {
+        script_pubkey: key.address.script_pubkey(),
+        value: Amount::from_sat(10_000),
+      }];
+
+      let transaction = Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+          previous_output: outpoint(1),
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+          script_pubkey: key.address.script_pubkey(),
+          value: Amount::from_sat(9_000),
+        }],
+      };
+
+      let mut cache = SighashCache::new(&transaction);
+
+      let (script_sig, witness) = key.sign(&secp, &mut cache, 0, &prevouts).unwrap();
+
+      match kind {
+        Kind::P2pkh => {
+          let sighash = cache
+            .legacy_signature_hash(0, &prevouts[0].script_pubkey, 1)
+            .unwrap();
+
+          let Some(Ok(script::Instruction::PushBytes(signature))) =
+            script_sig.instructions().next()
+          else {
+            panic!("script sig does not start with signature");
+          };
+
+          secp
+            .verify_ecdsa(
+              &Message::from_digest(sighash.to_byte_array()),
+              &ecdsa::Signature::from_slice(signature.as_bytes())
+                .unwrap()
+                .signature,
+              &public_key.inner,
+            )
+            .unwrap();
+
+          assert!(witness.is_empty());
+        }
+        Kind::P2shP2wpkh | Kind::P2wpkh => {
+          let script_code = key.redeem_script(&secp).unwrap().unwrap_or_else(|| {
+            ScriptBuf::new_p2wpkh(
+              &CompressedPublicKey::try_from(public_key)
+                .unwrap()
+                .wpubkey_hash(),
+            )
+          });
+
+          let sighash = cache
+            .p2wpkh_signature_hash(0, &script_code, prevouts[0].value, EcdsaSighashType::All)
+            .unwrap();
+
+          secp
+            .verify_ecdsa(
+              &Message::from_digest(sighash.to_byte_array()),
+              &ecdsa::Signature::from_slice(witness.nth(0).unwrap())
+                .unwrap()
+                .signature,
+              &public_key.inner,
+            )
+            .unwrap();
+
+          assert_eq!(witness.nth(1).unwrap(), public_key.to_bytes());
+
+          if kind == Kind::P2shP2wpkh {
+            assert_eq!(
+              ScriptBuf::new_p2sh(
+                &ScriptBuf::from_bytes(script_sig.as_bytes()[1..].to_vec()).script_hash()
+              ),
+              prevouts[0].script_pubkey,
+            );
+          } else {
+            assert!(script_sig.is_empty());
+          }
+        }
+        Kind::P2tr => {
+          let sighash = cache
+            .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), TapSighashType::Default)
+            .unwrap();
+
+          secp
+            .verify_schnorr(
+              &taproot::Signature::from_slice(witness.nth(0).unwrap())
+                .unwrap()
+                .signature,
+              &Message::from_digest(sighash.to_byte_array()),
+              &public_key
+                .inner
+                .x_only_public_key()
+                .0
+                .tap_tweak(&secp, None)
+                .0
+                .to_inner(),
+            )
+            .unwrap();
+
+          assert!(script_sig.is_empty());
+        }
+      }
+    }
+  }
+}
@@ -1,34 +1,681 @@
-use super::*;
+use {
+  super::*,
+  crate::{
+    index::{allocate, Lot},
+    runes::MintError,
+  },
+  std::collections::HashMap,
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Transactions {
   #[arg(long, help = "Fetch at most <LIMIT> transactions.")]
   limit: Option<u16>,
+  #[arg(long, help = "Print transactions as CSV.")]
+  csv: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
   pub transaction: Txid,
   pub confirmations: i32,
+  pub inscriptions_created: Vec<InscriptionId>,
+  pub inscriptions_received: Vec<InscriptionId>,
+  pub inscriptions_sent: Vec<InscriptionId>,
+  pub inscriptions_burned: Vec<InscriptionId>,
+  pub runes: BTreeMap<SpacedRune, Delta>,
+  pub runes_burned: BTreeMap<SpacedRune, Decimal>,
+  pub mint: Option<SpacedRune>,
+  pub etching: Option<SpacedRune>,
+  pub cenotaph: bool,
+  pub lost: bool,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, DeserializeFromStr, SerializeDisplay)]
+pub struct Delta {
+  pub negative: bool,
+  pub amount: Decimal,
+}
+
+impl Display for Delta {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    if self.negative {
+      write!(f, "-")?;
+    }
+
+    write!(f, "{}", self.amount)
+  }
+}
+
+impl FromStr for Delta {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(match s.strip_prefix('-') {
+      Some(amount) => Self {
+        negative: true,
+        amount: amount.parse()?,
+      },
+      None => Self {
+        negative: false,
+        amount: s.parse()?,
+      },
+    })
+  }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Assets {
+  inscriptions: Vec<(InscriptionId, u64)>,
+  runes: BTreeMap<RuneId, u128>,
+}
+
+#[derive(Clone, Debug)]
+struct Utxo {
+  assets: Assets,
+  mine: bool,
+  txout: TxOut,
+}
+
+/// Transactions printed as CSV instead of in the selected output format.
+pub struct Csv(String);
+
+impl subcommand::Output for Csv {
+  fn print(&self, _format: OutputFormat) {
+    print!("{}", self.0);
+  }
+
+  fn json(&self) -> serde_json::Result<serde_json::Value> {
+    Ok(self.0.clone().into())
+  }
+}
+
+// The index only knows the contents of unspent outputs, so outputs that have
+// since been spent are reconstructed by replaying the wallet transactions that
+// created them.
+struct History<'a> {
+  height: u64,
+  replayed: HashSet<Txid>,
+  runes: HashMap<RuneId, RuneEntry>,
+  utxos: HashMap<OutPoint, Utxo>,
+  wallet: &'a Wallet,
 }
 
 impl Transactions {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
     let client = wallet.bitcoin_client();
 
-    let mut output = Vec::new();
+    let mut seen = HashSet::new();
+    let mut transactions = Vec::new();
     for tx in client.list_transactions(
       None,
       Some(self.limit.unwrap_or(u16::MAX).into()),
       None,
       None,
     )? {
-      output.push(Output {
-        transaction: tx.info.txid,
-        confirmations: tx.info.confirmations,
-      });
+      if seen.insert(tx.info.txid) {
+        transactions.push((
+          tx.info.txid,
+          tx.info.confirmations,
+          client.get_raw_transaction(&tx.info.txid, None)?,
+        ));
+      }
+    }
+
+    let mut history = History {
+      height: client.get_block_count()?,
+      replayed: HashSet::new(),
+      runes: HashMap::new(),
+      utxos: HashMap::new(),
+      wallet: &wallet,
+    };
+
+    let mut output = Vec::new();
+    for (txid, confirmations, transaction) in Self::sort(transactions) {
+      output.push(history.annotate(txid, &transaction, confirmations)?);
+    }
+
+    if self.csv {
+      return Ok(Some(Box::new(Csv(Self::csv(&output)))));
     }
 
     Ok(Some(Box::new(output)))
   }
+
+  /// Order transactions by confirmations, most first, and transactions in the
+  /// same block after those they spend.
+  fn sort(mut transactions: Vec<(Txid, i32, Transaction)>) -> Vec<(Txid, i32, Transaction)> {
+    transactions.sort_by_key(|(_, confirmations, _)| cmp::Reverse(*confirmations));
+
+    let mut sorted = Vec::new();
+
+    for block in transactions.chunk_by(|(_, a, _), (_, b, _)| a == b) {
+      let mut pending = block.to_vec();
+
+      while !pending.is_empty() {
+        let txids = pending
+          .iter()
+          .map(|(txid, ..)| *txid)
+          .collect::<HashSet<Txid>>();
+
+        let next = pending
+          .iter()
+          .position(|(_, _, transaction)| {
+            !transaction
+              .input
+              .iter()
+              .any(|txin| txids.contains(&txin.previous_output.txid))
+          })
+          .unwrap_or_default();
+
+        sorted.push(pending.remove(next));
+      }
+    }
+
+    sorted
+  }
+
+  fn csv(output: &[Output]) -> String {
+    fn join<T: Display>(items: impl IntoIterator<Item = T>) -> String {
+      items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+    }
+
+    let mut csv = String::from(
+      "transaction,confirmations,inscriptions_created,inscriptions_received,inscriptions_sent,\
+      inscriptions_burned,runes,runes_burned,mint,etching,cenotaph,lost\n",
+    );
+
+    for entry in output {
+      csv.push_str(&format!(
+        "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+        entry.transaction,
+        entry.confirmations,
+        join(&entry.inscriptions_created),
+        join(&entry.inscriptions_received),
+        join(&entry.inscriptions_sent),
+        join(&entry.inscriptions_burned),
+        join(
+          entry
+            .runes
+            .iter()
+            .map(|(rune, delta)| format!("{delta}:{rune}"))
+        ),
+        join(
+          entry
+            .runes_burned
+            .iter()
+            .map(|(rune, amount)| format!("{amount}:{rune}"))
+        ),
+        join(entry.mint),
+        join(entry.etching),
+        entry.cenotaph,
+        entry.lost,
+      ));
+    }
+
+    csv
+  }
+}
+
+impl History<'_> {
+  fn annotate(
+    &mut self,
+    txid: Txid,
+    transaction: &Transaction,
+    confirmations: i32,
+  ) -> Result<Output> {
+    self.replay_ancestors(transaction)?;
+    self.apply(txid, transaction, confirmations)
+  }
+
+  /// Replay the wallet transactions which created the outputs `transaction`
+  /// spends, along with their own such ancestors, parents first.
+  fn replay_ancestors(&mut self, transaction: &Transaction) -> Result {
+    let mut stack = vec![(None, transaction.clone())];
+
+    while let Some((_, transaction)) = stack.last() {
+      let mut parent = None;
+
+      if !transaction.is_coinbase() {
+        for txin in &transaction.input {
+          let txid = txin.previous_output.txid;
+
+          if self.utxos.contains_key(&txin.previous_output)
+            || self.replayed.contains(&txid)
+            || stack
+              .iter()
+              .any(|(entry, _)| matches!(entry, Some((ancestor, _)) if *ancestor == txid))
+          {
+            continue;
+          }
+
+          let client = self.wallet.bitcoin_client();
+
+          let previous = client.get_raw_transaction(&txid, None)?;
+
+          let mine = previous
+            .output
+            .get(txin.previous_output.vout.into_usize())
+            .map(|txout| self.wallet.is_mine(&txout.script_pubkey))
+            .transpose()?
+            .unwrap_or_default();
+
+          if mine {
+            let confirmations = client.get_transaction(&txid, None)?.info.confirmations;
+            parent = Some((Some((txid, confirmations)), previous));
+            break;
+          }
+        }
+      }
+
+      match parent {
+        Some(parent) => stack.push(parent),
+        None => {
+          if let Some((Some((txid, confirmations)), transaction)) = stack.pop() {
+            self.apply(txid, &transaction, confirmations)?;
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  fn apply(&mut self, txid: Txid, transaction: &Transaction, confirmations: i32) -> Result<Output> {
+    self.replayed.insert(txid);
+
+    let height = match u64::try_from(confirmations) {
+      Ok(confirmations) if confirmations > 0 => self.height + 1 - confirmations,
+      _ => self.height + 1,
+    };
+
+    let mut inputs = Vec::new();
+    if !transaction.is_coinbase() {
+      for txin in &transaction.input {
+        inputs.push(self.utxo(txin.previous_output)?);
+      }
+    }
+
+    let mut outputs = vec![Assets::default(); transaction.output.len()];
+
+    let output_value = transaction
+      .output
+      .iter()
+      .map(|txout| txout.value.to_sat())
+      .sum::<u64>();
+
+    let mut inscriptions_burned = Vec::new();
+    let mut lost = false;
+
+    let mut place = |id: InscriptionId, mut offset: u64| {
+      for (vout, txout) in transaction.output.iter().enumerate() {
+        if offset < txout.value.to_sat() {
+          if txout.script_pubkey.is_op_return() {
+            inscriptions_burned.push(id);
+          } else {
+            outputs[vout].inscriptions.push((id, offset));
+          }
+          return;
+        }
+        offset -= txout.value.to_sat();
+      }
+
+      lost = true;
+    };
+
+    let mut input_offsets = Vec::new();
+    let mut input_offset = 0;
+    for input in &inputs {
+      input_offsets.push(input_offset);
+
+      for (id, offset) in &input.assets.inscriptions {
+        place(*id, input_offset + offset);
+      }
+
+      input_offset += input.txout.value.to_sat();
+    }
+
+    let mut inscriptions_created = Vec::new();
+    for (index, envelope) in ParsedEnvelope::from_transaction(transaction)
+      .into_iter()
+      .enumerate()
+    {
+      let id = InscriptionId {
+        txid,
+        index: index.try_into().unwrap(),
+      };
+
+      let offset = envelope
+        .payload
+        .pointer()
+        .filter(|&pointer| pointer < output_value)
+        .or_else(|| input_offsets.get(envelope.input.into_usize()).copied())
+        .unwrap_or_default();
+
+      place(id, offset);
+
+      inscriptions_created.push(id);
+    }
+
+    let artifact = Runestone::decipher(transaction);
+
+    let mut unallocated = HashMap::<RuneId, Lot>::new();
+    for input in &inputs {
+      for (id, amount) in &input.assets.runes {
+        *unallocated.entry(*id).or_default() += *amount;
+      }
+    }
+
+    let mut mint = None;
+    if let Some(id) = artifact.as_ref().and_then(Artifact::mint) {
+      if let Some((spaced_rune, amount)) = self.mint(id, height, confirmations > 0)? {
+        *unallocated.entry(id).or_default() += amount;
+        mint = Some(spaced_rune);
+      }
+    }
+
+    let etching = if confirmations > 0 {
+      self
+        .wallet
+        .get_transaction_info(txid)?
+        .and_then(|info| info.etching)
+    } else {
+      None
+    };
+
+    let mut etched = None;
+    if let Some(spaced_rune) = etching {
+      if let Some((id, entry, _)) = self.wallet.get_rune(spaced_rune.rune)? {
+        self.runes.insert(id, entry);
+        etched = Some(id);
+      }
+    }
+
+    let (allocated, burned) = allocate(transaction, artifact.as_ref(), etched, unallocated);
+
+    let cenotaph = matches!(artifact, Some(Artifact::Cenotaph(_)));
+
+    let mut burned = burned
+      .into_iter()
+      .filter(|(_, amount)| *amount > 0)
+      .map(|(id, amount)| (id, amount.0))
+      .collect::<BTreeMap<RuneId, u128>>();
+
+    if !burned.is_empty() {
+      lost = true;
+    }
+
+    for (vout, balances) in allocated.into_iter().enumerate() {
+      if transaction.output[vout].script_pubkey.is_op_return() {
+        for (id, balance) in balances {
+          *burned.entry(id).or_default() += balance.0;
+        }
+      } else {
+        outputs[vout].runes = balances
+          .into_iter()
+          .map(|(id, balance)| (id, balance.0))
+          .collect();
+      }
+    }
+
+    let mut received = Assets::default();
+    for (vout, (txout, mut assets)) in transaction.output.iter().zip(outputs).enumerate() {
+      let outpoint = OutPoint {
+        txid,
+        vout: vout.try_into().unwrap(),
+      };
+
+      let mine =
+        !txout.script_pubkey.is_op_return() && self.wallet.is_mine(&txout.script_pubkey)?;
+
+      if mine {
+        if let Some(indexed) = self.indexed_assets(outpoint)? {
+          assets = indexed;
+        }
+
+        received
+          .inscriptions
+          .extend(assets.inscriptions.iter().copied());
+
+        for (id, amount) in &assets.runes {
+          *received.runes.entry(*id).or_default() += amount;
+        }
+      }
+
+      self.utxos.insert(
+        outpoint,
+        Utxo {
+          assets,
+          mine,
+          txout: txout.clone(),
+        },
+      );
+    }
+
+    let mut spent = Assets::default();
+    for input in inputs.iter().filter(|input| input.mine) {
+      spent
+        .inscriptions
+        .extend(input.assets.inscriptions.iter().copied());
+
+      for (id, amount) in &input.assets.runes {
+        *spent.runes.entry(*id).or_default() += amount;
+      }
+    }
+
+    let received_ids = received
+      .inscriptions
+      .iter()
+      .map(|(id, _)| *id)
+      .collect::<HashSet<InscriptionId>>();
+
+    let spent_ids = spent
+      .inscriptions
+      .iter()
+      .map(|(id, _)| *id)
+      .collect::<HashSet<InscriptionId>>();
+
+    let mut runes = BTreeMap::new();
+    for id in received.runes.keys().chain(spent.runes.keys()) {
+      let received = received.runes.get(id).copied().unwrap_or_default();
+      let spent = spent.runes.get(id).copied().unwrap_or_default();
+
+      if received == spent {
+        continue;
+      }
+
+      let (spaced_rune, amount) = self.decimal(*id, received.abs_diff(spent))?;
+
+      runes.insert(
+        spaced_rune,
+        Delta {
+          negative: spent > received,
+          amount,
+        },
+      );
+    }
+
+    let mut runes_burned = BTreeMap::new();
+    for (id, amount) in burned {
+      let (spaced_rune, amount) = self.decimal(id, amount)?;
+      runes_burned.insert(spaced_rune, amount);
+    }
+
+    Ok(Output {
+      transaction: txid,
+      confirmations,
+      inscriptions_received: received
+        .inscriptions
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| !spent_ids.contains(id) && !inscriptions_created.contains(id))
+        .collect(),
+      inscriptions_sent: spent
+        .inscriptions
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| !received_ids.contains(id) && !inscriptions_burned.contains(id))
+        .collect(),
+      inscriptions_created,
+      inscriptions_burned,
+      runes,
+      runes_burned,
+      mint,
+      etching,
+      cenotaph,
+      lost,
+    })
+  }
+
+  fn utxo(&mut self, outpoint: OutPoint) -> Result<Utxo> {
+    if let Some(utxo) = self.utxos.get(&outpoint) {
+      return Ok(utxo.clone());
+    }
+
+    let txout = self
+      .wallet
+      .bitcoin_client()
+      .get_raw_transaction(&outpoint.txid, None)?
+      .output
+      .into_iter()
+      .nth(outpoint.vout.into_usize())
+      .ok_or_else(|| anyhow!("output {outpoint} not found"))?;
+
+    Ok(Utxo {
+      assets: self.indexed_assets(outpoint)?.unwrap_or_default(),
+      mine: self.wallet.is_mine(&txout.script_pubkey)?,
+      txout,
+    })
+  }
+
+  fn indexed_assets(&mut self, outpoint: OutPoint) -> Result<Option<Assets>> {
+    let Some(info) = self.wallet.get_output_info(outpoint)? else {
+      return Ok(None);
+    };
+
+    if info.spent {
+      return Ok(None);
+    }
+
+    let mut assets = Assets::default();
+
+    for id in info.inscriptions {
+      assets
+        .inscriptions
+        .push((id, self.wallet.get_inscription(id)?.satpoint.offset));
+    }
+
+    for (spaced_rune, pile) in info.runes {
+      let (id, entry, _) = self
+        .wallet
+        .get_rune(spaced_rune.rune)?
+        .ok_or_else(|| anyhow!("rune {spaced_rune} not found"))?;
+
+      self.runes.insert(id, entry);
+
+      assets.runes.insert(id, pile.amount);
+    }
+
+    Ok(Some(assets))
+  }
+
+  /// Amount minted by a mint of rune `id` at `height`. The index only knows
+  /// how many mints were made as of the tip, so once the cap has been reached,
+  /// confirmed mints are assumed to have been made before it was. Outputs which
+  /// are still unspent are corrected with their indexed balances.
+  fn mint(
+    &mut self,
+    id: RuneId,
+    height: u64,
+    confirmed: bool,
+  ) -> Result<Option<(SpacedRune, Lot)>> {
+    let Some(entry) = self.rune(id)? else {
+      return Ok(None);
+    };
+
+    let amount = match entry.mintable(height) {
+      Ok(amount) => amount,
+      Err(MintError::Cap(_)) if confirmed => entry
+        .terms
+        .and_then(|terms| terms.amount)
+        .unwrap_or_default(),
+      Err(_) => return Ok(None),
+    };
+
+    Ok(Some((entry.spaced_rune, Lot(amount))))
+  }
+
+  fn rune(&mut self, id: RuneId) -> Result<Option<RuneEntry>> {
+    if let Some(entry) = self.runes.get(&id) {
+      return Ok(Some(*entry));
+    }
+
+    let entry = self.wallet.get_rune_by_id(id)?;
+
+    if let Some(entry) = entry {
+      self.runes.insert(id, entry);
+    }
+
+    Ok(entry)
+  }
+
+  fn decimal(&mut self, id: RuneId, amount: u128) -> Result<(SpacedRune, Decimal)> {
+    let entry = self
+      .rune(id)?
+      .ok_or_else(|| anyhow!("rune {id} not found"))?;
+
+    Ok((
+      entry.spaced_rune,
+      Decimal {
+        value: amount,
+        scale: entry.divisibility,
+      },
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn transaction(parent: Option<Txid>, lock_time: u32) -> (Txid, Transaction) {
+    let transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::from_consensus(lock_time),
+      input: vec![TxIn {
+        previous_output: parent
+          .map(|txid| OutPoint { txid, vout: 0 })
+          .unwrap_or_else(OutPoint::null),
+        ..default()
+      }],
+      output: Vec::new(),
+    };
+
+    (transaction.compute_txid(), transaction)
+  }
+
+  #[test]
+  fn transactions_in_same_block_are_sorted_after_their_parents() {
+    let (older, older_tx) = transaction(None, 0);
+    let (parent, parent_tx) = transaction(None, 1);
+    let (child, child_tx) = transaction(Some(parent), 2);
+    let (grandchild, grandchild_tx) = transaction(Some(child), 3);
+
+    assert_eq!(
+      Transactions::sort(vec![
+        (grandchild, 1, grandchild_tx),
+        (child, 1, child_tx),
+        (parent, 1, parent_tx),
+        (older, 2, older_tx),
+      ])
+      .into_iter()
+      .map(|(txid, confirmations, _)| (txid, confirmations))
+      .collect::<Vec<(Txid, i32)>>(),
+      vec![(older, 2), (parent, 1), (child, 1), (grandchild, 1)],
+    );
+  }
 }
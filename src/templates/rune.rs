@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Rune")]
 pub struct RuneHtml {
   pub entry: RuneEntry,
   pub id: RuneId,
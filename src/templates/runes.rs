@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Runes")]
 pub struct RunesHtml {
   pub entries: Vec<(RuneId, RuneEntry)>,
   pub more: bool,
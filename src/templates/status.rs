@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Status")]
 pub struct StatusHtml {
   pub address_index: bool,
   pub blessed_inscriptions: u64,
@@ -16,10 +17,12 @@ pub struct StatusHtml {
   pub rune_index: bool,
   pub runes: u64,
   pub sat_index: bool,
+  #[schemars(with = "schema::DateTime")]
   pub started: DateTime<Utc>,
   pub transaction_index: bool,
   pub unrecoverably_reorged: bool,
   pub uptime: Duration,
+  pub usage: Option<BTreeMap<String, api::Usage>>,
 }
 
 impl PageContent for StatusHtml {
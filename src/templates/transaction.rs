@@ -1,11 +1,14 @@
 use super::*;
 
-#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "Transaction")]
 pub struct TransactionHtml {
   pub chain: Chain,
   pub etching: Option<SpacedRune>,
   pub inscription_count: u32,
+  #[schemars(with = "schema::Transaction")]
   pub transaction: Transaction,
+  #[schemars(with = "schema::Hash")]
   pub txid: Txid,
 }
 
@@ -6,15 +6,24 @@ use {
     bip32::{ChildNumber, DerivationPath, Xpriv},
     psbt::Psbt,
     secp256k1::Secp256k1,
+    sighash::{EcdsaSighashType, SighashCache, TapSighashType},
+  },
+  bitcoincore_rpc::json::{
+    ImportDescriptors, SignRawTransactionInput, SignRawTransactionResult,
+    SignRawTransactionResultError,
   },
-  bitcoincore_rpc::json::ImportDescriptors,
   entry::{EtchingEntry, EtchingEntryValue},
   fee_rate::FeeRate,
   index::entry::Entry,
   indicatif::{ProgressBar, ProgressStyle},
+  keychain::Keychain,
   log::log_enabled,
   miniscript::descriptor::{DescriptorSecretKey, DescriptorXKey, Wildcard},
-  redb::{Database, DatabaseError, ReadableTable, RepairSession, StorageError, TableDefinition},
+  protection::Protection,
+  redb::{
+    Database, DatabaseError, ReadableTable, RepairSession, StorageError, TableDefinition,
+    TableError,
+  },
   reqwest::header,
   std::sync::Once,
   transaction_builder::TransactionBuilder,
@@ -22,23 +31,40 @@ use {
 
 pub mod batch;
 pub mod entry;
+mod keychain;
+pub(crate) mod postage;
+pub mod protection;
 pub mod transaction_builder;
 pub mod wallet_constructor;
 
 const SCHEMA_VERSION: u64 = 1;
 
+define_table! { BATCH_PROGRESS, &str, &str }
+define_table! { DESCRIPTORS, &str, bool }
+define_table! { LABELS, (&str, &str), &str }
 define_table! { RUNE_TO_ETCHING, u128, EtchingEntryValue }
 define_table! { STATISTICS, u64, u64 }
+define_table! { TRANSACTIONS, &[u8; 32], &[u8] }
 
 #[derive(Copy, Clone)]
 pub(crate) enum Statistic {
   Schema = 0,
+  NextReceiveIndex = 1,
+  NextChangeIndex = 2,
 }
 
 impl Statistic {
   fn key(self) -> u64 {
     self.into()
   }
+
+  fn next_index(change: bool) -> Self {
+    if change {
+      Self::NextChangeIndex
+    } else {
+      Self::NextReceiveIndex
+    }
+  }
 }
 
 impl From<Statistic> for u64 {
@@ -75,6 +101,7 @@ pub(crate) enum Maturity {
 pub(crate) struct Wallet {
   bitcoin_client: Client,
   database: Database,
+  has_address_index: bool,
   has_inscription_index: bool,
   has_rune_index: bool,
   has_sat_index: bool,
@@ -84,7 +111,12 @@ pub(crate) struct Wallet {
   inscription_info: BTreeMap<InscriptionId, api::Inscription>,
   output_info: BTreeMap<OutPoint, api::Output>,
   inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
+  keychain: Option<Keychain>,
   locked_utxos: BTreeMap<OutPoint, TxOut>,
+  max_fee: Option<Amount>,
+  max_fee_rate: Option<FeeRate>,
+  name: String,
+  protection: Protection,
   settings: Settings,
 }
 
@@ -97,6 +129,11 @@ impl Wallet {
 
     let mut output_sat_ranges = Vec::new();
     for (output, info) in self.output_info.iter() {
+      // sat ranges of unconfirmed outputs are not yet known
+      if !info.indexed {
+        continue;
+      }
+
       if let Some(sat_ranges) = &info.sat_ranges {
         output_sat_ranges.push((*output, sat_ranges.clone()));
       } else {
@@ -160,6 +197,106 @@ impl Wallet {
     &self.utxos
   }
 
+  /// Update wallet outputs with `transaction`, broadcast after the wallet
+  /// was loaded, so that its outputs can be spent by later transactions.
+  pub(crate) fn apply_transaction(&mut self, transaction: &Transaction) -> Result {
+    for txin in &transaction.input {
+      self.utxos.remove(&txin.previous_output);
+      self.output_info.remove(&txin.previous_output);
+    }
+
+    let txid = transaction.compute_txid();
+
+    for (vout, output) in transaction.output.iter().enumerate() {
+      if self.is_mine(&output.script_pubkey)? {
+        self.utxos.insert(
+          OutPoint {
+            txid,
+            vout: vout.try_into().unwrap(),
+          },
+          output.clone(),
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Update outputs of a wallet using the ord backend with `transaction`,
+  /// broadcast by the wallet but not yet confirmed. Spent outputs are removed,
+  /// and outputs paying to the wallet are added if they can be shown to hold
+  /// no inscriptions, runes, or protected sats, since their contents are not
+  /// known to ord server until they are confirmed.
+  pub(crate) fn apply_unconfirmed_transaction(&mut self, transaction: &Transaction) -> Result {
+    let cardinal = self.get_cardinal_utxos()?;
+
+    // without edicts, runes minted or etched by a runestone can only be
+    // allocated to its pointer or default output
+    let runic_output = match Runestone::decipher(transaction) {
+      Some(Artifact::Runestone(runestone)) if runestone.edicts.is_empty() => Some(
+        runestone
+          .pointer
+          .map(|pointer| pointer.into_usize())
+          .or_else(|| {
+            transaction
+              .output
+              .iter()
+              .position(|txout| !txout.script_pubkey.is_op_return())
+          }),
+      ),
+      Some(_) => Some(None),
+      None => None,
+    };
+
+    let cardinal_outputs = runic_output != Some(None)
+      && ParsedEnvelope::from_transaction(transaction).is_empty()
+      && transaction
+        .input
+        .iter()
+        .all(|txin| cardinal.contains_key(&txin.previous_output));
+
+    for txin in &transaction.input {
+      self.utxos.remove(&txin.previous_output);
+      self.output_info.remove(&txin.previous_output);
+      self
+        .inscriptions
+        .retain(|satpoint, _| satpoint.outpoint != txin.previous_output);
+    }
+
+    if !cardinal_outputs {
+      return Ok(());
+    }
+
+    let txid = transaction.compute_txid();
+
+    for (vout, output) in transaction.output.iter().enumerate() {
+      if runic_output != Some(Some(vout)) && self.is_mine(&output.script_pubkey)? {
+        let outpoint = OutPoint {
+          txid,
+          vout: vout.try_into().unwrap(),
+        };
+
+        self.utxos.insert(outpoint, output.clone());
+
+        self.output_info.insert(
+          outpoint,
+          api::Output::new(
+            self.chain(),
+            Vec::new(),
+            outpoint,
+            output.clone(),
+            false,
+            BTreeMap::new(),
+            None,
+            false,
+          ),
+        );
+      }
+    }
+
+    Ok(())
+  }
+
   pub(crate) fn locked_utxos(&self) -> &BTreeMap<OutPoint, TxOut> {
     &self.locked_utxos
   }
@@ -182,17 +319,31 @@ impl Wallet {
       .keys()
       .filter(|utxo| inscriptions.contains(utxo))
       .chain(self.get_runic_outputs()?.iter())
+      .chain(self.get_protected_outputs()?.keys())
       .cloned()
       .filter(|utxo| !locked.contains(utxo))
+      .collect::<BTreeSet<OutPoint>>()
+      .into_iter()
       .collect::<Vec<OutPoint>>();
 
-    if !self.bitcoin_client().lock_unspent(&outputs)? {
+    if !self.lock_unspent(&outputs)? {
       bail!("failed to lock UTXOs");
     }
 
     Ok(())
   }
 
+  /// Lock `outputs` with `lockunspent`. Wallets using the ord backend select
+  /// coins themselves and never select non-cardinal outputs, so this is a
+  /// no-op for them.
+  pub(crate) fn lock_unspent(&self, outputs: &[OutPoint]) -> Result<bool> {
+    if self.keychain.is_some() {
+      return Ok(true);
+    }
+
+    Ok(self.bitcoin_client.lock_unspent(outputs)?)
+  }
+
   pub(crate) fn inscriptions(&self) -> &BTreeMap<SatPoint, Vec<InscriptionId>> {
     &self.inscriptions
   }
@@ -221,6 +372,68 @@ impl Wallet {
     self.output_info.get(output).unwrap().inscriptions.clone()
   }
 
+  pub(crate) fn get_inscription(&self, inscription_id: InscriptionId) -> Result<api::Inscription> {
+    let response = self
+      .ord_client
+      .get(
+        self
+          .rpc_url
+          .join(&format!("/inscription/{inscription_id}"))
+          .unwrap(),
+      )
+      .send()?
+      .error_for_status()?;
+
+    Ok(serde_json::from_str(&response.text()?)?)
+  }
+
+  pub(crate) fn get_output_info(&self, output: OutPoint) -> Result<Option<api::Output>> {
+    let response = self
+      .ord_client
+      .get(self.rpc_url.join(&format!("/output/{output}")).unwrap())
+      .send()?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
+
+    let output: api::Output = serde_json::from_str(&response.error_for_status()?.text()?)?;
+
+    Ok(output.indexed.then_some(output))
+  }
+
+  pub(crate) fn get_address_outputs(&self, address: &Address) -> Result<Vec<api::Output>> {
+    let response = self
+      .ord_client
+      .get(self.rpc_url.join(&format!("/outputs/{address}")).unwrap())
+      .send()?
+      .error_for_status()?;
+
+    Ok(serde_json::from_str(&response.text()?)?)
+  }
+
+  pub(crate) fn is_mine(&self, script_pubkey: &Script) -> Result<bool> {
+    if let Some(keychain) = &self.keychain {
+      return Ok(keychain.is_mine(script_pubkey));
+    }
+
+    #[derive(Deserialize)]
+    struct AddressInfo {
+      ismine: bool,
+    }
+
+    let Ok(address) = self.chain().address_from_script(script_pubkey) else {
+      return Ok(false);
+    };
+
+    Ok(
+      self
+        .bitcoin_client
+        .call::<AddressInfo>("getaddressinfo", &[address.to_string().into()])?
+        .ismine,
+    )
+  }
+
   pub(crate) fn get_parent_info(&self, parents: &[InscriptionId]) -> Result<Vec<ParentInfo>> {
     let mut parent_info = Vec::new();
     for parent_id in parents {
@@ -251,6 +464,228 @@ impl Wallet {
     Ok(parent_info)
   }
 
+  pub(crate) fn get_cardinal_utxos(&self) -> Result<BTreeMap<OutPoint, TxOut>> {
+    let inscribed = self
+      .inscriptions()
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<HashSet<OutPoint>>();
+
+    let runic = self.get_runic_outputs()?;
+
+    let protected = self.get_protected_outputs()?;
+
+    Ok(
+      self
+        .utxos()
+        .iter()
+        .filter(|(outpoint, _)| {
+          !inscribed.contains(outpoint)
+            && !runic.contains(outpoint)
+            && !protected.contains_key(outpoint)
+            && !self.locked_utxos().contains_key(outpoint)
+        })
+        .map(|(outpoint, txout)| (*outpoint, txout.clone()))
+        .collect(),
+    )
+  }
+
+  pub(crate) fn get_protected_outputs(&self) -> Result<BTreeMap<OutPoint, Sat>> {
+    if self.protection.is_empty() {
+      return Ok(BTreeMap::new());
+    }
+
+    ensure!(
+      self.has_sat_index,
+      "protecting sats requires index created with `--index-sats` flag"
+    );
+
+    let mut protected = BTreeMap::new();
+    for (output, ranges) in self.get_wallet_sat_ranges()? {
+      if let Some(sat) = ranges
+        .iter()
+        .find_map(|(start, end)| self.protection.first(*start, *end))
+      {
+        protected.insert(output, sat);
+      }
+    }
+
+    Ok(protected)
+  }
+
+  /// Locked outputs and outputs containing protected sats, neither of which
+  /// may be selected to fund a transaction.
+  pub(crate) fn get_unspendable_outputs(&self) -> Result<BTreeSet<OutPoint>> {
+    Ok(
+      self
+        .locked_utxos()
+        .keys()
+        .chain(self.get_protected_outputs()?.keys())
+        .cloned()
+        .collect(),
+    )
+  }
+
+  /// Errors if `transaction` would pay a protected sat as fee, or send it out
+  /// of the wallet. Protected sats in `outgoing` may be sent, since the user
+  /// selected that output explicitly.
+  pub(crate) fn check_protected_sats(
+    &self,
+    transaction: &Transaction,
+    outgoing: Option<OutPoint>,
+  ) -> Result {
+    if self.protection.is_empty() {
+      return Ok(());
+    }
+
+    ensure!(
+      self.has_sat_index,
+      "protecting sats requires index created with `--index-sats` flag"
+    );
+
+    let mut mine = Vec::new();
+    for output in &transaction.output {
+      mine.push(self.is_mine(&output.script_pubkey)?);
+    }
+
+    let mut output_end = Vec::new();
+    let mut end = 0;
+    for output in &transaction.output {
+      end += output.value.to_sat();
+      output_end.push(end);
+    }
+
+    let mut offset = 0;
+    for input in &transaction.input {
+      let outpoint = input.previous_output;
+
+      let info = match self.output_info.get(&outpoint) {
+        Some(info) => info.clone(),
+        None => self
+          .get_output_info(outpoint)?
+          .ok_or_else(|| anyhow!("input {outpoint} not found in index"))?,
+      };
+
+      let Some(sat_ranges) = info.sat_ranges else {
+        offset += info.value;
+        continue;
+      };
+
+      for (start, end) in sat_ranges {
+        let mut start = start;
+
+        while start < end {
+          let vout = output_end.partition_point(|output_end| *output_end <= offset);
+
+          let len = match output_end.get(vout) {
+            Some(output_end) => (output_end - offset).min(end - start),
+            None => end - start,
+          };
+
+          if let Some(sat) = self.protection.first(start, start + len) {
+            if vout == transaction.output.len() {
+              bail!("transaction would spend protected sat {sat} in output {outpoint} as fee");
+            }
+
+            ensure!(
+              mine[vout] || outgoing == Some(outpoint),
+              "transaction would send protected sat {sat} in output {outpoint} out of wallet",
+            );
+          }
+
+          start += len;
+          offset += len;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn fee_rate(&self, fee_rate: FeeRateArg) -> Result<FeeRate> {
+    let conf_target = match fee_rate {
+      FeeRateArg::Rate(fee_rate) => return Ok(fee_rate),
+      FeeRateArg::Auto(conf_target) => conf_target,
+    };
+
+    let estimate = self.bitcoin_client.estimate_smart_fee(conf_target, None)?;
+
+    let Some(estimate) = estimate.fee_rate else {
+      bail!(
+        "failed to estimate fee rate for confirmation target {conf_target}: {}",
+        estimate.errors.unwrap_or_default().join(", "),
+      );
+    };
+
+    let minimum = self.bitcoin_client.get_mempool_info()?.mempool_min_fee;
+
+    // estimates are in sats per kvB
+    #[allow(clippy::cast_precision_loss)]
+    FeeRate::try_from(estimate.max(minimum).to_sat() as f64 / 1000.0)
+  }
+
+  pub(crate) fn transaction_fee(&self, transaction: &Transaction) -> Result<Amount> {
+    let mut input_value = Amount::ZERO;
+    for txin in &transaction.input {
+      input_value += match self.utxos.get(&txin.previous_output) {
+        Some(txout) => txout.value,
+        None => {
+          self
+            .bitcoin_client
+            .get_raw_transaction(&txin.previous_output.txid, None)?
+            .output
+            .get(usize::try_from(txin.previous_output.vout).unwrap())
+            .ok_or_else(|| anyhow!("input {} not found", txin.previous_output))?
+            .value
+        }
+      };
+    }
+
+    input_value
+      .checked_sub(transaction.output.iter().map(|txout| txout.value).sum())
+      .ok_or_else(|| anyhow!("transaction outputs exceed inputs"))
+  }
+
+  /// Errors if the total fee of `transactions` exceeds `--max-fee`, or if any
+  /// of their fee rates exceeds `--max-fee-rate`.
+  pub(crate) fn check_fee(&self, transactions: &[(&Transaction, Amount)]) -> Result {
+    if let Some(max_fee) = self.max_fee {
+      let fee = transactions.iter().map(|(_, fee)| *fee).sum::<Amount>();
+
+      ensure!(
+        fee <= max_fee,
+        "fee of {} sat exceeds maximum of {} sat",
+        fee.to_sat(),
+        max_fee.to_sat(),
+      );
+    }
+
+    if let Some(max_fee_rate) = self.max_fee_rate {
+      for (transaction, fee) in transactions {
+        // unsigned inputs are assumed to be taproot key path spends
+        let vsize = transaction.vsize()
+          + transaction
+            .input
+            .iter()
+            .filter(|txin| txin.witness.is_empty())
+            .count()
+            * (TransactionBuilder::SCHNORR_SIGNATURE_SIZE / 4 + 1);
+
+        #[allow(clippy::cast_precision_loss)]
+        let fee_rate = fee.to_sat() as f64 / vsize as f64;
+
+        ensure!(
+          fee_rate <= max_fee_rate.n(),
+          "fee rate of {fee_rate:.2} sat/vB for transaction {} exceeds maximum of {} sat/vB",
+          transaction.compute_txid(),
+          max_fee_rate.n(),
+        );
+      }
+    }
+
+    Ok(())
+  }
+
   pub(crate) fn get_runic_outputs(&self) -> Result<BTreeSet<OutPoint>> {
     let mut runic_outputs = BTreeSet::new();
     for (output, info) in self.output_info.iter() {
@@ -280,14 +715,25 @@ impl Wallet {
     &self,
     rune: Rune,
   ) -> Result<Option<(RuneId, RuneEntry, Option<InscriptionId>)>> {
+    Ok(
+      self
+        .get_rune_json(&SpacedRune { rune, spacers: 0 }.to_string())?
+        .map(|rune_json| (rune_json.id, rune_json.entry, rune_json.parent)),
+    )
+  }
+
+  pub(crate) fn get_rune_by_id(&self, id: RuneId) -> Result<Option<RuneEntry>> {
+    Ok(
+      self
+        .get_rune_json(&id.to_string())?
+        .map(|rune_json| rune_json.entry),
+    )
+  }
+
+  fn get_rune_json(&self, query: &str) -> Result<Option<api::Rune>> {
     let response = self
       .ord_client
-      .get(
-        self
-          .rpc_url
-          .join(&format!("/rune/{}", SpacedRune { rune, spacers: 0 }))
-          .unwrap(),
-      )
+      .get(self.rpc_url.join(&format!("/rune/{query}")).unwrap())
       .send()?;
 
     if response.status() == StatusCode::NOT_FOUND {
@@ -296,12 +742,29 @@ impl Wallet {
 
     let response = response.error_for_status()?;
 
-    let rune_json: api::Rune = serde_json::from_str(&response.text()?)?;
+    Ok(Some(serde_json::from_str(&response.text()?)?))
+  }
+
+  pub(crate) fn get_transaction_info(&self, txid: Txid) -> Result<Option<api::Transaction>> {
+    let response = self
+      .ord_client
+      .get(self.rpc_url.join(&format!("/tx/{txid}")).unwrap())
+      .send()?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
 
-    Ok(Some((rune_json.id, rune_json.entry, rune_json.parent)))
+    Ok(Some(serde_json::from_str(
+      &response.error_for_status()?.text()?,
+    )?))
   }
 
   pub(crate) fn get_change_address(&self) -> Result<Address> {
+    if self.keychain.is_some() {
+      return self.next_address(true);
+    }
+
     Ok(
       self
         .bitcoin_client
@@ -311,6 +774,62 @@ impl Wallet {
     )
   }
 
+  pub(crate) fn get_receive_address(&self) -> Result<Address> {
+    if self.keychain.is_some() {
+      return self.next_address(false);
+    }
+
+    Ok(
+      self
+        .bitcoin_client
+        .get_new_address(None, Some(bitcoincore_rpc::json::AddressType::Bech32m))?
+        .require_network(self.chain().network())?,
+    )
+  }
+
+  fn next_address(&self, change: bool) -> Result<Address> {
+    let index = Self::next_index(&self.database, change)?;
+
+    Self::set_next_index(&self.database, change, index + 1)?;
+
+    self
+      .keychain
+      .as_ref()
+      .unwrap()
+      .derive(change, u32::try_from(index)?)
+  }
+
+  pub(crate) fn next_index(database: &Database, change: bool) -> Result<u64> {
+    Ok(
+      database
+        .begin_read()?
+        .open_table(STATISTICS)?
+        .get(&Statistic::next_index(change).key())?
+        .map(|index| index.value())
+        .unwrap_or_default(),
+    )
+  }
+
+  pub(crate) fn set_next_index(database: &Database, change: bool, index: u64) -> Result {
+    let wtx = database.begin_write()?;
+
+    wtx
+      .open_table(STATISTICS)?
+      .insert(&Statistic::next_index(change).key(), &index)?;
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  pub(crate) fn has_keychain(&self) -> bool {
+    self.keychain.is_some()
+  }
+
+  pub(crate) fn has_address_index(&self) -> bool {
+    self.has_address_index
+  }
+
   pub(crate) fn has_inscription_index(&self) -> bool {
     self.has_inscription_index
   }
@@ -342,32 +861,47 @@ impl Wallet {
   }
 
   pub(crate) fn check_maturity(&self, rune: Rune, commit: &Transaction) -> Result<Maturity> {
-    Ok(
-      if let Some(commit_tx) = self
+    let txid = commit.compute_txid();
+
+    // wallets using the ord backend have no wallet transactions, so
+    // confirmations are taken from the commit output
+    let confirmations = if self.keychain.is_some() {
+      match self.bitcoin_client().get_tx_out(&txid, 0, Some(true))? {
+        Some(output) => Some(output.confirmations),
+        None => self
+          .bitcoin_client()
+          .get_raw_transaction(&txid, None)
+          .into_option()?
+          .map(|_| 0),
+      }
+    } else {
+      self
         .bitcoin_client()
-        .get_transaction(&commit.compute_txid(), Some(true))
+        .get_transaction(&txid, Some(true))
         .into_option()?
+        .map(|commit_tx| u32::try_from(commit_tx.info.confirmations))
+        .transpose()?
+    };
+
+    Ok(if let Some(current_confirmations) = confirmations {
+      if self
+        .bitcoin_client()
+        .get_tx_out(&txid, 0, Some(true))?
+        .is_none()
       {
-        let current_confirmations = u32::try_from(commit_tx.info.confirmations)?;
-        if self
-          .bitcoin_client()
-          .get_tx_out(&commit.compute_txid(), 0, Some(true))?
-          .is_none()
-        {
-          Maturity::CommitSpent(commit_tx.info.txid)
-        } else if !self.is_above_minimum_at_height(rune)? {
-          Maturity::BelowMinimumHeight(self.bitcoin_client().get_block_count()? + 1)
-        } else if current_confirmations + 1 < Runestone::COMMIT_CONFIRMATIONS.into() {
-          Maturity::ConfirmationsPending(
-            u32::from(Runestone::COMMIT_CONFIRMATIONS) - current_confirmations - 1,
-          )
-        } else {
-          Maturity::Mature
-        }
+        Maturity::CommitSpent(txid)
+      } else if !self.is_above_minimum_at_height(rune)? {
+        Maturity::BelowMinimumHeight(self.bitcoin_client().get_block_count()? + 1)
+      } else if current_confirmations + 1 < Runestone::COMMIT_CONFIRMATIONS.into() {
+        Maturity::ConfirmationsPending(
+          u32::from(Runestone::COMMIT_CONFIRMATIONS) - current_confirmations - 1,
+        )
       } else {
-        Maturity::CommitNotFound
-      },
-    )
+        Maturity::Mature
+      }
+    } else {
+      Maturity::CommitNotFound
+    })
   }
 
   pub(crate) fn wait_for_maturation(&self, rune: Rune) -> Result<batch::Output> {
@@ -423,7 +957,7 @@ impl Wallet {
   }
 
   pub(crate) fn send_etching(&self, rune: Rune, entry: &EtchingEntry) -> Result<batch::Output> {
-    match self.bitcoin_client().send_raw_transaction(&entry.reveal) {
+    match self.send_raw_transaction(&entry.reveal) {
       Ok(txid) => txid,
       Err(err) => {
         return Err(anyhow!(
@@ -509,8 +1043,62 @@ impl Wallet {
       None,
     )?;
 
-    let network = settings.chain().network();
+    let descriptors = Self::derive_descriptors(settings.chain().network(), seed)?
+      .into_iter()
+      .map(|(descriptor, internal)| ImportDescriptors {
+        descriptor,
+        timestamp,
+        active: Some(true),
+        range: None,
+        next_index: None,
+        internal: Some(internal),
+        label: None,
+      })
+      .collect::<Vec<ImportDescriptors>>();
+
+    settings
+      .bitcoin_rpc_client(Some(name.clone()))?
+      .call::<serde_json::Value>("importdescriptors", &[serde_json::to_value(descriptors)?])?;
+
+    Ok(())
+  }
+
+  /// Create a wallet using the ord backend, which stores `descriptors` in the
+  /// wallet database instead of importing them into Bitcoin Core.
+  pub(crate) fn initialize_keychain(
+    name: String,
+    settings: &Settings,
+    descriptors: Vec<(String, bool)>,
+  ) -> Result {
+    ensure!(
+      !Self::check_version(settings.bitcoin_rpc_client(None)?)?
+        .list_wallet_dir()?
+        .contains(&name),
+      "wallet `{name}` already exists",
+    );
+
+    Keychain::new(&descriptors, settings.chain().network())?;
 
+    let database = Self::open_database(&name, settings)?;
+
+    ensure!(
+      Self::load_descriptors(&database)?.is_empty(),
+      "wallet `{name}` already exists",
+    );
+
+    for (descriptor, internal) in descriptors {
+      Self::save_descriptor(&database, &descriptor, internal)?;
+    }
+
+    Ok(())
+  }
+
+  /// Derive BIP-86 receive and change descriptors, with private keys, from
+  /// `seed`.
+  pub(crate) fn derive_descriptors(
+    network: Network,
+    seed: [u8; 64],
+  ) -> Result<Vec<(String, bool)>> {
     let secp = Secp256k1::new();
 
     let master_private_key = Xpriv::new_master(network, &seed)?;
@@ -544,24 +1132,86 @@ impl Wallet {
 
       let descriptor = miniscript::descriptor::Descriptor::new_tr(public_key, None)?;
 
-      descriptors.push(ImportDescriptors {
-        descriptor: descriptor.to_string_with_secret(&key_map),
-        timestamp,
-        active: Some(true),
-        range: None,
-        next_index: None,
-        internal: Some(change),
-        label: None,
-      });
+      descriptors.push((descriptor.to_string_with_secret(&key_map), change));
     }
 
-    settings
-      .bitcoin_rpc_client(Some(name.clone()))?
-      .call::<serde_json::Value>("importdescriptors", &[serde_json::to_value(descriptors)?])?;
+    Ok(descriptors)
+  }
+
+  pub(crate) fn load_descriptors(database: &Database) -> Result<Vec<(String, bool)>> {
+    let rtx = database.begin_read()?;
+
+    // wallet databases created before the ord backend have no descriptor table
+    let table = match rtx.open_table(DESCRIPTORS) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+      Err(err) => return Err(err.into()),
+    };
+
+    Ok(
+      table
+        .iter()?
+        .map(|result| result.map(|(key, value)| (key.value().into(), value.value())))
+        .collect::<Result<Vec<(String, bool)>, StorageError>>()?,
+    )
+  }
+
+  pub(crate) fn save_descriptor(database: &Database, descriptor: &str, internal: bool) -> Result {
+    let wtx = database.begin_write()?;
+
+    wtx.open_table(DESCRIPTORS)?.insert(descriptor, internal)?;
+
+    wtx.commit()?;
 
     Ok(())
   }
 
+  /// Save recovery key of a commit transaction, so that the commit output can
+  /// be spent by a wallet using the ord backend.
+  pub(crate) fn save_recovery_key(&self, recovery_private_key: bitcoin::PrivateKey) -> Result {
+    let descriptor = format!("rawtr({})", recovery_private_key.to_wif());
+
+    let checksum = miniscript::descriptor::checksum::desc_checksum(&descriptor)?;
+
+    Self::save_descriptor(&self.database, &format!("{descriptor}#{checksum}"), false)
+  }
+
+  pub(crate) fn list_descriptors(&self) -> Result<ListDescriptorsResult> {
+    if self.keychain.is_none() {
+      return Ok(
+        self
+          .bitcoin_client
+          .call::<ListDescriptorsResult>("listdescriptors", &[serde_json::to_value(true)?])?,
+      );
+    }
+
+    let mut descriptors = Vec::new();
+
+    for (desc, internal) in Self::load_descriptors(&self.database)? {
+      let ranged = !desc.starts_with("rawtr(");
+
+      let next = if ranged {
+        Some(Self::next_index(&self.database, internal)?)
+      } else {
+        None
+      };
+
+      descriptors.push(Descriptor {
+        desc,
+        timestamp: bitcoincore_rpc::json::Timestamp::Time(0),
+        active: ranged,
+        internal: ranged.then_some(internal),
+        range: next.map(|next| (0, next)),
+        next,
+      });
+    }
+
+    Ok(ListDescriptorsResult {
+      wallet_name: self.name.clone(),
+      descriptors,
+    })
+  }
+
   pub(crate) fn check_version(client: Client) -> Result<Client> {
     const MIN_VERSION: usize = 240000;
 
@@ -666,10 +1316,14 @@ impl Wallet {
 
         let tx = database.begin_write()?;
 
+        tx.open_table(BATCH_PROGRESS)?;
+        tx.open_table(DESCRIPTORS)?;
+        tx.open_table(LABELS)?;
         tx.open_table(RUNE_TO_ETCHING)?;
 
         tx.open_table(STATISTICS)?
           .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
+        tx.open_table(TRANSACTIONS)?;
 
         tx.commit()?;
 
@@ -681,6 +1335,83 @@ impl Wallet {
     Ok(database)
   }
 
+  /// Get the name of the last file inscribed from the directory with `key`.
+  pub(crate) fn batch_progress(&self, key: &str) -> Result<Option<String>> {
+    let rtx = self.database.begin_read()?;
+
+    // wallet databases created before batch progress tracking have no table
+    let table = match rtx.open_table(BATCH_PROGRESS) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+      Err(err) => return Err(err.into()),
+    };
+
+    Ok(table.get(key)?.map(|name| name.value().into()))
+  }
+
+  pub(crate) fn save_batch_progress(&self, key: &str, name: &str) -> Result {
+    let wtx = self.database.begin_write()?;
+
+    wtx.open_table(BATCH_PROGRESS)?.insert(key, name)?;
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  /// Broadcast `transaction`. Wallets using the ord backend only learn about
+  /// their outputs once they are confirmed and indexed, so the transaction is
+  /// also recorded, and applied to the wallet's outputs when it is loaded
+  /// while the transaction is still in the mempool.
+  pub(crate) fn send_raw_transaction(&self, transaction: &Transaction) -> Result<Txid> {
+    let txid = self.bitcoin_client.send_raw_transaction(transaction)?;
+
+    if self.keychain.is_some() {
+      let wtx = self.database.begin_write()?;
+
+      wtx.open_table(TRANSACTIONS)?.insert(
+        &txid.to_byte_array(),
+        consensus::encode::serialize(transaction).as_slice(),
+      )?;
+
+      wtx.commit()?;
+    }
+
+    Ok(txid)
+  }
+
+  pub(crate) fn load_transactions(database: &Database) -> Result<Vec<Transaction>> {
+    let rtx = database.begin_read()?;
+
+    // wallet databases created before transactions were recorded have no table
+    let table = match rtx.open_table(TRANSACTIONS) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+      Err(err) => return Err(err.into()),
+    };
+
+    table
+      .iter()?
+      .map(|result| {
+        result
+          .map_err(|err| err.into())
+          .and_then(|(_txid, transaction)| Ok(consensus::encode::deserialize(transaction.value())?))
+      })
+      .collect()
+  }
+
+  pub(crate) fn remove_transaction(database: &Database, txid: Txid) -> Result {
+    let wtx = database.begin_write()?;
+
+    wtx
+      .open_table(TRANSACTIONS)?
+      .remove(&txid.to_byte_array())?;
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
   pub(crate) fn save_etching(
     &self,
     rune: &Rune,
@@ -726,7 +1457,11 @@ impl Wallet {
   }
 
   pub(crate) fn pending_etchings(&self) -> Result<Vec<(Rune, EtchingEntry)>> {
-    let rtx = self.database.begin_read()?;
+    Self::load_pending_etchings(&self.database)
+  }
+
+  pub(crate) fn load_pending_etchings(database: &Database) -> Result<Vec<(Rune, EtchingEntry)>> {
+    let rtx = database.begin_read()?;
 
     Ok(
       rtx
@@ -739,62 +1474,347 @@ impl Wallet {
     )
   }
 
-  pub(super) fn sign_and_broadcast_transaction(
+  pub(crate) fn save_labels(&self, labels: &[(String, String, String)]) -> Result {
+    let wtx = self.database.begin_write()?;
+
+    {
+      let mut table = wtx.open_table(LABELS)?;
+
+      for (r#type, r#ref, label) in labels {
+        table.insert((r#type.as_str(), r#ref.as_str()), label.as_str())?;
+      }
+    }
+
+    wtx.commit()?;
+
+    Ok(())
+  }
+
+  pub(crate) fn labels(&self) -> Result<BTreeMap<(String, String), String>> {
+    let rtx = self.database.begin_read()?;
+
+    // wallet databases created before labels were added have no label table
+    let table = match rtx.open_table(LABELS) {
+      Ok(table) => table,
+      Err(TableError::TableDoesNotExist(_)) => return Ok(BTreeMap::new()),
+      Err(err) => return Err(err.into()),
+    };
+
+    Ok(
+      table
+        .iter()?
+        .map(|result| {
+          result.map(|(key, value)| {
+            let (r#type, r#ref) = key.value();
+            ((r#type.into(), r#ref.into()), value.value().into())
+          })
+        })
+        .collect::<Result<BTreeMap<(String, String), String>, StorageError>>()?,
+    )
+  }
+
+  pub(crate) fn output_labels(&self) -> Result<BTreeMap<OutPoint, String>> {
+    Ok(
+      self
+        .labels()?
+        .into_iter()
+        .filter(|((r#type, _), _)| r#type == "output")
+        .filter_map(|((_, r#ref), label)| Some((r#ref.parse().ok()?, label)))
+        .collect(),
+    )
+  }
+
+  pub(crate) fn sign_psbt(
     &self,
-    unsigned_transaction: Transaction,
-    dry_run: bool,
-  ) -> Result<(Txid, String, u64)> {
-    let unspent_outputs = self.utxos();
+    psbt: &Psbt,
+    sighash_type: Option<EcdsaSighashType>,
+  ) -> Result<Psbt> {
+    self.process_psbt(psbt, true, sighash_type)
+  }
 
-    let (txid, psbt) = if dry_run {
+  /// Add UTXO information to inputs of `psbt` spending wallet outputs, and,
+  /// if `sign` is true, sign them.
+  pub(crate) fn process_psbt(
+    &self,
+    psbt: &Psbt,
+    sign: bool,
+    sighash_type: Option<EcdsaSighashType>,
+  ) -> Result<Psbt> {
+    let Some(keychain) = &self.keychain else {
       let psbt = self
         .bitcoin_client()
         .wallet_process_psbt(
-          &base64::engine::general_purpose::STANDARD
-            .encode(Psbt::from_unsigned_tx(unsigned_transaction.clone())?.serialize()),
-          Some(false),
-          None,
+          &base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+          Some(sign),
+          sighash_type.map(Into::into),
           None,
         )?
         .psbt;
 
-      (unsigned_transaction.compute_txid(), psbt)
-    } else {
-      let psbt = self
-        .bitcoin_client()
-        .wallet_process_psbt(
-          &base64::engine::general_purpose::STANDARD
-            .encode(Psbt::from_unsigned_tx(unsigned_transaction.clone())?.serialize()),
-          Some(true),
-          None,
-          None,
-        )?
-        .psbt;
+      return Ok(Psbt::deserialize(
+        &base64::engine::general_purpose::STANDARD.decode(psbt)?,
+      )?);
+    };
 
-      let signed_tx = self
-        .bitcoin_client()
-        .finalize_psbt(&psbt, None)?
-        .hex
-        .ok_or_else(|| anyhow!("unable to sign transaction"))?;
+    let mut psbt = psbt.clone();
 
-      (
-        self.bitcoin_client().send_raw_transaction(&signed_tx)?,
-        psbt,
-      )
+    for (input, txin) in psbt.inputs.iter_mut().zip(&psbt.unsigned_tx.input) {
+      if input.witness_utxo.is_none() {
+        input.witness_utxo = self.utxos.get(&txin.previous_output).cloned();
+      }
+    }
+
+    if !sign {
+      return Ok(psbt);
+    }
+
+    let Some(prevouts) = psbt
+      .inputs
+      .iter()
+      .map(|input| input.witness_utxo.clone())
+      .collect::<Option<Vec<TxOut>>>()
+    else {
+      return Ok(psbt);
+    };
+
+    let sighash_type = match sighash_type {
+      Some(sighash_type) => {
+        TapSighashType::from_consensus_u8(u8::try_from(sighash_type.to_u32())?)?
+      }
+      None => TapSighashType::Default,
+    };
+
+    let mut cache = SighashCache::new(&psbt.unsigned_tx);
+
+    for (index, input) in psbt.inputs.iter_mut().enumerate() {
+      if input.final_script_witness.is_some() {
+        continue;
+      }
+
+      if let Some(signature) = keychain.sign(&mut cache, index, &prevouts, sighash_type)? {
+        input.tap_key_sig = Some(signature);
+        input.final_script_witness = Some(Witness::p2tr_key_spend(&signature));
+      }
+    }
+
+    Ok(psbt)
+  }
+
+  pub(crate) fn finalize_psbt(&self, psbt: &Psbt) -> Result<Transaction> {
+    if self.keychain.is_some() {
+      ensure!(
+        psbt.inputs.iter().all(|input| {
+          input.final_script_witness.is_some() || input.final_script_sig.is_some()
+        }),
+        "unable to sign transaction"
+      );
+
+      return Ok(psbt.clone().extract_tx_unchecked_fee_rate());
+    }
+
+    let signed_transaction = self
+      .bitcoin_client()
+      .finalize_psbt(
+        &base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+        None,
+      )?
+      .hex
+      .ok_or_else(|| anyhow!("unable to sign transaction"))?;
+
+    Ok(consensus::encode::deserialize(&signed_transaction)?)
+  }
+
+  /// Sign inputs of `transaction` spending wallet outputs, or outputs
+  /// described by `inputs`, with `signrawtransactionwithwallet` or, for
+  /// wallets using the ord backend, the wallet keychain.
+  pub(crate) fn sign_raw_transaction(
+    &self,
+    transaction: &Transaction,
+    inputs: Option<&[SignRawTransactionInput]>,
+  ) -> Result<SignRawTransactionResult> {
+    let Some(keychain) = &self.keychain else {
+      return Ok(self.bitcoin_client.sign_raw_transaction_with_wallet(
+        transaction,
+        inputs,
+        None,
+      )?);
     };
 
-    let mut fee = 0;
-    for txin in unsigned_transaction.input.iter() {
-      let Some(txout) = unspent_outputs.get(&txin.previous_output) else {
-        panic!("input {} not found in utxos", txin.previous_output);
+    let prevouts = transaction
+      .input
+      .iter()
+      .map(|txin| {
+        self.utxos.get(&txin.previous_output).cloned().or_else(|| {
+          inputs.unwrap_or_default().iter().find_map(|input| {
+            (input.txid == txin.previous_output.txid && input.vout == txin.previous_output.vout)
+              .then(|| TxOut {
+                script_pubkey: input.script_pub_key.clone(),
+                value: input.amount.unwrap_or_default(),
+              })
+          })
+        })
+      })
+      .collect::<Option<Vec<TxOut>>>();
+
+    let mut signed_transaction = transaction.clone();
+    let mut errors = Vec::new();
+    let mut cache = SighashCache::new(transaction);
+
+    for (index, txin) in transaction.input.iter().enumerate() {
+      if !txin.witness.is_empty() || !txin.script_sig.is_empty() {
+        continue;
+      }
+
+      let signature = match &prevouts {
+        Some(prevouts) => keychain.sign(&mut cache, index, prevouts, TapSighashType::Default)?,
+        None => None,
       };
-      fee += txout.value.to_sat();
+
+      match signature {
+        Some(signature) => {
+          signed_transaction.input[index].witness = Witness::p2tr_key_spend(&signature);
+        }
+        None => errors.push(SignRawTransactionResultError {
+          txid: txin.previous_output.txid,
+          vout: txin.previous_output.vout,
+          script_sig: ScriptBuf::new(),
+          sequence: txin.sequence.0,
+          error: if prevouts.is_some() {
+            "Unable to sign input".into()
+          } else {
+            "Input not found or already spent".into()
+          },
+        }),
+      }
+    }
+
+    Ok(SignRawTransactionResult {
+      hex: consensus::encode::serialize(&signed_transaction),
+      complete: errors.is_empty(),
+      errors: (!errors.is_empty()).then_some(errors),
+    })
+  }
+
+  /// Add cardinal inputs, largest first, and a change output to
+  /// `unfunded_transaction` until it pays for its outputs and fee. Used
+  /// instead of `fundrawtransaction` by wallets using the ord backend.
+  pub(crate) fn fund_transaction(
+    &self,
+    fee_rate: FeeRate,
+    unfunded_transaction: &Transaction,
+    change_address: Option<Address>,
+    excluded: &[OutPoint],
+  ) -> Result<Transaction> {
+    let mut cardinals = self
+      .get_cardinal_utxos()?
+      .into_iter()
+      .filter(|(outpoint, _)| {
+        !excluded.contains(outpoint)
+          && !unfunded_transaction
+            .input
+            .iter()
+            .any(|txin| txin.previous_output == *outpoint)
+      })
+      .collect::<Vec<(OutPoint, TxOut)>>();
+
+    cardinals.sort_by_key(|(_, txout)| cmp::Reverse(txout.value));
+
+    let mut cardinals = cardinals.into_iter();
+
+    let change = match change_address {
+      Some(address) => address,
+      None => self.get_change_address()?,
     }
+    .script_pubkey();
+
+    let outgoing = unfunded_transaction
+      .output
+      .iter()
+      .map(|txout| txout.value)
+      .sum::<Amount>();
+
+    let mut transaction = unfunded_transaction.clone();
+
+    loop {
+      let mut incoming = Amount::ZERO;
+
+      for txin in &transaction.input {
+        incoming += self
+          .utxos
+          .get(&txin.previous_output)
+          .ok_or_else(|| anyhow!("input {} not in wallet", txin.previous_output))?
+          .value;
+      }
+
+      let mut with_change = transaction.clone();
 
-    for txout in unsigned_transaction.output.iter() {
-      fee = fee.checked_sub(txout.value.to_sat()).unwrap();
+      with_change.output.push(TxOut {
+        script_pubkey: change.clone(),
+        value: Amount::ZERO,
+      });
+
+      // unsigned inputs are assumed to be taproot key path spends
+      let fee = |transaction: &Transaction| {
+        let mut transaction = transaction.clone();
+
+        for txin in &mut transaction.input {
+          if txin.witness.is_empty() {
+            txin.witness = Witness::from_slice(&[&[0; TransactionBuilder::SCHNORR_SIGNATURE_SIZE]]);
+          }
+        }
+
+        fee_rate.fee(transaction.vsize())
+      };
+
+      if let Some(remainder) = incoming.checked_sub(outgoing + fee(&with_change)) {
+        if remainder >= change.minimal_non_dust() {
+          with_change.output.last_mut().unwrap().value = remainder;
+          return Ok(with_change);
+        }
+      }
+
+      if incoming >= outgoing + fee(&transaction) {
+        return Ok(transaction);
+      }
+
+      let Some((outpoint, _)) = cardinals.next() else {
+        bail!("not enough cardinal utxos");
+      };
+
+      transaction.input.push(TxIn {
+        previous_output: outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      });
     }
+  }
+
+  pub(super) fn sign_and_broadcast_transaction(
+    &self,
+    unsigned_transaction: Transaction,
+    dry_run: bool,
+  ) -> Result<(Txid, String, u64)> {
+    let fee = self.transaction_fee(&unsigned_transaction)?;
+
+    self.check_fee(&[(&unsigned_transaction, fee)])?;
+
+    let psbt = self.process_psbt(
+      &Psbt::from_unsigned_tx(unsigned_transaction.clone())?,
+      !dry_run,
+      None,
+    )?;
+
+    let txid = if dry_run {
+      unsigned_transaction.compute_txid()
+    } else {
+      self.send_raw_transaction(&self.finalize_psbt(&psbt)?)?
+    };
 
-    Ok((txid, psbt, fee))
+    Ok((
+      txid,
+      base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+      fee.to_sat(),
+    ))
   }
 }
@@ -13,12 +13,13 @@ use {
   wallet::transaction_builder::Target,
 };
 
-pub(crate) use transactions::Transactions;
+pub(crate) use {directory::Directory, transactions::Transactions};
 
 pub use {
   entry::Entry, etching::Etching, file::File, mode::Mode, plan::Plan, range::Range, terms::Terms,
 };
 
+mod directory;
 pub mod entry;
 mod etching;
 pub mod file;
@@ -0,0 +1,341 @@
+use super::*;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Item {
+  pub(crate) cbor_metadata: Option<PathBuf>,
+  pub(crate) destination: Option<Address<NetworkUnchecked>>,
+  pub(crate) file: PathBuf,
+  pub(crate) json_metadata: Option<PathBuf>,
+  pub(crate) name: String,
+}
+
+/// A directory of files to inscribe, sorted by name. A file named
+/// `<name>.json` or `<name>.cbor` next to a file `<name>.<extension>` is
+/// sidecar metadata for that file, and not inscribed itself.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Directory {
+  pub(crate) items: Vec<Item>,
+  pub(crate) path: PathBuf,
+}
+
+impl Directory {
+  const SIDECAR_EXTENSIONS: [&'static str; 2] = ["cbor", "json"];
+
+  pub(crate) fn load(path: &Path, destinations: Option<&Path>) -> Result<Self> {
+    let path = path
+      .canonicalize()
+      .with_context(|| format!("failed to read directory `{}`", path.display()))?;
+
+    let mut files = BTreeMap::new();
+
+    for entry in fs::read_dir(&path)
+      .with_context(|| format!("failed to read directory `{}`", path.display()))?
+    {
+      let entry = entry?;
+
+      if !entry.file_type()?.is_file() {
+        continue;
+      }
+
+      let name = entry.file_name().into_string().map_err(|name| {
+        anyhow!(
+          "file name `{}` is not valid unicode",
+          name.to_string_lossy()
+        )
+      })?;
+
+      if name.starts_with('.') {
+        continue;
+      }
+
+      files.insert(name, entry.path());
+    }
+
+    let content_stems = files
+      .keys()
+      .filter_map(|name| match Self::split(name) {
+        (_, Some(extension)) if Self::SIDECAR_EXTENSIONS.contains(&extension) => None,
+        (stem, _) => Some(stem),
+      })
+      .collect::<HashSet<&str>>();
+
+    let mut items = Vec::new();
+
+    for (name, file) in &files {
+      let (stem, extension) = Self::split(name);
+
+      if extension.is_some_and(|extension| Self::SIDECAR_EXTENSIONS.contains(&extension))
+        && content_stems.contains(stem)
+      {
+        continue;
+      }
+
+      let sidecar = |extension: &str| {
+        let sidecar = format!("{stem}.{extension}");
+        (sidecar != *name)
+          .then(|| files.get(&sidecar).cloned())
+          .flatten()
+      };
+
+      let cbor_metadata = sidecar("cbor");
+      let json_metadata = sidecar("json");
+
+      ensure!(
+        cbor_metadata.is_none() || json_metadata.is_none(),
+        "`{name}` has both CBOR and JSON metadata",
+      );
+
+      items.push(Item {
+        cbor_metadata,
+        destination: None,
+        file: file.clone(),
+        json_metadata,
+        name: name.clone(),
+      });
+    }
+
+    ensure!(
+      !items.is_empty(),
+      "directory `{}` contains no files to inscribe",
+      path.display(),
+    );
+
+    if let Some(destinations) = destinations {
+      Self::load_destinations(&mut items, destinations)?;
+    }
+
+    Ok(Self { items, path })
+  }
+
+  /// Load per-file destinations from a CSV file with lines of the form
+  /// `<FILE>,<DESTINATION>`, optionally preceded by a `file,destination`
+  /// header.
+  fn load_destinations(items: &mut [Item], path: &Path) -> Result {
+    let csv = fs::read_to_string(path)
+      .with_context(|| format!("failed to read destinations file `{}`", path.display()))?;
+
+    for (i, line) in csv.lines().enumerate() {
+      let line = line.trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      let (name, destination) = line
+        .split_once(',')
+        .map(|(name, destination)| (name.trim(), destination.trim()))
+        .ok_or_else(|| anyhow!("invalid destinations line {}: `{line}`", i + 1))?;
+
+      if i == 0 && name == "file" && destination == "destination" {
+        continue;
+      }
+
+      let item = items
+        .binary_search_by(|item| item.name.as_str().cmp(name))
+        .map(|index| &mut items[index])
+        .map_err(|_| anyhow!("destinations file references unknown file `{name}`"))?;
+
+      ensure!(
+        item.destination.is_none(),
+        "destinations file contains multiple destinations for `{name}`",
+      );
+
+      item.destination = Some(
+        destination
+          .parse()
+          .with_context(|| format!("invalid destination on destinations line {}", i + 1))?,
+      );
+    }
+
+    Ok(())
+  }
+
+  fn split(name: &str) -> (&str, Option<&str>) {
+    match name.rsplit_once('.') {
+      Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+      _ => (name, None),
+    }
+  }
+
+  /// Key under which progress inscribing this directory is recorded.
+  pub(crate) fn key(&self) -> String {
+    self.path.display().to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn names(directory: &Directory) -> Vec<&str> {
+    directory
+      .items
+      .iter()
+      .map(|item| item.name.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn files_are_sorted_by_name() {
+    let tempdir = TempDir::new().unwrap();
+
+    for name in ["b.txt", "a.txt", "c.png", ".hidden"] {
+      fs::write(tempdir.path().join(name), name).unwrap();
+    }
+
+    fs::create_dir(tempdir.path().join("subdirectory")).unwrap();
+
+    let directory = Directory::load(tempdir.path(), None).unwrap();
+
+    assert_eq!(names(&directory), ["a.txt", "b.txt", "c.png"]);
+
+    assert_eq!(
+      directory.items[0],
+      Item {
+        cbor_metadata: None,
+        destination: None,
+        file: tempdir.path().canonicalize().unwrap().join("a.txt"),
+        json_metadata: None,
+        name: "a.txt".into(),
+      }
+    );
+  }
+
+  #[test]
+  fn sidecar_metadata_is_attached() {
+    let tempdir = TempDir::new().unwrap();
+
+    for name in ["1.png", "1.json", "2.png", "2.cbor", "3.json", "4"] {
+      fs::write(tempdir.path().join(name), name).unwrap();
+    }
+
+    fs::write(tempdir.path().join("4.json"), "{}").unwrap();
+
+    let directory = Directory::load(tempdir.path(), None).unwrap();
+
+    assert_eq!(names(&directory), ["1.png", "2.png", "3.json", "4"]);
+
+    let path = tempdir.path().canonicalize().unwrap();
+
+    assert_eq!(directory.items[0].json_metadata, Some(path.join("1.json")));
+    assert_eq!(directory.items[0].cbor_metadata, None);
+    assert_eq!(directory.items[1].cbor_metadata, Some(path.join("2.cbor")));
+    assert_eq!(directory.items[2].json_metadata, None);
+    assert_eq!(directory.items[3].json_metadata, Some(path.join("4.json")));
+  }
+
+  #[test]
+  fn conflicting_sidecars_are_an_error() {
+    let tempdir = TempDir::new().unwrap();
+
+    for name in ["1.png", "1.json", "1.cbor"] {
+      fs::write(tempdir.path().join(name), name).unwrap();
+    }
+
+    assert_eq!(
+      Directory::load(tempdir.path(), None)
+        .unwrap_err()
+        .to_string(),
+      "`1.png` has both CBOR and JSON metadata",
+    );
+  }
+
+  #[test]
+  fn empty_directory_is_an_error() {
+    let tempdir = TempDir::new().unwrap();
+
+    assert_eq!(
+      Directory::load(tempdir.path(), None)
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "directory `{}` contains no files to inscribe",
+        tempdir.path().canonicalize().unwrap().display()
+      ),
+    );
+  }
+
+  #[test]
+  fn destinations_are_loaded_from_csv() {
+    let tempdir = TempDir::new().unwrap();
+
+    let files = tempdir.path().join("files");
+
+    fs::create_dir(&files).unwrap();
+
+    for name in ["a.txt", "b.txt", "c.txt"] {
+      fs::write(files.join(name), name).unwrap();
+    }
+
+    let destinations = tempdir.path().join("destinations.csv");
+
+    fs::write(
+      &destinations,
+      format!(
+        "file,destination\na.txt,{}\n\nc.txt, {}\n",
+        address(0),
+        address(1)
+      ),
+    )
+    .unwrap();
+
+    let directory = Directory::load(&files, Some(&destinations)).unwrap();
+
+    assert_eq!(
+      directory
+        .items
+        .iter()
+        .map(|item| item.destination.clone())
+        .collect::<Vec<Option<Address<NetworkUnchecked>>>>(),
+      [
+        Some(address(0).as_unchecked().clone()),
+        None,
+        Some(address(1).as_unchecked().clone()),
+      ],
+    );
+  }
+
+  #[test]
+  fn destinations_must_reference_files_in_directory() {
+    let tempdir = TempDir::new().unwrap();
+
+    let files = tempdir.path().join("files");
+
+    fs::create_dir(&files).unwrap();
+
+    fs::write(files.join("a.txt"), "a").unwrap();
+
+    let destinations = tempdir.path().join("destinations.csv");
+
+    fs::write(&destinations, format!("b.txt,{}\n", address(0))).unwrap();
+
+    assert_eq!(
+      Directory::load(&files, Some(&destinations))
+        .unwrap_err()
+        .to_string(),
+      "destinations file references unknown file `b.txt`",
+    );
+
+    fs::write(
+      &destinations,
+      format!("a.txt,{}\na.txt,{}\n", address(0), address(1)),
+    )
+    .unwrap();
+
+    assert_eq!(
+      Directory::load(&files, Some(&destinations))
+        .unwrap_err()
+        .to_string(),
+      "destinations file contains multiple destinations for `a.txt`",
+    );
+
+    fs::write(&destinations, "a.txt\n").unwrap();
+
+    assert_eq!(
+      Directory::load(&files, Some(&destinations))
+        .unwrap_err()
+        .to_string(),
+      "invalid destinations line 1: `a.txt`",
+    );
+  }
+}
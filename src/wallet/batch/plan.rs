@@ -1,10 +1,12 @@
 use super::*;
 
 pub struct Plan {
+  pub(crate) change_address: Option<Address>,
   pub(crate) commit_fee_rate: FeeRate,
   pub(crate) destinations: Vec<Address>,
   pub(crate) dry_run: bool,
   pub(crate) etching: Option<Etching>,
+  pub(crate) include_outputs: Vec<OutPoint>,
   pub(crate) inscriptions: Vec<Inscription>,
   pub(crate) mode: Mode,
   pub(crate) no_backup: bool,
@@ -20,10 +22,12 @@ pub struct Plan {
 impl Default for Plan {
   fn default() -> Self {
     Self {
+      change_address: None,
       commit_fee_rate: 1.0.try_into().unwrap(),
       destinations: Vec::new(),
       dry_run: false,
       etching: None,
+      include_outputs: Vec::new(),
       inscriptions: Vec::new(),
       mode: Mode::SharedOutput,
       no_backup: false,
@@ -46,6 +50,27 @@ impl Plan {
     utxos: &BTreeMap<OutPoint, TxOut>,
     wallet: &Wallet,
   ) -> SubcommandResult {
+    let (_commit, _reveal, output) = self.execute(locked_utxos, runic_utxos, utxos, wallet)?;
+
+    match &output.rune {
+      Some(rune_info) if !self.dry_run => Ok(Some(Box::new(
+        wallet.wait_for_maturation(rune_info.rune.rune)?,
+      ))),
+      _ => Ok(Some(Box::new(output))),
+    }
+  }
+
+  /// Create, sign, and broadcast the commit and reveal transactions, unless
+  /// this is a dry run. Returns the unsigned commit and reveal transactions
+  /// along with the output. If etching a rune, the reveal transaction is not
+  /// broadcast, and must be broadcast once the commit transaction matures.
+  pub(crate) fn execute(
+    &self,
+    locked_utxos: &BTreeSet<OutPoint>,
+    runic_utxos: BTreeSet<OutPoint>,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+    wallet: &Wallet,
+  ) -> Result<(Transaction, Transaction, Output)> {
     let Transactions {
       commit_tx,
       commit_vout,
@@ -63,21 +88,29 @@ impl Plan {
       wallet.get_change_address()?,
     )?;
 
+    wallet.check_protected_sats(&commit_tx, self.satpoint.map(|satpoint| satpoint.outpoint))?;
+
+    let commit_fee = wallet.transaction_fee(&commit_tx)?;
+
+    wallet.check_fee(&[
+      (&commit_tx, commit_fee),
+      (&reveal_tx, Amount::from_sat(total_fees) - commit_fee),
+    ])?;
+
     if self.dry_run {
-      let commit_psbt = wallet
-        .bitcoin_client()
-        .wallet_process_psbt(
-          &base64::engine::general_purpose::STANDARD
-            .encode(Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?.serialize()),
-          Some(false),
-          None,
-          None,
-        )?
-        .psbt;
+      let commit_psbt = base64::engine::general_purpose::STANDARD.encode(
+        wallet
+          .process_psbt(
+            &Psbt::from_unsigned_tx(Self::remove_witnesses(commit_tx.clone()))?,
+            false,
+            None,
+          )?
+          .serialize(),
+      );
 
       let reveal_psbt = Psbt::from_unsigned_tx(Self::remove_witnesses(reveal_tx.clone()))?;
 
-      return Ok(Some(Box::new(self.output(
+      let output = self.output(
         commit_tx.compute_txid(),
         Some(commit_psbt),
         reveal_tx.compute_txid(),
@@ -86,15 +119,14 @@ impl Plan {
         total_fees,
         self.inscriptions.clone(),
         rune,
-      ))));
+      );
+
+      return Ok((commit_tx, reveal_tx, output));
     }
 
-    let signed_commit_tx = wallet
-      .bitcoin_client()
-      .sign_raw_transaction_with_wallet(&commit_tx, None, None)?
-      .hex;
+    let signed_commit_tx = wallet.sign_raw_transaction(&commit_tx, None)?.hex;
 
-    let result = wallet.bitcoin_client().sign_raw_transaction_with_wallet(
+    let result = wallet.sign_raw_transaction(
       &reveal_tx,
       Some(
         &commit_tx
@@ -110,7 +142,6 @@ impl Plan {
           })
           .collect::<Vec<SignRawTransactionInput>>(),
       ),
-      None,
     )?;
 
     ensure!(
@@ -124,43 +155,38 @@ impl Plan {
       Self::backup_recovery_key(wallet, recovery_key_pair)?;
     }
 
-    let commit_txid = wallet
-      .bitcoin_client()
-      .send_raw_transaction(&signed_commit_tx)?;
+    let signed_commit_tx = consensus::encode::deserialize::<Transaction>(&signed_commit_tx)?;
+    let signed_reveal_tx = consensus::encode::deserialize::<Transaction>(&signed_reveal_tx)?;
+
+    let commit_txid = wallet.send_raw_transaction(&signed_commit_tx)?;
 
     if let Some(ref rune_info) = rune {
-      wallet.bitcoin_client().lock_unspent(&[OutPoint {
+      wallet.lock_unspent(&[OutPoint {
         txid: commit_txid,
         vout: commit_vout.try_into().unwrap(),
       }])?;
 
-      let commit = consensus::encode::deserialize::<Transaction>(&signed_commit_tx)?;
-      let reveal = consensus::encode::deserialize::<Transaction>(&signed_reveal_tx)?;
+      let output = self.output(
+        commit_txid,
+        None,
+        signed_reveal_tx.compute_txid(),
+        false,
+        None,
+        total_fees,
+        self.inscriptions.clone(),
+        rune.clone(),
+      );
 
       wallet.save_etching(
         &rune_info.rune.rune,
-        &commit,
-        &reveal,
-        self.output(
-          commit.compute_txid(),
-          None,
-          reveal.compute_txid(),
-          false,
-          None,
-          total_fees,
-          self.inscriptions.clone(),
-          rune.clone(),
-        ),
+        &signed_commit_tx,
+        &signed_reveal_tx,
+        output.clone(),
       )?;
 
-      Ok(Some(Box::new(
-        wallet.wait_for_maturation(rune_info.rune.rune)?,
-      )))
+      Ok((commit_tx, reveal_tx, output))
     } else {
-      let reveal = match wallet
-        .bitcoin_client()
-        .send_raw_transaction(&signed_reveal_tx)
-      {
+      let reveal = match wallet.send_raw_transaction(&signed_reveal_tx) {
         Ok(txid) => txid,
         Err(err) => {
           return Err(anyhow!(
@@ -169,7 +195,7 @@ impl Plan {
         }
       };
 
-      Ok(Some(Box::new(self.output(
+      let output = self.output(
         commit_txid,
         None,
         reveal,
@@ -178,7 +204,9 @@ impl Plan {
         total_fees,
         self.inscriptions.clone(),
         rune,
-      ))))
+      );
+
+      Ok((commit_tx, reveal_tx, output))
     }
   }
 
@@ -535,6 +563,8 @@ impl Plan {
       Target::Value(target_value),
       chain.network(),
     )
+    .change_address(self.change_address.clone())
+    .include_outputs(self.include_outputs.clone())
     .build_transaction()?;
 
     let (vout, _commit_output) = unsigned_commit_tx
@@ -678,6 +708,10 @@ impl Plan {
       wallet.chain().network(),
     );
 
+    if wallet.has_keychain() {
+      return wallet.save_recovery_key(recovery_private_key);
+    }
+
     let info = wallet
       .bitcoin_client()
       .get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
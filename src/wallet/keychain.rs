@@ -0,0 +1,331 @@
+use {
+  super::*,
+  bitcoin::{
+    bip32::Xpriv,
+    key::{Keypair, TapTweak},
+    secp256k1::{All, Message},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot, PrivateKey,
+  },
+  miniscript::descriptor::{DescriptorPublicKey, DescriptorSecretKey, DescriptorXKey, Wildcard},
+};
+
+type Ranged = (
+  miniscript::Descriptor<DescriptorPublicKey>,
+  DescriptorXKey<Xpriv>,
+);
+
+/// Private keys of a wallet using the ord backend, loaded from the output
+/// descriptors stored in the wallet database. Ranged `tr()` descriptors
+/// provide receive and change addresses, and `rawtr()` descriptors hold
+/// single keys, such as commit transaction recovery keys. Descriptors, and
+/// thus private keys, are stored unencrypted.
+pub(crate) struct Keychain {
+  change: Ranged,
+  keys: Mutex<BTreeMap<ScriptBuf, Keypair>>,
+  network: Network,
+  raw: Vec<Address>,
+  receive: Ranged,
+  secp: Secp256k1<All>,
+}
+
+impl Keychain {
+  pub(crate) fn new(descriptors: &[(String, bool)], network: Network) -> Result<Self> {
+    let secp = Secp256k1::new();
+
+    let mut change = None;
+    let mut keys = BTreeMap::new();
+    let mut raw = Vec::new();
+    let mut receive = None;
+
+    for (descriptor, internal) in descriptors {
+      if let Some(private_key) = Self::parse_raw(descriptor)? {
+        let keypair = Keypair::from_secret_key(&secp, &private_key.inner);
+
+        let script_pubkey =
+          ScriptBuf::new_p2tr_tweaked(keypair.x_only_public_key().0.dangerous_assume_tweaked());
+
+        raw.push(Address::from_script(&script_pubkey, network)?);
+        keys.insert(script_pubkey, keypair);
+
+        continue;
+      }
+
+      let (descriptor, key_map) =
+        miniscript::Descriptor::<DescriptorPublicKey>::parse_descriptor(&secp, descriptor)?;
+
+      let miniscript::Descriptor::Tr(tr) = &descriptor else {
+        bail!("unsupported descriptor type: {descriptor}");
+      };
+
+      ensure!(
+        tr.tap_tree().is_none(),
+        "unsupported descriptor with script tree: {descriptor}"
+      );
+
+      let Some(DescriptorSecretKey::XPrv(xkey)) = key_map.into_values().next() else {
+        bail!("descriptor does not contain extended private key: {descriptor}");
+      };
+
+      ensure!(
+        xkey.wildcard == Wildcard::Unhardened,
+        "descriptor is not ranged: {descriptor}"
+      );
+
+      let slot = if *internal { &mut change } else { &mut receive };
+
+      ensure!(
+        slot.is_none(),
+        "wallet contains multiple {} descriptors",
+        if *internal { "change" } else { "receive" },
+      );
+
+      *slot = Some((descriptor, xkey));
+    }
+
+    Ok(Self {
+      change: change.ok_or_else(|| anyhow!("wallet does not contain change descriptor"))?,
+      keys: Mutex::new(keys),
+      network,
+      raw,
+      receive: receive.ok_or_else(|| anyhow!("wallet does not contain receive descriptor"))?,
+      secp,
+    })
+  }
+
+  fn parse_raw(descriptor: &str) -> Result<Option<PrivateKey>> {
+    let descriptor = descriptor.split('#').next().unwrap_or_default();
+
+    let Some(key) = descriptor
+      .strip_prefix("rawtr(")
+      .and_then(|key| key.strip_suffix(')'))
+    else {
+      return Ok(None);
+    };
+
+    Ok(Some(
+      PrivateKey::from_wif(key).context("invalid private key in rawtr descriptor")?,
+    ))
+  }
+
+  /// Get address `index` of the receive or change descriptor.
+  pub(crate) fn address(&self, change: bool, index: u32) -> Result<Address> {
+    let (descriptor, _) = if change { &self.change } else { &self.receive };
+
+    Ok(
+      descriptor
+        .at_derivation_index(index)?
+        .address(self.network)?,
+    )
+  }
+
+  /// Get address `index` of the receive or change descriptor, and derive its
+  /// private key, so that outputs to it can be signed for.
+  pub(crate) fn derive(&self, change: bool, index: u32) -> Result<Address> {
+    let address = self.address(change, index)?;
+
+    let (_, xkey) = if change { &self.change } else { &self.receive };
+
+    let private_key = xkey
+      .xkey
+      .derive_priv(
+        &self.secp,
+        &xkey
+          .derivation_path
+          .child(ChildNumber::from_normal_idx(index)?),
+      )?
+      .private_key;
+
+    let keypair = Keypair::from_secret_key(&self.secp, &private_key)
+      .tap_tweak(&self.secp, None)
+      .to_inner();
+
+    self
+      .keys
+      .lock()
+      .unwrap()
+      .insert(address.script_pubkey(), keypair);
+
+    Ok(address)
+  }
+
+  pub(crate) fn raw_addresses(&self) -> &[Address] {
+    &self.raw
+  }
+
+  pub(crate) fn is_mine(&self, script_pubkey: &Script) -> bool {
+    self.keys.lock().unwrap().contains_key(script_pubkey)
+  }
+
+  /// Create a key path signature for `input`, if its output is owned by the
+  /// keychain.
+  pub(crate) fn sign(
+    &self,
+    cache: &mut SighashCache<&Transaction>,
+    input: usize,
+    prevouts: &[TxOut],
+    sighash_type: TapSighashType,
+  ) -> Result<Option<taproot::Signature>> {
+    let Some(keypair) = self
+      .keys
+      .lock()
+      .unwrap()
+      .get(&prevouts[input].script_pubkey)
+      .copied()
+    else {
+      return Ok(None);
+    };
+
+    let sighash =
+      cache.taproot_key_spend_signature_hash(input, &Prevouts::All(prevouts), sighash_type)?;
+
+    Ok(Some(taproot::Signature {
+      signature: self
+        .secp
+        .sign_schnorr_no_aux_rand(&Message::from_digest(sighash.to_byte_array()), &keypair),
+      sighash_type,
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn keychain() -> Keychain {
+    Keychain::new(
+      &Wallet::derive_descriptors(Network::Regtest, [0; 64]).unwrap(),
+      Network::Regtest,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn derived_keys_match_addresses() {
+    let secp = Secp256k1::new();
+
+    let keychain = keychain();
+
+    for change in [false, true] {
+      for index in 0..3 {
+        let address = keychain.derive(change, index).unwrap();
+
+        assert_eq!(address, keychain.address(change, index).unwrap());
+
+        let keypair = keychain.keys.lock().unwrap()[&address.script_pubkey()];
+
+        assert_eq!(
+          address.script_pubkey(),
+          ScriptBuf::new_p2tr_tweaked(keypair.x_only_public_key().0.dangerous_assume_tweaked()),
+        );
+
+        assert!(secp
+          .verify_schnorr(
+            &secp.sign_schnorr_no_aux_rand(&Message::from_digest([1; 32]), &keypair),
+            &Message::from_digest([1; 32]),
+            &keypair.x_only_public_key().0,
+          )
+          .is_ok());
+      }
+    }
+
+    assert_ne!(
+      keychain.address(false, 0).unwrap(),
+      keychain.address(true, 0).unwrap(),
+    );
+  }
+
+  #[test]
+  fn raw_descriptors_are_loaded() {
+    let private_key = PrivateKey::from_slice(&[1; 32], Network::Regtest).unwrap();
+
+    let mut descriptors = Wallet::derive_descriptors(Network::Regtest, [0; 64]).unwrap();
+
+    descriptors.push((format!("rawtr({})#checksum", private_key.to_wif()), false));
+
+    let keychain = Keychain::new(&descriptors, Network::Regtest).unwrap();
+
+    assert_eq!(keychain.raw_addresses().len(), 1);
+
+    assert!(keychain.is_mine(&keychain.raw_addresses()[0].script_pubkey()));
+  }
+
+  #[test]
+  fn missing_descriptor_is_an_error() {
+    let descriptors = Wallet::derive_descriptors(Network::Regtest, [0; 64]).unwrap();
+
+    assert_eq!(
+      Keychain::new(&descriptors[..1], Network::Regtest)
+        .err()
+        .unwrap()
+        .to_string(),
+      "wallet does not contain change descriptor",
+    );
+  }
+
+  #[test]
+  fn signatures_are_valid() {
+    let secp = Secp256k1::new();
+
+    let keychain = keychain();
+
+    let address = keychain.derive(false, 0).unwrap();
+
+    let prevouts = [
+      TxOut {
+        script_pubkey: address.script_pubkey(),
+        value: Amount::from_sat(10_000),
+      },
+      TxOut {
+        script_pubkey: ScriptBuf::new_op_return([]),
+        value: Amount::from_sat(10_000),
+      },
+    ];
+
+    let transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: (0..2)
+        .map(|vout| TxIn {
+          previous_output: OutPoint {
+            txid: Txid::all_zeros(),
+            vout,
+          },
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: Vec::new(),
+    };
+
+    let mut cache = SighashCache::new(&transaction);
+
+    let signature = keychain
+      .sign(&mut cache, 0, &prevouts, TapSighashType::Default)
+      .unwrap()
+      .unwrap();
+
+    let sighash = cache
+      .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), TapSighashType::Default)
+      .unwrap();
+
+    secp
+      .verify_schnorr(
+        &signature.signature,
+        &Message::from_digest(sighash.to_byte_array()),
+        &address
+          .script_pubkey()
+          .as_bytes()
+          .get(2..)
+          .map(|bytes| bitcoin::XOnlyPublicKey::from_slice(bytes).unwrap())
+          .unwrap(),
+      )
+      .unwrap();
+
+    assert!(keychain
+      .sign(&mut cache, 1, &prevouts, TapSighashType::Default)
+      .unwrap()
+      .is_none());
+  }
+}
@@ -0,0 +1,94 @@
+/// Split an inscribed output of `value` sats with inscriptions at `offsets`
+/// into output values, one for each inscription. Each inscription output
+/// holds at most `postage` sats, and sats between inscriptions are placed
+/// into their own outputs. Gaps and inscriptions closer together than
+/// `dust_value` are merged.
+pub(crate) fn split(value: u64, offsets: &[u64], postage: u64, dust_value: u64) -> Vec<u64> {
+  let mut offsets = offsets.to_vec();
+  offsets.sort();
+  offsets.dedup();
+
+  let mut values = Vec::new();
+  let mut position = 0;
+  let mut offsets = offsets.into_iter().peekable();
+
+  while let Some(offset) = offsets.next() {
+    let gap = offset - position;
+
+    let carry = if values.is_empty() && gap < dust_value {
+      gap
+    } else {
+      if gap > 0 {
+        values.push(gap);
+      }
+      0
+    };
+
+    let mut last = offset;
+
+    while let Some(next) = offsets.next_if(|next| next - last < dust_value) {
+      last = next;
+    }
+
+    let end = match offsets.peek() {
+      Some(next) => (last + postage).max(last + dust_value).min(*next),
+      None => (last + postage).max(last + dust_value).min(value),
+    };
+
+    values.push(carry + end - offset);
+
+    position = end;
+  }
+
+  if value > position || values.is_empty() {
+    values.push(value - position);
+  }
+
+  let mut merged = Vec::<u64>::new();
+
+  for value in values {
+    match merged.last_mut() {
+      Some(last) if value < dust_value => *last += value,
+      _ => merged.push(value),
+    }
+  }
+
+  merged
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn split_single_inscription_at_start() {
+    assert_eq!(split(10_000, &[0], 10_000, 330), [10_000]);
+    assert_eq!(split(50_000, &[0], 10_000, 330), [10_000, 40_000]);
+  }
+
+  #[test]
+  fn split_leading_gap() {
+    assert_eq!(split(20_000, &[5_000], 10_000, 330), [5_000, 10_000, 5_000]);
+    assert_eq!(split(20_000, &[100], 10_000, 330), [10_100, 9_900]);
+  }
+
+  #[test]
+  fn split_multiple_inscriptions() {
+    assert_eq!(
+      split(30_000, &[0, 10_000, 20_000], 10_000, 330),
+      [10_000, 10_000, 10_000]
+    );
+    assert_eq!(
+      split(30_000, &[20_000, 0], 1_000, 330),
+      [1_000, 19_000, 1_000, 9_000]
+    );
+  }
+
+  #[test]
+  fn split_merges_dust() {
+    assert_eq!(split(30_000, &[0, 100], 10_000, 330), [10_100, 19_900]);
+    assert_eq!(split(10_100, &[0], 10_000, 330), [10_100]);
+    assert_eq!(split(20_000, &[0, 10_100], 10_000, 330), [10_100, 9_900]);
+    assert_eq!(split(20_000, &[19_990], 10_000, 330), [20_000]);
+  }
+}
@@ -0,0 +1,197 @@
+use {super::*, ordinals::COIN_VALUE};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Protection {
+  pub(crate) charms: Vec<Charm>,
+  pub(crate) rarity: Option<Rarity>,
+  pub(crate) sats: BTreeSet<Sat>,
+}
+
+impl Protection {
+  pub(crate) const CHARMS: [Charm; 8] = [
+    Charm::Coin,
+    Charm::Uncommon,
+    Charm::Rare,
+    Charm::Epic,
+    Charm::Legendary,
+    Charm::Mythic,
+    Charm::Nineball,
+    Charm::Palindrome,
+  ];
+
+  pub(crate) fn new(
+    rarity: Option<Rarity>,
+    charms: Vec<Charm>,
+    sats: BTreeSet<Sat>,
+  ) -> Result<Self> {
+    for charm in &charms {
+      ensure!(
+        Self::CHARMS.contains(charm),
+        "sats with charm `{charm}` cannot be protected",
+      );
+    }
+
+    Ok(Self {
+      charms,
+      rarity,
+      sats,
+    })
+  }
+
+  pub(crate) fn is_empty(&self) -> bool {
+    self.charms.is_empty() && self.rarity.is_none() && self.sats.is_empty()
+  }
+
+  /// Returns the first protected sat in the range `[start, end)`.
+  pub(crate) fn first(&self, start: u64, end: u64) -> Option<Sat> {
+    if start >= end {
+      return None;
+    }
+
+    // sats rarer than common are always the first sat of a block subsidy, so
+    // they can only be found at the start of a range
+    let rarity = Sat(start).rarity();
+
+    let mut candidates = Vec::new();
+
+    if self
+      .rarity
+      .map(|minimum| rarity >= minimum && rarity > Rarity::Common)
+      .unwrap_or_default()
+    {
+      candidates.push(start);
+    }
+
+    for charm in &self.charms {
+      let candidate = match charm {
+        Charm::Coin => start.checked_next_multiple_of(COIN_VALUE),
+        Charm::Nineball => Some(start.max(50 * COIN_VALUE * 9)).filter(|sat| Sat(*sat).nineball()),
+        Charm::Palindrome => Some(Self::next_palindrome(start)),
+        Charm::Uncommon | Charm::Rare | Charm::Epic | Charm::Legendary | Charm::Mythic => {
+          charm.is_set(Sat(start).charms()).then_some(start)
+        }
+        _ => None,
+      };
+
+      candidates.extend(candidate);
+    }
+
+    candidates.extend(self.sats.range(Sat(start)..).next().map(|sat| sat.n()));
+
+    candidates
+      .into_iter()
+      .filter(|sat| *sat < end)
+      .min()
+      .map(Sat)
+  }
+
+  fn next_palindrome(n: u64) -> u64 {
+    let digits = n.to_string();
+
+    let mirror = |half: &str| -> u64 {
+      let mut palindrome = half.to_string();
+      palindrome.extend(half[..digits.len() / 2].chars().rev());
+      palindrome.parse().unwrap()
+    };
+
+    let half = &digits[..digits.len().div_ceil(2)];
+
+    let palindrome = mirror(half);
+
+    if palindrome >= n {
+      return palindrome;
+    }
+
+    // the half cannot be all nines, since its mirror would then be at least n
+    mirror(&(half.parse::<u64>().unwrap() + 1).to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty_policy_protects_nothing() {
+    assert_eq!(Protection::default().first(0, Sat::SUPPLY), None);
+  }
+
+  #[test]
+  fn rarity() {
+    let protection = Protection::new(Some(Rarity::Uncommon), Vec::new(), BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(1, 50 * COIN_VALUE), None);
+    assert_eq!(
+      protection.first(50 * COIN_VALUE, 100 * COIN_VALUE),
+      Some(Sat(50 * COIN_VALUE))
+    );
+
+    let protection = Protection::new(Some(Rarity::Rare), Vec::new(), BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(50 * COIN_VALUE, 100 * COIN_VALUE), None);
+    assert_eq!(protection.first(0, 1), Some(Sat(0)));
+
+    let protection = Protection::new(Some(Rarity::Common), Vec::new(), BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(1, 50 * COIN_VALUE), None);
+  }
+
+  #[test]
+  fn charms() {
+    let protection = Protection::new(None, vec![Charm::Coin], BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(1, COIN_VALUE), None);
+    assert_eq!(protection.first(1, COIN_VALUE + 1), Some(Sat(COIN_VALUE)));
+
+    let protection = Protection::new(None, vec![Charm::Nineball], BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(0, 450 * COIN_VALUE), None);
+    assert_eq!(
+      protection.first(400 * COIN_VALUE, 460 * COIN_VALUE),
+      Some(Sat(450 * COIN_VALUE))
+    );
+    assert_eq!(protection.first(500 * COIN_VALUE, 600 * COIN_VALUE), None);
+
+    let protection = Protection::new(None, vec![Charm::Palindrome], BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(12, 22), None);
+    assert_eq!(protection.first(12, 23), Some(Sat(22)));
+    assert_eq!(protection.first(123, 200), Some(Sat(131)));
+    assert_eq!(protection.first(1299, 2000), Some(Sat(1331)));
+    assert_eq!(protection.first(999, 1000), Some(Sat(999)));
+
+    let protection = Protection::new(None, vec![Charm::Rare], BTreeSet::new()).unwrap();
+    assert_eq!(protection.first(50 * COIN_VALUE, 100 * COIN_VALUE), None);
+    assert_eq!(protection.first(0, 1), None);
+    assert_eq!(
+      protection.first(2016 * 50 * COIN_VALUE, 2017 * 50 * COIN_VALUE),
+      Some(Sat(2016 * 50 * COIN_VALUE))
+    );
+  }
+
+  #[test]
+  fn sats() {
+    let protection =
+      Protection::new(None, Vec::new(), [Sat(10), Sat(20)].into_iter().collect()).unwrap();
+    assert_eq!(protection.first(0, 10), None);
+    assert_eq!(protection.first(0, 11), Some(Sat(10)));
+    assert_eq!(protection.first(11, 21), Some(Sat(20)));
+    assert_eq!(protection.first(21, 100), None);
+  }
+
+  #[test]
+  fn first_protected_sat_is_returned() {
+    let protection = Protection::new(
+      None,
+      vec![Charm::Coin, Charm::Palindrome],
+      [Sat(5)].into_iter().collect(),
+    )
+    .unwrap();
+    assert_eq!(protection.first(3, 100), Some(Sat(3)));
+    assert_eq!(protection.first(12, 100), Some(Sat(22)));
+  }
+
+  #[test]
+  fn inscription_charms_cannot_be_protected() {
+    assert_eq!(
+      Protection::new(None, vec![Charm::Cursed], BTreeSet::new())
+        .unwrap_err()
+        .to_string(),
+      "sats with charm `cursed` cannot be protected",
+    );
+  }
+}
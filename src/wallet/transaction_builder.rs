@@ -43,6 +43,10 @@ pub enum Error {
     output_value: Amount,
     dust_value: Amount,
   },
+  IncludedOutputInscribed(OutPoint),
+  IncludedOutputLocked(OutPoint),
+  IncludedOutputNotInWallet(OutPoint),
+  IncludedOutputRunic(OutPoint),
   InvalidAddress(bitcoin::address::FromScriptError),
   NotEnoughCardinalUtxos,
   NotInWallet(SatPoint),
@@ -69,6 +73,10 @@ impl Display for Error {
         output_value,
         dust_value,
       } => write!(f, "output value is below dust value: {output_value} < {dust_value}"),
+      Error::IncludedOutputInscribed(outpoint) => write!(f, "included output {outpoint} contains inscriptions"),
+      Error::IncludedOutputLocked(outpoint) => write!(f, "included output {outpoint} is locked"),
+      Error::IncludedOutputNotInWallet(outpoint) => write!(f, "included output {outpoint} not in wallet"),
+      Error::IncludedOutputRunic(outpoint) => write!(f, "included output {outpoint} contains runes"),
       Error::InvalidAddress(source) => write!(f, "invalid address: {source}", ),
       Error::NotInWallet(outgoing_satpoint) => write!(f, "outgoing satpoint {outgoing_satpoint} not in wallet"),
       Error::OutOfRange(outgoing_satpoint, maximum) => write!(f, "outgoing satpoint {outgoing_satpoint} offset higher than maximum {maximum}"),
@@ -102,8 +110,10 @@ impl From<bitcoin::address::FromScriptError> for Error {
 #[derive(Debug, PartialEq)]
 pub struct TransactionBuilder {
   amounts: BTreeMap<OutPoint, TxOut>,
+  change_address: Option<Address>,
   change_addresses: BTreeSet<Address>,
   fee_rate: FeeRate,
+  included: Vec<OutPoint>,
   inputs: Vec<OutPoint>,
   inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
   locked_utxos: BTreeSet<OutPoint>,
@@ -122,7 +132,7 @@ type Result<T> = std::result::Result<T, Error>;
 impl TransactionBuilder {
   const ADDITIONAL_INPUT_VBYTES: usize = 57;
   const ADDITIONAL_OUTPUT_VBYTES: usize = 43;
-  const SCHNORR_SIGNATURE_SIZE: usize = 64;
+  pub(crate) const SCHNORR_SIGNATURE_SIZE: usize = 64;
   pub(crate) const MAX_POSTAGE: Amount = Amount::from_sat(2 * 10_000);
 
   pub fn new(
@@ -140,8 +150,10 @@ impl TransactionBuilder {
     Self {
       utxos: amounts.keys().cloned().collect(),
       amounts,
+      change_address: None,
       change_addresses: change.iter().cloned().collect(),
       fee_rate,
+      included: Vec::new(),
       inputs: Vec::new(),
       inscriptions,
       locked_utxos,
@@ -155,6 +167,58 @@ impl TransactionBuilder {
     }
   }
 
+  /// Builder for `build_fee_bump`, which pays for an existing transaction
+  /// instead of sending an outgoing sat, and so has no outgoing sat,
+  /// recipient, or target.
+  pub(crate) fn fee_bump(
+    inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
+    amounts: BTreeMap<OutPoint, TxOut>,
+    locked_utxos: BTreeSet<OutPoint>,
+    runic_utxos: BTreeSet<OutPoint>,
+    change: Address,
+    fee_rate: FeeRate,
+    network: Network,
+  ) -> Self {
+    Self {
+      utxos: amounts.keys().cloned().collect(),
+      amounts,
+      change_address: None,
+      change_addresses: [change.clone()].into(),
+      fee_rate,
+      included: Vec::new(),
+      inputs: Vec::new(),
+      inscriptions,
+      locked_utxos,
+      outgoing: SatPoint {
+        outpoint: OutPoint::null(),
+        offset: 0,
+      },
+      outputs: Vec::new(),
+      recipient: ScriptBuf::new(),
+      runic_utxos,
+      target: Target::Postage,
+      unused_change_addresses: vec![change],
+      network,
+    }
+  }
+
+  /// Send stripped excess value to `address` instead of a wallet change
+  /// address. Alignment padding still goes to wallet change addresses.
+  pub(crate) fn change_address(mut self, address: Option<Address>) -> Self {
+    if let Some(address) = &address {
+      self.change_addresses.insert(address.clone());
+    }
+    self.change_address = address;
+    self
+  }
+
+  /// Spend `outpoints` in addition to the outgoing output and any cardinal
+  /// outputs selected to pay for the transaction.
+  pub(crate) fn include_outputs(mut self, outpoints: Vec<OutPoint>) -> Self {
+    self.included = outpoints;
+    self
+  }
+
   pub fn build_transaction(self) -> Result<Transaction> {
     if self.change_addresses.len() < 2 {
       return Err(Error::DuplicateAddress(
@@ -185,12 +249,124 @@ impl TransactionBuilder {
       .select_outgoing()?
       .align_outgoing()
       .pad_alignment_output()?
+      .add_included()?
       .add_value()?
       .strip_value()
       .deduct_fee()
       .build()
   }
 
+  /// Add cardinal inputs to `transaction` until `input_value`, the value of
+  /// its inputs, covers its outputs and a fee of `fee(vbytes)`, and send the
+  /// remainder to output `change`, or to a new change output if `None`. Other
+  /// inputs and outputs are left as they are, so this can build replacements
+  /// and children, whose fees depend on the transactions they replace or pay
+  /// for. Returns the funded transaction and its fee.
+  pub(crate) fn build_fee_bump(
+    mut self,
+    transaction: &Transaction,
+    mut input_value: Amount,
+    change: Option<usize>,
+    fee: impl Fn(usize) -> Amount,
+  ) -> Result<(Transaction, Amount)> {
+    self.inputs = transaction
+      .input
+      .iter()
+      .map(|txin| txin.previous_output)
+      .collect();
+
+    for input in &self.inputs {
+      self.utxos.remove(input);
+    }
+
+    self.outputs = transaction.output.clone();
+
+    let change = match change {
+      Some(change) => change,
+      None => {
+        self.outputs.push(TxOut {
+          script_pubkey: self
+            .unused_change_addresses
+            .pop()
+            .expect("not enough change addresses")
+            .script_pubkey(),
+          value: Amount::ZERO,
+        });
+        self.outputs.len() - 1
+      }
+    };
+
+    let outgoing = self
+      .outputs
+      .iter()
+      .enumerate()
+      .filter(|(vout, _)| *vout != change)
+      .map(|(_, tx_out)| tx_out.value)
+      .sum::<Amount>();
+
+    let dust_value = self.outputs[change].script_pubkey.minimal_non_dust();
+
+    let fee = loop {
+      let fee = fee(self.estimate_vbytes());
+
+      let needed = outgoing
+        .checked_add(fee)
+        .and_then(|needed| needed.checked_add(dust_value))
+        .ok_or(Error::ValueOverflow)?;
+
+      if input_value >= needed {
+        self.outputs[change].value = input_value - outgoing - fee;
+        break fee;
+      }
+
+      let additional_fee = self.fee_rate.fee(Self::ADDITIONAL_INPUT_VBYTES);
+
+      let (utxo, value) = self.select_cardinal_utxo(
+        (needed - input_value)
+          .checked_add(additional_fee)
+          .ok_or(Error::ValueOverflow)?
+          .to_sat(),
+        false,
+      )?;
+
+      tprintln!("added {value} sat input to pay fee");
+
+      self.inputs.push(utxo);
+
+      input_value = input_value.checked_add(value).ok_or(Error::ValueOverflow)?;
+    };
+
+    let input = transaction
+      .input
+      .iter()
+      .map(|txin| TxIn {
+        witness: Witness::new(),
+        script_sig: ScriptBuf::new(),
+        ..txin.clone()
+      })
+      .chain(
+        self.inputs[transaction.input.len()..]
+          .iter()
+          .map(|outpoint| TxIn {
+            previous_output: *outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+          }),
+      )
+      .collect();
+
+    Ok((
+      Transaction {
+        version: transaction.version,
+        lock_time: transaction.lock_time,
+        input,
+        output: self.outputs,
+      },
+      fee,
+    ))
+  }
+
   fn select_outgoing(mut self) -> Result<Self> {
     let dust_limit = self
       .unused_change_addresses
@@ -303,6 +479,44 @@ impl TransactionBuilder {
     Ok(self)
   }
 
+  fn add_included(mut self) -> Result<Self> {
+    for outpoint in self.included.clone() {
+      if self.inputs.contains(&outpoint) {
+        continue;
+      }
+
+      let value = self
+        .amounts
+        .get(&outpoint)
+        .ok_or(Error::IncludedOutputNotInWallet(outpoint))?
+        .value;
+
+      if self
+        .inscriptions
+        .keys()
+        .any(|satpoint| satpoint.outpoint == outpoint)
+      {
+        return Err(Error::IncludedOutputInscribed(outpoint));
+      }
+
+      if self.runic_utxos.contains(&outpoint) {
+        return Err(Error::IncludedOutputRunic(outpoint));
+      }
+
+      if self.locked_utxos.contains(&outpoint) {
+        return Err(Error::IncludedOutputLocked(outpoint));
+      }
+
+      self.utxos.remove(&outpoint);
+      self.inputs.push(outpoint);
+      self.outputs.last_mut().unwrap().value += value;
+
+      tprintln!("included {value} sat input {outpoint}");
+    }
+
+    Ok(self)
+  }
+
   fn add_value(mut self) -> Result<Self> {
     let estimated_fee = self.estimate_fee();
 
@@ -375,26 +589,28 @@ impl TransactionBuilder {
         Target::Value(value) => (value, value),
       };
 
+      let change = self
+        .change_address
+        .clone()
+        .unwrap_or_else(|| self.unused_change_addresses.last().unwrap().clone());
+
       if excess > max
         && value.checked_sub(target).unwrap()
-          > self
-            .unused_change_addresses
-            .last()
-            .unwrap()
-            .script_pubkey()
-            .minimal_non_dust()
+          > change.script_pubkey().minimal_non_dust()
             + self
               .fee_rate
               .fee(self.estimate_vbytes() + Self::ADDITIONAL_OUTPUT_VBYTES)
       {
         tprintln!("stripped {} sats", (value - target).to_sat());
         self.outputs.last_mut().expect("no outputs found").value = target;
-        self.outputs.push(TxOut {
-          script_pubkey: self
+        if self.change_address.is_none() {
+          self
             .unused_change_addresses
             .pop()
-            .unwrap_or_else(|| panic!("not enough change addresses"))
-            .script_pubkey(),
+            .unwrap_or_else(|| panic!("not enough change addresses"));
+        }
+        self.outputs.push(TxOut {
+          script_pubkey: change.script_pubkey(),
           value: value - target,
         });
       }
@@ -503,6 +719,14 @@ impl TransactionBuilder {
       "invariant: inputs spend outgoing sat"
     );
 
+    assert!(
+      self.included.iter().all(|outpoint| transaction
+        .input
+        .iter()
+        .any(|tx_in| tx_in.previous_output == *outpoint)),
+      "invariant: included outputs are spent"
+    );
+
     let mut sat_offset = 0;
     let mut found = false;
     for tx_in in &transaction.input {
@@ -779,7 +1003,9 @@ mod tests {
       runic_utxos: BTreeSet::new(),
       recipient: recipient(),
       unused_change_addresses: vec![change(0), change(1)],
+      change_address: None,
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      included: Vec::new(),
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         TxOut {
@@ -1352,7 +1578,9 @@ mod tests {
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
       unused_change_addresses: vec![change(0), change(1)],
+      change_address: None,
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      included: Vec::new(),
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         TxOut {
@@ -1393,7 +1621,9 @@ mod tests {
       inscriptions: BTreeMap::new(),
       recipient: recipient(),
       unused_change_addresses: vec![change(0), change(1)],
+      change_address: None,
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      included: Vec::new(),
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         TxOut {
@@ -2196,4 +2426,328 @@ mod tests {
       outpoint(2),
     );
   }
+
+  #[test]
+  fn included_outputs_are_spent() {
+    let utxos = vec![
+      (outpoint(1), tx_out(10_000, address(0))),
+      (outpoint(2), tx_out(20_000, address(0))),
+      (outpoint(3), tx_out(5_000, address(0))),
+    ];
+
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let transaction = TransactionBuilder::new(
+      satpoint(1, 0),
+      BTreeMap::new(),
+      utxos.into_iter().collect(),
+      BTreeSet::new(),
+      BTreeSet::new(),
+      recipient(),
+      [change(0), change(1)],
+      fee_rate,
+      Target::Postage,
+      Network::Testnet,
+    )
+    .include_outputs(vec![outpoint(3)])
+    .build_transaction()
+    .unwrap();
+
+    let mut signed = transaction.clone();
+    for input in &mut signed.input {
+      input.witness = Witness::from_slice(&[&[0; TransactionBuilder::SCHNORR_SIGNATURE_SIZE]]);
+    }
+    let fee = fee_rate.fee(signed.vsize());
+
+    pretty_assert_eq!(
+      transaction,
+      Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(3))],
+        output: vec![tx_out(15_000 - fee.to_sat(), recipient_address())],
+      }
+    )
+  }
+
+  #[test]
+  fn included_outputs_may_not_contain_inscriptions() {
+    let utxos = vec![
+      (outpoint(1), tx_out(10_000, address(0))),
+      (outpoint(2), tx_out(10_000, address(0))),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::from([(satpoint(2, 0), vec![inscription_id(1)])]),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Postage,
+        Network::Testnet,
+      )
+      .include_outputs(vec![outpoint(2)])
+      .build_transaction(),
+      Err(Error::IncludedOutputInscribed(outpoint(2))),
+    )
+  }
+
+  #[test]
+  fn included_outputs_may_not_contain_runes() {
+    let utxos = vec![
+      (outpoint(1), tx_out(10_000, address(0))),
+      (outpoint(2), tx_out(10_000, address(0))),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        [outpoint(2)].into_iter().collect(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Postage,
+        Network::Testnet,
+      )
+      .include_outputs(vec![outpoint(2)])
+      .build_transaction(),
+      Err(Error::IncludedOutputRunic(outpoint(2))),
+    )
+  }
+
+  #[test]
+  fn included_outputs_must_be_in_wallet() {
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        [(outpoint(1), tx_out(10_000, address(0)))]
+          .into_iter()
+          .collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Postage,
+        Network::Testnet,
+      )
+      .include_outputs(vec![outpoint(2)])
+      .build_transaction(),
+      Err(Error::IncludedOutputNotInWallet(outpoint(2))),
+    )
+  }
+
+  #[test]
+  fn excess_value_is_sent_to_change_address() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let transaction = TransactionBuilder::new(
+      satpoint(1, 0),
+      BTreeMap::new(),
+      [(outpoint(1), tx_out(1_000_000, address(0)))]
+        .into_iter()
+        .collect(),
+      BTreeSet::new(),
+      BTreeSet::new(),
+      recipient(),
+      [change(0), change(1)],
+      fee_rate,
+      Target::Postage,
+      Network::Testnet,
+    )
+    .change_address(Some(change(2)))
+    .build_transaction()
+    .unwrap();
+
+    let fee =
+      fee_rate.fee(transaction.vsize() + TransactionBuilder::SCHNORR_SIGNATURE_SIZE / 4 + 1);
+
+    pretty_assert_eq!(
+      transaction,
+      Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(TARGET_POSTAGE.to_sat(), recipient_address()),
+          tx_out(
+            1_000_000 - TARGET_POSTAGE.to_sat() - fee.to_sat(),
+            change(2)
+          )
+        ],
+      }
+    )
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: included outputs are spent")]
+  fn invariant_included_outputs_are_spent() {
+    let utxos = vec![
+      (outpoint(1), tx_out(10_000, address(0))),
+      (outpoint(2), tx_out(10_000, address(0))),
+    ];
+
+    let mut builder = TransactionBuilder::new(
+      satpoint(1, 0),
+      BTreeMap::new(),
+      utxos.into_iter().collect(),
+      BTreeSet::new(),
+      BTreeSet::new(),
+      recipient(),
+      [change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      Target::Postage,
+      Network::Testnet,
+    )
+    .select_outgoing()
+    .unwrap()
+    .align_outgoing()
+    .add_value()
+    .unwrap()
+    .strip_value()
+    .deduct_fee();
+
+    builder.included = vec![outpoint(2)];
+
+    builder.build().unwrap();
+  }
+
+  fn fee_bump_builder(amounts: Vec<(OutPoint, TxOut)>) -> TransactionBuilder {
+    TransactionBuilder::fee_bump(
+      [(satpoint(2, 0), vec![inscription_id(1)])].into(),
+      amounts.into_iter().collect(),
+      BTreeSet::new(),
+      BTreeSet::new(),
+      change(0),
+      FeeRate::try_from(1.0).unwrap(),
+      Network::Testnet,
+    )
+  }
+
+  #[test]
+  fn fee_bump_pays_from_existing_change_output() {
+    let transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[&[0; 64]]),
+      }],
+      output: vec![
+        TxOut {
+          script_pubkey: recipient(),
+          value: Amount::from_sat(10_000),
+        },
+        tx_out(9_000, change(1)),
+      ],
+    };
+
+    let (replacement, fee) = fee_bump_builder(vec![(outpoint(3), tx_out(20_000, address(0)))])
+      .build_fee_bump(&transaction, Amount::from_sat(20_000), Some(1), |_| {
+        Amount::from_sat(2_000)
+      })
+      .unwrap();
+
+    assert_eq!(fee, Amount::from_sat(2_000));
+    pretty_assert_eq!(
+      replacement,
+      Transaction {
+        input: vec![TxIn {
+          witness: Witness::new(),
+          ..transaction.input[0].clone()
+        }],
+        output: vec![transaction.output[0].clone(), tx_out(8_000, change(1))],
+        ..transaction
+      }
+    );
+  }
+
+  #[test]
+  fn fee_bump_adds_cardinal_inputs_and_change_output() {
+    let transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        script_pubkey: recipient(),
+        value: Amount::from_sat(10_000),
+      }],
+    };
+
+    let (child, fee) = fee_bump_builder(vec![
+      (outpoint(1), tx_out(10_000, address(0))),
+      (outpoint(2), tx_out(50_000, address(0))),
+      (outpoint(3), tx_out(20_000, address(0))),
+    ])
+    .build_fee_bump(&transaction, Amount::from_sat(10_000), None, |vbytes| {
+      Amount::from_sat(vbytes.try_into().unwrap())
+    })
+    .unwrap();
+
+    assert_eq!(
+      child
+        .input
+        .iter()
+        .map(|txin| txin.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(1), outpoint(3)],
+    );
+
+    assert_eq!(
+      fee,
+      Amount::from_sat(
+        TransactionBuilder::estimate_vbytes_with(2, &child.output)
+          .try_into()
+          .unwrap()
+      ),
+    );
+
+    pretty_assert_eq!(
+      child.output,
+      [
+        transaction.output[0].clone(),
+        tx_out(20_000 - fee.to_sat(), change(0)),
+      ],
+    );
+  }
+
+  #[test]
+  fn fee_bump_fails_without_enough_cardinal_utxos() {
+    let transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: Vec::new(),
+    };
+
+    assert_eq!(
+      fee_bump_builder(vec![(outpoint(2), tx_out(50_000, address(0)))]).build_fee_bump(
+        &transaction,
+        Amount::from_sat(100),
+        None,
+        |_| Amount::from_sat(1_000),
+      ),
+      Err(Error::NotEnoughCardinalUtxos),
+    );
+  }
 }
@@ -4,17 +4,25 @@ use super::*;
 pub(crate) struct WalletConstructor {
   ord_client: reqwest::blocking::Client,
   name: String,
+  max_fee: Option<Amount>,
+  max_fee_rate: Option<FeeRate>,
   no_sync: bool,
+  protection: Protection,
   rpc_url: Url,
   settings: Settings,
 }
 
 impl WalletConstructor {
+  const GAP_LIMIT: u64 = 20;
+
   pub(crate) fn construct(
     name: String,
     no_sync: bool,
     settings: Settings,
     rpc_url: Url,
+    protection: Protection,
+    max_fee: Option<Amount>,
+    max_fee_rate: Option<FeeRate>,
   ) -> Result<Wallet> {
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -36,8 +44,11 @@ impl WalletConstructor {
         .timeout(None)
         .default_headers(headers.clone())
         .build()?,
+      max_fee,
+      max_fee_rate,
       name,
       no_sync,
+      protection,
       rpc_url,
       settings,
     }
@@ -47,7 +58,20 @@ impl WalletConstructor {
   pub(crate) fn build(self) -> Result<Wallet> {
     let database = Wallet::open_database(&self.name, &self.settings)?;
 
-    let bitcoin_client = {
+    let descriptors = Wallet::load_descriptors(&database)?;
+
+    let keychain = if descriptors.is_empty() {
+      None
+    } else {
+      Some(Keychain::new(
+        &descriptors,
+        self.settings.chain().network(),
+      )?)
+    };
+
+    let bitcoin_client = if keychain.is_some() {
+      Wallet::check_version(self.settings.bitcoin_rpc_client(None)?)?
+    } else {
       let client =
         Wallet::check_version(self.settings.bitcoin_rpc_client(Some(self.name.clone()))?)?;
 
@@ -87,9 +111,25 @@ impl WalletConstructor {
       }
     }
 
-    let mut utxos = Self::get_utxos(&bitcoin_client)?;
-    let locked_utxos = Self::get_locked_utxos(&bitcoin_client)?;
-    utxos.extend(locked_utxos.clone());
+    let status = self.get_server_status()?;
+
+    let (utxos, locked_utxos) = match &keychain {
+      Some(keychain) => {
+        ensure!(
+          status.address_index,
+          "wallets using the ord backend require index created with `--index-addresses` flag"
+        );
+
+        (self.scan(&database, keychain)?, BTreeMap::new())
+      }
+      None => {
+        let mut utxos = Self::get_utxos(&bitcoin_client)?;
+        let locked_utxos = Self::get_locked_utxos(&bitcoin_client)?;
+        utxos.extend(locked_utxos.clone());
+
+        (utxos, locked_utxos)
+      }
+    };
 
     let output_info = self.get_output_info(utxos.clone().into_keys().collect())?;
 
@@ -100,23 +140,162 @@ impl WalletConstructor {
 
     let (inscriptions, inscription_info) = self.get_inscriptions(&inscriptions)?;
 
-    let status = self.get_server_status()?;
-
-    Ok(Wallet {
+    let mut wallet = Wallet {
       bitcoin_client,
       database,
+      has_address_index: status.address_index,
       has_inscription_index: status.inscription_index,
       has_rune_index: status.rune_index,
       has_sat_index: status.sat_index,
       inscription_info,
       inscriptions,
+      keychain,
       locked_utxos,
       ord_client: self.ord_client,
+      max_fee: self.max_fee,
+      max_fee_rate: self.max_fee_rate,
+      name: self.name,
       output_info,
+      protection: self.protection,
       rpc_url: self.rpc_url,
       settings: self.settings,
       utxos,
-    })
+    };
+
+    if wallet.keychain.is_some() {
+      Self::apply_unconfirmed_transactions(&mut wallet)?;
+      wallet.locked_utxos = Self::get_pending_commit_outputs(&wallet.database, &wallet.utxos)?;
+    }
+
+    Ok(wallet)
+  }
+
+  /// Apply transactions broadcast by a wallet using the ord backend which are
+  /// still in the mempool, parents first, and forget those which have since
+  /// been confirmed or dropped from the mempool.
+  fn apply_unconfirmed_transactions(wallet: &mut Wallet) -> Result {
+    let mut unconfirmed = Vec::new();
+
+    for transaction in Wallet::load_transactions(&wallet.database)? {
+      let txid = transaction.compute_txid();
+
+      match wallet.bitcoin_client.get_mempool_entry(&txid) {
+        Ok(_) => unconfirmed.push(transaction),
+        Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(_))) => {
+          Wallet::remove_transaction(&wallet.database, txid)?
+        }
+        Err(err) => return Err(err.into()),
+      }
+    }
+
+    while !unconfirmed.is_empty() {
+      let txids = unconfirmed
+        .iter()
+        .map(Transaction::compute_txid)
+        .collect::<HashSet<Txid>>();
+
+      let next = unconfirmed
+        .iter()
+        .position(|transaction| {
+          !transaction
+            .input
+            .iter()
+            .any(|txin| txids.contains(&txin.previous_output.txid))
+        })
+        .unwrap_or_default();
+
+      wallet.apply_unconfirmed_transaction(&unconfirmed.remove(next))?;
+    }
+
+    Ok(())
+  }
+
+  /// Find unspent outputs of wallets using the ord backend by querying the
+  /// ord server for outputs of derived addresses. Addresses are derived until
+  /// `GAP_LIMIT` consecutive addresses after the last used or handed out
+  /// address have no outputs. Outputs are only visible once confirmed, so
+  /// unconfirmed transactions broadcast by the wallet are applied separately.
+  fn scan(&self, database: &Database, keychain: &Keychain) -> Result<BTreeMap<OutPoint, TxOut>> {
+    let mut utxos = BTreeMap::new();
+
+    let mut insert = |outputs: Vec<api::Output>| {
+      for output in outputs {
+        if !output.spent {
+          utxos.insert(
+            output.outpoint,
+            TxOut {
+              script_pubkey: output.script_pubkey,
+              value: Amount::from_sat(output.value),
+            },
+          );
+        }
+      }
+    };
+
+    for change in [false, true] {
+      let next = Wallet::next_index(database, change)?;
+
+      let mut used = next;
+      let mut end = next + Self::GAP_LIMIT;
+      let mut index = 0;
+
+      while index < end {
+        let address = keychain.derive(change, u32::try_from(index)?)?;
+
+        let outputs = self.get_address_outputs(&address)?;
+
+        if !outputs.is_empty() {
+          used = used.max(index + 1);
+          end = end.max(index + 1 + Self::GAP_LIMIT);
+        }
+
+        insert(outputs);
+
+        index += 1;
+      }
+
+      if used > next {
+        Wallet::set_next_index(database, change, used)?;
+      }
+    }
+
+    for address in keychain.raw_addresses() {
+      insert(self.get_address_outputs(address)?);
+    }
+
+    Ok(utxos)
+  }
+
+  /// Commit outputs of pending etchings, which must not be spent until the
+  /// reveal transaction is broadcast.
+  fn get_pending_commit_outputs(
+    database: &Database,
+    utxos: &BTreeMap<OutPoint, TxOut>,
+  ) -> Result<BTreeMap<OutPoint, TxOut>> {
+    let mut locked = BTreeMap::new();
+
+    for (_, entry) in Wallet::load_pending_etchings(database)? {
+      for txin in &entry.reveal.input {
+        if let Some(txout) = utxos.get(&txin.previous_output) {
+          locked.insert(txin.previous_output, txout.clone());
+        }
+      }
+    }
+
+    Ok(locked)
+  }
+
+  fn get_address_outputs(&self, address: &Address) -> Result<Vec<api::Output>> {
+    let response = self.get(&format!("/outputs/{address}"))?;
+
+    if !response.status().is_success() {
+      bail!(
+        "wallet failed get outputs of {address}: {}",
+        response.text()?
+      );
+    }
+
+    Ok(serde_json::from_str(&response.text()?)?)
   }
 
   fn get_output_info(&self, outputs: Vec<OutPoint>) -> Result<BTreeMap<OutPoint, api::Output>> {
@@ -0,0 +1,67 @@
+use super::*;
+
+#[test]
+fn admin_token_is_required() {
+  CommandBuilder::new("admin hidden")
+    .expected_exit_code(1)
+    .expected_stderr("error: no admin token specified, use `--admin-token`\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn hide_and_unhide() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--admin-token", "foo"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  ord.assert_response(format!("/content/{inscription}"), "FOO");
+
+  let hidden = CommandBuilder::new(format!(
+    "--regtest --admin-token foo admin hide {inscription} --content --reason spam"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<api::Hidden>();
+
+  assert_eq!(hidden.content.len(), 1);
+  assert_eq!(hidden.inscriptions[&inscription].reason, "spam");
+
+  ord.assert_response_regex(format!("/content/{inscription}"), r".*<body>\s*</body>.*");
+
+  assert_eq!(
+    CommandBuilder::new("--regtest --admin-token foo admin hidden")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<api::Hidden>(),
+    hidden,
+  );
+
+  let hidden = CommandBuilder::new(format!(
+    "--regtest --admin-token foo admin unhide {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<api::Hidden>();
+
+  assert_eq!(hidden, api::Hidden::default());
+
+  ord.assert_response(format!("/content/{inscription}"), "FOO");
+}
+
+#[test]
+fn wrong_admin_token_is_rejected() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--admin-token", "foo"], &[]);
+
+  CommandBuilder::new("--regtest --admin-token bar admin hidden")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: admin request failed: invalid admin token\n")
+    .run_and_extract_stdout();
+}
@@ -1,16 +1,18 @@
 use {
   super::*,
+  base64::Engine,
   bitcoin::{
-    absolute::LockTime, consensus::Encodable, opcodes, script, transaction::Version, ScriptBuf,
-    Sequence, Transaction, TxIn, TxOut, Witness,
+    absolute::LockTime, consensus::Encodable, opcodes, psbt::Psbt, script, transaction::Version,
+    ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
   },
   ord::{
-    subcommand::decode::{CompactInscription, CompactOutput, RawOutput},
+    api::{SimulatedInscription, SimulatedOutput},
+    subcommand::decode::{CompactInscription, CompactOutput, RawOutput, SimulationOutput},
     Envelope, Inscription,
   },
 };
 
-fn transaction() -> Vec<u8> {
+fn witness() -> Witness {
   let script = script::Builder::new()
     .push_opcode(opcodes::OP_FALSE)
     .push_opcode(opcodes::all::OP_IF)
@@ -27,6 +29,10 @@ fn transaction() -> Vec<u8> {
   witness.push(script);
   witness.push([]);
 
+  witness
+}
+
+fn transaction() -> Vec<u8> {
   let transaction = Transaction {
     version: Version(2),
     lock_time: LockTime::ZERO,
@@ -34,7 +40,7 @@ fn transaction() -> Vec<u8> {
       previous_output: OutPoint::null(),
       script_sig: ScriptBuf::new(),
       sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
-      witness,
+      witness: witness(),
     }],
     output: vec![TxOut {
       script_pubkey: Runestone::default().encipher(),
@@ -150,3 +156,55 @@ fn compact() {
     },
   );
 }
+
+#[test]
+fn psbt() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  core.mine_blocks(1);
+
+  let txid = core.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0, witness())],
+    fee: 1000,
+    ..default()
+  });
+
+  let tx = core.mempool()[0].clone();
+
+  let mut unsigned_tx = tx.clone();
+  unsigned_tx.input[0].witness = Witness::new();
+
+  let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).unwrap();
+  psbt.inputs[0].final_script_witness = Some(tx.input[0].witness.clone());
+
+  let output = CommandBuilder::new(format!(
+    "--regtest decode --psbt {}",
+    base64::engine::general_purpose::STANDARD.encode(psbt.serialize())
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<SimulationOutput>();
+
+  assert_eq!(output.inscriptions.len(), 1);
+  assert_eq!(output.runestone, None);
+
+  pretty_assert_eq!(
+    output.simulation,
+    ord::api::Simulation {
+      burned: None,
+      cenotaph: false,
+      fee: 1000,
+      inscriptions: vec![SimulatedInscription {
+        id: InscriptionId { txid, index: 0 },
+        new: true,
+        satpoint: Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        }),
+      }],
+      outputs: vec![SimulatedOutput {
+        runes: None,
+        value: 50 * COIN_VALUE - 1000,
+      }],
+    },
+  );
+}
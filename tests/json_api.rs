@@ -581,6 +581,7 @@ fn get_status() {
       transaction_index: false,
       unrecoverably_reorged: false,
       uptime: dummy_duration,
+      usage: None,
     }
   );
 }
@@ -53,6 +53,7 @@ mod command_builder;
 mod expected;
 mod test_server;
 
+mod admin;
 mod balances;
 mod decode;
 mod epochs;
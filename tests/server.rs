@@ -894,3 +894,112 @@ fn ctrl_c() {
     thread::sleep(Duration::from_millis(50));
   }
 }
+
+#[test]
+fn events_websocket_sends_events() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &["--enable-events"]);
+
+  create_wallet(&core, &ord);
+
+  let mut stream = std::net::TcpStream::connect(("127.0.0.1", ord.url().port().unwrap())).unwrap();
+
+  write!(
+    stream,
+    "GET /events/websocket HTTP/1.1\r\n\
+     Host: 127.0.0.1\r\n\
+     Connection: Upgrade\r\n\
+     Upgrade: websocket\r\n\
+     Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+     Sec-WebSocket-Version: 13\r\n\r\n"
+  )
+  .unwrap();
+
+  let mut reader = BufReader::new(stream);
+
+  let mut headers = Vec::new();
+
+  loop {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+
+    if line == "\r\n" {
+      break;
+    }
+
+    headers.push(line.trim_end().to_string());
+  }
+
+  assert_eq!(headers[0], "HTTP/1.1 101 Switching Protocols");
+  assert!(headers.iter().any(
+    |header| header.eq_ignore_ascii_case("sec-websocket-accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=")
+  ));
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  let mut read_event = || {
+    let mut header = [0; 2];
+    std::io::Read::read_exact(&mut reader, &mut header).unwrap();
+
+    assert_eq!(header[0], 0x81);
+
+    let len = match header[1] {
+      126 => {
+        let mut len = [0; 2];
+        std::io::Read::read_exact(&mut reader, &mut len).unwrap();
+        u16::from_be_bytes(len).into()
+      }
+      len => usize::from(len),
+    };
+
+    let mut payload = vec![0; len];
+    std::io::Read::read_exact(&mut reader, &mut payload).unwrap();
+
+    serde_json::from_slice::<api::Event>(&payload).unwrap()
+  };
+
+  loop {
+    if let api::Event::InscriptionCreated { inscription_id, .. } = read_event() {
+      assert_eq!(inscription_id, inscription);
+      break;
+    }
+  }
+
+  while !matches!(read_event(), api::Event::Block { height: 2, .. }) {}
+}
+
+#[test]
+fn events_websocket_drops_connection_on_unmasked_client_frame() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &["--enable-events"]);
+
+  let mut stream = std::net::TcpStream::connect(("127.0.0.1", ord.url().port().unwrap())).unwrap();
+
+  write!(
+    stream,
+    "GET /events/websocket HTTP/1.1\r\n\
+     Host: 127.0.0.1\r\n\
+     Connection: Upgrade\r\n\
+     Upgrade: websocket\r\n\
+     Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+     Sec-WebSocket-Version: 13\r\n\r\n"
+  )
+  .unwrap();
+
+  let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+  loop {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+
+    if line == "\r\n" {
+      break;
+    }
+  }
+
+  stream.write_all(b"\x81\x03foo").unwrap();
+
+  assert_eq!(std::io::Read::read(&mut reader, &mut [0]).unwrap(), 0);
+}
@@ -6,6 +6,9 @@ fn default() {
     .integration_test(false)
     .stdout_regex(
       r#"\{
+  "admin_token": null,
+  "anonymous_rate_limit": null,
+  "api_keys": null,
   "bitcoin_data_dir": ".*(Bitcoin|bitcoin)",
   "bitcoin_rpc_limit": 12,
   "bitcoin_rpc_password": null,
@@ -28,6 +31,8 @@ fn default() {
   "index_transactions": false,
   "integration_test": false,
   "no_index_inscriptions": false,
+  "response_cache_size": null,
+  "route_costs": null,
   "server_password": null,
   "server_url": null,
   "server_username": null
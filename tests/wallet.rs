@@ -2,20 +2,25 @@ use super::*;
 
 mod addresses;
 mod authentication;
+mod backend;
 mod balance;
 mod batch_command;
+mod bump;
 mod burn;
 mod cardinals;
 mod create;
 mod dump;
+mod extract;
 mod inscribe;
 mod inscriptions;
 mod label;
 mod mint;
+mod offer;
 mod outputs;
 #[cfg(unix)]
 mod pending;
 mod receive;
+mod resize_postage;
 mod restore;
 #[cfg(unix)]
 mod resume;
@@ -23,6 +28,8 @@ mod runics;
 mod sats;
 mod selection;
 mod send;
+mod server;
 mod sign;
 mod split;
+mod sweep;
 mod transactions;
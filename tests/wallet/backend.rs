@@ -0,0 +1,427 @@
+use {
+  super::*,
+  ord::subcommand::wallet::{create, mint, receive},
+};
+
+fn create_ord_wallet(core: &mockcore::Handle, ord: &TestServer, tempdir: &Arc<TempDir>) -> Address {
+  CommandBuilder::new("wallet --name foo create --backend ord")
+    .core(core)
+    .ord(ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<create::Output>();
+
+  CommandBuilder::new("wallet --name foo receive")
+    .core(core)
+    .ord(ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses
+    .into_iter()
+    .next()
+    .unwrap()
+    .assume_checked()
+}
+
+fn fund(core: &mockcore::Handle, ord: &TestServer, address: &Address, amount: &str) {
+  CommandBuilder::new(format!("wallet send --fee-rate 1 {address} {amount}"))
+    .core(core)
+    .ord(ord)
+    .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+}
+
+#[test]
+fn ord_backend_does_not_create_bitcoin_core_wallet() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-addresses"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let address = create_ord_wallet(&core, &ord, &tempdir);
+
+  assert!(!core.wallets().contains("foo"));
+
+  let addresses = CommandBuilder::new("wallet --name foo receive --number 2")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses;
+
+  assert_eq!(addresses.len(), 2);
+  assert!(!addresses.contains(address.as_unchecked()));
+  assert_ne!(addresses[0], addresses[1]);
+
+  CommandBuilder::new("wallet --name foo create --backend ord")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .expected_exit_code(1)
+    .expected_stderr("error: wallet `foo` already exists\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn ord_backend_requires_address_index() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("wallet --name foo create --backend ord")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<create::Output>();
+
+  CommandBuilder::new("wallet --name foo balance")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: wallets using the ord backend require index created with `--index-addresses` flag\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn ord_backend_finds_outputs_and_sends() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-addresses"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let address = create_ord_wallet(&core, &ord, &tempdir);
+
+  fund(&core, &ord, &address, "1btc");
+
+  let balance = CommandBuilder::new("wallet --name foo balance")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<Balance>();
+
+  assert_eq!(balance.cardinal, 100_000_000);
+
+  let output = CommandBuilder::new(
+    "wallet --name foo send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 0.25btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<Send>();
+
+  let transaction = core.mempool()[0].clone();
+
+  assert_eq!(transaction.compute_txid(), output.txid);
+  assert_eq!(transaction.input.len(), 1);
+  assert_eq!(transaction.input[0].witness.len(), 1);
+  assert_eq!(transaction.input[0].witness[0].len(), 64);
+  assert_eq!(transaction.output.len(), 2);
+  assert_eq!(transaction.output[0].value.to_sat(), 25_000_000);
+
+  let change = transaction.output[1].value.to_sat();
+
+  assert_eq!(change, 75_000_000 - output.fee);
+
+  core.mine_blocks(1);
+
+  let balance = CommandBuilder::new("wallet --name foo balance")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<Balance>();
+
+  assert_eq!(balance.cardinal, change);
+}
+
+#[test]
+fn ord_backend_inscribes() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-addresses"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let address = create_ord_wallet(&core, &ord, &tempdir);
+
+  fund(&core, &ord, &address, "1btc");
+
+  let output = CommandBuilder::new("wallet --name foo inscribe --fee-rate 1 --file foo.txt")
+    .temp_dir(tempdir.clone())
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let commit = core.mempool()[0].clone();
+
+  assert_eq!(commit.compute_txid(), output.commit);
+  assert_eq!(commit.input[0].witness[0].len(), 64);
+
+  core.mine_blocks(1);
+
+  let inscriptions = CommandBuilder::new("wallet --name foo inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions.len(), 1);
+  assert_eq!(inscriptions[0].inscription, output.inscriptions[0].id);
+
+  let dump = CommandBuilder::new("wallet --name foo dump")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .stderr_regex(".*")
+    .run_and_deserialize_output::<ListDescriptorsResult>();
+
+  assert_eq!(dump.descriptors.len(), 3);
+  assert!(dump
+    .descriptors
+    .iter()
+    .any(|descriptor| descriptor.desc.starts_with("rawtr(")));
+}
+
+#[test]
+fn ord_backend_restore_from_dump() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-addresses"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let address = create_ord_wallet(&core, &ord, &tempdir);
+
+  fund(&core, &ord, &address, "1btc");
+
+  let dump = CommandBuilder::new("wallet --name foo dump")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .stderr_regex(".*")
+    .stdout_regex(".*")
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet --name bar restore --backend ord --from descriptor")
+    .stdin(dump.into())
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_extract_stdout();
+
+  assert!(!core.wallets().contains("bar"));
+
+  let balance = CommandBuilder::new("wallet --name bar balance")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<Balance>();
+
+  assert_eq!(balance.cardinal, 100_000_000);
+
+  let addresses = CommandBuilder::new("wallet --name bar receive")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses;
+
+  assert_ne!(addresses[0], *address.as_unchecked());
+}
+
+#[test]
+fn ord_backend_does_not_support_bitcoin_core_wallet_history() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-addresses"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  create_ord_wallet(&core, &ord, &tempdir);
+
+  CommandBuilder::new("wallet --name foo transactions")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: `ord wallet transactions` is not supported by wallets using the ord backend\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn ord_backend_spends_change_of_unconfirmed_transactions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-addresses"], &[]);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let address = create_ord_wallet(&core, &ord, &tempdir);
+
+  fund(&core, &ord, &address, "1btc");
+
+  let first = CommandBuilder::new(
+    "wallet --name foo send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 0.25btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<Send>();
+
+  let second = CommandBuilder::new(
+    "wallet --name foo send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 0.25btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<Send>();
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[1].compute_txid(), second.txid);
+  assert_eq!(
+    mempool[1].input[0].previous_output,
+    OutPoint {
+      txid: first.txid,
+      vout: 1
+    },
+  );
+
+  let change = mempool[1].output[1].value.to_sat();
+
+  let balance = CommandBuilder::new("wallet --name foo balance")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<Balance>();
+
+  assert_eq!(balance.cardinal, change);
+
+  core.mine_blocks(1);
+
+  let balance = CommandBuilder::new("wallet --name foo balance")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<Balance>();
+
+  assert_eq!(balance.cardinal, change);
+}
+
+#[test]
+fn ord_backend_mints_multiple_times() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(
+    &core,
+    &["--regtest", "--index-runes", "--index-addresses"],
+    &[],
+  );
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  core.mine_blocks(1);
+
+  create_wallet(&core, &ord);
+
+  batch(
+    &core,
+    &ord,
+    batch::File {
+      etching: Some(batch::Etching {
+        divisibility: 0,
+        rune: SpacedRune {
+          rune: Rune(RUNE),
+          spacers: 0,
+        },
+        premine: "0".parse().unwrap(),
+        supply: "63".parse().unwrap(),
+        symbol: '¢',
+        turbo: false,
+        terms: Some(batch::Terms {
+          cap: 3,
+          offset: None,
+          amount: "21".parse().unwrap(),
+          height: None,
+        }),
+      }),
+      inscriptions: vec![batch::Entry {
+        file: Some("inscription.jpeg".into()),
+        ..default()
+      }],
+      ..default()
+    },
+  );
+
+  CommandBuilder::new("--regtest wallet --name foo create --backend ord")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<create::Output>();
+
+  let address = CommandBuilder::new("--regtest wallet --name foo receive")
+    .core(&core)
+    .ord(&ord)
+    .temp_dir(tempdir.clone())
+    .run_and_deserialize_output::<receive::Output>()
+    .addresses
+    .into_iter()
+    .next()
+    .unwrap()
+    .assume_checked();
+
+  CommandBuilder::new(format!("--regtest wallet send --fee-rate 1 {address} 1btc"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet --name foo mint --fee-rate 1 --rune {} --count 3",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .temp_dir(tempdir.clone())
+  .run_and_deserialize_output::<mint::Output>();
+
+  assert_eq!(output.mints.len(), 3);
+  assert_eq!(output.error, None);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 3);
+
+  for (parent, child) in mempool.iter().zip(mempool.iter().skip(1)) {
+    assert!(child.input.iter().all(
+      |txin| txin.previous_output.txid == parent.compute_txid() && txin.previous_output.vout != 1
+    ));
+  }
+}
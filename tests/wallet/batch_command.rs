@@ -2807,3 +2807,211 @@ fn forbid_etching_below_rune_activation_height() {
     .expected_exit_code(1)
     .run_and_extract_stdout();
 }
+
+#[test]
+fn batch_inscribe_directory_with_sidecar_metadata() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  fs::create_dir(tempdir.path().join("collection")).unwrap();
+
+  let output = CommandBuilder::new("wallet batch --fee-rate 1 --dir collection")
+    .temp_dir(tempdir)
+    .write("collection/2.txt", "two")
+    .write("collection/1.txt", "one")
+    .write("collection/1.json", r#"{"n":1}"#)
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Batch>>();
+
+  core.mine_blocks(1);
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].inscriptions.len(), 2);
+
+  for (inscription, content) in output[0].inscriptions.iter().zip(["one", "two"]) {
+    assert_eq!(
+      ord
+        .request(format!("/content/{}", inscription.id))
+        .text()
+        .unwrap(),
+      content
+    );
+  }
+
+  assert_eq!(
+    serde_json::from_str::<String>(
+      &ord
+        .json_request(format!("/r/metadata/{}", output[0].inscriptions[0].id))
+        .text()
+        .unwrap()
+    )
+    .unwrap(),
+    "a1616e01",
+  );
+
+  assert_eq!(
+    ord
+      .json_request(format!("/r/metadata/{}", output[0].inscriptions[1].id))
+      .status(),
+    StatusCode::NOT_FOUND,
+  );
+}
+
+#[test]
+fn batch_inscribe_directory_splits_reveal_transactions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (parent, _reveal) = inscribe(&core, &ord);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  fs::create_dir(tempdir.path().join("collection")).unwrap();
+
+  let output = CommandBuilder::new(format!(
+    "wallet batch --fee-rate 1 --dir collection --parent {parent}"
+  ))
+  .temp_dir(tempdir)
+  .write("collection/1.txt", "1".repeat(150_000))
+  .write("collection/2.txt", "2".repeat(150_000))
+  .write("collection/3.txt", "3".repeat(150_000))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Vec<Batch>>();
+
+  assert_eq!(output.len(), 2);
+  assert_eq!(output[0].inscriptions.len(), 2);
+  assert_eq!(output[1].inscriptions.len(), 1);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 4);
+
+  assert_eq!(
+    mempool
+      .iter()
+      .map(|tx| tx.compute_txid())
+      .collect::<Vec<Txid>>(),
+    [
+      output[0].commit,
+      output[0].reveal,
+      output[1].commit,
+      output[1].reveal
+    ],
+  );
+
+  assert!(mempool[2]
+    .input
+    .iter()
+    .all(|txin| !mempool[0].input.contains(txin)));
+
+  assert_eq!(
+    mempool[3].input[0].previous_output,
+    OutPoint {
+      txid: output[0].reveal,
+      vout: 0
+    },
+  );
+
+  core.mine_blocks(1);
+
+  for inscription in output.iter().flat_map(|output| &output.inscriptions) {
+    let inscription = serde_json::from_str::<api::Inscription>(
+      &ord
+        .json_request(format!("/inscription/{}", inscription.id))
+        .text()
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(inscription.parents, [parent]);
+  }
+
+  let parent = serde_json::from_str::<api::Inscription>(
+    &ord
+      .json_request(format!("/inscription/{parent}"))
+      .text()
+      .unwrap(),
+  )
+  .unwrap();
+
+  assert_eq!(parent.satpoint.outpoint.txid, output[1].reveal);
+  assert_eq!(parent.child_count, 3);
+}
+
+#[test]
+fn batch_inscribe_directory_resumes_after_last_inscribed_file() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  fs::create_dir(tempdir.path().join("collection")).unwrap();
+
+  let destination = "bc1pxwww0ct9ue7e8tdnlmug5m2tamfn7q06sahstg39ys4c9f3340qqxrdu9k";
+
+  let output = CommandBuilder::new(
+    "wallet batch --fee-rate 1 --dir collection --destinations destinations.csv",
+  )
+  .temp_dir(tempdir.clone())
+  .write("collection/a.txt", "a")
+  .write("collection/b.txt", "b")
+  .write(
+    "destinations.csv",
+    format!("file,destination\nb.txt,{destination}\n"),
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Vec<Batch>>();
+
+  core.mine_blocks(1);
+
+  assert_eq!(output[0].inscriptions.len(), 2);
+  assert_eq!(
+    output[0].inscriptions[1].destination,
+    destination.parse::<Address<NetworkUnchecked>>().unwrap()
+  );
+
+  CommandBuilder::new("wallet batch --fee-rate 1 --dir collection")
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .stderr_regex("error: all files in `.*collection` have already been inscribed\n")
+    .run_and_extract_stdout();
+
+  let output = CommandBuilder::new("wallet batch --fee-rate 1 --dir collection")
+    .temp_dir(tempdir)
+    .write("collection/c.txt", "c")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Batch>>();
+
+  core.mine_blocks(1);
+
+  assert_eq!(output[0].inscriptions.len(), 1);
+
+  assert_eq!(
+    ord
+      .request(format!("/content/{}", output[0].inscriptions[0].id))
+      .text()
+      .unwrap(),
+    "c"
+  );
+}
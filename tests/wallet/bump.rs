@@ -0,0 +1,277 @@
+use {super::*, ord::subcommand::wallet::bump};
+
+#[test]
+fn bumping_replaceable_send_replaces_it() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let send =
+    CommandBuilder::new("wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Send>();
+
+  let original = core.mempool()[0].clone();
+
+  assert!(original.is_explicitly_rbf());
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 10 {}", send.txid))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Rbf);
+  assert_eq!(output.original, send.txid);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 1);
+
+  let replacement = &mempool[0];
+
+  assert_eq!(replacement.compute_txid(), output.txid);
+  assert_eq!(replacement.input[0], {
+    let mut txin = original.input[0].clone();
+    txin.witness = replacement.input[0].witness.clone();
+    txin
+  });
+  assert_eq!(replacement.output[0], original.output[0]);
+  assert_eq!(
+    replacement.output[1].value,
+    original.output[1].value - (Amount::from_sat(output.fee) - Amount::from_sat(send.fee)),
+  );
+  assert!(output.fee >= u64::try_from(replacement.vsize()).unwrap() * 10);
+
+  core.mine_blocks(1);
+}
+
+#[test]
+fn bumping_inscription_send_preserves_postage() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let send = CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let original = core.mempool()[0].clone();
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 5 {}", send.txid))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Rbf);
+
+  let replacement = core.mempool()[0].clone();
+
+  assert_eq!(replacement.compute_txid(), output.txid);
+  assert_eq!(replacement.output[0], original.output[0]);
+
+  core.mine_blocks(1);
+
+  let inscription = ord.json_request(format!("/inscription/{inscription}"));
+
+  assert_eq!(inscription.status(), StatusCode::OK);
+
+  let inscription: api::Inscription = serde_json::from_str(&inscription.text().unwrap()).unwrap();
+
+  assert_eq!(
+    inscription.satpoint,
+    SatPoint {
+      outpoint: OutPoint {
+        txid: output.txid,
+        vout: 0,
+      },
+      offset: 0,
+    }
+  );
+}
+
+#[test]
+fn bumping_non_replaceable_transaction_uses_cpfp() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let address = CommandBuilder::new("wallet receive")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::wallet::receive::Output>()
+    .addresses
+    .into_iter()
+    .next()
+    .unwrap()
+    .require_network(Network::Bitcoin)
+    .unwrap();
+
+  let parent = core.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0, Default::default())],
+    fee: 1000,
+    recipient: Some(address),
+    ..default()
+  });
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 20 {parent}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Cpfp);
+  assert_eq!(output.original, parent);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 2);
+
+  let child = &mempool[1];
+
+  assert_eq!(child.compute_txid(), output.txid);
+  assert_eq!(
+    child.input[0].previous_output,
+    OutPoint {
+      txid: parent,
+      vout: 0
+    }
+  );
+
+  let package_vsize = u64::try_from(mempool[0].vsize() + child.vsize()).unwrap();
+
+  assert!(output.fee + 1000 >= package_vsize * 20);
+
+  core.mine_blocks(1);
+}
+
+#[test]
+fn bumping_confirmed_transaction_fails() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let send =
+    CommandBuilder::new("wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc")
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!("wallet bump --fee-rate 10 {}", send.txid))
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: transaction {} is already confirmed\n",
+      send.txid
+    ))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bumping_reveal_uses_cpfp_and_preserves_inscription() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  let batch = CommandBuilder::new("wallet inscribe --fee-rate 1 --file foo.txt")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  let reveal = core
+    .mempool()
+    .into_iter()
+    .find(|tx| tx.compute_txid() == batch.reveal)
+    .unwrap();
+
+  assert!(reveal.is_explicitly_rbf());
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 10 {}", batch.reveal))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Cpfp);
+  assert_eq!(output.original, batch.reveal);
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 3);
+
+  let child = &mempool[2];
+
+  assert_eq!(child.compute_txid(), output.txid);
+  assert_eq!(
+    child.input[0].previous_output,
+    OutPoint {
+      txid: batch.reveal,
+      vout: 0
+    }
+  );
+  assert_eq!(child.output[0], reveal.output[0]);
+
+  let commit = &mempool[0];
+
+  assert_eq!(commit.compute_txid(), batch.commit);
+
+  let reveal_fee = commit.output[usize::try_from(reveal.input[0].previous_output.vout).unwrap()]
+    .value
+    - reveal
+      .output
+      .iter()
+      .map(|txout| txout.value)
+      .sum::<Amount>();
+
+  let package_vsize = u64::try_from(reveal.vsize() + child.vsize()).unwrap();
+
+  assert!(output.fee + reveal_fee.to_sat() >= package_vsize * 10);
+
+  core.mine_blocks(1);
+
+  let inscription = ord.json_request(format!("/inscription/{}", batch.inscriptions[0].id));
+
+  assert_eq!(inscription.status(), StatusCode::OK);
+
+  let inscription: api::Inscription = serde_json::from_str(&inscription.text().unwrap()).unwrap();
+
+  assert_eq!(
+    inscription.satpoint,
+    SatPoint {
+      outpoint: OutPoint {
+        txid: output.txid,
+        vout: 0,
+      },
+      offset: 0,
+    }
+  );
+}
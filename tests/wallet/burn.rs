@@ -401,3 +401,76 @@ fn oversize_metadata_requires_no_limit_flag() {
   .expected_exit_code(1)
   .run_and_extract_stdout();
 }
+
+#[test]
+fn runes_can_be_burned() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let rune = Rune(RUNE);
+
+  etch(&core, &ord, rune);
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet burn --fee-rate 1 100:{rune}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let transaction = core.mempool()[0].clone();
+
+  assert_eq!(transaction.compute_txid(), output.txid);
+
+  let Some(Artifact::Runestone(runestone)) = Runestone::decipher(&transaction) else {
+    panic!("burn transaction must contain a runestone");
+  };
+
+  assert_eq!(runestone.edicts[0].output, 0);
+  assert_eq!(runestone.pointer, Some(1));
+
+  core.mine_blocks(1);
+
+  ord.assert_response_regex(
+    format!("/rune/{rune}"),
+    r".*<dt>burned</dt>\s*<dd>100\u{A0}¢</dd>.*",
+  );
+
+  let balances = CommandBuilder::new("--regtest --index-runes balances")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Balances>();
+
+  assert_eq!(
+    balances.runes[&SpacedRune { rune, spacers: 0 }]
+      .values()
+      .map(|pile| pile.amount)
+      .sum::<u128>(),
+    900,
+  );
+}
+
+#[test]
+fn cannot_burn_more_runes_than_wallet_holds() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let rune = Rune(RUNE);
+
+  etch(&core, &ord, rune);
+
+  CommandBuilder::new(format!(
+    "--regtest --index-runes wallet burn --fee-rate 1 1001:{rune}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr("error: insufficient `AAAAAAAAAAAAA` balance, only 1000\u{A0}¢ in wallet\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
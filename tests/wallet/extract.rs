@@ -0,0 +1,172 @@
+use {
+  super::*,
+  ord::subcommand::wallet::{
+    extract::{ExtractedSat, Output},
+    sats::OutputRare,
+  },
+};
+
+#[test]
+fn extract_requires_sat_index() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new("wallet extract --fee-rate 1 --rarity uncommon")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: extracting sats requires index created with `--index-sats` flag\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn extract_by_rarity() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("--index-sats wallet extract --fee-rate 1 --rarity uncommon")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(
+    output.extracted,
+    vec![ExtractedSat {
+      sat: Sat(50 * COIN_VALUE),
+      output: OutPoint::new(output.txid, 0),
+    }]
+  );
+
+  core.mine_blocks(1);
+
+  let rare = CommandBuilder::new("--index-sats wallet sats")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<OutputRare>>();
+
+  assert!(rare.iter().any(|rare| rare.sat == Sat(50 * COIN_VALUE)
+    && rare.output == OutPoint::new(output.txid, 0)
+    && rare.offset == 0));
+
+  CommandBuilder::new("--index-sats wallet extract --fee-rate 1 --sat 5000000000")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: no sats to extract\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn extract_sat_from_middle_of_output() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let coinbase = core.mine_blocks(1)[0].txdata[0].compute_txid();
+
+  let output = CommandBuilder::new(format!(
+    "--index-sats wallet extract --fee-rate 1 --sat {}",
+    50 * COIN_VALUE + 1000
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(
+    output.extracted,
+    vec![ExtractedSat {
+      sat: Sat(50 * COIN_VALUE + 1000),
+      output: OutPoint::new(output.txid, 1),
+    }]
+  );
+
+  core.mine_blocks(1);
+
+  let tx = core.tx_by_id(output.txid);
+
+  assert_eq!(tx.input.len(), 1);
+  assert_eq!(tx.input[0].previous_output, OutPoint::new(coinbase, 0));
+  assert_eq!(tx.output[0].value.to_sat(), 1000);
+  assert_eq!(tx.output[1].value.to_sat(), 10_000);
+}
+
+#[test]
+fn extract_pads_sats_too_close_to_start_of_output() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--index-sats wallet extract --fee-rate 1 --sat {}",
+    50 * COIN_VALUE + 100
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: wallet contains no cardinal utxos to pad sat at offset 100\n")
+  .run_and_extract_stdout();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--index-sats wallet extract --fee-rate 1 --sat {}",
+    50 * COIN_VALUE + 100
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(
+    output.extracted,
+    vec![ExtractedSat {
+      sat: Sat(50 * COIN_VALUE + 100),
+      output: OutPoint::new(output.txid, 1),
+    }]
+  );
+
+  core.mine_blocks(1);
+
+  let tx = core.tx_by_id(output.txid);
+
+  assert_eq!(tx.input.len(), 2);
+  assert_eq!(tx.output[0].value.to_sat(), 50 * COIN_VALUE + 100);
+  assert_eq!(tx.output[1].value.to_sat(), 10_000);
+}
+
+#[test]
+fn extract_fails_if_sats_are_too_close() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--index-sats wallet extract --fee-rate 1 --sat {} --sat {}",
+    50 * COIN_VALUE + 1000,
+    50 * COIN_VALUE + 1100,
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: sats at offsets 1000 and 1100 are too close to extract into separate outputs\n",
+  )
+  .run_and_extract_stdout();
+}
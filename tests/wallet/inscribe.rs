@@ -1258,3 +1258,61 @@ fn file_inscribe_with_non_existent_delegate_inscription() {
   .expected_exit_code(1)
   .run_and_extract_stdout();
 }
+
+#[test]
+fn inscribe_fails_if_fee_exceeds_max_fee() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new("wallet --max-fee 1000sat inscribe --file foo.txt --fee-rate 10")
+    .write("foo.txt", "FOO")
+    .core(&core)
+    .ord(&ord)
+    .stderr_regex(r"error: fee of \d+ sat exceeds maximum of 1000 sat\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_included_output_and_change_address() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(3);
+
+  let included = OutPoint::new(core.tx(2, 0).compute_txid(), 0);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file foo.txt --fee-rate 1 --include-output {included} --change-address bc1qhl452zcq3ng5kzajzkx9jnzncml9tnsk3w96s6"
+  ))
+  .write("foo.txt", "FOO")
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>();
+
+  let commit = &core.mempool()[0];
+
+  assert!(commit
+    .input
+    .iter()
+    .any(|input| input.previous_output == included));
+
+  assert!(commit.output.iter().any(|output| {
+    Address::from_script(&output.script_pubkey, Network::Bitcoin).unwrap()
+      == "bc1qhl452zcq3ng5kzajzkx9jnzncml9tnsk3w96s6"
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .assume_checked()
+      && output.value.to_sat() > 50 * COIN_VALUE
+  }));
+}
@@ -1,4 +1,7 @@
-use super::*;
+use {
+  super::*,
+  ord::subcommand::wallet::{cardinals::CardinalUtxo, label, outputs::Output},
+};
 
 #[test]
 fn label() {
@@ -28,3 +31,284 @@ fn label() {
 
   assert!(output.contains(&inscription.to_string()));
 }
+
+#[test]
+fn label_export_is_bip329() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let etched = etch(&core, &ord, Rune(RUNE));
+
+  let records = CommandBuilder::new("--regtest --index-runes wallet label")
+    .core(&core)
+    .ord(&ord)
+    .stdout_regex(".*")
+    .run_and_extract_stdout()
+    .lines()
+    .map(|line| serde_json::from_str::<label::Record>(line).unwrap())
+    .collect::<Vec<label::Record>>();
+
+  assert!(records
+    .iter()
+    .any(|record| record.r#type == "xpub" && record.r#ref.starts_with("tpub")));
+
+  assert!(records
+    .iter()
+    .any(|record| record.r#type == "tx" && record.r#ref == etched.output.reveal.to_string()));
+
+  assert!(records.iter().any(|record| record.r#type == "addr"));
+
+  let rune_output = records
+    .iter()
+    .find(|record| {
+      record.r#type == "output"
+        && record.r#ref
+          == etched
+            .output
+            .rune
+            .as_ref()
+            .unwrap()
+            .location
+            .unwrap()
+            .to_string()
+    })
+    .unwrap();
+
+  assert_eq!(rune_output.spendable, Some(true));
+  assert!(rune_output
+    .label
+    .as_ref()
+    .unwrap()
+    .contains(r#""runes":{"AAAAAAAAAAAAA":"1000"}"#));
+}
+
+#[test]
+fn label_import() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _reveal) = inscribe(&core, &ord);
+
+  let outpoint = CommandBuilder::new("wallet inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>()[0]
+    .location
+    .outpoint;
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let output = CommandBuilder::new("wallet label import labels.jsonl")
+    .temp_dir(tempdir.clone())
+    .write(
+      "labels.jsonl",
+      format!(
+        "{{\"type\":\"output\",\"ref\":\"{outpoint}\",\"label\":\"my inscription\"}}\n\
+        {{\"type\":\"tx\",\"ref\":\"{}\",\"label\":\"reveal\"}}\n\
+        {{\"type\":\"addr\",\"ref\":\"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\"}}\n",
+        outpoint.txid,
+      ),
+    )
+    .core(&core)
+    .ord(&ord)
+    .spawn()
+    .child
+    .wait_with_output()
+    .unwrap();
+
+  assert!(output.status.success());
+
+  assert_eq!(
+    serde_json::from_slice::<label::ImportOutput>(&output.stdout)
+      .unwrap()
+      .labels,
+    2
+  );
+
+  let inscriptions = serde_json::from_slice::<Inscriptions>(
+    &CommandBuilder::new("wallet inscriptions")
+      .temp_dir(tempdir.clone())
+      .core(&core)
+      .ord(&ord)
+      .spawn()
+      .child
+      .wait_with_output()
+      .unwrap()
+      .stdout,
+  )
+  .unwrap();
+
+  assert_eq!(inscriptions[0].inscription, inscription);
+  assert_eq!(inscriptions[0].label, Some("my inscription".into()));
+
+  let outputs = serde_json::from_slice::<Vec<Output>>(
+    &CommandBuilder::new("wallet outputs")
+      .temp_dir(tempdir.clone())
+      .core(&core)
+      .ord(&ord)
+      .spawn()
+      .child
+      .wait_with_output()
+      .unwrap()
+      .stdout,
+  )
+  .unwrap();
+
+  assert_eq!(
+    outputs
+      .iter()
+      .find(|output| output.output == outpoint)
+      .unwrap()
+      .label,
+    Some("my inscription".into()),
+  );
+
+  let records = CommandBuilder::new("wallet label export")
+    .temp_dir(tempdir)
+    .core(&core)
+    .ord(&ord)
+    .stdout_regex(".*")
+    .run_and_extract_stdout()
+    .lines()
+    .map(|line| serde_json::from_str::<label::Record>(line).unwrap())
+    .collect::<Vec<label::Record>>();
+
+  assert!(records.contains(&label::Record {
+    r#type: "tx".into(),
+    r#ref: outpoint.txid.to_string(),
+    label: Some("reveal".into()),
+    origin: None,
+    spendable: None,
+  }));
+}
+
+#[test]
+fn label_export_round_trips_through_import() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let (inscription, _reveal) = inscribe(&core, &ord);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let export = || {
+    CommandBuilder::new("wallet label export")
+      .temp_dir(tempdir.clone())
+      .core(&core)
+      .ord(&ord)
+      .stdout_regex(".*")
+      .run_and_extract_stdout()
+  };
+
+  let exported = export();
+
+  let import = |labels: &str| {
+    let output = CommandBuilder::new("wallet label import labels.jsonl")
+      .temp_dir(tempdir.clone())
+      .write("labels.jsonl", labels)
+      .core(&core)
+      .ord(&ord)
+      .spawn()
+      .child
+      .wait_with_output()
+      .unwrap();
+
+    assert!(output.status.success());
+
+    serde_json::from_slice::<label::ImportOutput>(&output.stdout).unwrap()
+  };
+
+  assert_eq!(
+    import(&exported),
+    label::ImportOutput {
+      labels: 0,
+      locked: 0,
+      unlocked: 0,
+    },
+  );
+
+  let inscriptions = CommandBuilder::new("wallet inscriptions")
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions[0].inscription, inscription);
+  assert_eq!(inscriptions[0].label, None);
+
+  assert!(CommandBuilder::new("wallet outputs")
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Output>>()
+    .iter()
+    .all(|output| output.label.is_none()));
+
+  core.mine_blocks(1);
+
+  assert!(export().contains(r#"\"number\":15000000000"#));
+
+  let cardinal = CommandBuilder::new("wallet cardinals")
+    .temp_dir(tempdir.clone())
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<CardinalUtxo>>()[0]
+    .output;
+
+  assert_eq!(
+    import(&format!(
+      "{{\"type\":\"output\",\"ref\":\"{cardinal}\",\"spendable\":false}}\n"
+    )),
+    label::ImportOutput {
+      labels: 0,
+      locked: 1,
+      unlocked: 0,
+    },
+  );
+
+  assert!(core.get_locked().contains(&cardinal));
+
+  assert_eq!(
+    import(&format!(
+      "{{\"type\":\"output\",\"ref\":\"{cardinal}\",\"spendable\":true}}\n"
+    )),
+    label::ImportOutput {
+      labels: 0,
+      locked: 0,
+      unlocked: 1,
+    },
+  );
+
+  assert!(!core.get_locked().contains(&cardinal));
+}
+
+#[test]
+fn label_import_rejects_invalid_type() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  CommandBuilder::new("wallet label import labels.jsonl")
+    .write(
+      "labels.jsonl",
+      "{\"type\":\"foo\",\"ref\":\"bar\",\"label\":\"baz\"}\n",
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: invalid label type `foo` on line 1\n")
+    .run_and_extract_stdout();
+}
@@ -0,0 +1,240 @@
+use {
+  super::*,
+  base64::Engine,
+  bitcoin::psbt::Psbt,
+  ord::subcommand::wallet::offer::{accept, create},
+};
+
+#[test]
+fn inscription_offer_can_be_created_and_accepted() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  let create = CommandBuilder::new(format!(
+    "wallet offer create --outgoing {inscription} --amount 1btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<create::Output>();
+
+  assert_eq!(create.outgoing, Outgoing::InscriptionId(inscription));
+  assert_eq!(create.amount, 100_000_000);
+
+  let offer = Psbt::deserialize(
+    &base64::engine::general_purpose::STANDARD
+      .decode(&create.psbt)
+      .unwrap(),
+  )
+  .unwrap();
+
+  assert_eq!(offer.unsigned_tx.input.len(), 1);
+  assert_eq!(offer.unsigned_tx.output.len(), 1);
+  assert_eq!(
+    offer.unsigned_tx.output[0].value,
+    Amount::from_sat(100_000_000)
+  );
+  assert!(core
+    .get_locked()
+    .contains(&offer.unsigned_tx.input[0].previous_output));
+  assert_eq!(
+    offer.inputs[0]
+      .final_script_witness
+      .as_ref()
+      .unwrap()
+      .nth(0)
+      .unwrap()
+      .last(),
+    Some(&0x83),
+  );
+
+  let accept = CommandBuilder::new(format!(
+    "wallet offer accept --fee-rate 1 --outgoing {inscription} --amount 1btc --psbt {}",
+    create.psbt
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<accept::Output>();
+
+  let mempool = core.mempool();
+
+  assert_eq!(mempool.len(), 1);
+
+  let transaction = &mempool[0];
+
+  assert_eq!(transaction.compute_txid(), accept.txid);
+  assert_eq!(
+    transaction.input[1].previous_output,
+    offer.unsigned_tx.input[0].previous_output
+  );
+  assert_eq!(transaction.output[1], offer.unsigned_tx.output[0]);
+
+  core.mine_blocks(1);
+
+  let inscriptions = CommandBuilder::new("wallet inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions.len(), 1);
+  assert_eq!(inscriptions[0].inscription, inscription);
+  assert_eq!(inscriptions[0].location.outpoint.txid, accept.txid);
+  assert_eq!(inscriptions[0].location.outpoint.vout, 0);
+}
+
+#[test]
+fn rune_offer_can_be_created_and_accepted() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  etch(&core, &ord, Rune(RUNE));
+
+  let address = CommandBuilder::new("--regtest wallet receive")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::wallet::receive::Output>()
+    .addresses
+    .into_iter()
+    .next()
+    .unwrap()
+    .assume_checked();
+
+  CommandBuilder::new(format!(
+    "--regtest --index-runes wallet send --fee-rate 1 {address} 100:{}",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let create = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet offer create --outgoing 100:{} --amount 1btc",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<create::Output>();
+
+  let accept = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet offer accept --fee-rate 1 --outgoing 100:{} --amount 1btc --psbt {}",
+    Rune(RUNE),
+    create.psbt,
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<accept::Output>();
+
+  core.mine_blocks(1);
+
+  let balances = CommandBuilder::new("--regtest --index-runes balances")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Balances>();
+
+  assert_eq!(
+    balances.runes[&SpacedRune {
+      rune: Rune(RUNE),
+      spacers: 0
+    }][&OutPoint {
+      txid: accept.txid,
+      vout: 0
+    }]
+      .amount,
+    100,
+  );
+}
+
+#[test]
+fn rune_offer_requires_output_with_exact_amount() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  etch(&core, &ord, Rune(RUNE));
+
+  CommandBuilder::new(format!(
+    "--regtest --index-runes wallet offer create --outgoing 1:{} --amount 1btc",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: wallet contains no output holding exactly 1 AAAAAAAAAAAAA and no other runes, use `ord wallet split` to create one\n",
+  )
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn accepting_offer_with_wrong_amount_fails() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  let create = CommandBuilder::new(format!(
+    "wallet offer create --outgoing {inscription} --amount 1btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<create::Output>();
+
+  CommandBuilder::new(format!(
+    "wallet offer accept --fee-rate 1 --outgoing {inscription} --amount 2btc --psbt {}",
+    create.psbt
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: offer price of 1 BTC does not match amount 2 BTC\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn accepting_offer_with_input_spent_in_mempool_fails() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, reveal) = inscribe(&core, &ord);
+
+  let create = CommandBuilder::new(format!(
+    "wallet offer create --outgoing {inscription} --amount 1btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<create::Output>();
+
+  let (block, transaction) = core.tx_index(reveal);
+
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(block, transaction, 0, Default::default())],
+    ..default()
+  });
+
+  CommandBuilder::new(format!(
+    "wallet offer accept --fee-rate 1 --outgoing {inscription} --amount 1btc --psbt {}",
+    create.psbt
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!("error: offer input {reveal}:0 already spent\n"))
+  .run_and_extract_stdout();
+}
@@ -0,0 +1,197 @@
+use {super::*, ord::subcommand::wallet::resize_postage::Output};
+
+#[test]
+fn resize_postage_of_single_inscription() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe_with_postage(&core, &ord, Some(50_000));
+
+  let output = CommandBuilder::new(format!("wallet resize-postage --fee-rate 1 {inscription}"))
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(
+    output.inscriptions,
+    [(
+      inscription,
+      SatPoint {
+        outpoint: OutPoint {
+          txid: output.txid,
+          vout: 0,
+        },
+        offset: 0,
+      }
+    )]
+    .into(),
+  );
+
+  let transaction = core.mempool()[0].clone();
+
+  assert_eq!(transaction.compute_txid(), output.txid);
+  assert_eq!(transaction.input.len(), 1);
+  assert_eq!(transaction.output.len(), 2);
+  assert_eq!(transaction.output[0].value.to_sat(), 10_000);
+  assert_eq!(transaction.output[1].value.to_sat(), 40_000 - output.fee,);
+
+  core.mine_blocks(1);
+
+  let inscriptions = CommandBuilder::new("wallet inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions.len(), 1);
+  assert_eq!(inscriptions[0].postage, 10_000);
+}
+
+#[test]
+fn resize_postage_with_custom_postage() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe_with_postage(&core, &ord, Some(50_000));
+
+  CommandBuilder::new(format!(
+    "wallet resize-postage --fee-rate 1 --postage 1000sat {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(core.mempool()[0].output[0].value.to_sat(), 1_000);
+}
+
+#[test]
+fn resize_postage_of_output_with_multiple_inscriptions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let batch = CommandBuilder::new("wallet batch --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("tulip.png", [0; 555])
+    .write("meow.wav", [0; 2048])
+    .write(
+      "batch.yaml",
+      "mode: shared-output\npostage: 30000\ninscriptions:\n- file: inscription.txt\n- file: tulip.png\n- file: meow.wav\n",
+    )
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "wallet resize-postage --fee-rate 1 {}",
+    batch.inscriptions[1].id
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.inscriptions.len(), 3);
+
+  for (inscription, vout) in batch.inscriptions.iter().zip([0, 2, 4]) {
+    assert_eq!(
+      output.inscriptions[&inscription.id],
+      SatPoint {
+        outpoint: OutPoint {
+          txid: output.txid,
+          vout,
+        },
+        offset: 0,
+      }
+    );
+  }
+
+  let transaction = core.mempool()[0].clone();
+
+  assert_eq!(
+    transaction
+      .output
+      .iter()
+      .map(|txout| txout.value.to_sat())
+      .take(5)
+      .collect::<Vec<u64>>(),
+    [10_000, 20_000, 10_000, 20_000, 10_000],
+  );
+
+  assert_eq!(transaction.output.len(), 6);
+}
+
+#[test]
+fn resize_postage_of_all_inscriptions_above_amount() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (a, _) = inscribe_with_postage(&core, &ord, Some(50_000));
+  let (b, _) = inscribe_with_postage(&core, &ord, Some(20_000));
+  let (c, _) = inscribe_with_postage(&core, &ord, Some(10_000));
+
+  let output = CommandBuilder::new("wallet resize-postage --fee-rate 1 --all-above 15000sat")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.inscriptions.len(), 2);
+  assert!(output.inscriptions.contains_key(&a));
+  assert!(output.inscriptions.contains_key(&b));
+  assert!(!output.inscriptions.contains_key(&c));
+
+  core.mine_blocks(1);
+
+  let inscriptions = CommandBuilder::new("wallet inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions.len(), 3);
+
+  for inscription in inscriptions {
+    assert_eq!(inscription.postage, 10_000);
+  }
+}
+
+#[test]
+fn resize_postage_with_nothing_to_resize_is_an_error() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, reveal) = inscribe_with_postage(&core, &ord, Some(10_000));
+
+  CommandBuilder::new(format!(
+    "wallet resize-postage --fee-rate 1 {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: output {reveal}:0 containing inscription {inscription} already has postage of 0.00010000 BTC\n"
+  ))
+  .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet resize-postage --fee-rate 1 --all-above 10000sat")
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr("error: no inscription outputs with more than 0.00010000 BTC postage\n")
+    .run_and_extract_stdout();
+}
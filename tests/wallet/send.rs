@@ -1319,3 +1319,494 @@ fn error_messages_use_spaced_runes() {
     .expected_stderr("error: rune `FOO` has not been etched\n")
     .run_and_extract_stdout();
 }
+
+#[test]
+fn send_btc_locks_outputs_containing_protected_sats() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let coinbase = core.mine_blocks(1)[0].txdata[0].compute_txid();
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--index-sats wallet --protect-sats protected.tsv send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc",
+  )
+  .write("protected.tsv", format!("{}\n", 50 * COIN_VALUE + 5))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  assert!(core.get_locked().contains(&OutPoint {
+    txid: coinbase,
+    vout: 0,
+  }));
+}
+
+#[test]
+fn send_fails_if_protected_sat_would_be_spent_as_fee() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let coinbase = core.mine_blocks(1)[0].txdata[0].compute_txid();
+
+  CommandBuilder::new(format!(
+    "--index-sats wallet --protect-sats protected.tsv send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {coinbase}:0:0",
+  ))
+  .write("protected.tsv", format!("{}\n", 100 * COIN_VALUE - 1))
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(format!(
+    "error: transaction would spend protected sat {} in output {coinbase}:0 as fee\n",
+    100 * COIN_VALUE - 1,
+  ))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn protected_sat_may_be_sent_explicitly() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-sats"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--index-sats wallet --protect-rarity uncommon send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {}",
+    Sat(50 * COIN_VALUE).name(),
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+}
+
+#[test]
+fn protecting_sats_requires_sat_index() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet --protect-rarity uncommon send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr("error: protecting sats requires index created with `--index-sats` flag\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn send_btc_with_auto_fee_rate() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.set_fee_estimate(Amount::from_sat(13_300));
+
+  CommandBuilder::new(
+    "wallet send --fee-rate auto bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 2btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let tx = &core.mempool()[0];
+
+  let mut fee = Amount::ZERO;
+  for input in &tx.input {
+    fee += core.get_utxo_amount(&input.previous_output).unwrap();
+  }
+
+  for output in &tx.output {
+    fee -= output.value;
+  }
+
+  let fee_rate = fee.to_sat() as f64 / tx.vsize() as f64;
+
+  assert!(f64::abs(fee_rate - 13.3) < 0.1);
+}
+
+#[test]
+fn auto_fee_rate_is_at_least_mempool_minimum() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.set_fee_estimate(Amount::from_sat(100));
+
+  CommandBuilder::new(
+    "wallet send --fee-rate auto:2 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 2btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let tx = &core.mempool()[0];
+
+  let mut fee = Amount::ZERO;
+  for input in &tx.input {
+    fee += core.get_utxo_amount(&input.previous_output).unwrap();
+  }
+
+  for output in &tx.output {
+    fee -= output.value;
+  }
+
+  let fee_rate = fee.to_sat() as f64 / tx.vsize() as f64;
+
+  assert!(f64::abs(fee_rate - 1.0) < 0.1);
+}
+
+#[test]
+fn auto_fee_rate_fails_without_estimate() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet send --fee-rate auto bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 2btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .expected_stderr(
+    "error: failed to estimate fee rate for confirmation target 6: Insufficient data or no feerate found\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn send_fails_if_fee_exceeds_max_fee() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet --max-fee 1000sat send --fee-rate 100 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 2btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(r"error: fee of \d+ sat exceeds maximum of 1000 sat\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn send_fails_if_fee_rate_exceeds_max_fee_rate() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  core.set_fee_estimate(Amount::from_sat(50_000));
+
+  CommandBuilder::new(
+    "wallet --max-fee-rate 20 send --fee-rate auto bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 2btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .stderr_regex(
+    r"error: fee rate of \d+\.\d+ sat/vB for transaction [[:xdigit:]]{64} exceeds maximum of 20 sat/vB\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  assert!(core.mempool().is_empty());
+}
+
+#[test]
+fn send_btc_spends_included_output() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(3);
+
+  let included = OutPoint::new(core.tx(2, 0).compute_txid(), 0);
+
+  CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --include-output {included} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let tx = &core.mempool()[0];
+
+  assert_eq!(tx.input.len(), 1);
+  assert_eq!(tx.input[0].previous_output, included);
+}
+
+#[test]
+fn send_btc_does_not_spend_excluded_output() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(2);
+
+  let outputs = [
+    OutPoint::new(core.tx(1, 0).compute_txid(), 0),
+    OutPoint::new(core.tx(2, 0).compute_txid(), 0),
+  ];
+
+  CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --exclude-output {} --exclude-output {} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc",
+    outputs[0], outputs[1],
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: not enough cardinal utxos\n")
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --exclude-output {} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc",
+    outputs[0],
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let tx = &core.mempool()[0];
+
+  assert_eq!(tx.input.len(), 1);
+  assert_eq!(tx.input[0].previous_output, outputs[1]);
+
+  assert!(core.get_locked().is_empty());
+}
+
+#[test]
+fn send_btc_with_change_address() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet send --fee-rate 1 --change-address bc1qhl452zcq3ng5kzajzkx9jnzncml9tnsk3w96s6 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc",
+  )
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  let tx = &core.mempool()[0];
+
+  assert_eq!(tx.output.len(), 2);
+
+  assert_eq!(
+    Address::from_script(&tx.output[1].script_pubkey, Network::Bitcoin).unwrap(),
+    "bc1qhl452zcq3ng5kzajzkx9jnzncml9tnsk3w96s6"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+  );
+}
+
+#[test]
+fn send_inscription_with_included_output_and_change_address() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(3);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let included = OutPoint::new(core.tx(2, 0).compute_txid(), 0);
+
+  let output = CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --include-output {included} --change-address bc1qhl452zcq3ng5kzajzkx9jnzncml9tnsk3w96s6 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let tx = core.tx_by_id(output.txid);
+
+  assert_eq!(tx.input.len(), 2);
+  assert_eq!(tx.input[1].previous_output, included);
+
+  assert_eq!(tx.output.len(), 2);
+  assert_eq!(tx.output[0].value.to_sat(), 10_000);
+  assert_eq!(
+    Address::from_script(&tx.output[1].script_pubkey, Network::Bitcoin).unwrap(),
+    "bc1qhl452zcq3ng5kzajzkx9jnzncml9tnsk3w96s6"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+  );
+}
+
+#[test]
+fn included_outputs_may_not_contain_inscriptions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let (inscription, reveal) = inscribe(&core, &ord);
+
+  core.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --include-output {reveal}:0 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: included output {reveal}:0 contains inscriptions\n"
+  ))
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 --include-output {reveal}:0 --exclude-output {reveal}:0 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: output {reveal}:0 cannot be both included and excluded\n"
+  ))
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn sending_rune_spends_included_runic_output() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  etch(&core, &ord, Rune(RUNE));
+  let b = etch(&core, &ord, Rune(RUNE + 1));
+
+  core.mine_blocks(1);
+
+  let included = OutPoint {
+    txid: b.output.reveal,
+    vout: 1,
+  };
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet send --fee-rate 1 --include-output {included} bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw 1btc",
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!("error: included output {included} contains runes\n"))
+  .run_and_extract_stdout();
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet send --fee-rate 1 --include-output {included} bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw 1000:{}",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  assert!(core
+    .tx_by_id(output.txid)
+    .input
+    .iter()
+    .any(|input| input.previous_output == included));
+
+  let balances = CommandBuilder::new("--regtest --index-runes balances")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<ord::subcommand::balances::Output>();
+
+  pretty_assert_eq!(
+    balances,
+    ord::subcommand::balances::Output {
+      runes: [
+        (
+          SpacedRune::new(Rune(RUNE), 0),
+          [(
+            OutPoint {
+              txid: output.txid,
+              vout: 2
+            },
+            Pile {
+              amount: 1000,
+              divisibility: 0,
+              symbol: Some('¢')
+            },
+          )]
+          .into()
+        ),
+        (
+          SpacedRune::new(Rune(RUNE + 1), 0),
+          [(
+            OutPoint {
+              txid: output.txid,
+              vout: 1
+            },
+            Pile {
+              amount: 1000,
+              divisibility: 0,
+              symbol: Some('¢')
+            },
+          )]
+          .into()
+        )
+      ]
+      .into()
+    }
+  );
+}
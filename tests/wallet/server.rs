@@ -0,0 +1,324 @@
+use {
+  super::*,
+  command_builder::Spawn,
+  reqwest::blocking::{Client, RequestBuilder},
+};
+
+struct WalletServer {
+  port: u16,
+  spawn: Spawn,
+}
+
+impl WalletServer {
+  fn spawn(core: &mockcore::Handle, ord: &TestServer) -> Self {
+    Self::spawn_with_args(core, ord, "")
+  }
+
+  fn spawn_with_args(core: &mockcore::Handle, ord: &TestServer, args: &str) -> Self {
+    let port = TcpListener::bind("127.0.0.1:0")
+      .unwrap()
+      .local_addr()
+      .unwrap()
+      .port();
+
+    let spawn = CommandBuilder::new(format!(
+      "{args} wallet server --http-port {port} --username foo --password bar"
+    ))
+    .core(core)
+    .ord(ord)
+    .spawn();
+
+    for i in 0.. {
+      match reqwest::blocking::get(format!("http://127.0.0.1:{port}/balance")) {
+        Ok(_) => break,
+        Err(err) => {
+          if i == 400 {
+            panic!("wallet server failed to start: {err}");
+          }
+        }
+      }
+
+      thread::sleep(Duration::from_millis(50));
+    }
+
+    Self { port, spawn }
+  }
+
+  fn get(&self, path: &str) -> RequestBuilder {
+    Client::new()
+      .get(format!("http://127.0.0.1:{}{path}", self.port))
+      .basic_auth("foo", Some("bar"))
+  }
+
+  fn post(&self, path: &str, body: serde_json::Value) -> RequestBuilder {
+    Client::new()
+      .post(format!("http://127.0.0.1:{}{path}", self.port))
+      .basic_auth("foo", Some("bar"))
+      .json(&body)
+  }
+}
+
+impl Drop for WalletServer {
+  fn drop(&mut self) {
+    self.spawn.child.kill().unwrap();
+  }
+}
+
+#[test]
+fn wallet_server_requires_authentication() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let server = WalletServer::spawn(&core, &ord);
+
+  let response =
+    reqwest::blocking::get(format!("http://127.0.0.1:{}/balance", server.port)).unwrap();
+
+  assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+  let response = Client::new()
+    .get(format!("http://127.0.0.1:{}/balance", server.port))
+    .basic_auth("foo", Some("baz"))
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn wallet_server_balance_and_inscriptions() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _) = inscribe(&core, &ord);
+
+  let server = WalletServer::spawn(&core, &ord);
+
+  let response = server.get("/balance").send().unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let balance = response.json::<Balance>().unwrap();
+
+  assert_eq!(balance.ordinal, 10_000);
+
+  let inscriptions = server
+    .get("/inscriptions")
+    .send()
+    .unwrap()
+    .json::<Inscriptions>()
+    .unwrap();
+
+  assert_eq!(inscriptions.len(), 1);
+  assert_eq!(inscriptions[0].inscription, inscription);
+
+  let runics = server
+    .get("/runics")
+    .send()
+    .unwrap()
+    .json::<Vec<ord::subcommand::wallet::runics::RunicUtxo>>()
+    .unwrap();
+
+  assert!(runics.is_empty());
+
+  let pending = server
+    .get("/pending")
+    .send()
+    .unwrap()
+    .json::<Vec<ord::subcommand::wallet::pending::PendingOutput>>()
+    .unwrap();
+
+  assert!(pending.is_empty());
+}
+
+#[test]
+fn wallet_server_receive() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  let server = WalletServer::spawn(&core, &ord);
+
+  let output = server
+    .post("/receive", serde_json::json!({ "number": 2 }))
+    .send()
+    .unwrap()
+    .json::<ord::subcommand::wallet::receive::Output>()
+    .unwrap();
+
+  assert_eq!(output.addresses.len(), 2);
+
+  let output = Client::new()
+    .post(format!("http://127.0.0.1:{}/receive", server.port))
+    .basic_auth("foo", Some("bar"))
+    .send()
+    .unwrap()
+    .json::<ord::subcommand::wallet::receive::Output>()
+    .unwrap();
+
+  assert_eq!(output.addresses.len(), 1);
+}
+
+#[test]
+fn wallet_server_send() {
+  let core = mockcore::spawn();
+
+  let ord = TestServer::spawn(&core);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let server = WalletServer::spawn(&core, &ord);
+
+  let response = server
+    .post(
+      "/send",
+      serde_json::json!({
+        "address": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        "fee_rate": 1,
+        "outgoing": "1btc",
+      }),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let output = response.json::<Send>().unwrap();
+
+  assert_eq!(core.mempool()[0].compute_txid(), output.txid);
+
+  let response = server
+    .post("/send", serde_json::json!({ "fee_rate": 1, "foo": "bar" }))
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  assert_eq!(response.text().unwrap(), "unknown argument `foo`");
+
+  let response = server
+    .post(
+      "/send",
+      serde_json::json!({
+        "address": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        "fee_rate": 1,
+        "outgoing": "1000btc",
+      }),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn wallet_server_batch_etching_returns_pending_etching() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let inscription = tempdir.path().join("inscription.jpeg");
+
+  fs::write(&inscription, "inscription").unwrap();
+
+  let batchfile = tempdir.path().join("batch.yaml");
+
+  fs::write(
+    &batchfile,
+    serde_yaml::to_string(&batch::File {
+      etching: Some(batch::Etching {
+        supply: "1000".parse().unwrap(),
+        divisibility: 0,
+        terms: None,
+        premine: "1000".parse().unwrap(),
+        rune: SpacedRune {
+          rune: Rune(RUNE),
+          spacers: 0,
+        },
+        symbol: '¢',
+        turbo: false,
+      }),
+      inscriptions: vec![batch::Entry {
+        file: Some(inscription),
+        ..default()
+      }],
+      ..default()
+    })
+    .unwrap(),
+  )
+  .unwrap();
+
+  let server = WalletServer::spawn_with_args(&core, &ord, "--regtest --index-runes");
+
+  let response = server
+    .post(
+      "/batch",
+      serde_json::json!({
+        "batch": batchfile,
+        "fee_rate": 0,
+      }),
+    )
+    .send()
+    .unwrap();
+
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let output = response.json::<Batch>().unwrap();
+
+  assert!(!output.reveal_broadcast);
+
+  let pending = server
+    .get("/pending")
+    .send()
+    .unwrap()
+    .json::<Vec<ord::subcommand::wallet::pending::PendingOutput>>()
+    .unwrap();
+
+  assert_eq!(pending.len(), 1);
+  assert_eq!(pending[0].commit, output.commit);
+
+  let resumed = server
+    .post("/resume", serde_json::json!({}))
+    .send()
+    .unwrap()
+    .json::<ord::subcommand::wallet::resume::ResumeOutput>()
+    .unwrap();
+
+  assert!(resumed.etchings.is_empty());
+
+  core.mine_blocks(5);
+
+  let resumed = server
+    .post("/resume", serde_json::json!({}))
+    .send()
+    .unwrap()
+    .json::<ord::subcommand::wallet::resume::ResumeOutput>()
+    .unwrap();
+
+  assert_eq!(resumed.etchings.len(), 1);
+  assert!(resumed.etchings[0].reveal_broadcast);
+  assert_eq!(resumed.etchings[0].reveal, output.reveal);
+
+  assert!(server
+    .get("/pending")
+    .send()
+    .unwrap()
+    .json::<Vec<ord::subcommand::wallet::pending::PendingOutput>>()
+    .unwrap()
+    .is_empty());
+}
@@ -0,0 +1,249 @@
+use {
+  super::*,
+  bitcoin::{
+    key::{CompressedPublicKey, Secp256k1},
+    PrivateKey,
+  },
+  ord::{decimal::Decimal, subcommand::wallet::sweep::Output},
+};
+
+fn key() -> (PrivateKey, Address) {
+  let private_key = PrivateKey::from_slice(&[1; 32], Network::Regtest).unwrap();
+
+  let address = Address::p2wpkh(
+    &CompressedPublicKey::from_private_key(&Secp256k1::new(), &private_key).unwrap(),
+    Network::Regtest,
+  );
+
+  (private_key, address)
+}
+
+fn inscribe_to(core: &mockcore::Handle, ord: &TestServer, address: &Address) -> InscriptionId {
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet inscribe --fee-rate 1 --file foo.txt --destination {address}"
+  ))
+  .write("foo.txt", "FOO")
+  .core(core)
+  .ord(ord)
+  .run_and_deserialize_output::<Batch>();
+
+  core.mine_blocks(1);
+
+  output.inscriptions[0].id
+}
+
+#[test]
+fn sweep_requires_address_index() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--index-runes", "--regtest"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (private_key, _) = key();
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet sweep --fee-rate 1 --wif {private_key}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: sweeping requires index created with `--index-addresses` flag\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn sweep_with_no_outputs_is_an_error() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(
+    &core,
+    &["--index-runes", "--index-addresses", "--regtest"],
+    &[],
+  );
+
+  create_wallet(&core, &ord);
+
+  let (private_key, _) = key();
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet sweep --fee-rate 1 --wif {private_key}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: no outputs to sweep\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn sweep_inscription_with_wif() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(
+    &core,
+    &["--index-runes", "--index-addresses", "--regtest"],
+    &[],
+  );
+
+  create_wallet(&core, &ord);
+
+  let (private_key, address) = key();
+
+  let inscription = inscribe_to(&core, &ord, &address);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet sweep --fee-rate 1 --wif {private_key}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(core.mempool()[0].compute_txid(), output.txid);
+  assert_eq!(output.swept.len(), 1);
+  assert!(output.runes.is_empty());
+  assert_eq!(
+    output.inscriptions,
+    [(
+      inscription,
+      SatPoint {
+        outpoint: OutPoint {
+          txid: output.txid,
+          vout: 0,
+        },
+        offset: 0,
+      }
+    )]
+    .into(),
+  );
+
+  let fee = output.fee;
+
+  let transaction = core.mempool()[0].clone();
+
+  assert_eq!(transaction.output[0].value.to_sat(), 10_000);
+
+  core.mine_blocks(1);
+
+  let inscriptions = CommandBuilder::new("--chain regtest --index-runes wallet inscriptions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Inscriptions>();
+
+  assert_eq!(inscriptions.len(), 1);
+  assert_eq!(inscriptions[0].inscription, inscription);
+  assert_eq!(inscriptions[0].postage, 10_000);
+  assert!(fee > 0);
+}
+
+#[test]
+fn sweep_inscription_with_descriptor() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(
+    &core,
+    &["--index-runes", "--index-addresses", "--regtest"],
+    &[],
+  );
+
+  create_wallet(&core, &ord);
+
+  let (private_key, address) = key();
+
+  let inscription = inscribe_to(&core, &ord, &address);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet sweep --fee-rate 1 --descriptor wpkh({private_key})"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(
+    output.inscriptions.keys().collect::<Vec<_>>(),
+    [&inscription]
+  );
+}
+
+#[test]
+fn sweep_runes() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(
+    &core,
+    &["--index-runes", "--index-addresses", "--regtest"],
+    &[],
+  );
+
+  create_wallet(&core, &ord);
+
+  etch(&core, &ord, Rune(RUNE));
+
+  let (private_key, address) = key();
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet send --fee-rate 1 {address} 1000:{}",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--chain regtest --index-runes wallet sweep --fee-rate 1 --wif {private_key}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(
+    output.runes,
+    [(
+      SpacedRune {
+        rune: Rune(RUNE),
+        spacers: 0,
+      },
+      Decimal {
+        value: 1000,
+        scale: 0,
+      }
+    )]
+    .into(),
+  );
+
+  let transaction = core.mempool()[0].clone();
+
+  assert_eq!(
+    Runestone::decipher(&transaction),
+    Some(Artifact::Runestone(Runestone {
+      pointer: Some(0),
+      ..default()
+    })),
+  );
+
+  core.mine_blocks(1);
+
+  let balance = CommandBuilder::new("--regtest --index-runes wallet balance")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Balance>();
+
+  assert_eq!(
+    balance.runes.unwrap(),
+    [(
+      SpacedRune {
+        rune: Rune(RUNE),
+        spacers: 0,
+      },
+      Decimal {
+        value: 1000,
+        scale: 0,
+      }
+    )]
+    .into(),
+  );
+}
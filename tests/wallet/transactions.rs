@@ -65,3 +65,148 @@ fn transactions_with_limit() {
 
   assert_eq!(output.len(), 1);
 }
+
+#[test]
+fn transactions_are_annotated_with_inscriptions() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, reveal) = inscribe(&core, &ord);
+
+  let send = CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet transactions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  let reveal = output
+    .iter()
+    .find(|entry| entry.transaction == reveal)
+    .unwrap();
+
+  assert_eq!(reveal.inscriptions_created, vec![inscription]);
+  assert_eq!(reveal.inscriptions_received, Vec::new());
+  assert!(!reveal.lost);
+
+  let send = output
+    .iter()
+    .find(|entry| entry.transaction == send.txid)
+    .unwrap();
+
+  assert_eq!(send.inscriptions_sent, vec![inscription]);
+  assert_eq!(send.inscriptions_created, Vec::new());
+  assert!(!send.lost);
+}
+
+#[test]
+fn transactions_are_annotated_with_rune_deltas() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-runes"], &[]);
+
+  create_wallet(&core, &ord);
+
+  let etched = etch(&core, &ord, Rune(RUNE));
+
+  let send = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet send --fee-rate 1 bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw 100:{}",
+    Rune(RUNE)
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("--regtest --index-runes wallet transactions")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  let spaced_rune = SpacedRune {
+    rune: Rune(RUNE),
+    spacers: 0,
+  };
+
+  let reveal = output
+    .iter()
+    .find(|entry| entry.transaction == etched.output.reveal)
+    .unwrap();
+
+  assert_eq!(reveal.etching, Some(spaced_rune));
+  assert_eq!(reveal.runes[&spaced_rune].to_string(), "1000");
+
+  let send = output
+    .iter()
+    .find(|entry| entry.transaction == send.txid)
+    .unwrap();
+
+  assert_eq!(send.etching, None);
+  assert_eq!(send.runes[&spaced_rune].to_string(), "-100");
+  assert!(send.runes_burned.is_empty());
+  assert!(!send.cenotaph);
+}
+
+#[test]
+fn transactions_as_csv() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, reveal) = inscribe(&core, &ord);
+
+  CommandBuilder::new("wallet transactions --csv")
+    .core(&core)
+    .ord(&ord)
+    .stdout_regex(format!(
+      "transaction,confirmations,inscriptions_created,inscriptions_received,inscriptions_sent,\
+      inscriptions_burned,runes,runes_burned,mint,etching,cenotaph,lost\n\
+      .*{reveal},1,{inscription},,,,,,,,false,false\n"
+    ))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn transactions_with_limit_replay_spent_ancestors() {
+  let core = mockcore::spawn();
+  let ord = TestServer::spawn_with_server_args(&core, &[], &[]);
+
+  create_wallet(&core, &ord);
+
+  let (inscription, _reveal) = inscribe(&core, &ord);
+
+  let send = CommandBuilder::new(format!(
+    "wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 {inscription}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Send>();
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet transactions --limit 2")
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 2);
+
+  let send = output
+    .iter()
+    .find(|entry| entry.transaction == send.txid)
+    .unwrap();
+
+  assert_eq!(send.inscriptions_sent, vec![inscription]);
+  assert!(!send.lost);
+}